@@ -1,35 +1,166 @@
-use std::path::PathBuf;
+use std::{collections::HashMap, num::NonZeroUsize, path::PathBuf, time::Duration};
 
 use alloy_primitives::Address;
 use angstrom_metrics::initialize_prometheus_metrics;
-use angstrom_types::contract_bindings::angstrom::Angstrom::PoolKey;
+use angstrom_network::{GossipBatchConfig, OrderSamplerConfig, SampledFields};
+use angstrom_types::{contract_bindings::angstrom::Angstrom::PoolKey, primitive::PoolId};
+use consensus::QuorumTracker;
 use eyre::Context;
+use matching_engine::{MatchingSchedule, SelfTradePrevention};
+use order_pool::{CancelledOrdersMode, MinOrderLifetime, ETH_BLOCK_TIME};
 use serde::Deserialize;
+use uniswap_v4::uniswap::circuit_breaker::PoolCircuitBreaker;
+use validation::order::state::config::ValidationConfig;
 
 #[derive(Debug, Clone, Default, clap::Args)]
 pub struct AngstromConfig {
     #[clap(long)]
-    pub mev_guard:           bool,
+    pub mev_guard:            bool,
     #[clap(long)]
-    pub secret_key_location: PathBuf,
+    pub secret_key_location:  PathBuf,
     #[clap(long)]
-    pub angstrom_addr:       Option<Address>,
+    pub angstrom_addr:        Option<Address>,
     #[clap(long)]
-    pub node_config:         PathBuf,
+    pub node_config:          PathBuf,
     /// enables the metrics
     #[clap(long, default_value = "false", global = true)]
-    pub metrics:             bool,
+    pub metrics:              bool,
     /// spawns the prometheus metrics exporter at the specified port
     /// Default: 6969
     #[clap(long, default_value = "6969", global = true)]
-    pub metrics_port:        u16
+    pub metrics_port:         u16,
+    /// forwards each order to this many random peers instead of all of them,
+    /// at least one
+    #[clap(long)]
+    pub gossip_fanout:        Option<NonZeroUsize>,
+    /// collects the orders gossiped to a peer for this many milliseconds and
+    /// sends them as one message
+    #[clap(long)]
+    pub gossip_batch_ms:      Option<u64>,
+    /// sends a gossip batch early once it holds this many orders
+    #[clap(long, requires = "gossip_batch_ms")]
+    pub gossip_batch_size:    Option<usize>,
+    /// applies cancellations relayed by peers without forwarding them
+    #[clap(long)]
+    pub no_cancel_gossip:     bool,
+    /// holds at most this many orders relayed by a single peer, evicting the
+    /// peer's oldest orders beyond it
+    #[clap(long)]
+    pub max_orders_per_peer:  Option<usize>,
+    /// rejects order queries over rpc whose response takes more than this
+    /// many bytes, asking the caller to narrow the query
+    #[clap(long)]
+    pub rpc_response_limit:   Option<usize>,
+    /// runs matching every this many milliseconds instead of once per block
+    #[clap(long, conflicts_with = "matching_on_demand")]
+    pub matching_interval_ms: Option<u64>,
+    /// only runs matching when triggered through the admin rpc
+    #[clap(long)]
+    pub matching_on_demand:   bool,
+    /// keeps the pools paused through the admin rpc in this file, so they
+    /// stay paused across restarts
+    #[clap(long)]
+    pub paused_pools_file:    Option<PathBuf>,
+    /// logs the orders accepted into and removed from the pool to this file,
+    /// replayed on startup so resting orders survive a crash
+    #[clap(long)]
+    pub order_wal_file:       Option<PathBuf>,
+    /// validates and matches orders and builds bundles as usual, but never
+    /// submits a bundle or sends orders and consensus messages to peers
+    #[clap(long)]
+    pub dry_run:              bool,
+    /// gives up on a block that isn't committed within this percentage of
+    /// the block time after its pre-proposals reached quorum
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub quorum_timeout_pct:   Option<u32>,
+    /// writes a sample of the orders the node sees to this file, one json
+    /// object per line, for offline analysis
+    #[clap(long)]
+    pub order_sample_file:    Option<PathBuf>,
+    /// share of orders written to the sample file, between 0 and 1
+    #[clap(long, default_value = "0.01", requires = "order_sample_file")]
+    pub order_sample_rate:    f64,
+    /// what is captured of a sampled order besides its hash
+    #[clap(long, value_delimiter = ',', requires = "order_sample_file")]
+    pub order_sample_fields:  Vec<OrderSampleField>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OrderSampleField {
+    Order,
+    Sender,
+    Peer
+}
+
+impl AngstromConfig {
+    pub fn matching_schedule(&self) -> MatchingSchedule {
+        match (self.matching_interval_ms, self.matching_on_demand) {
+            (Some(interval_ms), _) => {
+                MatchingSchedule::Interval(Duration::from_millis(interval_ms))
+            }
+            (None, true) => MatchingSchedule::OnDemand,
+            (None, false) => MatchingSchedule::PerBlock
+        }
+    }
+
+    pub fn quorum_timeout(&self) -> Option<Duration> {
+        let percent = self.quorum_timeout_pct?;
+        QuorumTracker::relative_to_block_time(ETH_BLOCK_TIME, percent).timeout()
+    }
+
+    /// the sampler's config and the file it writes to
+    pub fn order_sampler(&self) -> Option<(OrderSamplerConfig, PathBuf)> {
+        let path = self.order_sample_file.clone()?;
+        let fields = SampledFields {
+            order:  self.order_sample_fields.contains(&OrderSampleField::Order),
+            sender: self.order_sample_fields.contains(&OrderSampleField::Sender),
+            peer:   self.order_sample_fields.contains(&OrderSampleField::Peer)
+        };
+        let config =
+            OrderSamplerConfig { rate: self.order_sample_rate, fields, ..Default::default() };
+
+        Some((config, path))
+    }
+
+    pub fn gossip_batch(&self) -> Option<GossipBatchConfig> {
+        let flush_interval = Duration::from_millis(self.gossip_batch_ms?);
+        let default = GossipBatchConfig::default();
+
+        Some(GossipBatchConfig {
+            max_orders: self.gossip_batch_size.unwrap_or(default.max_orders),
+            flush_interval
+        })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct NodeConfig {
-    pub secret_key:       String,
-    pub angstrom_address: Address,
-    pub pools:            Vec<PoolKey>
+    pub secret_key:              String,
+    pub angstrom_address:        Address,
+    pub pools:                   Vec<PoolKey>,
+    /// halts matching on a pool whose price moves more than this many basis
+    /// points within a block
+    #[serde(default)]
+    pub max_price_move_bps:      Option<u64>,
+    /// per pool overrides of `max_price_move_bps`
+    #[serde(default)]
+    pub pool_max_price_move_bps: HashMap<PoolId, u64>,
+    /// whether orders of the same sender may be matched against each other.
+    /// Has to be the same on every node
+    #[serde(default)]
+    pub self_trade_prevention:   SelfTradePrevention,
+    /// limits orders are validated against, e.g. the per pool minimum
+    /// notional
+    #[serde(default)]
+    pub validation:              ValidationConfig,
+    /// how long orders of a pool rest before they can be cancelled, as
+    /// `{ blocks = n }` or `{ seconds = n }`
+    #[serde(default)]
+    pub min_order_lifetimes:     HashMap<PoolId, MinOrderLifetime>,
+    /// how cancelled order hashes are remembered, `"exact"` or `{ bloom = {
+    /// exact_capacity = n, expected_items = n, false_positive_rate = r } }`
+    #[serde(default)]
+    pub cancelled_orders:        CancelledOrdersMode
 }
 
 impl NodeConfig {
@@ -48,6 +179,16 @@ impl NodeConfig {
 
         Ok(node_config)
     }
+
+    /// the breaker halting pools whose price moves too much within a block
+    pub fn circuit_breaker(&self) -> PoolCircuitBreaker<PoolId> {
+        self.pool_max_price_move_bps.iter().fold(
+            PoolCircuitBreaker::new(self.max_price_move_bps),
+            |breaker, (&pool_id, &max_move_bps)| {
+                breaker.with_pool_max_move_bps(pool_id, max_move_bps)
+            }
+        )
+    }
 }
 
 pub async fn init_metrics(metrics_port: u16) {
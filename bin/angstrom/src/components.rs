@@ -1,6 +1,9 @@
 //! CLI definition and entrypoint to executable
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::HashSet,
+    sync::{atomic::Ordering, Arc}
+};
 
 use alloy::{
     eips::{BlockId, BlockNumberOrTag},
@@ -18,9 +21,10 @@ use angstrom_eth::{
 use angstrom_network::{
     manager::StromConsensusEvent,
     pool_manager::{OrderCommand, PoolHandle},
-    NetworkBuilder as StromNetworkBuilder, NetworkOrderEvent, PoolManagerBuilder, StatusState,
-    VerificationSidecar
+    JsonLinesSink, NetworkBuilder as StromNetworkBuilder, NetworkOrderEvent, PoolManagerBuilder,
+    StatusState, StromNetworkConfig, VerificationSidecar
 };
+use angstrom_rpc::types::NodeStatusSources;
 use angstrom_types::{
     block_sync::{BlockSyncProducer, GlobalBlockSync},
     contract_payloads::angstrom::{AngstromPoolConfigStore, UniswapAngstromRegistry},
@@ -28,8 +32,10 @@ use angstrom_types::{
     reth_db_wrapper::RethDbWrapper
 };
 use consensus::{AngstromValidator, ConsensusManager, ManagerNetworkDeps, Signer};
-use matching_engine::{manager::MatcherCommand, MatchingManager};
-use order_pool::{order_storage::OrderStorage, PoolConfig, PoolManagerUpdate};
+use matching_engine::{
+    manager::MatcherCommand, MatchingManager, MatchingScheduler, MatchingSchedulerHandle
+};
+use order_pool::{order_storage::OrderStorage, PausedPools, PoolConfig, PoolManagerUpdate};
 use reth::{
     api::NodeAddOns,
     builder::FullNodeComponents,
@@ -44,7 +50,8 @@ use tokio::sync::mpsc::{
     channel, unbounded_channel, Receiver, Sender, UnboundedReceiver, UnboundedSender
 };
 use uniswap_v4::uniswap::{
-    pool::EnhancedUniswapPool, pool_data_loader::DataLoader, pool_manager::UniswapPoolManager,
+    circuit_breaker::PoolCircuitBreaker, pool::EnhancedUniswapPool, pool_data_loader::DataLoader,
+    pool_manager::UniswapPoolManager,
     pool_providers::canonical_state_adapter::CanonicalStateAdapter
 };
 use validation::{
@@ -56,14 +63,20 @@ use validation::{
 
 use crate::{cli::NodeConfig, AngstromConfig};
 
-pub fn init_network_builder(secret_key: SecretKey) -> eyre::Result<StromNetworkBuilder> {
+/// `pools` are declared to peers in the status handshake, so they only
+/// gossip orders for these pools to us
+pub fn init_network_builder(
+    secret_key: SecretKey,
+    pools: impl IntoIterator<Item = AngstromPoolId>
+) -> eyre::Result<StromNetworkBuilder> {
     let public_key = PublicKey::from_secret_key(&Secp256k1::new(), &secret_key);
 
     let state = StatusState {
         version:   0,
         chain:     Chain::mainnet().id(),
         peer:      pk2id(&public_key),
-        timestamp: 0
+        timestamp: 0,
+        pools:     pools.into_iter().collect()
     };
 
     let verification =
@@ -95,7 +108,14 @@ pub struct StromHandles {
     pub consensus_rx_op: UnboundedMeteredReceiver<StromConsensusEvent>,
 
     pub matching_tx: Sender<MatcherCommand>,
-    pub matching_rx: Receiver<MatcherCommand>
+    pub matching_rx: Receiver<MatcherCommand>,
+
+    pub matching_scheduler:        MatchingScheduler,
+    pub matching_scheduler_handle: MatchingSchedulerHandle,
+
+    pub node_status:     NodeStatusSources,
+    pub paused_pools:    PausedPools,
+    pub circuit_breaker: Arc<PoolCircuitBreaker<AngstromPoolId>>
 }
 
 impl StromHandles {
@@ -110,6 +130,7 @@ impl StromHandles {
 pub fn initialize_strom_handles() -> StromHandles {
     let (eth_tx, eth_rx) = channel(100);
     let (matching_tx, matching_rx) = channel(100);
+    let (matching_scheduler, matching_scheduler_handle) = MatchingScheduler::new();
     let (pool_manager_tx, _) = tokio::sync::broadcast::channel(100);
     let (pool_tx, pool_rx) = reth_metrics::common::mpsc::metered_unbounded_channel("orderpool");
     let (orderpool_tx, orderpool_rx) = unbounded_channel();
@@ -130,20 +151,32 @@ pub fn initialize_strom_handles() -> StromHandles {
         consensus_tx_op,
         consensus_rx_op,
         matching_tx,
-        matching_rx
+        matching_rx,
+        matching_scheduler,
+        matching_scheduler_handle,
+        node_status: NodeStatusSources::default(),
+        paused_pools: PausedPools::default(),
+        circuit_breaker: Arc::new(PoolCircuitBreaker::default())
     }
 }
 
 pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeAddOns<Node>>(
     angstrom_address: Option<Address>,
     config: AngstromConfig,
+    node_config: NodeConfig,
     secret_key: SecretKey,
     handles: StromHandles,
     network_builder: StromNetworkBuilder,
     node: FullNode<Node, AddOns>,
     executor: &TaskExecutor
 ) {
-    let node_config = NodeConfig::load_from_config(Some(config.node_config)).unwrap();
+    let matching_schedule = config.matching_schedule();
+    let quorum_timeout = config.quorum_timeout();
+    let order_sampler = config.order_sampler();
+    let dry_run = config.dry_run;
+    if dry_run {
+        tracing::warn!("dry run, no bundle is submitted and nothing is sent to peers");
+    }
 
     let signer = LocalSigner::<SigningKey>::from_bytes(&secret_key.secret_bytes().into()).unwrap();
     let node_address = signer.address();
@@ -185,6 +218,11 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
     );
 
     let uniswap_registry: UniswapPoolRegistry = node_config.pools.into();
+    let configured_pairs = uniswap_registry
+        .pools()
+        .into_values()
+        .map(|key| (key.currency0, key.currency1))
+        .collect::<Vec<_>>();
     let uni_ang_registry =
         UniswapAngstromRegistry::new(uniswap_registry.clone(), pool_config_store.clone());
 
@@ -208,7 +246,9 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         block_id,
         global_block_sync.clone()
     )
-    .await;
+    .await
+    .with_circuit_breaker(handles.circuit_breaker.clone())
+    .with_synced_block(handles.node_status.pools_synced_block.clone());
 
     let uniswap_pools = uniswap_pool_manager.pools();
     executor.spawn(Box::pin(async move {
@@ -218,17 +258,27 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
             .expect("watch for uniswap pool changes");
     }));
 
-    let price_generator =
+    let mut price_generator =
         TokenPriceGenerator::new(provider.clone(), block_id, uniswap_pools.clone())
             .await
             .expect("failed to start token price generator");
+    if node_config.validation.backfill_prices {
+        price_generator
+            .backfill(provider.clone(), &uniswap_pools)
+            .await;
+    }
 
     let block_height = node.provider.best_block_number().unwrap();
+    handles
+        .node_status
+        .validation_block
+        .store(block_height, Ordering::SeqCst);
 
-    init_validation(
+    let validation = init_validation(
         RethDbWrapper::new(node.provider.clone()),
-        block_height,
-        angstrom_address,
+        handles.node_status.validation_block.clone(),
+        handles.node_status.validations_queued.clone(),
+        angstrom_address.unwrap_or(node_config.angstrom_address),
         node_address,
         // Because this is incapsulated under the orderpool syncer. this is the only case
         // we can use the raw stream.
@@ -236,7 +286,22 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         uniswap_pools.clone(),
         price_generator,
         pool_config_store.clone(),
-        handles.validator_rx
+        handles.validator_rx,
+        node_config.validation,
+        Some(Arc::new(node.provider.clone()))
+    )
+    .expect("failed to start validation");
+    let token_prices = validation.token_prices();
+    // a node that no longer validates orders can't take part, shut it down
+    executor.spawn_critical(
+        "order validation",
+        Box::pin(async move {
+            let exit = validation.stopped().await;
+            // the request senders are dropped as the node shuts down
+            if !exit.is_shutdown() {
+                panic!("order validation stopped: {exit}");
+            }
+        })
     );
 
     let validation_handle = ValidationClient(handles.validator_tx.clone());
@@ -246,12 +311,22 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         .with_consensus_manager(handles.consensus_tx_op)
         .build_handle(executor.clone(), node.provider.clone());
 
-    let pool_config = PoolConfig::default();
-    let order_storage = Arc::new(OrderStorage::new(&pool_config));
+    let pool_config = PoolConfig {
+        order_wal: config.order_wal_file.clone(),
+        min_order_lifetimes: node_config.min_order_lifetimes,
+        cancelled_orders: node_config.cancelled_orders,
+        ..Default::default()
+    };
+    let order_storage = Arc::new(
+        OrderStorage::new(&pool_config)
+            .with_paused_pools(handles.paused_pools.clone())
+            .with_token_prices(token_prices)
+    );
     let angstrom_pool_tracker =
-        AngstromPoolsTracker::new(node_config.angstrom_address, pool_config_store.clone());
+        AngstromPoolsTracker::new(node_config.angstrom_address, pool_config_store.clone())
+            .with_pools(configured_pairs);
 
-    let _pool_handle = PoolManagerBuilder::new(
+    let pool_manager = PoolManagerBuilder::new(
         validation_handle.clone(),
         Some(order_storage.clone()),
         network_handle.clone(),
@@ -260,7 +335,22 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         global_block_sync.clone()
     )
     .with_config(pool_config)
-    .build_with_channels(
+    .with_egress(!dry_run)
+    .with_network_config(StromNetworkConfig {
+        gossip_fanout:        config.gossip_fanout,
+        gossip_batch:         config.gossip_batch(),
+        gossip_cancellations: !config.no_cancel_gossip,
+        max_orders_per_peer:  config.max_orders_per_peer
+    });
+    let pool_manager = match order_sampler {
+        Some((sampler_config, path)) => {
+            tracing::info!(?path, rate = sampler_config.rate, "sampling orders");
+            let sink = JsonLinesSink::open(&path).expect("failed to open the order sample file");
+            pool_manager.with_order_sampler(sampler_config, sink)
+        }
+        None => pool_manager
+    };
+    let _pool_handle = pool_manager.build_with_channels(
         executor.clone(),
         handles.orderpool_tx,
         handles.orderpool_rx,
@@ -278,7 +368,11 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
     ];
 
     // spinup matching engine
-    let matching_handle = MatchingManager::spawn(executor.clone(), validation_handle.clone());
+    let matching_handle = MatchingManager::spawn_with_self_trade_prevention(
+        executor.clone(),
+        validation_handle.clone(),
+        node_config.self_trade_prevention
+    );
 
     let manager = ConsensusManager::new(
         ManagerNetworkDeps::new(
@@ -295,7 +389,12 @@ pub async fn initialize_strom_components<Node: FullNodeComponents, AddOns: NodeA
         provider,
         matching_handle,
         global_block_sync.clone()
-    );
+    )
+    .with_circuit_breaker(handles.circuit_breaker)
+    .with_matching_scheduler(handles.matching_scheduler.with_schedule(matching_schedule))
+    .with_dry_run(dry_run)
+    .with_quorum_timeout(quorum_timeout)
+    .with_validator_set_handle(handles.node_status.validator_set.clone());
 
     let _consensus_handle = executor.spawn_critical("consensus", Box::pin(manager));
     // ensure no more modules can be added to block sync.
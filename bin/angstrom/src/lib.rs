@@ -2,11 +2,18 @@
 //!
 //! ## Feature Flags
 
+use std::sync::Arc;
+
 use angstrom_metrics::METRICS_ENABLED;
-use angstrom_rpc::{api::OrderApiServer, OrderApi};
+use angstrom_rpc::{
+    api::{AdminApiServer, OrderApiServer},
+    AdminApi, OrderApi
+};
+use angstrom_types::primitive::PoolId;
 use clap::Parser;
-use cli::AngstromConfig;
+use cli::{AngstromConfig, NodeConfig};
 use network_builder::AngstromNetworkBuilder;
+use order_pool::PausedPools;
 use reth::{chainspec::EthereumChainSpecParser, cli::Cli};
 use reth_cli_util::get_secret_key;
 use reth_node_builder::{Node, NodeHandle};
@@ -37,14 +44,26 @@ pub fn run() -> eyre::Result<()> {
 
         let secret_key = get_secret_key(&args.secret_key_location)?;
 
-        let mut network = init_network_builder(secret_key)?;
+        let node_config = NodeConfig::load_from_config(Some(args.node_config.clone()))?;
+        // peers only gossip us orders for the pools we're configured with
+        let pools = node_config.pools.iter().cloned().map(PoolId::from);
+        let mut network = init_network_builder(secret_key, pools)?;
         let protocol_handle = network.build_protocol_handler();
-        let channels = initialize_strom_handles();
+        let mut channels = initialize_strom_handles();
+        if let Some(path) = args.paused_pools_file.clone() {
+            channels.paused_pools = PausedPools::load(path)?;
+        }
+        channels.circuit_breaker = Arc::new(node_config.circuit_breaker());
 
         // for rpc
         let pool = channels.get_pool_handle();
         let executor_clone = executor.clone();
         let validation_client = ValidationClient(channels.validator_tx.clone());
+        let status_sources = channels.node_status.clone();
+        let matching_scheduler = channels.matching_scheduler_handle.clone();
+        let paused_pools = channels.paused_pools.clone();
+        let circuit_breaker = channels.circuit_breaker.clone();
+        let rpc_response_limit = args.rpc_response_limit;
         let NodeHandle { node, node_exit_future } = builder
             .with_types::<EthereumNode>()
             .with_components(
@@ -54,8 +73,19 @@ pub fn run() -> eyre::Result<()> {
             )
             .with_add_ons::<EthereumAddOns>(Default::default())
             .extend_rpc_modules(move |rpc_context| {
-                let order_api = OrderApi::new(pool.clone(), executor_clone, validation_client);
+                let admin_api = AdminApi::new(validation_client.clone())
+                    .with_matching_scheduler(matching_scheduler)
+                    .with_paused_pools(paused_pools)
+                    .with_circuit_breaker(circuit_breaker);
+                let mut order_api = OrderApi::new(pool.clone(), executor_clone, validation_client)
+                    .with_status_sources(status_sources);
+                if let Some(limit) = rpc_response_limit {
+                    order_api = order_api.with_max_response_size(limit);
+                }
                 rpc_context.modules.merge_configured(order_api.into_rpc())?;
+                // pausing pools and triggering matching is for the operator, the
+                // admin namespace is only served over the local ipc socket
+                rpc_context.modules.merge_ipc(admin_api.into_rpc())?;
 
                 Ok(())
             })
@@ -65,6 +95,7 @@ pub fn run() -> eyre::Result<()> {
         initialize_strom_components(
             args.angstrom_addr,
             args,
+            node_config,
             secret_key,
             channels,
             network,
@@ -1,22 +1,43 @@
-use clap::{ArgAction, Parser};
-use testing_tools::testnet_controllers::{AngstromTestnetConfig, TestnetKind};
+use clap::{error::ErrorKind, ArgAction, CommandFactory, Parser};
+use testing_tools::testnet_controllers::{AngstromTestnetConfig, RpcTransport, TestnetKind};
 use tracing::Level;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter, Layer};
 
+/// every node runs in this process with its own anvil, rpc and network
+/// sockets, past this the machine runs out of ports and file descriptors
+const MAX_NODES_IN_NETWORK: u64 = 64;
+
+/// binding a port below this needs root
+const MIN_UNPRIVILEGED_PORT: u16 = 1024;
+
 #[derive(Parser)]
 pub struct Cli {
     /// starting port for the rpc for submitting transactions.
     /// each node will have an rpc submission endpoint at this port + their
     /// node's number
-    /// i.e. node 3/3 will have port 4202 if this value is set to 4200
-    #[clap(short = 'p', long, default_value_t = 4200)]
+    /// i.e. node 3/3 will have port 4202 if this value is set to 4200.
+    /// if set to 0, every node gets a free port assigned instead
+    #[clap(short = 'p', long, default_value_t = 4200, value_parser = parse_port)]
     pub starting_port:           u16,
+    /// the amount of nodes, starting from the first, that serve rpc. every
+    /// node does if not set
+    #[clap(long)]
+    pub rpc_nodes:               Option<u64>,
+    /// what the rpc accepts connections over. order and quote subscriptions
+    /// only work over a websocket
+    #[clap(long, value_enum, default_value_t = RpcTransport::Both)]
+    pub rpc_transport:           RpcTransport,
     /// the speed in which anvil will mine blocks.
-    #[clap(short, long, default_value = "12")]
+    #[clap(short, long, default_value = "12", value_parser = clap::value_parser!(u64).range(1..))]
     pub testnet_block_time_secs: u64,
     /// the amount of testnet nodes that will be spawned and connected to.
     /// this will change in the future but is good enough for testing currently
-    #[clap(short, long, default_value = "2")]
+    #[clap(
+        short,
+        long,
+        default_value = "2",
+        value_parser = clap::value_parser!(u64).range(1..=MAX_NODES_IN_NETWORK)
+    )]
     pub nodes_in_network:        u64,
     /// Set the minimum log level.
     ///
@@ -32,16 +53,44 @@ pub struct Cli {
 impl Cli {
     pub fn build_config() -> AngstromTestnetConfig {
         let this = Self::parse();
+        if let Err(msg) = this.check_ports() {
+            Self::command()
+                .error(ErrorKind::ArgumentConflict, msg)
+                .exit()
+        }
         this.init_tracing();
 
         AngstromTestnetConfig {
             intial_node_count:       this.nodes_in_network,
             initial_rpc_port:        this.starting_port,
+            rpc_node_count:          this.rpc_nodes,
             testnet_block_time_secs: this.testnet_block_time_secs,
-            testnet_kind:            TestnetKind::new_raw()
+            testnet_kind:            TestnetKind::new_raw(),
+            gossip_fanout:           None,
+            rpc_transport:           this.rpc_transport,
+            reconnect:               Default::default()
         }
     }
 
+    /// every node's rpc port has to fit below the highest port
+    fn check_ports(&self) -> Result<(), String> {
+        if self.starting_port == 0 {
+            return Ok(())
+        }
+
+        let last_port = self.starting_port as u64 + self.nodes_in_network - 1;
+        if last_port > u16::MAX as u64 {
+            return Err(format!(
+                "{} nodes starting at port {} would need port {last_port}, the highest is {}",
+                self.nodes_in_network,
+                self.starting_port,
+                u16::MAX
+            ))
+        }
+
+        Ok(())
+    }
+
     fn init_tracing(&self) {
         let level = match self.verbosity - 1 {
             0 => Level::ERROR,
@@ -66,3 +115,56 @@ impl Cli {
         tracing_subscriber::registry().with(vec![layer]).init();
     }
 }
+
+/// a port the nodes can bind without root, or 0 to have them assigned
+fn parse_port(value: &str) -> Result<u16, String> {
+    let port = value.parse::<u16>().map_err(|e| e.to_string())?;
+    if port != 0 && port < MIN_UNPRIVILEGED_PORT {
+        return Err(format!(
+            "port {port} is privileged, use one from {MIN_UNPRIVILEGED_PORT} up or 0 for free \
+             ports"
+        ))
+    }
+
+    Ok(port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("testnet").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_node_count_and_block_time_are_bounded() {
+        assert!(parse(&["--nodes-in-network", "0"]).is_err());
+        assert!(parse(&["--nodes-in-network", "65"]).is_err());
+        assert!(parse(&["--testnet-block-time-secs", "0"]).is_err());
+
+        let cli = parse(&["--nodes-in-network", "64", "--testnet-block-time-secs", "1"]).unwrap();
+        assert_eq!((cli.nodes_in_network, cli.testnet_block_time_secs), (64, 1));
+    }
+
+    #[test]
+    fn test_privileged_ports_are_rejected() {
+        assert!(parse(&["--starting-port", "80"]).is_err());
+        assert!(parse(&["--starting-port", "65536"]).is_err());
+
+        assert_eq!(parse(&["--starting-port", "1024"]).unwrap().starting_port, 1024);
+        // assigned ports
+        assert_eq!(parse(&["--starting-port", "0"]).unwrap().starting_port, 0);
+    }
+
+    #[test]
+    fn test_every_node_port_has_to_fit() {
+        let cli = parse(&["--starting-port", "65534", "--nodes-in-network", "2"]).unwrap();
+        assert!(cli.check_ports().is_ok());
+
+        let cli = parse(&["--starting-port", "65535", "--nodes-in-network", "2"]).unwrap();
+        assert!(cli.check_ports().is_err());
+        let cli = parse(&["--starting-port", "0", "--nodes-in-network", "64"]).unwrap();
+        assert!(cli.check_ports().is_ok());
+    }
+}
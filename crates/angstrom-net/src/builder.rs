@@ -4,7 +4,7 @@ use std::{collections::HashSet, sync::Arc};
 
 use alloy::primitives::{Address, FixedBytes};
 use alloy_chains::Chain;
-use angstrom_types::primitive::PeerId;
+use angstrom_types::primitive::{PeerId, PoolId};
 use futures::FutureExt;
 use parking_lot::RwLock;
 use reth_metrics::common::mpsc::{MeteredPollSender, UnboundedMeteredSender};
@@ -126,6 +126,12 @@ impl StatusBuilder {
         self.state.chain = chain.id();
         self
     }
+
+    /// Sets the pools that this node tracks orders for.
+    pub fn pools(mut self, pools: impl IntoIterator<Item = PoolId>) -> Self {
+        self.state = self.state.with_pools(pools);
+        self
+    }
 }
 
 impl From<StatusState> for StatusBuilder {
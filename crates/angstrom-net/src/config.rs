@@ -1 +1,52 @@
-pub struct StromNetworkConfig {}
+use std::num::NonZeroUsize;
+
+use crate::GossipBatchConfig;
+
+#[derive(Debug, Clone, Copy)]
+pub struct StromNetworkConfig {
+    /// number of randomly selected peers each valid order is forwarded to.
+    /// `None` sends every order to all peers that track its pool.
+    pub gossip_fanout:        Option<NonZeroUsize>,
+    /// groups orders gossiped to the same peer into one message. `None`
+    /// sends each order as soon as it is validated.
+    pub gossip_batch:         Option<GossipBatchConfig>,
+    /// whether cancellations this node applies are forwarded to all peers.
+    /// Incoming cancellations are applied either way.
+    pub gossip_cancellations: bool,
+    /// most orders relayed by a single peer the pool holds at once, the
+    /// peer's oldest orders are evicted beyond it. `None` doesn't bound them.
+    pub max_orders_per_peer:  Option<usize>
+}
+
+impl Default for StromNetworkConfig {
+    fn default() -> Self {
+        Self {
+            gossip_fanout:        None,
+            gossip_batch:         None,
+            gossip_cancellations: true,
+            max_orders_per_peer:  None
+        }
+    }
+}
+
+impl StromNetworkConfig {
+    pub fn with_gossip_fanout(mut self, gossip_fanout: NonZeroUsize) -> Self {
+        self.gossip_fanout = Some(gossip_fanout);
+        self
+    }
+
+    pub fn with_gossip_batch(mut self, gossip_batch: GossipBatchConfig) -> Self {
+        self.gossip_batch = Some(gossip_batch);
+        self
+    }
+
+    pub fn with_gossip_cancellations(mut self, gossip_cancellations: bool) -> Self {
+        self.gossip_cancellations = gossip_cancellations;
+        self
+    }
+
+    pub fn with_max_orders_per_peer(mut self, max_orders_per_peer: usize) -> Self {
+        self.max_orders_per_peer = Some(max_orders_per_peer);
+        self
+    }
+}
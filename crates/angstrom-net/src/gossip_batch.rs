@@ -0,0 +1,109 @@
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration
+};
+
+use angstrom_types::{primitive::PeerId, sol_bindings::grouped_orders::AllOrders};
+use futures::FutureExt;
+use tokio::time::{sleep, Sleep};
+
+/// How outgoing orders are grouped before being gossiped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GossipBatchConfig {
+    /// a peer's batch is sent as soon as it holds this many orders
+    pub max_orders:     usize,
+    /// how long the first order of a batch waits for others to join it
+    pub flush_interval: Duration
+}
+
+impl Default for GossipBatchConfig {
+    fn default() -> Self {
+        Self { max_orders: 256, flush_interval: Duration::from_millis(50) }
+    }
+}
+
+/// Collects the orders gossiped to each peer so they go out as a single
+/// `PropagatePooledOrders` message per window instead of one per order.
+pub(crate) struct GossipBatcher {
+    config:  GossipBatchConfig,
+    pending: HashMap<PeerId, Vec<AllOrders>>,
+    /// started by the first order queued after a flush
+    flush:   Option<Pin<Box<Sleep>>>
+}
+
+impl GossipBatcher {
+    pub(crate) fn new(config: GossipBatchConfig) -> Self {
+        Self { config, pending: HashMap::new(), flush: None }
+    }
+
+    /// queues the order for the peer. Returns the peer's batch if it is full
+    /// and should be sent right away
+    pub(crate) fn push(&mut self, peer_id: PeerId, order: AllOrders) -> Option<Vec<AllOrders>> {
+        let batch = self.pending.entry(peer_id).or_default();
+        batch.push(order);
+        if batch.len() >= self.config.max_orders {
+            return self.pending.remove(&peer_id)
+        }
+
+        if self.flush.is_none() {
+            self.flush = Some(Box::pin(sleep(self.config.flush_interval)));
+        }
+
+        None
+    }
+
+    /// resolves with every queued batch once the flush interval has passed
+    pub(crate) fn poll_flush(
+        &mut self,
+        cx: &mut Context<'_>
+    ) -> Poll<Vec<(PeerId, Vec<AllOrders>)>> {
+        let Some(flush) = self.flush.as_mut() else { return Poll::Pending };
+        ready!(flush.poll_unpin(cx));
+        self.flush = None;
+
+        Poll::Ready(self.pending.drain().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::future::poll_fn;
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    fn order(nonce: u64) -> AllOrders {
+        UserOrderBuilder::new().nonce(nonce).build().into()
+    }
+
+    #[tokio::test]
+    async fn test_rapid_orders_are_sent_as_one_batch() {
+        let mut batcher = GossipBatcher::new(GossipBatchConfig::default());
+        let peer_id = PeerId::random();
+
+        for nonce in 0..10 {
+            assert!(batcher.push(peer_id, order(nonce)).is_none());
+        }
+
+        let batches = poll_fn(|cx| batcher.poll_flush(cx)).await;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].0, peer_id);
+        assert_eq!(batches[0].1.len(), 10);
+        assert!(poll_fn(|cx| batcher.poll_flush(cx))
+            .now_or_never()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_full_batch_is_sent_right_away() {
+        let config = GossipBatchConfig { max_orders: 3, ..Default::default() };
+        let mut batcher = GossipBatcher::new(config);
+        let peer_id = PeerId::random();
+
+        assert!(batcher.push(peer_id, order(0)).is_none());
+        assert!(batcher.push(peer_id, order(1)).is_none());
+        assert_eq!(batcher.push(peer_id, order(2)).map(|batch| batch.len()), Some(3));
+    }
+}
@@ -26,6 +26,14 @@ pub use network::*;
 pub mod config;
 pub use config::*;
 
+pub mod gossip_batch;
+pub use gossip_batch::GossipBatchConfig;
+
+pub mod order_sampler;
+pub use order_sampler::{JsonLinesSink, OrderSampleSink, OrderSamplerConfig, SampledFields};
+
+pub mod peer_orders;
+
 pub mod cache;
 pub use cache::*;
 
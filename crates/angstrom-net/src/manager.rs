@@ -1,4 +1,5 @@
 use std::{
+    collections::{HashMap, HashSet},
     future::Future,
     pin::Pin,
     sync::{atomic::AtomicUsize, Arc},
@@ -8,9 +9,10 @@ use std::{
 use alloy::primitives::BlockNumber;
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
-    primitive::PeerId
+    primitive::{PeerId, PoolId}
 };
 use futures::StreamExt;
+use parking_lot::RwLock;
 use reth_eth_wire::DisconnectReason;
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use tokio::sync::mpsc::UnboundedSender;
@@ -34,7 +36,10 @@ pub struct StromNetworkManager<DB> {
     /// This is updated via internal events and shared via `Arc` with the
     /// [`NetworkHandle`] Updated by the `NetworkWorker` and loaded by the
     /// `NetworkService`.
-    num_active_peers: Arc<AtomicUsize>
+    num_active_peers: Arc<AtomicUsize>,
+    /// The pools each peer declared in its status message. Shared with the
+    /// [`StromNetworkHandle`].
+    peer_pools:       Arc<RwLock<HashMap<PeerId, HashSet<PoolId>>>>
 }
 
 impl<DB: Unpin> StromNetworkManager<DB> {
@@ -46,12 +51,17 @@ impl<DB: Unpin> StromNetworkManager<DB> {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
 
         let peers = Arc::new(AtomicUsize::default());
-        let handle =
-            StromNetworkHandle::new(peers.clone(), UnboundedMeteredSender::new(tx, "strom handle"));
+        let peer_pools = Arc::new(RwLock::new(HashMap::default()));
+        let handle = StromNetworkHandle::new(
+            peers.clone(),
+            peer_pools.clone(),
+            UnboundedMeteredSender::new(tx, "strom handle")
+        );
 
         Self {
             handle: handle.clone(),
             num_active_peers: peers,
+            peer_pools,
             swarm,
             from_handle_rx: rx.into(),
             to_pool_manager,
@@ -174,6 +184,11 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
             if let Poll::Ready(Some(event)) = self.swarm.poll_next_unpin(cx) {
                 match event {
                     SwarmEvent::ValidMessage { peer_id, msg } => match msg {
+                        StromMessage::Status(status) => {
+                            self.peer_pools
+                                .write()
+                                .insert(peer_id, status.state.pools.into_iter().collect());
+                        }
                         StromMessage::PrePropose(p) => {
                             self.to_consensus_manager.as_ref().inspect(|tx| {
                                 let _ = tx.send(StromConsensusEvent::PreProposal(peer_id, p));
@@ -190,9 +205,50 @@ impl<DB: Unpin> Future for StromNetworkManager<DB> {
                                     .send(NetworkOrderEvent::IncomingOrders { peer_id, orders: a });
                             });
                         }
-                        _ => {}
+                        StromMessage::RequestOrders(order_hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(NetworkOrderEvent::OrdersRequested {
+                                    peer_id,
+                                    order_hashes
+                                });
+                            });
+                        }
+                        // responses go through validation like any gossiped order
+                        StromMessage::OrdersResponse(orders) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ =
+                                    tx.send(NetworkOrderEvent::IncomingOrders { peer_id, orders });
+                            });
+                        }
+                        StromMessage::CancelOrders(cancellations) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(NetworkOrderEvent::CancelOrders {
+                                    peer_id,
+                                    cancellations
+                                });
+                            });
+                        }
+                        StromMessage::BookChecksum(pool_id, checksum) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(NetworkOrderEvent::BookChecksum {
+                                    peer_id,
+                                    pool_id,
+                                    checksum
+                                });
+                            });
+                        }
+                        StromMessage::BookOrderHashes(pool_id, order_hashes) => {
+                            self.to_pool_manager.as_ref().inspect(|tx| {
+                                let _ = tx.send(NetworkOrderEvent::BookOrderHashes {
+                                    peer_id,
+                                    pool_id,
+                                    order_hashes
+                                });
+                            });
+                        }
                     },
                     SwarmEvent::Disconnected { peer_id } => {
+                        self.peer_pools.write().remove(&peer_id);
                         self.notify_listeners(StromNetworkEvent::SessionClosed {
                             peer_id,
                             reason: None
@@ -1,6 +1,15 @@
-use std::sync::{atomic::AtomicUsize, Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{atomic::AtomicUsize, Arc}
+};
 
-use angstrom_types::{primitive::PeerId, sol_bindings::grouped_orders::AllOrders};
+use alloy::primitives::B256;
+use angstrom_types::{
+    orders::SignedCancellation,
+    primitive::{PeerId, PoolId},
+    sol_bindings::grouped_orders::AllOrders
+};
+use parking_lot::RwLock;
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_network::DisconnectReason;
 use tokio::sync::{
@@ -24,9 +33,10 @@ pub struct StromNetworkHandle {
 impl StromNetworkHandle {
     pub fn new(
         num_active_peers: Arc<AtomicUsize>,
+        peer_pools: Arc<RwLock<HashMap<PeerId, HashSet<PoolId>>>>,
         to_manager_tx: UnboundedMeteredSender<StromNetworkHandleMsg>
     ) -> Self {
-        Self { inner: Arc::new(StromNetworkInner { num_active_peers, to_manager_tx }) }
+        Self { inner: Arc::new(StromNetworkInner { num_active_peers, peer_pools, to_manager_tx }) }
     }
 
     /// Sends a [`NetworkHandleMessage`] to the manager
@@ -77,12 +87,25 @@ impl StromNetworkHandle {
             .num_active_peers
             .load(std::sync::atomic::Ordering::SeqCst)
     }
+
+    /// The pools the peer declared in its status message. An empty set means
+    /// the peer is interested in orders for all pools.
+    pub fn peer_pools(&self, peer_id: PeerId) -> HashSet<PoolId> {
+        self.inner
+            .peer_pools
+            .read()
+            .get(&peer_id)
+            .cloned()
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug)]
 #[allow(dead_code)]
 struct StromNetworkInner {
     num_active_peers: Arc<AtomicUsize>,
+    /// pools each connected peer tracks, learned during the status handshake
+    peer_pools:       Arc<RwLock<HashMap<PeerId, HashSet<PoolId>>>>,
 
     to_manager_tx: UnboundedMeteredSender<StromNetworkHandleMsg>
 }
@@ -90,7 +113,33 @@ struct StromNetworkInner {
 /// All events related to orders emitted by the network.
 #[derive(Debug, Clone, PartialEq)]
 pub enum NetworkOrderEvent {
-    IncomingOrders { peer_id: PeerId, orders: Vec<AllOrders> }
+    IncomingOrders {
+        peer_id: PeerId,
+        orders:  Vec<AllOrders>
+    },
+    /// the peer asked for the orders with these hashes
+    OrdersRequested {
+        peer_id:      PeerId,
+        order_hashes: Vec<B256>
+    },
+    /// the peer relayed these cancellations, their signatures aren't checked
+    /// yet
+    CancelOrders {
+        peer_id:       PeerId,
+        cancellations: Vec<SignedCancellation>
+    },
+    /// the peer's checksum over the live orders of the pool
+    BookChecksum {
+        peer_id:  PeerId,
+        pool_id:  PoolId,
+        checksum: B256
+    },
+    /// the peer's live order hashes for the pool, our checksums differed
+    BookOrderHashes {
+        peer_id:      PeerId,
+        pool_id:      PoolId,
+        order_hashes: Vec<B256>
+    }
 }
 
 #[derive(Debug)]
@@ -0,0 +1,307 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH}
+};
+
+use alloy::primitives::{Address, B256};
+use angstrom_types::{
+    primitive::{PeerId, PoolId},
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use reth_tasks::TaskSpawner;
+use serde::Serialize;
+use tokio::sync::mpsc::{channel, error::TrySendError, Receiver, Sender};
+
+/// Which parts of a sampled order are captured besides its hash. Everything
+/// that identifies a user or a peer is left out unless asked for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SampledFields {
+    /// the whole signed order
+    pub order:  bool,
+    /// the address the order is from
+    pub sender: bool,
+    /// the peer that gossiped the order
+    pub peer:   bool
+}
+
+/// How the pool manager samples the orders it sees for offline analysis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderSamplerConfig {
+    /// share of orders that are captured, between 0 and 1
+    pub rate:     f64,
+    pub fields:   SampledFields,
+    /// samples waiting for the sink, further ones are dropped
+    pub capacity: usize
+}
+
+impl Default for OrderSamplerConfig {
+    fn default() -> Self {
+        Self { rate: 0.01, fields: SampledFields::default(), capacity: 4096 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderSampleKind {
+    /// the order reached the pool, over rpc or from a peer
+    Received,
+    /// the order passed validation and was added to the pool
+    Accepted
+}
+
+/// A captured order event, as handed to the [`OrderSampleSink`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderSample {
+    /// unix timestamp in milliseconds
+    pub timestamp_ms: u64,
+    pub kind:         OrderSampleKind,
+    pub order_hash:   B256,
+    /// only known once the order was validated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_id:      Option<PoolId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender:       Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peer_id:      Option<PeerId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order:        Option<AllOrders>
+}
+
+/// Where sampled orders end up. Runs on its own blocking task, so writes
+/// may block.
+pub trait OrderSampleSink: Send + 'static {
+    fn write(&mut self, sample: &OrderSample) -> io::Result<()>;
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl OrderSampleSink for Box<dyn OrderSampleSink> {
+    fn write(&mut self, sample: &OrderSample) -> io::Result<()> {
+        (**self).write(sample)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        (**self).flush()
+    }
+}
+
+/// Writes each sample as a line of json.
+pub struct JsonLinesSink<W: Write> {
+    writer: W
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl JsonLinesSink<BufWriter<File>> {
+    /// appends to the file at `path`, creating it if needed
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::options().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write + Send + 'static> OrderSampleSink for JsonLinesSink<W> {
+    fn write(&mut self, sample: &OrderSample) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, sample)?;
+        self.writer.write_all(b"\n")
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Captures a sampled share of the orders the pool manager sees. Whether an
+/// order is sampled depends only on its hash, so all events of a sampled
+/// order are captured. Samples are handed to the sink through a bounded
+/// channel and dropped when it is full, the pool manager never waits on the
+/// sink.
+pub struct OrderSampler {
+    fields:    SampledFields,
+    /// orders whose hash prefix is below this are sampled
+    threshold: u64,
+    samples:   Sender<OrderSample>,
+    dropped:   u64
+}
+
+impl OrderSampler {
+    /// the sampler and the receiving end to drain with [`run_sink`]
+    pub fn new(config: OrderSamplerConfig) -> (Self, Receiver<OrderSample>) {
+        let (samples, rx) = channel(config.capacity.max(1));
+        let threshold = (config.rate.clamp(0.0, 1.0) * u64::MAX as f64) as u64;
+
+        (Self { fields: config.fields, threshold, samples, dropped: 0 }, rx)
+    }
+
+    /// a sampler whose samples are written to `sink` on a blocking task
+    pub fn spawn(
+        config: OrderSamplerConfig,
+        sink: impl OrderSampleSink,
+        task_spawner: &impl TaskSpawner
+    ) -> Self {
+        let (sampler, samples) = Self::new(config);
+        task_spawner.spawn_blocking(Box::pin(async move {
+            run_sink(samples, sink).await;
+        }));
+
+        sampler
+    }
+
+    pub fn is_sampled(&self, order_hash: &B256) -> bool {
+        let prefix = u64::from_be_bytes(order_hash[..8].try_into().unwrap());
+        prefix < self.threshold
+    }
+
+    pub fn received(&mut self, order: &AllOrders, peer_id: Option<PeerId>) {
+        self.sample(OrderSampleKind::Received, order, None, peer_id);
+    }
+
+    pub fn accepted(&mut self, order: &AllOrders, pool_id: PoolId) {
+        self.sample(OrderSampleKind::Accepted, order, Some(pool_id), None);
+    }
+
+    /// samples that were dropped because the sink fell behind
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    fn sample(
+        &mut self,
+        kind: OrderSampleKind,
+        order: &AllOrders,
+        pool_id: Option<PoolId>,
+        peer_id: Option<PeerId>
+    ) {
+        let order_hash = order.order_hash();
+        if !self.is_sampled(&order_hash) {
+            return
+        }
+
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let sample = OrderSample {
+            timestamp_ms,
+            kind,
+            order_hash,
+            pool_id,
+            sender: self.fields.sender.then(|| order.from()),
+            peer_id: peer_id.filter(|_| self.fields.peer),
+            order: self.fields.order.then(|| order.clone())
+        };
+
+        match self.samples.try_send(sample) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped += 1;
+                tracing::trace!(dropped = self.dropped, "order sink is behind, dropping sample");
+            }
+            Err(TrySendError::Closed(_)) => {}
+        }
+    }
+}
+
+/// Writes the samples to `sink` until the sampler is dropped, then hands the
+/// sink back. Blocks on the sink's writes, so it belongs on a blocking task.
+pub async fn run_sink<S: OrderSampleSink>(mut samples: Receiver<OrderSample>, mut sink: S) -> S {
+    while let Some(sample) = samples.recv().await {
+        if let Err(error) = sink.write(&sample) {
+            tracing::warn!(%error, "failed to write order sample");
+        }
+        // flush once caught up, not on every sample
+        if samples.is_empty() {
+            let _ = sink.flush();
+        }
+    }
+    let _ = sink.flush();
+
+    sink
+}
+
+#[cfg(test)]
+mod test {
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    fn order(nonce: u64) -> AllOrders {
+        UserOrderBuilder::new().nonce(nonce).build().into()
+    }
+
+    #[test]
+    fn test_rate_bounds_what_is_sampled() {
+        let sampler = |rate| OrderSampler::new(OrderSamplerConfig { rate, ..Default::default() }).0;
+        let (none, all, half) = (sampler(0.0), sampler(1.0), sampler(0.5));
+
+        let hashes = (0..1000).map(|_| B256::random()).collect::<Vec<_>>();
+        assert!(hashes.iter().all(|hash| !none.is_sampled(hash)));
+        assert!(hashes.iter().all(|hash| all.is_sampled(hash)));
+        let sampled = hashes.iter().filter(|hash| half.is_sampled(hash)).count();
+        assert!((400..600).contains(&sampled));
+    }
+
+    #[test]
+    fn test_only_configured_fields_are_captured() {
+        let config = OrderSamplerConfig {
+            rate: 1.0,
+            fields: SampledFields { sender: true, ..Default::default() },
+            ..Default::default()
+        };
+        let (mut sampler, mut rx) = OrderSampler::new(config);
+        let order = order(0);
+
+        sampler.received(&order, Some(PeerId::random()));
+        let sample = rx.try_recv().unwrap();
+        assert_eq!(sample.kind, OrderSampleKind::Received);
+        assert_eq!(sample.order_hash, order.order_hash());
+        assert_eq!(sample.sender, Some(order.from()));
+        assert!(sample.peer_id.is_none());
+        assert!(sample.order.is_none());
+    }
+
+    #[test]
+    fn test_samples_are_dropped_when_the_sink_is_behind() {
+        let config = OrderSamplerConfig { rate: 1.0, capacity: 2, ..Default::default() };
+        let (mut sampler, mut rx) = OrderSampler::new(config);
+
+        for nonce in 0..5 {
+            sampler.accepted(&order(nonce), PoolId::random());
+        }
+        assert_eq!(sampler.dropped(), 3);
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_ok());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_sink_writes_a_line_per_sample() {
+        let config = OrderSamplerConfig { rate: 1.0, ..Default::default() };
+        let (mut sampler, rx) = OrderSampler::new(config);
+        sampler.received(&order(0), None);
+        sampler.accepted(&order(0), PoolId::random());
+        drop(sampler);
+
+        let sink = run_sink(rx, JsonLinesSink::new(Vec::new())).await;
+
+        let written = String::from_utf8(sink.into_inner()).unwrap();
+        let kinds = written
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["kind"].clone())
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["received", "accepted"]);
+    }
+}
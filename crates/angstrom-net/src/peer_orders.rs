@@ -0,0 +1,155 @@
+use std::collections::{HashMap, VecDeque};
+
+use alloy::primitives::B256;
+use angstrom_types::primitive::PeerId;
+
+/// Relayed orders remembered while they are validated. Orders that don't make
+/// it into the pool are forgotten once this many newer ones were relayed.
+const PENDING_ORDERS_LIMIT: usize = 1024 * 10;
+
+/// Bounds how many orders relayed by a single peer the pool holds at once, so
+/// a peer flooding distinct valid orders can't grow the pool without limit.
+/// An order counts against the first peer that relayed it, whichever signers
+/// the peer relays orders for.
+pub(crate) struct PeerOrders {
+    max_per_peer:  usize,
+    /// relayed orders awaiting validation and the peer they count against
+    pending:       HashMap<B256, PeerId>,
+    /// the pending orders, oldest first
+    pending_order: VecDeque<B256>,
+    /// each peer's orders that were accepted into the pool, oldest first
+    resting:       HashMap<PeerId, VecDeque<B256>>
+}
+
+impl PeerOrders {
+    pub(crate) fn new(max_per_peer: usize) -> Self {
+        Self {
+            max_per_peer:  max_per_peer.max(1),
+            pending:       HashMap::new(),
+            pending_order: VecDeque::new(),
+            resting:       HashMap::new()
+        }
+    }
+
+    /// the peer relayed the order, it counts against the peer if the pool
+    /// accepts it. Orders other peers relayed first keep counting against
+    /// those
+    pub(crate) fn relayed(&mut self, peer_id: PeerId, order_hash: B256) {
+        if self.pending.contains_key(&order_hash) {
+            return
+        }
+        self.pending.insert(order_hash, peer_id);
+        self.pending_order.push_back(order_hash);
+        if self.pending_order.len() > PENDING_ORDERS_LIMIT {
+            if let Some(oldest) = self.pending_order.pop_front() {
+                self.pending.remove(&oldest);
+            }
+        }
+    }
+
+    /// the order was accepted into the pool. Returns the orders of the peer
+    /// that relayed it to evict, oldest first, to get the peer back under its
+    /// cap, they no longer count against the peer. `is_resting` tells whether
+    /// an order is still in the pool, orders that were filled, cancelled or
+    /// evicted otherwise are released from the peer's count here
+    pub(crate) fn accepted(
+        &mut self,
+        order_hash: B256,
+        is_resting: impl Fn(&B256) -> bool
+    ) -> Vec<B256> {
+        let Some(peer_id) = self.pending.remove(&order_hash) else { return vec![] };
+        let orders = self.resting.entry(peer_id).or_default();
+        orders.push_back(order_hash);
+        if orders.len() <= self.max_per_peer {
+            return vec![]
+        }
+
+        orders.retain(|hash| is_resting(hash));
+        let excess = orders.len().saturating_sub(self.max_per_peer);
+        let evicted = orders.drain(..excess).collect::<Vec<_>>();
+        if !evicted.is_empty() {
+            tracing::debug!(
+                ?peer_id,
+                evicted = evicted.len(),
+                max_per_peer = self.max_per_peer,
+                "peer is over its order cap, evicting its oldest orders"
+            );
+        }
+
+        evicted
+    }
+
+    /// the peer's session closed, its orders stay in the pool but no longer
+    /// count against it, should it reconnect
+    pub(crate) fn session_closed(&mut self, peer_id: &PeerId) {
+        self.resting.remove(peer_id);
+        self.pending.retain(|_, relayer| relayer != peer_id);
+        let pending = &self.pending;
+        self.pending_order.retain(|hash| pending.contains_key(hash));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_oldest_orders_are_evicted_beyond_the_cap() {
+        let mut peer_orders = PeerOrders::new(2);
+        let (peer, other_peer) = (PeerId::random(), PeerId::random());
+        let hashes = (0..3).map(|_| B256::random()).collect::<Vec<_>>();
+        let other = B256::random();
+
+        for hash in &hashes {
+            peer_orders.relayed(peer, *hash);
+        }
+        peer_orders.relayed(other_peer, other);
+        // relayed again by another peer, still counts against the first one
+        peer_orders.relayed(other_peer, hashes[2]);
+
+        assert!(peer_orders.accepted(hashes[0], |_| true).is_empty());
+        assert!(peer_orders.accepted(hashes[1], |_| true).is_empty());
+        assert!(peer_orders.accepted(other, |_| true).is_empty());
+        assert_eq!(peer_orders.accepted(hashes[2], |_| true), vec![hashes[0]]);
+        assert_eq!(peer_orders.resting[&peer].len(), 2);
+        assert_eq!(peer_orders.resting[&other_peer].len(), 1);
+    }
+
+    #[test]
+    fn test_orders_that_left_the_pool_no_longer_count() {
+        let mut peer_orders = PeerOrders::new(2);
+        let peer = PeerId::random();
+        let hashes = (0..3).map(|_| B256::random()).collect::<Vec<_>>();
+
+        for hash in &hashes {
+            peer_orders.relayed(peer, *hash);
+        }
+        peer_orders.accepted(hashes[0], |_| true);
+        peer_orders.accepted(hashes[1], |_| true);
+
+        // the first order was filled in the meantime
+        let evicted = peer_orders.accepted(hashes[2], |hash| *hash != hashes[0]);
+        assert!(evicted.is_empty());
+        assert_eq!(peer_orders.resting[&peer].len(), 2);
+
+        // orders that were never relayed, e.g. local ones, don't count
+        assert!(peer_orders.accepted(B256::random(), |_| true).is_empty());
+    }
+
+    #[test]
+    fn test_closed_sessions_release_the_peers_orders() {
+        let mut peer_orders = PeerOrders::new(1);
+        let peer = PeerId::random();
+        let (resting, pending) = (B256::random(), B256::random());
+
+        peer_orders.relayed(peer, resting);
+        peer_orders.accepted(resting, |_| true);
+        peer_orders.relayed(peer, pending);
+        peer_orders.session_closed(&peer);
+
+        assert!(!peer_orders.resting.contains_key(&peer));
+        assert!(peer_orders.pending.is_empty() && peer_orders.pending_order.is_empty());
+        // the order validated after the session closed counts against nobody
+        assert!(peer_orders.accepted(pending, |_| true).is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     num::NonZeroUsize,
     pin::Pin,
     sync::Arc,
@@ -10,15 +10,17 @@ use alloy::primitives::{Address, FixedBytes, B256};
 use angstrom_eth::manager::EthEvent;
 use angstrom_types::{
     block_sync::BlockSyncConsumer,
-    orders::{OrderLocation, OrderOrigin, OrderStatus},
+    orders::{OrderLocation, OrderOrigin, OrderStatus, SignedCancellation, TransactionId},
     primitive::PeerId,
-    sol_bindings::grouped_orders::AllOrders
+    sol_bindings::grouped_orders::{AllOrders, OrderWithStorageData}
 };
 use futures::{Future, FutureExt, StreamExt};
 use order_pool::{
-    order_storage::OrderStorage, OrderIndexer, OrderPoolHandle, PoolConfig, PoolInnerEvent,
-    PoolManagerUpdate
+    order_storage::OrderStorage, AcceptancePolicy, AllowAll, BookNotional, CancelOrderError,
+    OrderEvent, OrderIndexer, OrderPoolHandle, PoolConfig, PoolInnerEvent, PoolManagerUpdate,
+    PoolStatus, RejectReason
 };
+use rand::seq::IteratorRandom;
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_tasks::TaskSpawner;
 use tokio::sync::{
@@ -27,16 +29,28 @@ use tokio::sync::{
 };
 use tokio_stream::wrappers::{BroadcastStream, UnboundedReceiverStream};
 use validation::order::{
-    state::pools::AngstromPoolsTracker, OrderValidationResults, OrderValidatorHandle
+    rejection::{Rejection, RejectionReason},
+    state::pools::{AngstromPoolsTracker, TrackedPool},
+    OrderValidationResults, OrderValidatorHandle
 };
 
-use crate::{LruCache, NetworkOrderEvent, StromMessage, StromNetworkEvent, StromNetworkHandle};
+use crate::{
+    gossip_batch::GossipBatcher,
+    order_sampler::{OrderSampleSink, OrderSampler, OrderSamplerConfig},
+    peer_orders::PeerOrders,
+    LruCache, NetworkOrderEvent, ReputationChangeKind, StromMessage, StromNetworkConfig,
+    StromNetworkEvent, StromNetworkHandle
+};
 
 const MODULE_NAME: &str = "Order Pool";
 
 /// Cache limit of transactions to keep track of for a single peer.
 const PEER_ORDER_CACHE_LIMIT: usize = 1024 * 10;
 
+/// Max number of order hashes answered for a single reconciliation request,
+/// the rest of the request is ignored.
+const MAX_REQUESTED_ORDERS: usize = 1024;
+
 /// Api to interact with [`PoolManager`] task.
 #[derive(Debug, Clone)]
 pub struct PoolHandle {
@@ -48,16 +62,39 @@ pub struct PoolHandle {
 pub enum OrderCommand {
     // new orders
     NewOrder(OrderOrigin, AllOrders, tokio::sync::oneshot::Sender<OrderValidationResults>),
-    CancelOrder(Address, B256, tokio::sync::oneshot::Sender<bool>),
+    CancelOrder(SignedCancellation, tokio::sync::oneshot::Sender<Result<bool, CancelOrderError>>),
     PendingOrders(Address, tokio::sync::oneshot::Sender<Vec<AllOrders>>),
-    OrdersByPool(FixedBytes<32>, OrderLocation, tokio::sync::oneshot::Sender<Vec<AllOrders>>),
-    OrderStatus(B256, tokio::sync::oneshot::Sender<Option<OrderStatus>>)
+    OrdersBySender(Address, tokio::sync::oneshot::Sender<Vec<OrderWithStorageData<AllOrders>>>),
+    OrdersByPool(
+        FixedBytes<32>,
+        OrderLocation,
+        Option<usize>,
+        tokio::sync::oneshot::Sender<Vec<AllOrders>>
+    ),
+    OrderStatus(B256, tokio::sync::oneshot::Sender<Option<(OrderStatus, TransactionId)>>),
+    OrderRejection(B256, tokio::sync::oneshot::Sender<Option<Rejection>>),
+    OrdersInRange(u64, u64, tokio::sync::oneshot::Sender<Vec<OrderEvent>>),
+    PoolStatus(tokio::sync::oneshot::Sender<PoolStatus>),
+    BookNotional(FixedBytes<32>, tokio::sync::oneshot::Sender<Option<BookNotional>>),
+    Pools(tokio::sync::oneshot::Sender<Vec<TrackedPool>>)
 }
 
 impl PoolHandle {
     fn send(&self, cmd: OrderCommand) -> Result<(), SendError<OrderCommand>> {
         self.manager_tx.send(cmd)
     }
+
+    /// Order events between blocks `from` and `to` (inclusive). Only returns
+    /// events if the pool was configured to keep history.
+    pub fn orders_in_range(
+        &self,
+        from: u64,
+        to: u64
+    ) -> impl Future<Output = Vec<OrderEvent>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::OrdersInRange(from, to, tx));
+        rx.map(|res| res.unwrap_or_default())
+    }
 }
 
 impl OrderPoolHandle for PoolHandle {
@@ -65,14 +102,24 @@ impl OrderPoolHandle for PoolHandle {
         &self,
         origin: OrderOrigin,
         order: AllOrders
-    ) -> impl Future<Output = bool> + Send {
+    ) -> impl Future<Output = Result<(), Rejection>> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let _ = self.send(OrderCommand::NewOrder(origin, order, tx));
         rx.map(|result| match result {
-            Ok(OrderValidationResults::Valid(_)) => true,
-            Ok(OrderValidationResults::Invalid(_)) => false,
-            Ok(OrderValidationResults::TransitionedToBlock) => false,
-            Err(_) => false
+            Ok(OrderValidationResults::Valid(_)) => Ok(()),
+            Ok(OrderValidationResults::Invalid(_, rejection)) => Err(rejection),
+            Ok(OrderValidationResults::TransitionedToBlock) => Err(Rejection::new(
+                RejectionReason::StaleBlock,
+                "the pool moved to a new block while the order was validated"
+            )),
+            // held until the pool syncs or is initialized
+            Ok(
+                OrderValidationResults::PoolNotSynced { .. }
+                | OrderValidationResults::PoolNotInitialized { .. }
+            ) => Ok(()),
+            Err(_) => {
+                Err(Rejection::new(RejectionReason::Unavailable, "the order pool isn't running"))
+            }
         })
     }
 
@@ -83,13 +130,14 @@ impl OrderPoolHandle for PoolHandle {
     fn fetch_orders_from_pool(
         &self,
         pool_id: FixedBytes<32>,
-        location: OrderLocation
+        location: OrderLocation,
+        depth: Option<usize>
     ) -> impl Future<Output = Vec<AllOrders>> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
 
         let _ = self
             .manager_tx
-            .send(OrderCommand::OrdersByPool(pool_id, location, tx));
+            .send(OrderCommand::OrdersByPool(pool_id, location, depth, tx));
 
         rx.map(|v| v.unwrap_or_default())
     }
@@ -97,7 +145,7 @@ impl OrderPoolHandle for PoolHandle {
     fn fetch_order_status(
         &self,
         order_hash: B256
-    ) -> impl Future<Output = Option<OrderStatus>> + Send {
+    ) -> impl Future<Output = Option<(OrderStatus, TransactionId)>> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
         let _ = self
             .manager_tx
@@ -112,14 +160,53 @@ impl OrderPoolHandle for PoolHandle {
         rx.map(|res| res.unwrap_or_default())
     }
 
-    fn cancel_order(&self, from: Address, order_hash: B256) -> impl Future<Output = bool> + Send {
+    fn orders_by_sender(
+        &self,
+        sender: Address
+    ) -> impl Future<Output = Vec<OrderWithStorageData<AllOrders>>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::OrdersBySender(sender, tx));
+        rx.map(|res| res.unwrap_or_default())
+    }
+
+    fn cancel_order(
+        &self,
+        cancellation: SignedCancellation
+    ) -> impl Future<Output = Result<bool, CancelOrderError>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::CancelOrder(cancellation, tx));
+        rx.map(|res| res.unwrap_or(Ok(false)))
+    }
+
+    fn order_rejection(&self, order_hash: B256) -> impl Future<Output = Option<Rejection>> + Send {
         let (tx, rx) = tokio::sync::oneshot::channel();
-        let _ = self.send(OrderCommand::CancelOrder(from, order_hash, tx));
-        rx.map(|res| res.unwrap_or(false))
+        let _ = self.send(OrderCommand::OrderRejection(order_hash, tx));
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn pool_status(&self) -> impl Future<Output = PoolStatus> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::PoolStatus(tx));
+        rx.map(|res| res.unwrap_or_default())
+    }
+
+    fn book_notional(
+        &self,
+        pool_id: FixedBytes<32>
+    ) -> impl Future<Output = Option<BookNotional>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::BookNotional(pool_id, tx));
+        rx.map(|res| res.ok().flatten())
+    }
+
+    fn pools(&self) -> impl Future<Output = Vec<TrackedPool>> + Send {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let _ = self.send(OrderCommand::Pools(tx));
+        rx.map(|res| res.unwrap_or_default())
     }
 }
 
-pub struct PoolManagerBuilder<V, GlobalSync>
+pub struct PoolManagerBuilder<V, GlobalSync, Policy = AllowAll>
 where
     V: OrderValidatorHandle,
     GlobalSync: BlockSyncConsumer
@@ -131,7 +218,12 @@ where
     strom_network_events: UnboundedReceiverStream<StromNetworkEvent>,
     eth_network_events:   UnboundedReceiverStream<EthEvent>,
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
-    config:               PoolConfig
+    config:               PoolConfig,
+    network_config:       StromNetworkConfig,
+    egress:               bool,
+    ingress:              bool,
+    acceptance_policy:    Policy,
+    order_sampler:        Option<(OrderSamplerConfig, Box<dyn OrderSampleSink>)>
 }
 
 impl<V, GlobalSync> PoolManagerBuilder<V, GlobalSync>
@@ -155,20 +247,85 @@ where
             network_handle,
             validator,
             order_storage,
-            config: Default::default()
+            config: Default::default(),
+            network_config: Default::default(),
+            egress: true,
+            ingress: true,
+            acceptance_policy: AllowAll,
+            order_sampler: None
         }
     }
+}
 
+impl<V, GlobalSync, Policy> PoolManagerBuilder<V, GlobalSync, Policy>
+where
+    V: OrderValidatorHandle<Order = AllOrders> + Unpin,
+    GlobalSync: BlockSyncConsumer,
+    Policy: AcceptancePolicy
+{
     pub fn with_config(mut self, config: PoolConfig) -> Self {
         self.config = config;
         self
     }
 
+    pub fn with_network_config(mut self, network_config: StromNetworkConfig) -> Self {
+        self.network_config = network_config;
+        self
+    }
+
+    /// whether valid orders are gossiped to peers. A node without egress
+    /// still validates and stores its orders, it just never sends them out
+    pub fn with_egress(mut self, egress: bool) -> Self {
+        self.egress = egress;
+        self
+    }
+
+    /// whether orders gossiped by peers are taken in. Turning it off together
+    /// with egress makes an observer node that only knows its own orders
+    pub fn with_ingress(mut self, ingress: bool) -> Self {
+        self.ingress = ingress;
+        self
+    }
+
+    /// captures a sampled share of the orders the pool sees to `sink`, for
+    /// offline analysis. Samples are dropped rather than slowing the pool
+    /// down if the sink can't keep up
+    pub fn with_order_sampler(
+        mut self,
+        config: OrderSamplerConfig,
+        sink: impl OrderSampleSink
+    ) -> Self {
+        self.order_sampler = Some((config, Box::new(sink)));
+        self
+    }
+
     pub fn with_storage(mut self, order_storage: Arc<OrderStorage>) -> Self {
         let _ = self.order_storage.insert(order_storage);
         self
     }
 
+    /// orders that don't pass the policy are rejected before validation
+    pub fn with_acceptance_policy<P: AcceptancePolicy>(
+        self,
+        acceptance_policy: P
+    ) -> PoolManagerBuilder<V, GlobalSync, P> {
+        PoolManagerBuilder {
+            validator: self.validator,
+            global_sync: self.global_sync,
+            order_storage: self.order_storage,
+            network_handle: self.network_handle,
+            strom_network_events: self.strom_network_events,
+            eth_network_events: self.eth_network_events,
+            order_events: self.order_events,
+            config: self.config,
+            network_config: self.network_config,
+            egress: self.egress,
+            ingress: self.ingress,
+            acceptance_policy,
+            order_sampler: self.order_sampler
+        }
+    }
+
     pub fn build_with_channels<TP: TaskSpawner>(
         self,
         task_spawner: TP,
@@ -189,19 +346,34 @@ where
             0,
             pool_manager_tx.clone(),
             pool_storage
-        );
+        )
+        .with_order_history(self.config.history_blocks)
+        .with_cancelled_orders_mode(self.config.cancelled_orders)
+        .with_min_order_lifetimes(self.config.min_order_lifetimes.clone())
+        .with_order_wal(self.config.order_wal.clone());
+        let order_sampler = self
+            .order_sampler
+            .map(|(config, sink)| OrderSampler::spawn(config, sink, &task_spawner));
 
         task_spawner.spawn_critical(
             "transaction manager",
             Box::pin(PoolManager {
-                eth_network_events:   self.eth_network_events,
+                eth_network_events: self.eth_network_events,
                 strom_network_events: self.strom_network_events,
-                order_events:         self.order_events,
-                peer_to_info:         HashMap::default(),
-                order_indexer:        inner,
-                network:              self.network_handle,
-                command_rx:           rx,
-                global_sync:          self.global_sync
+                order_events: self.order_events,
+                peer_to_info: HashMap::default(),
+                order_indexer: inner,
+                network: self.network_handle,
+                command_rx: rx,
+                global_sync: self.global_sync,
+                gossip_fanout: self.network_config.gossip_fanout,
+                gossip_batcher: self.network_config.gossip_batch.map(GossipBatcher::new),
+                gossip_cancellations: self.network_config.gossip_cancellations,
+                peer_orders: self.network_config.max_orders_per_peer.map(PeerOrders::new),
+                egress: self.egress,
+                ingress: self.ingress,
+                acceptance_policy: self.acceptance_policy,
+                order_sampler
             })
         );
 
@@ -227,19 +399,34 @@ where
             0,
             pool_manager_tx.clone(),
             pool_storage
-        );
+        )
+        .with_order_history(self.config.history_blocks)
+        .with_cancelled_orders_mode(self.config.cancelled_orders)
+        .with_min_order_lifetimes(self.config.min_order_lifetimes.clone())
+        .with_order_wal(self.config.order_wal.clone());
+        let order_sampler = self
+            .order_sampler
+            .map(|(config, sink)| OrderSampler::spawn(config, sink, &task_spawner));
 
         task_spawner.spawn_critical(
             "transaction manager",
             Box::pin(PoolManager {
-                eth_network_events:   self.eth_network_events,
+                eth_network_events: self.eth_network_events,
                 strom_network_events: self.strom_network_events,
-                order_events:         self.order_events,
-                peer_to_info:         HashMap::default(),
-                order_indexer:        inner,
-                network:              self.network_handle,
-                command_rx:           rx,
-                global_sync:          self.global_sync
+                order_events: self.order_events,
+                peer_to_info: HashMap::default(),
+                order_indexer: inner,
+                network: self.network_handle,
+                command_rx: rx,
+                global_sync: self.global_sync,
+                gossip_fanout: self.network_config.gossip_fanout,
+                gossip_batcher: self.network_config.gossip_batch.map(GossipBatcher::new),
+                gossip_cancellations: self.network_config.gossip_cancellations,
+                peer_orders: self.network_config.max_orders_per_peer.map(PeerOrders::new),
+                egress: self.egress,
+                ingress: self.ingress,
+                acceptance_policy: self.acceptance_policy,
+                order_sampler
             })
         );
 
@@ -247,7 +434,7 @@ where
     }
 }
 
-pub struct PoolManager<V, GlobalSync>
+pub struct PoolManager<V, GlobalSync, Policy = AllowAll>
 where
     V: OrderValidatorHandle,
     GlobalSync: BlockSyncConsumer
@@ -269,7 +456,24 @@ where
     /// Incoming events from the ProtocolManager.
     order_events:         UnboundedMeteredReceiver<NetworkOrderEvent>,
     /// All the connected peers.
-    peer_to_info:         HashMap<PeerId, StromPeer>
+    peer_to_info:         HashMap<PeerId, StromPeer>,
+    /// Number of peers each order is forwarded to, all peers if `None`.
+    gossip_fanout:        Option<NonZeroUsize>,
+    /// Groups outgoing orders per peer, each order is sent on its own if
+    /// `None`.
+    gossip_batcher:       Option<GossipBatcher>,
+    /// Whether applied cancellations are forwarded to peers.
+    gossip_cancellations: bool,
+    /// Bounds the orders each peer has in the pool, unbounded if `None`.
+    peer_orders:          Option<PeerOrders>,
+    /// Whether valid orders are forwarded to peers.
+    egress:               bool,
+    /// Whether orders received from peers are validated and stored.
+    ingress:              bool,
+    /// Checked for every new order before it is validated.
+    acceptance_policy:    Policy,
+    /// Captures a share of the orders for offline analysis.
+    order_sampler:        Option<OrderSampler>
 }
 
 impl<V, GlobalSync> PoolManager<V, GlobalSync>
@@ -297,32 +501,93 @@ where
             order_events,
             command_rx,
             eth_network_events,
-            global_sync
+            global_sync,
+            gossip_fanout: None,
+            gossip_batcher: None,
+            gossip_cancellations: true,
+            peer_orders: None,
+            egress: true,
+            ingress: true,
+            acceptance_policy: AllowAll,
+            order_sampler: None
         }
     }
+}
+
+impl<V, GlobalSync, Policy> PoolManager<V, GlobalSync, Policy>
+where
+    V: OrderValidatorHandle<Order = AllOrders>,
+    GlobalSync: BlockSyncConsumer,
+    Policy: AcceptancePolicy
+{
+    fn check_acceptance(&self, order: &AllOrders, origin: OrderOrigin) -> Result<(), RejectReason> {
+        self.acceptance_policy
+            .check(order, origin)
+            .inspect_err(|reason| {
+                tracing::debug!(
+                    order_hash = ?order.order_hash(),
+                    %reason,
+                    "order rejected by acceptance policy"
+                );
+            })
+    }
 
     fn on_command(&mut self, cmd: OrderCommand) {
         match cmd {
-            OrderCommand::NewOrder(_, order, validation_response) => self
-                .order_indexer
-                .new_rpc_order(OrderOrigin::External, order, validation_response),
-            OrderCommand::CancelOrder(from, order_hash, receiver) => {
-                let res = self.order_indexer.cancel_order(from, order_hash);
-                let _ = receiver.send(res);
+            OrderCommand::NewOrder(_, order, validation_response) => {
+                if let Some(sampler) = self.order_sampler.as_mut() {
+                    sampler.received(&order, None);
+                }
+                if let Err(reason) = self.check_acceptance(&order, OrderOrigin::External) {
+                    let _ = validation_response.send(OrderValidationResults::Invalid(
+                        order.order_hash(),
+                        Rejection::from(&reason)
+                    ));
+                    return
+                }
+
+                self.order_indexer
+                    .new_rpc_order(OrderOrigin::External, order, validation_response)
+            }
+            OrderCommand::CancelOrder(cancellation, receiver) => {
+                let mut res = self.on_cancellations(None, vec![cancellation]);
+                let _ = receiver.send(res.remove(0));
             }
             OrderCommand::PendingOrders(from, receiver) => {
-                let res = self.order_indexer.pending_orders_for_address(from);
+                let res = self.order_indexer.orders_by_sender(from);
                 let _ = receiver.send(res.into_iter().map(|o| o.order).collect());
             }
+            OrderCommand::OrdersBySender(sender, receiver) => {
+                let _ = receiver.send(self.order_indexer.orders_by_sender(sender));
+            }
             OrderCommand::OrderStatus(order_hash, tx) => {
                 let res = self.order_indexer.order_status(order_hash);
                 let _ = tx.send(res);
             }
+            OrderCommand::OrderRejection(order_hash, tx) => {
+                let _ = tx.send(self.order_indexer.rejection(&order_hash));
+            }
 
-            OrderCommand::OrdersByPool(pool_id, location, tx) => {
-                let res = self.order_indexer.orders_by_pool(pool_id, location);
+            OrderCommand::OrdersByPool(pool_id, location, depth, tx) => {
+                let res = self.order_indexer.orders_by_pool(pool_id, location, depth);
+                let _ = tx.send(res);
+            }
+            OrderCommand::OrdersInRange(from, to, tx) => {
+                let res = self.order_indexer.orders_in_range(from, to);
                 let _ = tx.send(res);
             }
+            OrderCommand::PoolStatus(tx) => {
+                let _ = tx.send(PoolStatus {
+                    peer_count:     self.peer_to_info.len(),
+                    pending_orders: self.order_indexer.total_orders()
+                });
+            }
+            OrderCommand::BookNotional(pool_id, tx) => {
+                let _ = tx.send(self.order_indexer.book_notional(pool_id));
+            }
+            OrderCommand::Pools(tx) => {
+                let _ = tx.send(self.order_indexer.pools());
+            }
         }
     }
 
@@ -352,10 +617,28 @@ where
         match event {
             NetworkOrderEvent::IncomingOrders { peer_id, orders } => {
                 tracing::debug!("recieved IncomingOrders from peer {:?}", peer_id);
+                if !self.ingress {
+                    tracing::trace!(?peer_id, count = orders.len(), "ingress disabled, dropping");
+                    return
+                }
+
                 orders.into_iter().for_each(|order| {
                     self.peer_to_info
                         .get_mut(&peer_id)
                         .map(|peer| peer.orders.insert(order.order_hash()));
+                    if let Some(sampler) = self.order_sampler.as_mut() {
+                        sampler.received(&order, Some(peer_id));
+                    }
+
+                    if self
+                        .check_acceptance(&order, OrderOrigin::External)
+                        .is_err()
+                    {
+                        return
+                    }
+                    if let Some(peer_orders) = self.peer_orders.as_mut() {
+                        peer_orders.relayed(peer_id, order.order_hash());
+                    }
 
                     self.order_indexer.new_network_order(
                         peer_id,
@@ -364,6 +647,166 @@ where
                     );
                 });
             }
+            NetworkOrderEvent::OrdersRequested { peer_id, mut order_hashes } => {
+                if !self.egress {
+                    tracing::trace!(?peer_id, "egress disabled, ignoring order request");
+                    return
+                }
+
+                order_hashes.truncate(MAX_REQUESTED_ORDERS);
+                let orders = self.order_indexer.orders_by_hash(&order_hashes);
+                tracing::debug!(?peer_id, count = orders.len(), "answering order request");
+
+                if let Some(info) = self.peer_to_info.get_mut(&peer_id) {
+                    orders.iter().for_each(|order| {
+                        info.orders.insert(order.order_hash());
+                    });
+                }
+                self.network
+                    .send_message(peer_id, StromMessage::OrdersResponse(orders));
+            }
+            NetworkOrderEvent::CancelOrders { peer_id, cancellations } => {
+                if !self.ingress {
+                    tracing::trace!(?peer_id, "ingress disabled, dropping cancellations");
+                    return
+                }
+
+                self.on_cancellations(Some(peer_id), cancellations);
+            }
+            NetworkOrderEvent::BookChecksum { peer_id, pool_id, checksum } => {
+                if !self.egress || self.order_indexer.book_checksum(pool_id) == checksum {
+                    return
+                }
+
+                let mut order_hashes = self.order_indexer.book_order_hashes(pool_id);
+                order_hashes.truncate(MAX_REQUESTED_ORDERS);
+                tracing::debug!(?peer_id, ?pool_id, "book differs from the peer's, sending hashes");
+                self.network
+                    .send_message(peer_id, StromMessage::BookOrderHashes(pool_id, order_hashes));
+            }
+            NetworkOrderEvent::BookOrderHashes { peer_id, pool_id, order_hashes } => {
+                if !self.ingress {
+                    tracing::trace!(?peer_id, "ingress disabled, dropping book hashes");
+                    return
+                }
+
+                let mut missing = self.order_indexer.missing_orders(&order_hashes);
+                if missing.is_empty() {
+                    return
+                }
+                missing.truncate(MAX_REQUESTED_ORDERS);
+                tracing::debug!(
+                    ?peer_id,
+                    ?pool_id,
+                    count = missing.len(),
+                    "requesting the orders missing from our book"
+                );
+                self.network
+                    .send_message(peer_id, StromMessage::RequestOrders(missing));
+            }
+        }
+    }
+
+    /// sends every peer our checksum of each pool it tracks, peers holding
+    /// different orders answer with their order hashes
+    fn gossip_book_checksums(&self) {
+        if !self.egress || self.peer_to_info.is_empty() {
+            return
+        }
+
+        let checksums = self
+            .order_indexer
+            .pools()
+            .into_iter()
+            .map(|pool| (pool.pool_id, self.order_indexer.book_checksum(pool.pool_id)))
+            .collect::<Vec<_>>();
+        for peer_id in self.peer_to_info.keys() {
+            let peer_pools = self.network.peer_pools(*peer_id);
+            checksums
+                .iter()
+                .filter(|(pool_id, _)| peer_pools.is_empty() || peer_pools.contains(pool_id))
+                .for_each(|(pool_id, checksum)| {
+                    self.network
+                        .send_message(*peer_id, StromMessage::BookChecksum(*pool_id, *checksum));
+                });
+        }
+    }
+
+    /// Applies the cancellations and forwards the ones that cancelled an order
+    /// of the pool. Peers relaying a cancellation that isn't signed by the
+    /// order's sender are penalised, relayed cancellations of orders the pool
+    /// doesn't hold are dropped so they can't be stored or forwarded in bulk.
+    /// Returns whether each cancellation was accepted, or why it was refused.
+    fn on_cancellations(
+        &mut self,
+        peer_id: Option<PeerId>,
+        cancellations: Vec<SignedCancellation>
+    ) -> Vec<Result<bool, CancelOrderError>> {
+        let mut results = Vec::with_capacity(cancellations.len());
+        let mut applied = Vec::new();
+
+        for cancellation in cancellations {
+            let action = cancellation_action(
+                cancellation.recover_signer(),
+                self.order_indexer.order_owner(&cancellation.hash),
+                self.order_indexer.is_cancelled(&cancellation.hash),
+                peer_id.is_some()
+            );
+
+            let result = match action {
+                CancellationAction::Refuse { penalise } => {
+                    if let Some(peer_id) = peer_id.filter(|_| penalise) {
+                        self.network
+                            .peer_reputation_change(peer_id, ReputationChangeKind::BadMessage);
+                    }
+                    Ok(false)
+                }
+                CancellationAction::Drop => {
+                    tracing::trace!(
+                        ?peer_id,
+                        hash = ?cancellation.hash,
+                        "dropping cancellation of unknown order"
+                    );
+                    Ok(false)
+                }
+                CancellationAction::Known => Ok(true),
+                CancellationAction::Hold(from) | CancellationAction::Apply(from) => {
+                    self.order_indexer.cancel_order(from, cancellation.hash)
+                }
+            };
+
+            if matches!(action, CancellationAction::Known | CancellationAction::Apply(_)) {
+                if let Some(peer) = peer_id.and_then(|peer_id| self.peer_to_info.get_mut(&peer_id))
+                {
+                    peer.cancellations.insert(cancellation.hash);
+                }
+            }
+            if matches!(action, CancellationAction::Apply(_)) && result == Ok(true) {
+                applied.push(cancellation);
+            }
+            results.push(result);
+        }
+
+        self.broadcast_cancellations_to_peers(applied);
+        results
+    }
+
+    /// sends every peer the cancellations it hasn't seen yet
+    fn broadcast_cancellations_to_peers(&mut self, cancellations: Vec<SignedCancellation>) {
+        if !self.egress || !self.gossip_cancellations || cancellations.is_empty() {
+            return
+        }
+
+        for (peer_id, info) in self.peer_to_info.iter_mut() {
+            let unseen = cancellations
+                .iter()
+                .filter(|cancellation| info.cancellations.insert(cancellation.hash))
+                .cloned()
+                .collect::<Vec<_>>();
+            if !unseen.is_empty() {
+                self.network
+                    .send_message(*peer_id, StromMessage::CancelOrders(unseen));
+            }
         }
     }
 
@@ -371,36 +814,38 @@ where
         match event {
             StromNetworkEvent::SessionEstablished { peer_id } => {
                 // insert a new peer into the peerset
-                self.peer_to_info.insert(
-                    peer_id,
-                    StromPeer {
-                        orders: LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap())
-                    }
-                );
+                self.peer_to_info.insert(peer_id, StromPeer::new());
             }
             StromNetworkEvent::SessionClosed { peer_id, .. } => {
                 // remove the peer
                 self.peer_to_info.remove(&peer_id);
+                if let Some(peer_orders) = self.peer_orders.as_mut() {
+                    peer_orders.session_closed(&peer_id);
+                }
             }
             StromNetworkEvent::PeerRemoved(peer_id) => {
                 self.peer_to_info.remove(&peer_id);
+                if let Some(peer_orders) = self.peer_orders.as_mut() {
+                    peer_orders.session_closed(&peer_id);
+                }
             }
             StromNetworkEvent::PeerAdded(peer_id) => {
-                self.peer_to_info.insert(
-                    peer_id,
-                    StromPeer {
-                        orders: LruCache::new(NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap())
-                    }
-                );
+                self.peer_to_info.insert(peer_id, StromPeer::new());
             }
         }
     }
 
     fn on_pool_events(&mut self, orders: Vec<PoolInnerEvent>, waker: impl Fn() -> Waker) {
+        let mut new_block = false;
         let valid_orders = orders
             .into_iter()
             .filter_map(|order| match order {
-                PoolInnerEvent::Propagation(order) => Some(order),
+                PoolInnerEvent::Propagation(order) => {
+                    if let Some(sampler) = self.order_sampler.as_mut() {
+                        sampler.accepted(&order.order, order.pool_id);
+                    }
+                    Some(order)
+                }
                 PoolInnerEvent::BadOrderMessages(o) => {
                     o.into_iter().for_each(|peer| {
                         self.network.peer_reputation_change(
@@ -413,24 +858,56 @@ where
                 PoolInnerEvent::HasTransitionedToNewBlock(block) => {
                     self.global_sync
                         .sign_off_on_block(MODULE_NAME, block, Some(waker()));
+                    new_block = true;
                     None
                 }
                 PoolInnerEvent::None => None
             })
             .collect::<Vec<_>>();
+        let valid_orders = match self.peer_orders.as_mut() {
+            Some(peer_orders) => {
+                enforce_peer_caps(peer_orders, &mut self.order_indexer, valid_orders)
+            }
+            None => valid_orders
+        };
 
         self.broadcast_orders_to_peers(valid_orders);
+        // the book is settled for the block, a good time to compare it
+        if new_block {
+            self.gossip_book_checksums();
+        }
     }
 
-    fn broadcast_orders_to_peers(&mut self, valid_orders: Vec<AllOrders>) {
+    fn broadcast_orders_to_peers(&mut self, valid_orders: Vec<OrderWithStorageData<AllOrders>>) {
+        if !self.egress {
+            return
+        }
+
+        let mut rng = rand::thread_rng();
         for order in valid_orders.iter() {
-            for (peer_id, info) in self.peer_to_info.iter_mut() {
-                let order_hash = order.order_hash();
-                if !info.orders.contains(&order_hash) {
-                    self.network.send_message(
-                        *peer_id,
-                        StromMessage::PropagatePooledOrders(vec![order.clone()])
-                    );
+            let order_hash = order.order_hash();
+            let candidates = self
+                .peer_to_info
+                .iter()
+                .filter(|(peer_id, info)| {
+                    // skip peers that declared pools and don't track this one
+                    let peer_pools = self.network.peer_pools(**peer_id);
+                    (peer_pools.is_empty() || peer_pools.contains(&order.pool_id))
+                        && !info.orders.contains(&order_hash)
+                })
+                .map(|(peer_id, _)| *peer_id);
+            let peers = select_gossip_peers(candidates, self.gossip_fanout, &mut rng);
+
+            for peer_id in peers {
+                let batch = match self.gossip_batcher.as_mut() {
+                    Some(batcher) => batcher.push(peer_id, order.order.clone()),
+                    None => Some(vec![order.order.clone()])
+                };
+                if let Some(batch) = batch {
+                    self.network
+                        .send_message(peer_id, StromMessage::PropagatePooledOrders(batch));
+                }
+                if let Some(info) = self.peer_to_info.get_mut(&peer_id) {
                     info.orders.insert(order_hash);
                 }
             }
@@ -438,10 +915,52 @@ where
     }
 }
 
-impl<V, GlobalSync> Future for PoolManager<V, GlobalSync>
+/// evicts the oldest orders of the peers the accepted orders put over their
+/// cap, returns the accepted orders that are still in the pool
+fn enforce_peer_caps<V: OrderValidatorHandle<Order = AllOrders>>(
+    peer_orders: &mut PeerOrders,
+    order_indexer: &mut OrderIndexer<V>,
+    valid_orders: Vec<OrderWithStorageData<AllOrders>>
+) -> Vec<OrderWithStorageData<AllOrders>> {
+    let mut evicted = HashSet::new();
+    for order in &valid_orders {
+        let to_evict = peer_orders
+            .accepted(order.order_hash(), |hash| order_indexer.order_owner(hash).is_some());
+        for hash in to_evict {
+            if order_indexer.evict_order(&hash) {
+                evicted.insert(hash);
+            }
+        }
+    }
+    if evicted.is_empty() {
+        return valid_orders
+    }
+
+    valid_orders
+        .into_iter()
+        .filter(|order| !evicted.contains(&order.order_hash()))
+        .collect()
+}
+
+/// Picks the peers an order gets forwarded to. With a fanout set, a random
+/// subset of that size is taken so orders spread epidemically instead of every
+/// node sending to every peer.
+fn select_gossip_peers<R: rand::Rng>(
+    candidates: impl Iterator<Item = PeerId>,
+    fanout: Option<NonZeroUsize>,
+    rng: &mut R
+) -> Vec<PeerId> {
+    match fanout {
+        Some(fanout) => candidates.choose_multiple(rng, fanout.get()),
+        None => candidates.collect()
+    }
+}
+
+impl<V, GlobalSync, Policy> Future for PoolManager<V, GlobalSync, Policy>
 where
     V: OrderValidatorHandle<Order = AllOrders> + Unpin,
-    GlobalSync: BlockSyncConsumer
+    GlobalSync: BlockSyncConsumer,
+    Policy: AcceptancePolicy
 {
     type Output = ();
 
@@ -463,6 +982,16 @@ where
             this.on_pool_events(orders, || cx.waker().clone());
         }
 
+        // send out the gossip batches whose window has passed
+        if let Some(batcher) = this.gossip_batcher.as_mut() {
+            if let Poll::Ready(batches) = batcher.poll_flush(cx) {
+                for (peer_id, orders) in batches {
+                    this.network
+                        .send_message(peer_id, StromMessage::PropagatePooledOrders(orders));
+                }
+            }
+        }
+
         // halt dealing with these till we have synced
         if this.global_sync.can_operate() {
             // drain commands
@@ -491,9 +1020,455 @@ pub enum NetworkTransactionEvent {
     IncomingOrders { peer_id: PeerId, msg: Vec<AllOrders> }
 }
 
+/// What is done with a cancellation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CancellationAction {
+    /// the signature doesn't recover or isn't the sender's of the order
+    Refuse { penalise: bool },
+    /// the order isn't in the pool and the cancellation was relayed
+    Drop,
+    /// the cancellation was already applied
+    Known,
+    /// the order isn't in the pool yet, the cancellation is held so it's
+    /// rejected once it arrives. Only for local cancellations, they aren't
+    /// forwarded
+    Hold(Address),
+    /// cancels an order of the pool
+    Apply(Address)
+}
+
+/// decides what to do with a cancellation signed by `signer` for an order of
+/// `owner`, `relayed` if it came from a peer
+fn cancellation_action(
+    signer: Option<Address>,
+    owner: Option<Address>,
+    already_cancelled: bool,
+    relayed: bool
+) -> CancellationAction {
+    // a garbage signature recovers to some unrelated address more often than
+    // not, so only a match with the order's sender counts
+    let Some(signer) = signer else { return CancellationAction::Refuse { penalise: relayed } };
+    if already_cancelled {
+        return CancellationAction::Known
+    }
+
+    match owner {
+        Some(owner) if owner == signer => CancellationAction::Apply(signer),
+        Some(_) => CancellationAction::Refuse { penalise: relayed },
+        None if relayed => CancellationAction::Drop,
+        None => CancellationAction::Hold(signer)
+    }
+}
+
 /// Tracks a single peer
 #[derive(Debug)]
 struct StromPeer {
     /// Keeps track of transactions that we know the peer has seen.
-    orders: LruCache<B256>
+    orders:        LruCache<B256>,
+    /// Hashes of the orders whose cancellation we know the peer has seen.
+    cancellations: LruCache<B256>
+}
+
+impl StromPeer {
+    fn new() -> Self {
+        let limit = NonZeroUsize::new(PEER_ORDER_CACHE_LIMIT).unwrap();
+        Self { orders: LruCache::new(limit), cancellations: LruCache::new(limit) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use alloy::signers::local::PrivateKeySigner;
+    use angstrom_types::primitive::{PoolId, ANGSTROM_DOMAIN};
+    use futures::task::noop_waker_ref;
+    use parking_lot::RwLock;
+    use reth_metrics::common::mpsc::{metered_unbounded_channel, UnboundedMeteredSender};
+    use testing_tools::{
+        mocks::validator::MockValidator,
+        type_generator::orders::{SigningInfo, UserOrderBuilder},
+        types::MockBlockSync
+    };
+
+    use super::*;
+    use crate::StromNetworkHandleMsg;
+
+    /// a pool manager for a single pool with `peer` connected, along with what
+    /// it hands to the network. The peer declared `peer_pools` in its status
+    fn pool_manager(
+        validator: &MockValidator,
+        pool_id: PoolId,
+        (peer, peer_pools): (PeerId, &[PoolId]),
+        egress: bool
+    ) -> (PoolManager<MockValidator, MockBlockSync>, UnboundedReceiver<StromNetworkHandleMsg>) {
+        let order_storage =
+            Arc::new(OrderStorage::new(&PoolConfig { ids: vec![pool_id], ..Default::default() }));
+        let pools = AngstromPoolsTracker::new(Address::random(), Default::default());
+        let (sub_tx, _sub_rx) = broadcast::channel(100);
+        let indexer = OrderIndexer::new(validator.clone(), order_storage, 0, sub_tx.clone(), pools);
+
+        let (handle_tx, handle_rx) = unbounded_channel();
+        let peer_pools = HashMap::from([(peer, peer_pools.iter().copied().collect())]);
+        let network = StromNetworkHandle::new(
+            Default::default(),
+            Arc::new(RwLock::new(peer_pools)),
+            UnboundedMeteredSender::new(handle_tx, "strom handle")
+        );
+        let (_network_tx, network_rx) = unbounded_channel();
+        let (_eth_tx, eth_rx) = unbounded_channel();
+        let (command_tx, command_rx) = unbounded_channel();
+        let (_order_tx, order_rx) = metered_unbounded_channel("orders");
+
+        let mut manager = PoolManager::new(
+            indexer,
+            network,
+            network_rx.into(),
+            eth_rx.into(),
+            MockBlockSync,
+            command_tx,
+            command_rx.into(),
+            order_rx,
+            sub_tx
+        );
+        manager.egress = egress;
+        manager.on_network_event(StromNetworkEvent::PeerAdded(peer));
+
+        (manager, handle_rx)
+    }
+
+    /// an order for the pool the validator accepts
+    fn valid_order(validator: &MockValidator, pool_id: PoolId) -> AllOrders {
+        let order = UserOrderBuilder::new()
+            .standing()
+            .with_storage()
+            .pool_id(pool_id)
+            .build()
+            .try_map_inner(|order| Ok(AllOrders::from(order)))
+            .unwrap();
+        validator.add_order(order.from(), OrderValidationResults::Valid(order.clone()));
+
+        order.order
+    }
+
+    fn poll(manager: &mut PoolManager<MockValidator, MockBlockSync>) {
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let _ = manager.poll_unpin(&mut cx);
+    }
+
+    /// submits a valid order over rpc and lets the manager process it
+    fn submit(
+        manager: &mut PoolManager<MockValidator, MockBlockSync>,
+        validator: &MockValidator,
+        pool_id: PoolId
+    ) -> B256 {
+        let order = valid_order(validator, pool_id);
+        let hash = order.order_hash();
+
+        let (tx, _rx) = tokio::sync::oneshot::channel();
+        manager.on_command(OrderCommand::NewOrder(OrderOrigin::External, order, tx));
+        poll(manager);
+
+        hash
+    }
+
+    /// a peer gossips a valid order and the manager processes it
+    fn receive(
+        manager: &mut PoolManager<MockValidator, MockBlockSync>,
+        validator: &MockValidator,
+        (peer_id, pool_id): (PeerId, PoolId)
+    ) -> B256 {
+        let order = valid_order(validator, pool_id);
+        let hash = order.order_hash();
+
+        let event = NetworkOrderEvent::IncomingOrders { peer_id, orders: vec![order] };
+        manager.on_network_order_event(event);
+        poll(manager);
+
+        hash
+    }
+
+    #[test]
+    fn test_valid_orders_are_gossiped_with_egress() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, mut to_network) = pool_manager(&validator, pool_id, (peer, &[]), true);
+
+        let hash = submit(&mut manager, &validator, pool_id);
+        match to_network.try_recv() {
+            Ok(StromNetworkHandleMsg::SendStromMessage {
+                peer_id,
+                msg: StromMessage::PropagatePooledOrders(orders)
+            }) => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(orders.iter().map(|o| o.order_hash()).collect::<Vec<_>>(), vec![hash]);
+            }
+            other => panic!("expected the order to be gossiped, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn test_orders_are_only_gossiped_to_peers_tracking_their_pool() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+
+        let other_pools = [PoolId::random()];
+        let (mut manager, mut to_network) =
+            pool_manager(&validator, pool_id, (peer, &other_pools), true);
+        submit(&mut manager, &validator, pool_id);
+        assert!(to_network.try_recv().is_err());
+
+        let (mut manager, mut to_network) =
+            pool_manager(&validator, pool_id, (peer, &[other_pools[0], pool_id]), true);
+        submit(&mut manager, &validator, pool_id);
+        assert!(to_network.try_recv().is_ok());
+    }
+
+    #[test]
+    fn test_nothing_is_gossiped_without_egress() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, mut to_network) = pool_manager(&validator, pool_id, (peer, &[]), false);
+
+        let hash = submit(&mut manager, &validator, pool_id);
+        assert!(manager.order_indexer.order_owner(&hash).is_some());
+
+        // neither gossiped nor handed out to a peer asking for it
+        manager.on_network_order_event(NetworkOrderEvent::OrdersRequested {
+            peer_id:      peer,
+            order_hashes: vec![hash]
+        });
+        assert!(to_network.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_orders_from_peers_are_dropped_without_ingress() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, _to_network) = pool_manager(&validator, pool_id, (peer, &[]), true);
+
+        let hash = receive(&mut manager, &validator, (peer, pool_id));
+        assert!(manager.order_indexer.order_owner(&hash).is_some());
+
+        manager.ingress = false;
+        let hash = receive(&mut manager, &validator, (peer, pool_id));
+        assert!(manager.order_indexer.order_owner(&hash).is_none());
+        // the node's own orders are still taken in
+        let hash = submit(&mut manager, &validator, pool_id);
+        assert!(manager.order_indexer.order_owner(&hash).is_some());
+    }
+
+    #[test]
+    fn test_gossip_selects_fanout_peers() {
+        let peers = (0..10).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let selected = select_gossip_peers(
+            peers.iter().copied(),
+            NonZeroUsize::new(3),
+            &mut rand::thread_rng()
+        );
+
+        assert_eq!(selected.len(), 3);
+        assert_eq!(selected.iter().collect::<HashSet<_>>().len(), 3);
+        assert!(selected.iter().all(|peer| peers.contains(peer)));
+    }
+
+    #[test]
+    fn test_gossip_without_fanout_selects_all_peers() {
+        let peers = (0..10).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let selected = select_gossip_peers(peers.iter().copied(), None, &mut rand::thread_rng());
+
+        assert_eq!(selected, peers);
+    }
+
+    #[test]
+    fn test_cancellations_are_applied_only_by_the_sender() {
+        let sender = Address::random();
+
+        assert_eq!(
+            cancellation_action(Some(sender), Some(sender), false, true),
+            CancellationAction::Apply(sender)
+        );
+        assert_eq!(
+            cancellation_action(Some(sender), Some(sender), false, false),
+            CancellationAction::Apply(sender)
+        );
+        assert_eq!(
+            cancellation_action(Some(sender), Some(sender), true, true),
+            CancellationAction::Known
+        );
+    }
+
+    #[test]
+    fn test_peers_relaying_forged_cancellations_are_penalised() {
+        let sender = Address::random();
+
+        // a signature that doesn't recover, or recovers to anyone else
+        assert_eq!(
+            cancellation_action(None, Some(sender), false, true),
+            CancellationAction::Refuse { penalise: true }
+        );
+        assert_eq!(
+            cancellation_action(Some(Address::random()), Some(sender), false, true),
+            CancellationAction::Refuse { penalise: true }
+        );
+
+        // local cancellations are refused without a peer to penalise
+        assert_eq!(
+            cancellation_action(Some(Address::random()), Some(sender), false, false),
+            CancellationAction::Refuse { penalise: false }
+        );
+    }
+
+    #[test]
+    fn test_relayed_cancellations_of_unknown_orders_are_dropped() {
+        let signer = Address::random();
+
+        assert_eq!(cancellation_action(Some(signer), None, false, true), CancellationAction::Drop);
+        // only local ones are held until the order arrives
+        assert_eq!(
+            cancellation_action(Some(signer), None, false, false),
+            CancellationAction::Hold(signer)
+        );
+    }
+
+    #[test]
+    fn test_peers_over_their_cap_lose_their_oldest_orders() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, _to_network) = pool_manager(&validator, pool_id, (peer, &[]), true);
+        manager.peer_orders = Some(PeerOrders::new(2));
+
+        // a single peer relays orders of many signers, they all count against
+        // the peer
+        let relay = |manager: &mut PoolManager<MockValidator, MockBlockSync>,
+                     peer_id: PeerId,
+                     nonce: u64| {
+            let signer = PrivateKeySigner::random();
+            let order = UserOrderBuilder::new()
+                .standing()
+                .nonce(nonce)
+                .signing_key(Some(SigningInfo {
+                    domain:  ANGSTROM_DOMAIN,
+                    address: signer.address(),
+                    key:     signer.credential().clone()
+                }))
+                .with_storage()
+                .pool_id(pool_id)
+                .build()
+                .try_map_inner(|order| Ok(AllOrders::from(order)))
+                .unwrap();
+            validator.add_order(order.from(), OrderValidationResults::Valid(order.clone()));
+            let hash = order.order_hash();
+
+            let event = NetworkOrderEvent::IncomingOrders { peer_id, orders: vec![order.order] };
+            manager.on_network_order_event(event);
+            poll(manager);
+            hash
+        };
+
+        let hashes = (0..3)
+            .map(|nonce| relay(&mut manager, peer, nonce))
+            .collect::<Vec<_>>();
+        assert!(
+            manager.order_indexer.order_owner(&hashes[0]).is_none(),
+            "oldest should be evicted"
+        );
+        assert!(manager.order_indexer.order_owner(&hashes[1]).is_some());
+        assert!(manager.order_indexer.order_owner(&hashes[2]).is_some());
+
+        // other peers' orders don't count against it
+        let other = relay(&mut manager, PeerId::random(), 3);
+        assert!(manager.order_indexer.order_owner(&other).is_some());
+        assert!(manager.order_indexer.order_owner(&hashes[1]).is_some());
+
+        // once its session closed, the peer starts from a clean slate
+        manager.on_network_event(StromNetworkEvent::SessionClosed { peer_id: peer, reason: None });
+        let fresh = (4..6)
+            .map(|nonce| relay(&mut manager, peer, nonce))
+            .collect::<Vec<_>>();
+        assert!(hashes[1..]
+            .iter()
+            .chain(&fresh)
+            .all(|hash| manager.order_indexer.order_owner(hash).is_some()));
+    }
+
+    #[test]
+    fn test_differing_book_checksums_are_answered_with_order_hashes() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, mut to_network) = pool_manager(&validator, pool_id, (peer, &[]), true);
+        let hash = submit(&mut manager, &validator, pool_id);
+        while to_network.try_recv().is_ok() {}
+
+        // the peer holds the same book
+        manager.on_network_order_event(NetworkOrderEvent::BookChecksum {
+            peer_id: peer,
+            pool_id,
+            checksum: order_pool::book_checksum([hash])
+        });
+        assert!(to_network.try_recv().is_err());
+
+        // the peer's book is empty
+        manager.on_network_order_event(NetworkOrderEvent::BookChecksum {
+            peer_id: peer,
+            pool_id,
+            checksum: order_pool::book_checksum([])
+        });
+        match to_network.try_recv() {
+            Ok(StromNetworkHandleMsg::SendStromMessage {
+                peer_id,
+                msg: StromMessage::BookOrderHashes(book, order_hashes)
+            }) => {
+                assert_eq!((peer_id, book), (peer, pool_id));
+                assert_eq!(order_hashes, vec![hash]);
+            }
+            other => panic!("expected the book's hashes, got {other:?}")
+        }
+    }
+
+    #[test]
+    fn test_orders_missing_from_the_book_are_requested() {
+        let (pool_id, peer) = (PoolId::random(), PeerId::random());
+        let validator = MockValidator::default();
+        let (mut manager, mut to_network) = pool_manager(&validator, pool_id, (peer, &[]), true);
+        let held = submit(&mut manager, &validator, pool_id);
+        while to_network.try_recv().is_ok() {}
+
+        let missing = B256::random();
+        manager.on_network_order_event(NetworkOrderEvent::BookOrderHashes {
+            peer_id: peer,
+            pool_id,
+            order_hashes: vec![held, missing]
+        });
+        match to_network.try_recv() {
+            Ok(StromNetworkHandleMsg::SendStromMessage {
+                peer_id,
+                msg: StromMessage::RequestOrders(order_hashes)
+            }) => {
+                assert_eq!(peer_id, peer);
+                assert_eq!(order_hashes, vec![missing]);
+            }
+            other => panic!("expected the missing order to be requested, got {other:?}")
+        }
+
+        // nothing to ask for once the books agree
+        manager.on_network_order_event(NetworkOrderEvent::BookOrderHashes {
+            peer_id: peer,
+            pool_id,
+            order_hashes: vec![held]
+        });
+        assert!(to_network.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_gossip_fanout_larger_than_peer_set() {
+        let peers = (0..2).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let selected = select_gossip_peers(
+            peers.iter().copied(),
+            NonZeroUsize::new(5),
+            &mut rand::thread_rng()
+        );
+
+        assert_eq!(selected.len(), 2);
+    }
 }
@@ -23,7 +23,7 @@ use tokio_util::sync::PollSender;
 use super::handle::SessionCommand;
 use crate::{
     types::{
-        message::StromProtocolMessage,
+        message::{StromMessageID, StromProtocolMessage},
         status::{Status, StatusState}
     },
     StatusBuilder, StromMessage, StromSessionHandle, StromSessionMessage
@@ -46,7 +46,7 @@ impl VerificationSidecar {
             panic!("can only send the status message once");
         }
 
-        StatusBuilder::from(self.status.with_peer(peer)).build(self.secret_key)
+        StatusBuilder::from(self.status.clone().with_peer(peer)).build(self.secret_key)
     }
 
     pub fn is_verified(&self) -> bool {
@@ -233,11 +233,21 @@ impl StromSession {
 
                     msg.map_or(false, |msg| {
                         // first message has to be status
-                        if let StromMessage::Status(status) = msg.message {
-                            self.verify_incoming_status(status)
-                        } else {
-                            false
+                        let StromMessage::Status(status) = msg.message else { return false };
+                        if !self.verify_incoming_status(status.clone()) {
+                            return false
                         }
+
+                        // forward the status so the manager learns which pools the peer tracks
+                        self.outbound_buffer
+                            .push_back(StromSessionMessage::ValidMessage {
+                                peer_id: self.remote_peer_id,
+                                message: StromProtocolMessage {
+                                    message_id: StromMessageID::Status,
+                                    message:    StromMessage::Status(status)
+                                }
+                            });
+                        true
                     })
                 })
                 // if false, i.e verification failed. then we disconnect
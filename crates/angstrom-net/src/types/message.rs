@@ -1,9 +1,14 @@
 #![allow(missing_docs)]
 use std::{fmt::Debug, sync::Arc};
 
-use alloy::rlp::{Buf, BufMut, Decodable, Encodable};
+use alloy::{
+    primitives::B256,
+    rlp::{Buf, BufMut, Decodable, Encodable}
+};
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
+    orders::SignedCancellation,
+    primitive::PoolId,
     sol_bindings::grouped_orders::AllOrders
 };
 use reth_eth_wire::{protocol::Protocol, Capability};
@@ -20,17 +25,25 @@ use crate::Status;
 pub const MAX_MESSAGE_SIZE: usize = 10 * 1024 * 1024;
 
 const STROM_CAPABILITY: Capability = Capability::new_static("strom", 1);
-const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 5);
+const STROM_PROTOCOL: Protocol = Protocol::new(STROM_CAPABILITY, 9);
 /// Represents message IDs for eth protocol messages.
 #[repr(u8)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StromMessageID {
-    Status     = 0,
+    Status          = 0,
     /// Consensus
-    PrePropose = 1,
-    Propose    = 2,
+    PrePropose      = 1,
+    Propose         = 2,
     /// Propagation messages that broadcast new orders to all peers
-    PropagatePooledOrders = 3
+    PropagatePooledOrders = 3,
+    /// Order reconciliation, asking a peer for orders by hash
+    RequestOrders   = 4,
+    OrdersResponse  = 5,
+    /// Propagation of signed order cancellations
+    CancelOrders    = 6,
+    /// Comparing books with a peer
+    BookChecksum    = 7,
+    BookOrderHashes = 8
 }
 
 impl Encodable for StromMessageID {
@@ -51,6 +64,11 @@ impl Decodable for StromMessageID {
             1 => StromMessageID::PrePropose,
             2 => StromMessageID::Propose,
             3 => StromMessageID::PropagatePooledOrders,
+            4 => StromMessageID::RequestOrders,
+            5 => StromMessageID::OrdersResponse,
+            6 => StromMessageID::CancelOrders,
+            7 => StromMessageID::BookChecksum,
+            8 => StromMessageID::BookOrderHashes,
             _ => return Err(alloy::rlp::Error::Custom("Invalid message ID"))
         };
         buf.advance(1);
@@ -114,7 +132,22 @@ pub enum StromMessage {
     Propose(Proposal),
 
     /// Propagation messages that broadcast new orders to all peers
-    PropagatePooledOrders(Vec<AllOrders>)
+    PropagatePooledOrders(Vec<AllOrders>),
+
+    /// Order reconciliation. Asks a peer for the orders with the given hashes,
+    /// the peer answers with the ones it holds and skips the unknown ones
+    RequestOrders(Vec<B256>),
+    OrdersResponse(Vec<AllOrders>),
+
+    /// Cancellations gossiped on their own, so a node that missed the cancel
+    /// drops the order instead of keeping it in its book
+    CancelOrders(Vec<SignedCancellation>),
+
+    /// Anti-entropy. A node's checksum over the live orders of a pool, a peer
+    /// holding different orders answers with its order hashes so the missing
+    /// orders can be requested
+    BookChecksum(PoolId, B256),
+    BookOrderHashes(PoolId, Vec<B256>)
 }
 impl StromMessage {
     /// Returns the message's ID.
@@ -123,7 +156,12 @@ impl StromMessage {
             StromMessage::Status(_) => StromMessageID::Status,
             StromMessage::PrePropose(_) => StromMessageID::PrePropose,
             StromMessage::Propose(_) => StromMessageID::Propose,
-            StromMessage::PropagatePooledOrders(_) => StromMessageID::PropagatePooledOrders
+            StromMessage::PropagatePooledOrders(_) => StromMessageID::PropagatePooledOrders,
+            StromMessage::RequestOrders(_) => StromMessageID::RequestOrders,
+            StromMessage::OrdersResponse(_) => StromMessageID::OrdersResponse,
+            StromMessage::CancelOrders(_) => StromMessageID::CancelOrders,
+            StromMessage::BookChecksum(..) => StromMessageID::BookChecksum,
+            StromMessage::BookOrderHashes(..) => StromMessageID::BookOrderHashes
         }
     }
 }
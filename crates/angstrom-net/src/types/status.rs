@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     fmt::{Debug, Display},
     time::{SystemTime, UNIX_EPOCH}
 };
@@ -7,7 +8,7 @@ use alloy::{
     primitives::{keccak256, FixedBytes},
     rlp::{BufMut, BytesMut}
 };
-use angstrom_types::primitive::{PeerId, Signature};
+use angstrom_types::primitive::{PeerId, PoolId, Signature};
 use serde::{Deserialize, Serialize};
 
 use crate::StatusBuilder;
@@ -60,7 +61,7 @@ impl Debug for Status {
     }
 }
 
-#[derive(Default, Copy, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StatusState {
     /// The current protocol version.
     pub version: u8,
@@ -73,7 +74,10 @@ pub struct StatusState {
     pub peer:      PeerId,
     /// The current timestamp. Used to make sure that the status message will
     /// expire
-    pub timestamp: u128
+    pub timestamp: u128,
+    /// The pools this peer tracks orders for. An empty set means the peer is
+    /// interested in all pools
+    pub pools:     BTreeSet<PoolId>
 }
 
 impl StatusState {
@@ -86,14 +90,20 @@ impl StatusState {
         self
     }
 
+    pub fn with_pools(mut self, pools: impl IntoIterator<Item = PoolId>) -> Self {
+        self.pools = pools.into_iter().collect();
+        self
+    }
+
     /// creates message for signing.
-    /// keccak256(version || peer || timestamp)
+    /// keccak256(version || chain || peer || timestamp || pools)
     pub fn to_message(&self) -> FixedBytes<32> {
-        let mut buf = BytesMut::with_capacity(113);
+        let mut buf = BytesMut::with_capacity(113 + self.pools.len() * 32);
         buf.put_u8(self.version);
         buf.put_u64(self.chain);
         buf.put(self.peer.0.as_ref());
         buf.put_u128(self.timestamp);
+        self.pools.iter().for_each(|pool| buf.put(pool.as_slice()));
 
         keccak256(buf)
     }
@@ -105,3 +115,27 @@ impl StatusState {
             .as_millis();
     }
 }
+
+#[cfg(test)]
+mod test {
+    use reth_network_peers::pk2id;
+    use secp256k1::{PublicKey, Secp256k1, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn test_declared_pools_are_signed() {
+        let key = SecretKey::new(&mut rand::thread_rng());
+        let peer = pk2id(&PublicKey::from_secret_key(&Secp256k1::new(), &key));
+        let pools = [PoolId::random(), PoolId::random()];
+
+        let status = Status::builder(PeerId::random()).pools(pools).build(key);
+        assert_eq!(status.state.pools, BTreeSet::from(pools));
+        assert_eq!(status.clone().verify().ok(), Some(peer));
+
+        // a peer can't claim other pools than the ones signed over
+        let mut forged = status;
+        forged.state.pools.insert(PoolId::random());
+        assert_ne!(forged.verify().ok(), Some(peer));
+    }
+}
@@ -1,8 +1,19 @@
 use std::time::Duration;
 
+use alloy::{primitives::B256, signers::local::PrivateKeySigner};
 use angstrom_network::StromMessage;
+use angstrom_rpc::api::OrderApiClient;
+use angstrom_types::{
+    primitive::ANGSTROM_DOMAIN,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use futures::future::join_all;
+use jsonrpsee::http_client::HttpClientBuilder;
 use reth_provider::test_utils::NoopProvider;
-use testing_tools::testnet_controllers::{AngstromTestnet, AngstromTestnetConfig, TestnetKind};
+use testing_tools::{
+    testnet_controllers::{AngstromTestnet, AngstromTestnetConfig, RpcTransport, TestnetKind},
+    type_generator::orders::{SigningInfo, UserOrderBuilder}
+};
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 5)]
 #[serial_test::serial]
@@ -11,8 +22,12 @@ async fn test_broadcast_order_propagation() {
     let config = AngstromTestnetConfig {
         intial_node_count:       3,
         initial_rpc_port:        5000,
+        rpc_node_count:          None,
         testnet_block_time_secs: 12,
-        testnet_kind:            TestnetKind::new_raw()
+        testnet_kind:            TestnetKind::new_raw(),
+        gossip_fanout:           None,
+        rpc_transport:           RpcTransport::default(),
+        reconnect:               Default::default()
     };
     let mut testnet = AngstromTestnet::spawn_testnet(NoopProvider::default(), config)
         .await
@@ -61,8 +76,12 @@ async fn test_singular_order_propagation() {
     let config = AngstromTestnetConfig {
         intial_node_count:       3,
         initial_rpc_port:        5000,
+        rpc_node_count:          None,
         testnet_block_time_secs: 12,
-        testnet_kind:            TestnetKind::new_raw()
+        testnet_kind:            TestnetKind::new_raw(),
+        gossip_fanout:           None,
+        rpc_transport:           RpcTransport::default(),
+        reconnect:               Default::default()
     };
 
     // connect all peers
@@ -112,3 +131,91 @@ async fn test_singular_order_propagation() {
 
     assert_eq!(res, Ok(true), "failed to receive and react to order within 4 seconds");
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 5)]
+#[serial_test::serial]
+async fn test_late_peer_reconciles_orders() {
+    reth_tracing::init_test_tracing();
+    let config = AngstromTestnetConfig {
+        intial_node_count:       2,
+        initial_rpc_port:        5000,
+        rpc_node_count:          None,
+        testnet_block_time_secs: 12,
+        testnet_kind:            TestnetKind::new_raw(),
+        gossip_fanout:           None,
+        rpc_transport:           RpcTransport::Http,
+        reconnect:               Default::default()
+    };
+    let mut testnet = AngstromTestnet::spawn_testnet(NoopProvider::default(), config)
+        .await
+        .unwrap();
+
+    // the book is seeded before the late peer joins, so it never sees the
+    // orders gossiped. Their signers hold no tokens, so they rest in the pool
+    let addr = testnet
+        .get_peer(0)
+        .rpc_addr()
+        .expect("every node serves rpc");
+    let client = HttpClientBuilder::default()
+        .build(format!("http://{addr}"))
+        .unwrap();
+    let pool = testnet.get_peer(0).pool_keys()[0].clone();
+    let orders = (0..2)
+        .map(|_| {
+            let signer = PrivateKeySigner::random();
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(pool.currency0)
+                .asset_out(pool.currency1)
+                .amount(1_000)
+                .signing_key(Some(SigningInfo {
+                    domain:  ANGSTROM_DOMAIN,
+                    address: signer.address(),
+                    key:     signer.into_credential()
+                }))
+                .build()
+                .into()
+        })
+        .collect::<Vec<AllOrders>>();
+    for order in &orders {
+        assert!(client.send_order(order.clone()).await.unwrap(), "node 0 rejected the order");
+    }
+    let order_hashes = orders
+        .iter()
+        .map(|order| order.order_hash())
+        .collect::<Vec<_>>();
+    let seeded = tokio::time::timeout(Duration::from_secs(20), async {
+        loop {
+            let statuses =
+                join_all(order_hashes.iter().map(|hash| client.order_status(*hash))).await;
+            if statuses.iter().all(|status| matches!(status, Ok(Some(_)))) {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    })
+    .await;
+    assert!(seeded.is_ok(), "node 0 didn't hold the seeded orders within 20 seconds");
+
+    let late_node = tokio::time::timeout(
+        Duration::from_secs(30),
+        testnet.spawn_late_node(NoopProvider::default())
+    )
+    .await;
+    assert!(matches!(late_node, Ok(Ok(_))), "failed to connect the late peer within 30 seconds");
+    let late_node = late_node.unwrap().unwrap();
+
+    // hashes the responder doesn't know are left out of the response
+    let mut requested = order_hashes.clone();
+    requested.insert(1, B256::random());
+
+    let delay_seconds = 8;
+    let res = tokio::time::timeout(
+        Duration::from_secs(delay_seconds),
+        testnet.reconcile_orders(late_node, 0, requested, orders)
+    )
+    .await;
+
+    assert_eq!(res, Ok(true), "failed to reconcile the book within {} seconds", delay_seconds);
+}
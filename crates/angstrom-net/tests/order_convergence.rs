@@ -0,0 +1,111 @@
+use std::{num::NonZeroUsize, time::Duration};
+
+use alloy::signers::local::PrivateKeySigner;
+use angstrom_rpc::api::OrderApiClient;
+use angstrom_types::{
+    primitive::ANGSTROM_DOMAIN,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use futures::future::join_all;
+use jsonrpsee::http_client::HttpClientBuilder;
+use reth_provider::test_utils::NoopProvider;
+use testing_tools::{
+    testnet_controllers::{AngstromTestnet, AngstromTestnetConfig, RpcTransport, TestnetKind},
+    type_generator::orders::{SigningInfo, UserOrderBuilder}
+};
+
+/// An order submitted to one node's rpc is validated by that node, gossiped
+/// and validated by every other node. Covers the whole rpc -> pool -> network
+/// -> pool path.
+#[tokio::test(flavor = "multi_thread", worker_threads = 5)]
+#[serial_test::serial]
+async fn test_rpc_order_converges_on_all_nodes() {
+    reth_tracing::init_test_tracing();
+    assert_rpc_order_converges(testnet_config(None)).await;
+}
+
+/// With a fanout of one each node forwards the order to a single peer that
+/// hasn't seen it. Node 0 sends it to one of its two peers, which has to pass
+/// it on to the last one for the order to converge.
+#[tokio::test(flavor = "multi_thread", worker_threads = 5)]
+#[serial_test::serial]
+async fn test_rpc_order_converges_with_a_fanout_of_one() {
+    reth_tracing::init_test_tracing();
+    assert_rpc_order_converges(testnet_config(NonZeroUsize::new(1))).await;
+}
+
+fn testnet_config(gossip_fanout: Option<NonZeroUsize>) -> AngstromTestnetConfig {
+    AngstromTestnetConfig {
+        intial_node_count: 3,
+        initial_rpc_port: 5000,
+        rpc_node_count: None,
+        testnet_block_time_secs: 12,
+        testnet_kind: TestnetKind::new_raw(),
+        gossip_fanout,
+        rpc_transport: RpcTransport::Http,
+        reconnect: Default::default()
+    }
+}
+
+async fn assert_rpc_order_converges(config: AngstromTestnetConfig) {
+    let node_count = config.intial_node_count;
+    let testnet = tokio::time::timeout(
+        Duration::from_secs(30),
+        AngstromTestnet::spawn_testnet(NoopProvider::default(), config)
+    )
+    .await;
+    assert!(matches!(testnet, Ok(Ok(_))), "failed to connect all peers within 30 seconds");
+    let testnet = testnet.unwrap().unwrap();
+
+    let clients = (0..node_count)
+        .map(|id| {
+            let addr = testnet
+                .get_peer(id)
+                .rpc_addr()
+                .expect("every node serves rpc");
+            HttpClientBuilder::default()
+                .build(format!("http://{addr}"))
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    // the order's signer holds no tokens, so the order rests in the pool
+    // without being matched
+    let pool = testnet.get_peer(0).pool_keys()[0].clone();
+    let signer = PrivateKeySigner::random();
+    let order: AllOrders = UserOrderBuilder::new()
+        .standing()
+        .exact()
+        .asset_in(pool.currency0)
+        .asset_out(pool.currency1)
+        .amount(1_000)
+        .signing_key(Some(SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: signer.address(),
+            key:     signer.into_credential()
+        }))
+        .build()
+        .into();
+    let order_hash = order.order_hash();
+
+    assert!(clients[0].send_order(order).await.unwrap(), "node 0 rejected the order");
+
+    let delay_seconds = 20;
+    let converged = tokio::time::timeout(Duration::from_secs(delay_seconds), async {
+        loop {
+            let statuses =
+                join_all(clients.iter().map(|client| client.order_status(order_hash))).await;
+            if statuses.iter().all(|status| matches!(status, Ok(Some(_)))) {
+                break
+            }
+            tokio::time::sleep(Duration::from_millis(250)).await;
+        }
+    })
+    .await;
+
+    assert!(
+        converged.is_ok(),
+        "order wasn't validated by all nodes within {} seconds",
+        delay_seconds
+    );
+}
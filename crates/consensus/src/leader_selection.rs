@@ -2,10 +2,11 @@ use std::{
     cmp::Ordering,
     collections::HashSet,
     fs::File,
-    io::{self, Read, Write}
+    io::{self, Read, Write},
+    sync::{Arc, RwLock}
 };
 
-use alloy::primitives::BlockNumber;
+use alloy::primitives::{Address, BlockNumber};
 use angstrom_types::primitive::PeerId;
 
 const ROUND_ROBIN_CACHE: &str = "./";
@@ -13,6 +14,66 @@ const ROUND_ROBIN_CACHE: &str = "./";
 // https://github.com/tendermint/tendermint/pull/2785#discussion_r235038971
 const PENALTY_FACTOR: f64 = 1.125;
 
+/// Decides which validator proposes each block. Only the proposer builds and
+/// submits a proposal, every other node validates it.
+pub trait LeaderSchedule: Send + 'static {
+    /// the proposer of `block_number`, advancing the schedule up to it.
+    /// Blocks are asked for in ascending order, a schedule may answer an
+    /// older block with the last proposer
+    fn proposer(&mut self, block_number: BlockNumber) -> Option<PeerId>;
+
+    /// whether `node` proposes `block_number`, without advancing the
+    /// schedule. Blocks the schedule hasn't reached yet have no leader
+    fn is_leader(&self, block_number: BlockNumber, node: PeerId) -> bool;
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LeaderScheduleError {
+    #[error("the leader schedule has no proposer for block {0}")]
+    NoProposer(BlockNumber)
+}
+
+/// Rotates through the validators one block at a time, ignoring voting
+/// power. The set is ordered by peer id so every node picks the same one.
+#[derive(Clone, Debug)]
+pub struct RoundRobin {
+    validators: Vec<PeerId>
+}
+
+impl RoundRobin {
+    pub fn new(validators: &[AngstromValidator]) -> Self {
+        let mut validators = validators
+            .iter()
+            .map(|validator| validator.peer_id)
+            .collect::<Vec<_>>();
+        validators.sort_unstable();
+        validators.dedup();
+
+        Self { validators }
+    }
+
+    fn proposer_of(&self, block_number: BlockNumber) -> Option<PeerId> {
+        let index = block_number.checked_rem(self.validators.len() as u64)?;
+        self.validators.get(index as usize).copied()
+    }
+}
+
+/// the schedule a [`ConsensusManager`](crate::ConsensusManager) picks
+/// proposers with unless it's given another one
+pub(crate) fn default_schedule(validators: &[AngstromValidator]) -> RoundRobin {
+    RoundRobin::new(validators)
+}
+
+impl LeaderSchedule for RoundRobin {
+    fn proposer(&mut self, block_number: BlockNumber) -> Option<PeerId> {
+        self.proposer_of(block_number)
+    }
+
+    fn is_leader(&self, block_number: BlockNumber, node: PeerId) -> bool {
+        self.proposer_of(block_number) == Some(node)
+    }
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AngstromValidator {
     peer_id:      PeerId,
@@ -24,6 +85,39 @@ impl AngstromValidator {
     pub fn new(name: PeerId, voting_power: u64) -> Self {
         AngstromValidator { peer_id: name, voting_power, priority: 0.0 }
     }
+
+    pub fn info(&self) -> ValidatorInfo {
+        ValidatorInfo {
+            pubkey:  self.peer_id,
+            address: Address::from_raw_public_key(self.peer_id.as_slice()),
+            weight:  self.voting_power
+        }
+    }
+}
+
+/// A validator of the set as seen from outside of consensus, the address is
+/// the one its commits are signed with.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ValidatorInfo {
+    pub pubkey:  PeerId,
+    pub address: Address,
+    pub weight:  u64
+}
+
+/// The validator set a [`ConsensusManager`](crate::ConsensusManager) runs
+/// with, readable from outside of consensus. Clones share the same set, the
+/// manager it's handed to publishes its set into it.
+#[derive(Clone, Debug, Default)]
+pub struct ValidatorSetHandle(Arc<RwLock<Vec<ValidatorInfo>>>);
+
+impl ValidatorSetHandle {
+    pub fn validator_set(&self) -> Vec<ValidatorInfo> {
+        self.0.read().unwrap().clone()
+    }
+
+    pub(crate) fn publish(&self, validator_set: Vec<ValidatorInfo>) {
+        *self.0.write().unwrap() = validator_set;
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -173,6 +267,16 @@ impl WeightedRoundRobin {
     }
 }
 
+impl LeaderSchedule for WeightedRoundRobin {
+    fn proposer(&mut self, block_number: BlockNumber) -> Option<PeerId> {
+        self.choose_proposer(block_number)
+    }
+
+    fn is_leader(&self, block_number: BlockNumber, node: PeerId) -> bool {
+        block_number <= self.block_number && self.last_proposer == Some(node)
+    }
+}
+
 #[cfg(not(test))]
 impl Drop for WeightedRoundRobin {
     fn drop(&mut self) {
@@ -198,6 +302,9 @@ impl std::hash::Hash for AngstromValidator {
 mod tests {
     use std::collections::HashMap;
 
+    use alloy::signers::local::PrivateKeySigner;
+    use secp256k1::SecretKey;
+
     use super::*;
 
     #[test]
@@ -283,6 +390,103 @@ mod tests {
         assert!(!after_remove_stats.contains_key(&peers["Bob"]));
     }
 
+    #[test]
+    fn test_validator_info_matches_the_validator() {
+        let signer = crate::Signer::new(SecretKey::new(&mut secp256k1::rand::thread_rng()));
+        let wallet = PrivateKeySigner::from_slice(&signer.key.secret_bytes()).unwrap();
+
+        let info = AngstromValidator::new(signer.my_id, 300).info();
+        assert_eq!(info.pubkey, signer.my_id);
+        assert_eq!(info.address, wallet.address());
+        assert_eq!(info.weight, 300);
+    }
+
+    #[test]
+    fn test_validator_set_handle_clones_share_the_set() {
+        let handle = ValidatorSetHandle::default();
+        let rpc = handle.clone();
+        assert!(rpc.validator_set().is_empty());
+
+        let validator = AngstromValidator::new(PeerId::random(), 100).info();
+        handle.publish(vec![validator.clone()]);
+        assert_eq!(rpc.validator_set(), vec![validator]);
+    }
+
+    #[test]
+    fn test_round_robin_rotates_every_block() {
+        let peers = [PeerId::random(), PeerId::random(), PeerId::random()];
+        let validators = peers
+            .iter()
+            .rev()
+            .map(|peer| AngstromValidator::new(*peer, 100))
+            .collect::<Vec<_>>();
+        let mut schedule = RoundRobin::new(&validators);
+        let mut sorted = peers;
+        sorted.sort_unstable();
+
+        for block in 10..16 {
+            let proposer = schedule.proposer(block).unwrap();
+            assert_eq!(proposer, sorted[block as usize % 3]);
+            assert!(schedule.is_leader(block, proposer));
+            assert_eq!(
+                peers
+                    .iter()
+                    .filter(|peer| schedule.is_leader(block, **peer))
+                    .count(),
+                1
+            );
+        }
+        assert_eq!(RoundRobin::new(&[]).proposer(1), None);
+    }
+
+    #[test]
+    fn test_default_schedule_ignores_voting_power() {
+        let validators = [100, 200, 300]
+            .map(|voting_power| AngstromValidator::new(PeerId::random(), voting_power));
+        let mut schedule = default_schedule(&validators);
+
+        // every validator proposes once every three blocks, however much
+        // voting power it has
+        let proposers = (1..=3)
+            .map(|block| schedule.proposer(block).unwrap())
+            .collect::<HashSet<_>>();
+        assert_eq!(proposers.len(), 3);
+        assert_eq!(schedule.proposer(4), schedule.proposer(1));
+    }
+
+    #[test]
+    fn test_is_leader_does_not_advance_the_schedule() {
+        let peers = [PeerId::random(), PeerId::random(), PeerId::random()];
+        // built directly, `new` would pick up state saved by other tests
+        let mut schedule = WeightedRoundRobin {
+            validators:                peers
+                .iter()
+                .map(|peer| AngstromValidator::new(*peer, 100))
+                .collect(),
+            new_joiner_penalty_factor: PENALTY_FACTOR,
+            block_number:              10,
+            last_proposer:             None
+        };
+
+        // the schedule hasn't reached the block yet
+        assert!(peers.iter().all(|peer| !schedule.is_leader(11, *peer)));
+        assert_eq!(schedule.block_number, 10);
+
+        let proposer = schedule.proposer(11).unwrap();
+        for _ in 0..3 {
+            assert!(schedule.is_leader(11, proposer));
+        }
+        assert_eq!(
+            peers
+                .iter()
+                .filter(|peer| schedule.is_leader(11, **peer))
+                .count(),
+            1
+        );
+        assert_eq!(schedule.proposer(11), Some(proposer));
+        assert_eq!(schedule.block_number, 11);
+    }
+
     #[test]
     fn test_save_load_state() {
         let peers = HashMap::from([
@@ -1,5 +1,6 @@
 mod leader_selection;
 mod manager;
+mod quorum;
 mod round_state;
 mod signer;
 
@@ -7,8 +8,12 @@ use std::pin::Pin;
 
 use angstrom_types::consensus::{PreProposal, Proposal};
 use futures::Stream;
-pub use leader_selection::AngstromValidator;
+pub use leader_selection::{
+    AngstromValidator, LeaderSchedule, LeaderScheduleError, RoundRobin, ValidatorInfo,
+    ValidatorSetHandle, WeightedRoundRobin
+};
 pub use manager::*;
+pub use quorum::QuorumTracker;
 pub use round_state::ConsensusState;
 pub use signer::*;
 
@@ -3,42 +3,53 @@ use std::{
     future::Future,
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll, Waker}
+    task::{Context, Poll, Waker},
+    time::Duration
 };
 
 use alloy::{primitives::BlockNumber, providers::Provider, transports::Transport};
 use angstrom_metrics::ConsensusMetricsWrapper;
 use angstrom_network::{manager::StromConsensusEvent, StromMessage, StromNetworkHandle};
 use angstrom_types::{
-    block_sync::BlockSyncConsumer, contract_payloads::angstrom::UniswapAngstromRegistry
+    block_sync::BlockSyncConsumer,
+    contract_payloads::angstrom::UniswapAngstromRegistry,
+    orders::PoolSolution,
+    primitive::{PeerId, PoolId}
 };
 use futures::StreamExt;
-use matching_engine::MatchingEngineHandle;
+use matching_engine::{MatchTrigger, MatchingEngineHandle, MatchingScheduler};
 use order_pool::order_storage::OrderStorage;
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
 use reth_provider::{CanonStateNotification, CanonStateNotifications};
+use tokio::task::JoinSet;
 use tokio_stream::wrappers::BroadcastStream;
-use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
+use uniswap_v4::uniswap::{circuit_breaker::PoolCircuitBreaker, pool_manager::SyncedUniswapPools};
 
 use crate::{
-    leader_selection::WeightedRoundRobin,
+    leader_selection::{default_schedule, LeaderSchedule, LeaderScheduleError},
     round_state::{
         ConsensusState, Finalization, PreProposalAggregation, PreProposalSubmission,
         RoundStateMachine
     },
-    AngstromValidator, Signer
+    AngstromValidator, Signer, ValidatorInfo, ValidatorSetHandle
 };
 
 const MODULE_NAME: &str = "Consensus";
 
 pub struct ConsensusManager<T, Matching, BlockSync> {
     current_height:         BlockNumber,
-    leader_selection:       WeightedRoundRobin,
+    leader_selection:       Box<dyn LeaderSchedule>,
     state_transition:       RoundStateMachine<T, Matching>,
     canonical_block_stream: BroadcastStream<CanonStateNotification>,
     strom_consensus_event:  UnboundedMeteredReceiver<StromConsensusEvent>,
     network:                StromNetworkHandle,
     block_sync:             BlockSync,
+    /// Drives matching runs outside of the consensus rounds, if set
+    matching_scheduler:     Option<MatchingScheduler>,
+    /// scheduled matching runs in flight on blocking threads
+    matching_runs:          JoinSet<(MatchTrigger, Vec<PoolSolution>)>,
+    /// Nothing is sent to peers, the leader doesn't submit its bundle
+    dry_run:                bool,
 
     /// Track broadcasted messages to avoid rebroadcasting
     broadcasted_messages: HashSet<StromConsensusEvent>
@@ -65,12 +76,12 @@ where
     ) -> Self {
         let ManagerNetworkDeps { network, canonical_block_stream, strom_consensus_event } = netdeps;
         let wrapped_broadcast_stream = BroadcastStream::new(canonical_block_stream);
-        let mut leader_selection = WeightedRoundRobin::new(validators.clone(), current_height);
-        let leader = leader_selection.choose_proposer(current_height).unwrap();
+        let mut leader_selection = default_schedule(&validators);
+        let leader = leader_selection.proposer(current_height).unwrap();
         Self {
             strom_consensus_event,
             current_height,
-            leader_selection,
+            leader_selection: Box::new(leader_selection),
             state_transition: RoundStateMachine::new(
                 current_height,
                 order_storage,
@@ -86,20 +97,91 @@ where
             block_sync,
             network,
             canonical_block_stream: wrapped_broadcast_stream,
+            matching_scheduler: None,
+            matching_runs: JoinSet::new(),
+            dry_run: false,
             broadcasted_messages: HashSet::new()
         }
     }
 
+    /// pools halted by the breaker are left out of matching
+    pub fn with_circuit_breaker(
+        mut self,
+        circuit_breaker: Arc<PoolCircuitBreaker<PoolId>>
+    ) -> Self {
+        self.state_transition.set_circuit_breaker(circuit_breaker);
+        self
+    }
+
+    /// runs matching over the live order book whenever the scheduler says so,
+    /// independently of the rounds that build the block's bundle
+    pub fn with_matching_scheduler(mut self, matching_scheduler: MatchingScheduler) -> Self {
+        self.matching_scheduler = Some(matching_scheduler);
+        self
+    }
+
+    /// runs the rounds as usual without sending anything to peers or
+    /// submitting the bundle, for shadowing live order flow
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self.state_transition.set_dry_run(dry_run);
+        self
+    }
+
+    /// gives up on a block that isn't committed within `timeout` of its
+    /// pre-proposals reaching quorum, see [`crate::QuorumTracker`]. Rounds
+    /// wait until the next block without one
+    pub fn with_quorum_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.state_transition.set_quorum_timeout(timeout);
+        self
+    }
+
+    /// picks the proposer of each block with `leader_schedule` instead of the
+    /// plain [`RoundRobin`](crate::RoundRobin) over the validators. Fails if
+    /// the schedule has no proposer for the current block, e.g. because its
+    /// validator set is empty
+    pub fn with_leader_schedule(
+        mut self,
+        mut leader_schedule: impl LeaderSchedule
+    ) -> Result<Self, LeaderScheduleError> {
+        let leader = leader_schedule
+            .proposer(self.current_height)
+            .ok_or(LeaderScheduleError::NoProposer(self.current_height))?;
+        self.leader_selection = Box::new(leader_schedule);
+        self.state_transition
+            .reset_round(self.current_height, leader);
+
+        Ok(self)
+    }
+
+    /// the validators the rounds are run with and their voting power
+    pub fn validator_set(&self) -> Vec<ValidatorInfo> {
+        self.state_transition.validator_set()
+    }
+
+    /// publishes the validator set into `handle`, for reading it outside of
+    /// consensus once the manager is spawned
+    pub fn with_validator_set_handle(self, handle: ValidatorSetHandle) -> Self {
+        handle.publish(self.validator_set());
+        self
+    }
+
+    /// whether this node proposes `block_number`
+    pub fn is_leader(&self, block_number: BlockNumber) -> bool {
+        let my_id = self.state_transition.my_id();
+        self.leader_selection.is_leader(block_number, my_id)
+    }
+
     fn on_blockchain_state(&mut self, notification: CanonStateNotification, waker: Waker) {
         let new_block = notification.tip();
         self.current_height = new_block.block.number;
-        let round_leader = self
-            .leader_selection
-            .choose_proposer(self.current_height)
-            .unwrap();
+        let round_leader = self.leader_selection.proposer(self.current_height).unwrap();
         self.state_transition
             .reset_round(self.current_height, round_leader);
         self.broadcasted_messages.clear();
+        if let Some(scheduler) = self.matching_scheduler.as_mut() {
+            scheduler.on_new_block(self.current_height);
+        }
 
         self.block_sync
             .sign_off_on_block(MODULE_NAME, self.current_height, Some(waker));
@@ -127,19 +209,35 @@ where
         }
 
         if !self.broadcasted_messages.contains(&event) {
-            self.network.broadcast_message(event.clone().into());
+            self.broadcast(event.clone().into());
             self.broadcasted_messages.insert(event.clone());
         }
 
         if let Some((peer_id, msg)) = self.state_transition.on_strom_message(event.clone()) {
             if let Some(peer_id) = peer_id {
-                self.network.send_message(peer_id, msg);
+                self.send(peer_id, msg);
             } else {
-                self.network.broadcast_message(msg);
+                self.broadcast(msg);
             }
         }
     }
 
+    fn broadcast(&self, msg: StromMessage) {
+        if self.dry_run {
+            tracing::trace!("dry run, not broadcasting consensus message");
+            return
+        }
+        self.network.broadcast_message(msg);
+    }
+
+    fn send(&self, peer_id: PeerId, msg: StromMessage) {
+        if self.dry_run {
+            tracing::trace!(?peer_id, "dry run, not sending consensus message");
+            return
+        }
+        self.network.send_message(peer_id, msg);
+    }
+
     pub fn on_state_start(&mut self, new_stat: ConsensusState) {
         match new_stat {
             // means we transitioned from commit phase to bid submission.
@@ -151,7 +249,7 @@ where
                 pre_proposals,
                 ..
             }) => {
-                self.network.broadcast_message(
+                self.broadcast(
                     self.state_transition
                         .my_pre_proposal(&pre_proposals)
                         .unwrap()
@@ -162,8 +260,7 @@ where
             ConsensusState::Finalization(finalization) => {
                 // tell everyone what we sent out to Ethereum
                 if self.state_transition.i_am_leader() {
-                    self.network
-                        .broadcast_message(StromMessage::Propose(finalization.proposal.unwrap()))
+                    self.broadcast(StromMessage::Propose(finalization.proposal.unwrap()))
                 }
             }
         }
@@ -209,6 +306,29 @@ where
                     }
                 };
             }
+
+            if let Some(scheduler) = this.matching_scheduler.as_mut() {
+                while let Poll::Ready(Some(trigger)) = scheduler.poll_next_unpin(cx) {
+                    let run = this.state_transition.match_pools(trigger.pool_id());
+                    this.matching_runs.spawn_blocking(move || (trigger, run()));
+                }
+            }
+        }
+
+        while let Poll::Ready(Some(res)) = this.matching_runs.poll_join_next(cx) {
+            match res {
+                Ok((trigger, solutions)) => {
+                    tracing::debug!(
+                        ?trigger,
+                        solutions = solutions.len(),
+                        "ran scheduled matching"
+                    );
+                    if let Some(scheduler) = this.matching_scheduler.as_ref() {
+                        scheduler.publish(trigger, solutions);
+                    }
+                }
+                Err(e) => tracing::error!("scheduled matching run failed: {}", e)
+            }
         }
 
         Poll::Pending
@@ -0,0 +1,131 @@
+use std::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::{Duration, Instant}
+};
+
+use alloy::primitives::BlockNumber;
+use futures::FutureExt;
+use tokio::time::{sleep_until, Sleep};
+
+/// Bounds the commit phase of a round, from its pre-proposals reaching quorum
+/// until its block is committed: the leader submitted the bundle or the
+/// others received the leader's proposal. A round whose block isn't
+/// committed in time gives up on it instead of waiting on it until the next
+/// block arrives.
+#[derive(Default)]
+pub struct QuorumTracker {
+    /// rounds wait for their commit indefinitely without one
+    timeout: Option<Duration>,
+    /// the block being waited on and when it's given up on
+    waiting: Option<(BlockNumber, Instant)>,
+    /// started by the first poll after the round started
+    timer:   Option<Pin<Box<Sleep>>>
+}
+
+impl QuorumTracker {
+    pub fn new(timeout: Option<Duration>) -> Self {
+        Self { timeout, waiting: None, timer: None }
+    }
+
+    /// a timeout of `percent` of the block time
+    pub fn relative_to_block_time(block_time: Duration, percent: u32) -> Self {
+        Self::new(Some(block_time * percent / 100))
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// starts waiting for `block` to be committed, replacing the block waited
+    /// on before. Waiting on the same block again keeps its deadline
+    pub fn start(&mut self, block: BlockNumber) {
+        if self
+            .waiting
+            .is_some_and(|(waited_on, _)| waited_on == block)
+        {
+            return
+        }
+        self.waiting = self
+            .timeout
+            .map(|timeout| (block, Instant::now() + timeout));
+        self.timer = None;
+    }
+
+    /// the block was committed or the round moved on, it no longer times out
+    pub fn stop(&mut self) {
+        self.waiting = None;
+        self.timer = None;
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.is_some()
+    }
+
+    /// resolves with the block waited on once the timeout passed without it
+    /// being committed. Each block times out at most once
+    pub fn poll_timeout(&mut self, cx: &mut Context<'_>) -> Poll<BlockNumber> {
+        let Some((block, deadline)) = self.waiting else { return Poll::Pending };
+        let timer = self
+            .timer
+            .get_or_insert_with(|| Box::pin(sleep_until(deadline.into())));
+        ready!(timer.poll_unpin(cx));
+        self.stop();
+
+        Poll::Ready(block)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use futures::future::poll_fn;
+    use order_pool::ETH_BLOCK_TIME;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_times_out_without_a_commit() {
+        let mut tracker = QuorumTracker::new(Some(Duration::from_millis(40)));
+        tracker.start(10);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        // starting the same block again doesn't push its deadline back
+        tracker.start(10);
+
+        let timed_out =
+            tokio::time::timeout(Duration::from_millis(25), poll_fn(|cx| tracker.poll_timeout(cx)));
+        assert_eq!(timed_out.await, Ok(10));
+        assert!(!tracker.is_waiting());
+        assert!(poll_fn(|cx| tracker.poll_timeout(cx))
+            .now_or_never()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_commit_stops_the_timeout() {
+        let mut tracker = QuorumTracker::new(Some(Duration::from_millis(20)));
+        tracker.start(10);
+        tracker.stop();
+
+        tokio::time::sleep(Duration::from_millis(40)).await;
+        assert!(poll_fn(|cx| tracker.poll_timeout(cx))
+            .now_or_never()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_without_timeout_never_gives_up() {
+        let mut tracker = QuorumTracker::default();
+        tracker.start(10);
+
+        assert!(!tracker.is_waiting());
+        assert!(poll_fn(|cx| tracker.poll_timeout(cx))
+            .now_or_never()
+            .is_none());
+    }
+
+    #[test]
+    fn test_timeout_relative_to_block_time() {
+        let tracker = QuorumTracker::relative_to_block_time(ETH_BLOCK_TIME, 50);
+        assert_eq!(tracker.timeout(), Some(Duration::from_secs(6)));
+    }
+}
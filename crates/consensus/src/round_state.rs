@@ -3,7 +3,8 @@ use std::{
     hash::Hash,
     pin::Pin,
     sync::Arc,
-    task::{Context, Poll, Waker}
+    task::{Context, Poll, Waker},
+    time::Duration
 };
 
 use alloy::{
@@ -18,8 +19,9 @@ use angstrom_network::{manager::StromConsensusEvent, StromMessage};
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
     contract_payloads::angstrom::{AngstromBundle, UniswapAngstromRegistry},
-    orders::OrderSet,
-    primitive::PeerId,
+    matching::uniswap::PoolSnapshot,
+    orders::{OrderSet, PoolSolution},
+    primitive::{PeerId, PoolId},
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
         rpc_orders::TopOfBlockOrder
@@ -29,14 +31,14 @@ use angstrom_utils::timer::async_time_fn;
 use eyre::Report;
 use futures::{future::BoxFuture, Future, Stream};
 use itertools::Itertools;
-use matching_engine::MatchingEngineHandle;
+use matching_engine::{build_solution, MatchingEngineHandle};
 use order_pool::order_storage::OrderStorage;
 use pade::PadeEncode;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
+use uniswap_v4::uniswap::{circuit_breaker::PoolCircuitBreaker, pool_manager::SyncedUniswapPools};
 
-use crate::{AngstromValidator, Signer};
+use crate::{quorum::QuorumTracker, AngstromValidator, Signer, ValidatorInfo};
 
 #[derive(Error, Debug)]
 pub enum RoundStateMachineError {
@@ -52,12 +54,19 @@ pub struct RoundStateMachine<T, Matching> {
     signer:            Signer,
     round_leader:      PeerId,
     validators:        Vec<AngstromValidator>,
-    _order_storage:    Arc<OrderStorage>,
+    order_storage:     Arc<OrderStorage>,
     metrics:           ConsensusMetricsWrapper,
     transition_future: Option<BoxFuture<'static, Result<ConsensusState, RoundStateMachineError>>>,
     waker:             Option<Waker>,
     pool_registry:     UniswapAngstromRegistry,
     uniswap_pools:     SyncedUniswapPools,
+    circuit_breaker:   Arc<PoolCircuitBreaker<PoolId>>,
+    /// builds the bundle as usual but never submits it
+    dry_run:           bool,
+    /// gives up on the round's block if it isn't committed in time
+    quorum:            QuorumTracker,
+    /// the round gave up on its block, messages are ignored until the next one
+    abandoned:         bool,
     provider:          Arc<Pin<Box<dyn Provider<T>>>>
 }
 
@@ -83,9 +92,13 @@ where
             current_state: Self::initial_state(block_height),
             round_leader,
             validators,
-            _order_storage: order_storage,
+            order_storage,
             pool_registry,
             uniswap_pools,
+            circuit_breaker: Arc::new(PoolCircuitBreaker::default()),
+            dry_run: false,
+            quorum: QuorumTracker::default(),
+            abandoned: false,
             signer,
             metrics,
             transition_future: None,
@@ -95,6 +108,18 @@ where
         }
     }
 
+    pub fn set_circuit_breaker(&mut self, circuit_breaker: Arc<PoolCircuitBreaker<PoolId>>) {
+        self.circuit_breaker = circuit_breaker;
+    }
+
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn set_quorum_timeout(&mut self, timeout: Option<Duration>) {
+        self.quorum = QuorumTracker::new(timeout);
+    }
+
     pub fn my_id(&self) -> PeerId {
         self.signer.my_id
     }
@@ -107,6 +132,13 @@ where
         self.is_leader(self.my_id())
     }
 
+    pub fn validator_set(&self) -> Vec<ValidatorInfo> {
+        self.validators
+            .iter()
+            .map(AngstromValidator::info)
+            .collect()
+    }
+
     pub fn has_quorum(&self, voters: usize) -> bool {
         voters > (self.validators.len() * 2) / 3
     }
@@ -115,6 +147,8 @@ where
         self.round_leader = leader;
         self.current_state = Self::initial_state(block);
         self.transition_future = None;
+        self.abandoned = false;
+        self.quorum.stop();
     }
 
     pub fn initial_state(block_height: BlockNumber) -> ConsensusState {
@@ -124,6 +158,43 @@ where
         })
     }
 
+    /// pools left out of matching, those halted by the circuit breaker and
+    /// those paused by an operator
+    fn halted_pools(&self) -> HashSet<PoolId> {
+        let mut halted_pools = self.circuit_breaker.halted_pools();
+        halted_pools.extend(self.order_storage.paused_pools().paused());
+        halted_pools
+    }
+
+    /// Clears the orders currently in storage against the pools' AMMs, outside
+    /// of a consensus round. Only `pool_id` is matched if given, halted pools
+    /// are skipped either way. Matching is cpu heavy, the returned job is
+    /// meant to be run on a blocking thread
+    pub fn match_pools(
+        &self,
+        pool_id: Option<PoolId>
+    ) -> impl FnOnce() -> Vec<PoolSolution> + Send + 'static {
+        let halted_pools = self.halted_pools();
+        let uniswap_pools = self.uniswap_pools.clone();
+        let pool_registry = self.pool_registry.clone();
+        let order_storage = self.order_storage.clone();
+
+        move || {
+            let pool_snapshots =
+                fetch_pool_snapshots(&uniswap_pools, &pool_registry, &halted_pools);
+            let orders = order_storage.get_all_orders();
+
+            pool_snapshots
+                .into_iter()
+                .filter(|(key, _)| pool_id.map_or(true, |pool_id| pool_id == *key))
+                .filter_map(|(key, (_, _, snapshot, _))| {
+                    let searcher = orders.searcher.iter().find(|order| order.pool_id == key);
+                    build_solution(key, &orders, searcher, Some(snapshot))
+                })
+                .collect()
+        }
+    }
+
     pub fn my_pre_proposal(&self, pre_proposals: &HashSet<PreProposal>) -> Option<StromMessage> {
         pre_proposals
             .iter()
@@ -139,6 +210,10 @@ where
         &mut self,
         strom_msg: StromConsensusEvent
     ) -> Option<(Option<PeerId>, StromMessage)> {
+        if self.abandoned {
+            return None
+        }
+
         let i_am_leader = self.i_am_leader();
         match strom_msg {
             StromConsensusEvent::PreProposal(_, pre_proposal) => {
@@ -178,6 +253,8 @@ where
                     if self.have_quorum(self.all_searcher_orders(pre_proposals))
                         && self.have_quorum(self.all_limit_orders(pre_proposals))
                     {
+                        // the commit phase starts, we wait for the leader's proposal
+                        self.quorum.start(block_height);
                         // send the quorum pre_proposal to the leader
                         return Some((
                             Some(self.round_leader),
@@ -209,6 +286,20 @@ where
 
                 let pre_proposals = self.current_state.pre_proposals();
                 if proposal.is_valid() && !i_am_leader {
+                    let leader_halted: HashSet<PoolId> =
+                        proposal.halted_pools.iter().copied().collect();
+                    let our_halted = self.halted_pools();
+                    if our_halted != leader_halted {
+                        let only_leader = leader_halted.difference(&our_halted).collect_vec();
+                        let only_us = our_halted.difference(&leader_halted).collect_vec();
+                        tracing::warn!(
+                            block_height = proposal_block_height,
+                            halted_by_leader = ?only_leader,
+                            halted_by_us = ?only_us,
+                            "the leader halted different pools than we did"
+                        );
+                    }
+
                     self.force_transition(ConsensusState::Finalization(Finalization {
                         block_height:  proposal_block_height,
                         proposal:      Some(proposal),
@@ -237,7 +328,7 @@ where
         block_height: BlockNumber,
         pre_proposals: &HashSet<PreProposal>
     ) -> PreProposalAggregation {
-        let OrderSet { limit, searcher } = self._order_storage.get_all_orders();
+        let OrderSet { limit, searcher } = self.order_storage.get_all_orders();
         let mut pre_proposals = pre_proposals.clone();
 
         let pre_proposal = Self::generate_our_merged_pre_proposal(
@@ -326,6 +417,9 @@ where
     }
 
     fn force_transition(&mut self, new_state: ConsensusState) {
+        // only ever transitions to finalization, the block is committed once
+        // the transition finishes
+        self.quorum.start(new_state.block_height());
         self.transition_future = Some(Box::pin(self.build_transition_future(new_state)));
 
         // wake up the poller
@@ -346,7 +440,10 @@ where
         let provider = self.provider.clone();
         let pool_registry = self.pool_registry.clone();
         let uniswap_pools = self.uniswap_pools.clone();
+        let halted_pools = self.halted_pools();
         let matching = self.matching_engine.clone();
+        let i_am_leader = self.i_am_leader();
+        let dry_run = self.dry_run;
 
         async move {
             if let ConsensusState::Finalization(finalization) = &mut new_state {
@@ -355,26 +452,47 @@ where
                     // TODO: use this opportunity to trigger the proposal validation
                     return Ok(new_state)
                 }
+                // only the round's leader builds and submits a proposal
+                if !i_am_leader {
+                    tracing::debug!(
+                        block_height = pre_proposal_height,
+                        "not the round leader, skipping proposal building"
+                    );
+                    return Ok(new_state)
+                }
 
-                let pool_snapshots = uniswap_pools
+                // halted pools are left out of matching entirely. the pre-proposals
+                // are still signed over as is, their orders are only stripped for
+                // matching and the proposal names the pools that were left out
+                let matching_pre_proposals = pre_proposals
                     .iter()
-                    .filter_map(|(key, pool)| {
-                        let (token_a, token_b, snapshot) =
-                            pool.read().unwrap().fetch_pool_snapshot().ok()?;
-                        let entry = pool_registry.get_ang_entry(key)?;
-
-                        Some((*key, (token_a, token_b, snapshot, entry.store_index as u16)))
+                    .cloned()
+                    .map(|mut pre_proposal| {
+                        pre_proposal
+                            .limit
+                            .retain(|order| !halted_pools.contains(&order.pool_id));
+                        pre_proposal
+                            .searcher
+                            .retain(|order| !halted_pools.contains(&order.pool_id));
+                        pre_proposal
                     })
-                    .collect::<HashMap<_, _>>();
+                    .collect::<Vec<_>>();
+
+                let pool_snapshots =
+                    fetch_pool_snapshots(&uniswap_pools, &pool_registry, &halted_pools);
 
                 let (proposal, timer) = async_time_fn(|| async {
                     match matching
-                        .solve_pools(pre_proposals.clone(), pool_snapshots.clone())
+                        .solve_pools(matching_pre_proposals, pool_snapshots.clone())
                         .await
                     {
                         Ok((solutions, gas_info)) => {
-                            let proposal =
-                                signer.sign_proposal(pre_proposal_height, pre_proposals, solutions);
+                            let proposal = signer.sign_proposal(
+                                pre_proposal_height,
+                                pre_proposals,
+                                solutions,
+                                halted_pools.into_iter().collect()
+                            );
                             Ok((proposal, gas_info))
                         }
                         Err(err) => Err(RoundStateMachineError::ProposalBuildError(err))
@@ -386,25 +504,65 @@ where
 
                 let bundle =
                     AngstromBundle::from_proposal(&proposal, gas_info, &pool_snapshots).unwrap();
-
-                let tx = TransactionRequest::default()
-                    .with_to(Address::default())
-                    .with_input(bundle.pade_encode());
-
-                let submitted_tx = provider
-                    .send_transaction(tx)
-                    .await
-                    .map_err(|_| RoundStateMachineError::TransactionError)?;
-                let _receipt = submitted_tx
-                    .get_receipt()
-                    .await
-                    .map_err(|_| RoundStateMachineError::TransactionError)?;
+                submit_bundle(&**provider, bundle, pre_proposal_height, dry_run).await?;
             }
             Ok(new_state)
         }
     }
 }
 
+/// submits the bundle and waits for its receipt, a dry run only logs it
+async fn submit_bundle<T: Transport + Clone>(
+    provider: &dyn Provider<T>,
+    bundle: AngstromBundle,
+    block_height: BlockNumber,
+    dry_run: bool
+) -> Result<(), RoundStateMachineError> {
+    if dry_run {
+        tracing::info!(
+            block_height,
+            pairs = bundle.pairs.len(),
+            "dry run, not submitting the bundle"
+        );
+        return Ok(())
+    }
+
+    let tx = TransactionRequest::default()
+        .with_to(Address::default())
+        .with_input(bundle.pade_encode());
+
+    let submitted_tx = provider
+        .send_transaction(tx)
+        .await
+        .map_err(|_| RoundStateMachineError::TransactionError)?;
+    let _receipt = submitted_tx
+        .get_receipt()
+        .await
+        .map_err(|_| RoundStateMachineError::TransactionError)?;
+
+    Ok(())
+}
+
+/// snapshots of the pools that are registered with angstrom and not halted,
+/// with the pool's fee applied
+fn fetch_pool_snapshots(
+    uniswap_pools: &SyncedUniswapPools,
+    pool_registry: &UniswapAngstromRegistry,
+    halted_pools: &HashSet<PoolId>
+) -> HashMap<PoolId, (Address, Address, PoolSnapshot, u16)> {
+    uniswap_pools
+        .iter()
+        .filter(|(key, _)| !halted_pools.contains(*key))
+        .filter_map(|(key, pool)| {
+            let (token_a, token_b, snapshot) = pool.read().unwrap().fetch_pool_snapshot().ok()?;
+            let entry = pool_registry.get_ang_entry(key)?;
+            let snapshot = snapshot.with_fee(entry.fee_in_e6);
+
+            Some((*key, (token_a, token_b, snapshot, entry.store_index as u16)))
+        })
+        .collect()
+}
+
 impl<T, Matching> Stream for RoundStateMachine<T, Matching>
 where
     T: Transport + Clone,
@@ -417,9 +575,24 @@ where
 
         this.waker = Some(cx.waker().clone());
 
+        if let Poll::Ready(block_height) = this.quorum.poll_timeout(cx) {
+            tracing::warn!(
+                block_height,
+                timeout = ?this.quorum.timeout(),
+                state = this.current_state.name(),
+                "consensus timeout, the block wasn't committed in time, abandoning it"
+            );
+            this.metrics.increment_timeouts();
+            this.abandoned = true;
+            this.transition_future = None;
+        }
+
         if let Some(future) = &mut this.transition_future {
             return match future.as_mut().poll(cx) {
-                Poll::Ready(result) => Poll::Ready(Some(result)),
+                Poll::Ready(result) => {
+                    this.quorum.stop();
+                    Poll::Ready(Some(result))
+                }
                 Poll::Pending => Poll::Pending
             }
         }
@@ -490,3 +663,152 @@ impl ConsensusState {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use alloy::{
+        providers::ProviderBuilder,
+        transports::http::{Client, Http}
+    };
+    use angstrom_types::contract_payloads::angstrom::{AngstromPoolConfigStore, BundleGasDetails};
+    use futures::StreamExt;
+    use order_pool::PoolConfig;
+
+    use super::*;
+
+    /// matching never finishes, a leader's round stalls before its bundle is
+    /// submitted
+    #[derive(Clone)]
+    struct StalledMatching;
+
+    impl MatchingEngineHandle for StalledMatching {
+        fn solve_pools(
+            &self,
+            _: Vec<PreProposal>,
+            _: HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
+        ) -> BoxFuture<eyre::Result<(Vec<PoolSolution>, BundleGasDetails)>> {
+            Box::pin(futures::future::pending())
+        }
+    }
+
+    /// a round for block 10 of three validators, aggregating pre-proposals
+    /// and timing out 20ms into its commit phase
+    fn aggregating_round(
+        signer: Signer,
+        leader: PeerId,
+        peer: PeerId
+    ) -> RoundStateMachine<Http<Client>, StalledMatching> {
+        let validators = vec![
+            AngstromValidator::new(signer.my_id, 100),
+            AngstromValidator::new(peer, 100),
+            AngstromValidator::new(PeerId::random(), 100),
+        ];
+        let config_store = Arc::new(AngstromPoolConfigStore::default());
+        let registry = UniswapAngstromRegistry::new(vec![].into(), config_store);
+        let mut round = RoundStateMachine::new(
+            10,
+            Arc::new(OrderStorage::new(&PoolConfig::default())),
+            signer,
+            leader,
+            validators,
+            ConsensusMetricsWrapper::new(),
+            registry,
+            Arc::new(HashMap::new()),
+            ProviderBuilder::new().on_http("http://localhost:8545".parse().unwrap()),
+            StalledMatching
+        );
+        round.set_quorum_timeout(Some(Duration::from_millis(20)));
+        round.current_state = ConsensusState::PreProposalAggregation(PreProposalAggregation {
+            block_height: 10,
+            ..Default::default()
+        });
+
+        round
+    }
+
+    fn pre_proposal_of(peer: &Signer) -> StromConsensusEvent {
+        let pre_proposal =
+            PreProposal::generate_pre_proposal(10, peer.my_id, vec![], vec![], &peer.key);
+        StromConsensusEvent::PreProposal(peer.my_id, pre_proposal)
+    }
+
+    #[tokio::test]
+    async fn test_round_stalled_before_its_commit_is_abandoned() {
+        let signer = Signer::default();
+        let leader = signer.my_id;
+        let peer = Signer::default();
+        let mut round = aggregating_round(signer, leader, peer.my_id);
+
+        // the pre-proposals reach quorum, the leader starts building its
+        // proposal and matching never finishes
+        assert!(round.on_strom_message(pre_proposal_of(&peer)).is_none());
+        assert!(round.transition_future.is_some());
+        assert!(round.quorum.is_waiting());
+
+        let polled = tokio::time::timeout(Duration::from_millis(200), round.next()).await;
+        assert!(polled.is_err());
+        assert!(round.abandoned);
+        assert!(round.transition_future.is_none());
+        assert!(!round.quorum.is_waiting());
+
+        // the next block starts a round that isn't timed until its commit phase
+        round.reset_round(11, leader);
+        assert!(!round.abandoned);
+        assert!(!round.quorum.is_waiting());
+        assert_eq!(round.current_state.block_height(), 11);
+    }
+
+    #[tokio::test]
+    async fn test_round_without_the_leaders_proposal_is_abandoned() {
+        let leader = Signer::default();
+        let mut round = aggregating_round(Signer::default(), leader.my_id, leader.my_id);
+
+        // collecting pre-proposals isn't timed
+        let polled = tokio::time::timeout(Duration::from_millis(50), round.next()).await;
+        assert!(polled.is_err());
+        assert!(!round.abandoned);
+
+        // quorum is reached, the round waits for the leader's proposal
+        let sent = round.on_strom_message(pre_proposal_of(&leader));
+        assert!(matches!(sent, Some((Some(to), _)) if to == leader.my_id));
+        assert!(round.quorum.is_waiting());
+
+        let polled = tokio::time::timeout(Duration::from_millis(200), round.next()).await;
+        assert!(polled.is_err());
+        assert!(round.abandoned);
+
+        // a late proposal is ignored
+        let proposal = leader.sign_proposal(10, vec![], vec![], vec![]);
+        let event = StromConsensusEvent::Proposal(leader.my_id, proposal);
+        assert!(round.on_strom_message(event).is_none());
+        assert!(round.transition_future.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_committed_rounds_do_not_time_out() {
+        let leader = Signer::default();
+        let mut round = aggregating_round(Signer::default(), leader.my_id, leader.my_id);
+
+        round.on_strom_message(pre_proposal_of(&leader));
+        let proposal = leader.sign_proposal(10, vec![], vec![], vec![]);
+        round.on_strom_message(StromConsensusEvent::Proposal(leader.my_id, proposal));
+
+        let committed = round.next().await.unwrap().unwrap();
+        assert!(matches!(committed, ConsensusState::Finalization(_)));
+        assert!(!round.quorum.is_waiting());
+        assert!(!round.abandoned);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_never_submits_the_bundle() {
+        // nothing listens on the port, so any request fails
+        let provider = ProviderBuilder::new().on_http("http://127.0.0.1:1".parse().unwrap());
+        let bundle = || AngstromBundle::new(vec![], vec![], vec![], vec![], vec![]);
+
+        assert!(submit_bundle(&provider, bundle(), 10, true).await.is_ok());
+        assert!(matches!(
+            submit_bundle(&provider, bundle(), 10, false).await,
+            Err(RoundStateMachineError::TransactionError)
+        ));
+    }
+}
@@ -2,7 +2,7 @@ use alloy::primitives::{BlockNumber, FixedBytes};
 use angstrom_types::{
     consensus::{PreProposal, Proposal},
     orders::PoolSolution,
-    primitive::PeerId
+    primitive::{PeerId, PoolId}
 };
 use secp256k1::{Secp256k1, SecretKey};
 
@@ -32,8 +32,16 @@ impl Signer {
         &self,
         ethereum_block: BlockNumber,
         preproposals: Vec<PreProposal>,
-        solutions: Vec<PoolSolution>
+        solutions: Vec<PoolSolution>,
+        halted_pools: Vec<PoolId>
     ) -> Proposal {
-        Proposal::generate_proposal(ethereum_block, self.my_id, preproposals, solutions, &self.key)
+        Proposal::generate_proposal(
+            ethereum_block,
+            self.my_id,
+            preproposals,
+            solutions,
+            halted_pools,
+            &self.key
+        )
     }
 }
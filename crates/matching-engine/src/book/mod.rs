@@ -8,6 +8,7 @@ use angstrom_types::{
 use order::OrderCoordinate;
 
 use self::sort::SortStrategy;
+use crate::matcher::SelfTradePrevention;
 
 pub mod order;
 pub mod sort;
@@ -15,10 +16,11 @@ pub mod xpool;
 
 #[derive(Debug, Default)]
 pub struct OrderBook {
-    id:   PoolId,
-    amm:  Option<PoolSnapshot>,
-    bids: Vec<OrderWithStorageData<GroupedVanillaOrder>>,
-    asks: Vec<OrderWithStorageData<GroupedVanillaOrder>>
+    id:                    PoolId,
+    amm:                   Option<PoolSnapshot>,
+    bids:                  Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+    asks:                  Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+    self_trade_prevention: SelfTradePrevention
 }
 
 impl OrderBook {
@@ -33,7 +35,12 @@ impl OrderBook {
         let strategy = sort.unwrap_or_default();
         strategy.sort_bids(&mut bids);
         strategy.sort_asks(&mut asks);
-        Self { id, amm, bids, asks }
+        Self { id, amm, bids, asks, self_trade_prevention: SelfTradePrevention::default() }
+    }
+
+    pub fn with_self_trade_prevention(mut self, mode: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = mode;
+        self
     }
 
     pub fn id(&self) -> PoolId {
@@ -52,6 +59,10 @@ impl OrderBook {
         self.amm.as_ref()
     }
 
+    pub fn self_trade_prevention(&self) -> SelfTradePrevention {
+        self.self_trade_prevention
+    }
+
     pub fn find_coordinate(&self, coord: &OrderCoordinate) -> Option<(bool, usize)> {
         let OrderCoordinate { book, order } = coord;
         if *book != self.id {
@@ -1,10 +1,13 @@
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use angstrom_types::{
     matching::uniswap::PoolPriceVec,
     orders::{OrderID, OrderId, OrderPrice, OrderVolume},
     primitive::PoolId,
-    sol_bindings::grouped_orders::{
-        FlashVariants, GroupedVanillaOrder, OrderWithStorageData, StandingVariants
+    sol_bindings::{
+        grouped_orders::{
+            FlashVariants, GroupedVanillaOrder, OrderWithStorageData, StandingVariants
+        },
+        RawPoolOrder
     }
 };
 
@@ -69,6 +72,15 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         matches!(self, Self::AMM(_))
     }
 
+    /// The sender of the underlying book order, `None` for the AMM
+    pub fn sender(&self) -> Option<Address> {
+        match self {
+            Self::BookOrder(o) => Some(o.from()),
+            Self::BookOrderFragment(o) => Some(o.from()),
+            Self::AMM(_) => None
+        }
+    }
+
     /// Is the underlying order a Partial Fill compatible order
     pub fn is_partial(&self) -> bool {
         match self {
@@ -90,21 +102,22 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         }
     }
 
-    /// Retrieve the quantity available within the bounds of a given order
+    /// Retrieve the quantity available within the bounds of a given order.
+    /// The AMM only offers what it can fill at `limit_price` after its fee
     pub fn quantity(&self, limit_price: OrderPrice) -> OrderVolume {
         match self {
-            Self::BookOrder(o) => o.quantity(),
-            Self::BookOrderFragment(o) => o.quantity(),
-            Self::AMM(ammo) => ammo.quantity(limit_price).0
+            Self::BookOrder(o) => o.remaining_amount(),
+            Self::BookOrderFragment(o) => o.remaining_amount(),
+            Self::AMM(ammo) => ammo.quantity_after_fee(limit_price).0
         }
     }
 
-    /// Retrieve the price for a given order
+    /// Retrieve the price for a given order, the AMM's is net of its fee
     pub fn price(&self) -> OrderPrice {
         match self {
             Self::BookOrder(o) => o.price().into(),
             Self::BookOrderFragment(o) => o.price().into(),
-            Self::AMM(o) => (*o.start_bound.price()).into()
+            Self::AMM(o) => o.start_price_after_fee()
         }
     }
 
@@ -114,14 +127,14 @@ impl<'a, 'b> OrderContainer<'a, 'b> {
         match self {
             Self::AMM(_) => panic!("This should never happen"),
             Self::BookOrder(o) => {
-                let newo = (**o).clone();
-                newo.try_map_inner(|f| Ok(f.fill(filled_quantity.to())))
-                    .unwrap()
+                let mut newo = (**o).clone();
+                newo.record_fill(filled_quantity.to());
+                newo
             }
             Self::BookOrderFragment(o) => {
-                let newo = (**o).clone();
-                newo.try_map_inner(|f| Ok(f.fill(filled_quantity.to())))
-                    .unwrap()
+                let mut newo = (**o).clone();
+                newo.record_fill(filled_quantity.to());
+                newo
             }
         }
     }
@@ -167,7 +180,7 @@ impl<'a> Order<'a> {
                 StandingVariants::Exact(e) => U256::from(e.amount),
                 StandingVariants::Partial(p) => U256::from(p.max_amount_in)
             },
-            Self::AMM(ammo) => ammo.quantity(limit_price).0
+            Self::AMM(ammo) => ammo.quantity_after_fee(limit_price).0
         }
     }
 
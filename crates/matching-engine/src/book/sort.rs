@@ -1,3 +1,5 @@
+use std::cmp::Ordering;
+
 use angstrom_types::sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData};
 
 /// There are lots of different ways we can sort the orders we get in, so let's
@@ -5,7 +7,10 @@ use angstrom_types::sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWit
 
 pub enum SortStrategy {
     Unsorted,
-    ByPriceByVolume
+    ByPriceByVolume,
+    /// Orders at the same price go by effective tip, highest first, the way
+    /// the pending pool orders them
+    ByPriceByTip
 }
 
 impl Default for SortStrategy {
@@ -16,18 +21,109 @@ impl Default for SortStrategy {
 
 impl SortStrategy {
     pub fn sort_bids(&self, bids: &mut [OrderWithStorageData<GroupedVanillaOrder>]) {
-        if let Self::ByPriceByVolume = self {
-            // Sort by price and then by volume - highest price first, highest volume first
-            // for same price
-            bids.sort_by(|a, b| b.priority_data.cmp(&a.priority_data));
+        match self {
+            Self::Unsorted => {}
+            Self::ByPriceByVolume => {
+                // Sort by price and then by volume - highest price first, highest volume
+                // first for same price. Full ties go by order hash so the book doesn't
+                // depend on the order it was built in
+                bids.sort_by(|a, b| {
+                    b.priority_data
+                        .cmp(&a.priority_data)
+                        .then_with(|| a.order_id.hash.cmp(&b.order_id.hash))
+                });
+            }
+            // highest price first
+            Self::ByPriceByTip => bids.sort_by(|a, b| {
+                b.priority_data
+                    .price
+                    .cmp(&a.priority_data.price)
+                    .then_with(|| by_tip(a, b))
+            })
         }
     }
 
     pub fn sort_asks(&self, asks: &mut [OrderWithStorageData<GroupedVanillaOrder>]) {
-        if let Self::ByPriceByVolume = self {
-            // Sort by price and then by volume - lowest price first, highest volume first
-            // for same price
-            asks.sort_by(|a, b| a.priority_data.cmp(&b.priority_data));
+        match self {
+            Self::Unsorted => {}
+            Self::ByPriceByVolume => {
+                // Sort by price and then by volume - lowest price first, highest volume
+                // first for same price. Full ties go by order hash, same as bids
+                asks.sort_by(|a, b| {
+                    a.priority_data
+                        .cmp(&b.priority_data)
+                        .then_with(|| a.order_id.hash.cmp(&b.order_id.hash))
+                });
+            }
+            // lowest price first
+            Self::ByPriceByTip => asks.sort_by(|a, b| {
+                a.priority_data
+                    .price
+                    .cmp(&b.priority_data.price)
+                    .then_with(|| by_tip(a, b))
+            })
         }
     }
 }
+
+/// highest effective tip first. The pending pool keeps equal tips in arrival
+/// order, which the book doesn't know, so they go by order hash instead
+fn by_tip(
+    a: &OrderWithStorageData<GroupedVanillaOrder>,
+    b: &OrderWithStorageData<GroupedVanillaOrder>
+) -> Ordering {
+    b.effective_tip_per_gas()
+        .cmp(&a.effective_tip_per_gas())
+        .then_with(|| a.order_id.hash.cmp(&b.order_id.hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{B256, U256};
+    use angstrom_types::{
+        orders::{OrderId, OrderPriorityData},
+        sol_bindings::{grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder}
+    };
+
+    use super::*;
+
+    fn order(price: u64, max_gas: u128, hash: B256) -> OrderWithStorageData<GroupedVanillaOrder> {
+        let inner = ExactStandingOrder { max_extra_fee_asset0: max_gas, ..Default::default() };
+        OrderWithStorageData {
+            order: GroupedVanillaOrder::Standing(StandingVariants::Exact(inner)),
+            priority_data: OrderPriorityData {
+                price: U256::from(price),
+                gas: U256::from(100),
+                gas_units: 10,
+                ..Default::default()
+            },
+            order_id: OrderId { hash, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn hashes(orders: &[OrderWithStorageData<GroupedVanillaOrder>]) -> Vec<B256> {
+        orders.iter().map(|order| order.order_id.hash).collect()
+    }
+
+    #[test]
+    fn same_price_goes_by_tip_before_hash() {
+        // the hashes alone would put the low tip first
+        let low_tip = order(2, 200, B256::with_last_byte(1));
+        let high_tip = order(2, 500, B256::with_last_byte(2));
+        let same_low_tip = order(2, 200, B256::with_last_byte(3));
+        let other_price = order(3, 0, B256::with_last_byte(4));
+        let level = [high_tip, low_tip, same_low_tip];
+
+        let mut bids = [&level[1..], &[other_price.clone()][..], &level[..1]].concat();
+        SortStrategy::ByPriceByTip.sort_bids(&mut bids);
+        let expected = [&[other_price.clone()][..], &level[..]].concat();
+        assert_eq!(hashes(&bids), hashes(&expected));
+
+        let mut asks = bids;
+        asks.reverse();
+        SortStrategy::ByPriceByTip.sort_asks(&mut asks);
+        let expected = [&level[..], &[other_price][..]].concat();
+        assert_eq!(hashes(&asks), hashes(&expected));
+    }
+}
@@ -5,20 +5,29 @@ use angstrom_types::{
     consensus::PreProposal,
     contract_payloads::angstrom::BundleGasDetails,
     matching::uniswap::PoolSnapshot,
-    orders::PoolSolution,
+    orders::{OrderSet, PoolSolution},
     primitive::PoolId,
-    sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
+    sol_bindings::{
+        grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
+        rpc_orders::TopOfBlockOrder
+    }
 };
 use book::OrderBook;
 use futures_util::future::BoxFuture;
+use strategy::{MatchingStrategy, SimpleCheckpointStrategy};
 
 pub mod book;
 pub mod manager;
 pub mod matcher;
+pub mod scheduler;
 pub mod simulation;
 pub mod strategy;
 
 pub use manager::MatchingManager;
+pub use matcher::SelfTradePrevention;
+pub use scheduler::{
+    MatchOutcome, MatchTrigger, MatchingSchedule, MatchingScheduler, MatchingSchedulerHandle
+};
 
 pub trait MatchingEngineHandle: Send + Sync + Clone + Unpin + 'static {
     fn solve_pools(
@@ -35,5 +44,100 @@ pub fn build_book(
 ) -> OrderBook {
     let (bids, asks) = orders.into_iter().partition(|o| o.is_bid);
 
-    OrderBook::new(id, amm, bids, asks, Some(book::sort::SortStrategy::ByPriceByVolume))
+    OrderBook::new(id, amm, bids, asks, Some(book::sort::SortStrategy::ByPriceByTip))
+}
+
+/// Runs the matching strategy over the book and attaches the searcher order
+/// to the result. `None` if the book can't be solved.
+pub fn solve_book(
+    book: &OrderBook,
+    searcher: Option<OrderWithStorageData<TopOfBlockOrder>>
+) -> Option<PoolSolution> {
+    SimpleCheckpointStrategy::run(book).map(|solver| solver.solution(searcher))
+}
+
+/// Clears the limit orders of `pool_id` in the set against each other and the
+/// pool's AMM, without any of the consensus machinery around it.
+///
+/// The result only depends on the orders, not the order they're listed in:
+/// orders at the same price go by effective tip like in the pending pool, and
+/// full ties are broken by order hash. Orders for other pools are ignored.
+pub fn build_solution(
+    pool_id: PoolId,
+    orders: &OrderSet<GroupedVanillaOrder, TopOfBlockOrder>,
+    searcher: Option<&OrderWithStorageData<TopOfBlockOrder>>,
+    amm: Option<PoolSnapshot>
+) -> Option<PoolSolution> {
+    let limit = orders
+        .limit
+        .iter()
+        .filter(|order| order.pool_id == pool_id)
+        .cloned()
+        .collect();
+
+    solve_book(&build_book(pool_id, amm, limit), searcher.cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Uint;
+    use angstrom_types::{
+        matching::Ray,
+        orders::{OrderFillState, OrderSet},
+        primitive::PoolId
+    };
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::build_solution;
+
+    #[test]
+    fn build_solution_does_not_depend_on_order_arrival() {
+        let pool_id = PoolId::random();
+        let high_price = Ray::from(Uint::from(1_000_000_000_u128));
+        let low_price = Ray::from(Uint::from(1_000_u128));
+        let order = |nonce: u64, is_bid: bool| {
+            UserOrderBuilder::new()
+                .partial()
+                .amount(10)
+                .nonce(nonce)
+                .min_price(if is_bid { high_price } else { low_price })
+                .with_storage()
+                .is_bid(is_bid)
+                .pool_id(pool_id)
+                .build()
+        };
+
+        let mut limit: Vec<_> = (0..3)
+            .map(|nonce| order(nonce, true))
+            .chain((3..5).map(|nonce| order(nonce, false)))
+            .collect();
+        // belongs to another pool, so it's never part of the solution
+        let other_pool = UserOrderBuilder::new()
+            .partial()
+            .amount(10)
+            .min_price(high_price)
+            .with_storage()
+            .bid()
+            .pool_id(PoolId::random())
+            .build();
+        limit.push(other_pool.clone());
+
+        let set = OrderSet { limit: limit.clone(), searcher: vec![] };
+        let solution = build_solution(pool_id, &set, None, None).expect("book is solvable");
+
+        limit.reverse();
+        let reversed = OrderSet { limit, searcher: vec![] };
+        let reversed = build_solution(pool_id, &reversed, None, None).expect("book is solvable");
+
+        assert_eq!(solution, reversed);
+        assert_eq!(solution.limit.len(), 5);
+        assert!(solution
+            .limit
+            .iter()
+            .all(|outcome| outcome.id != other_pool.order_id));
+        assert!(solution
+            .limit
+            .iter()
+            .any(|outcome| outcome.outcome != OrderFillState::Unfilled));
+    }
 }
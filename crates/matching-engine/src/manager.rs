@@ -12,7 +12,7 @@ use angstrom_types::{
     orders::PoolSolution,
     primitive::PoolId,
     sol_bindings::{
-        grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
+        grouped_orders::{GroupedVanillaOrder, OrderWithStorageData, TobRank},
         rpc_orders::TopOfBlockOrder
     }
 };
@@ -30,10 +30,7 @@ use tokio::{
 use validation::bundle::BundleValidatorHandle;
 
 use crate::{
-    book::OrderBook,
-    build_book,
-    strategy::{MatchingStrategy, SimpleCheckpointStrategy},
-    MatchingEngineHandle
+    book::OrderBook, build_book, matcher::SelfTradePrevention, solve_book, MatchingEngineHandle
 };
 
 pub enum MatcherCommand {
@@ -81,25 +78,40 @@ impl MatchingEngineHandle for MatcherHandle {
 }
 
 pub struct MatchingManager<TP: TaskSpawner, V> {
-    _futures:          FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Sync + Send + 'static>>>,
-    validation_handle: V,
-    _tp:               Arc<TP>
+    _futures: FuturesUnordered<Pin<Box<dyn Future<Output = ()> + Sync + Send + 'static>>>,
+    validation_handle:     V,
+    _tp:                   Arc<TP>,
+    self_trade_prevention: SelfTradePrevention
 }
 
 impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V> {
     pub fn new(tp: TP, validation: V) -> Self {
         Self {
-            _futures:          FuturesUnordered::default(),
-            validation_handle: validation,
-            _tp:               tp.into()
+            _futures:              FuturesUnordered::default(),
+            validation_handle:     validation,
+            _tp:                   tp.into(),
+            self_trade_prevention: SelfTradePrevention::default()
         }
     }
 
+    pub fn with_self_trade_prevention(mut self, mode: SelfTradePrevention) -> Self {
+        self.self_trade_prevention = mode;
+        self
+    }
+
     pub fn spawn(tp: TP, validation: V) -> MatcherHandle {
+        Self::spawn_with_self_trade_prevention(tp, validation, SelfTradePrevention::default())
+    }
+
+    pub fn spawn_with_self_trade_prevention(
+        tp: TP,
+        validation: V,
+        self_trade_prevention: SelfTradePrevention
+    ) -> MatcherHandle {
         let (tx, rx) = tokio::sync::mpsc::channel(100);
         let tp = Arc::new(tp);
 
-        let fut = manager_thread(rx, tp.clone(), validation).boxed();
+        let fut = manager_thread(rx, tp.clone(), validation, self_trade_prevention).boxed();
         tp.spawn_critical("matching_engine", fut);
 
         MatcherHandle { sender: tx }
@@ -118,6 +130,24 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
             })
     }
 
+    /// the winning searcher order of every pool, ranked by [`TobRank`] like
+    /// the order pool ranks them
+    pub fn top_searcher_orders<'a>(
+        searcher: impl IntoIterator<Item = &'a OrderWithStorageData<TopOfBlockOrder>>
+    ) -> HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> {
+        let mut top = HashMap::<PoolId, &OrderWithStorageData<TopOfBlockOrder>>::new();
+        for order in searcher {
+            let best = top.entry(order.pool_id).or_insert(order);
+            if TobRank(order) > TobRank(*best) {
+                *best = order;
+            }
+        }
+
+        top.into_iter()
+            .map(|(pool_id, order)| (pool_id, order.clone()))
+            .collect()
+    }
+
     pub fn build_non_proposal_books(
         limit: Vec<OrderWithStorageData<GroupedVanillaOrder>>,
         pool_snapshots: &HashMap<PoolId, (Address, Address, PoolSnapshot, u16)>
@@ -159,25 +189,19 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
         // them.  This is ugly and inefficient right now
         let books = Self::build_books(&preproposals, &pool_snapshots);
 
-        let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> = preproposals
-            .iter()
-            .flat_map(|p| p.searcher.iter())
-            .fold(HashMap::new(), |mut acc, order| {
-                acc.entry(order.pool_id).or_insert(order.clone());
-                acc
-            });
+        let searcher_orders =
+            Self::top_searcher_orders(preproposals.iter().flat_map(|p| p.searcher.iter()));
 
         let mut solution_set = JoinSet::new();
         books.into_iter().for_each(|b| {
+            let b = b.with_self_trade_prevention(self.self_trade_prevention);
             let searcher = searcher_orders.get(&b.id()).cloned();
             // Using spawn-blocking here is not BAD but it might be suboptimal as it allows
             // us to spawn many more tasks that the CPu has threads.  Better solution is a
             // dedicated threadpool and some suggest the `rayon` crate.  This is probably
             // not a problem while I'm testing, but leaving this note here as it may be
             // important for future efficiency gains
-            solution_set.spawn_blocking(move || {
-                SimpleCheckpointStrategy::run(&b).map(|s| s.solution(searcher))
-            });
+            solution_set.spawn_blocking(move || solve_book(&b, searcher));
         });
         let mut solutions = Vec::new();
         while let Some(res) = solution_set.join_next().await {
@@ -218,23 +242,18 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
     ) -> eyre::Result<BundleEstimate> {
         let books = Self::build_non_proposal_books(limit.clone(), &pool_snapshots);
 
-        let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> =
-            searcher.into_iter().fold(HashMap::new(), |mut acc, order| {
-                acc.entry(order.pool_id).or_insert(order);
-                acc
-            });
+        let searcher_orders = Self::top_searcher_orders(&searcher);
 
         let mut solution_set = JoinSet::new();
         books.into_iter().for_each(|b| {
+            let b = b.with_self_trade_prevention(self.self_trade_prevention);
             let searcher = searcher_orders.get(&b.id()).cloned();
             // Using spawn-blocking here is not BAD but it might be suboptimal as it allows
             // us to spawn many more tasks that the CPu has threads.  Better solution is a
             // dedicated threadpool and some suggest the `rayon` crate.  This is probably
             // not a problem while I'm testing, but leaving this note here as it may be
             // important for future efficiency gains
-            solution_set.spawn_blocking(move || {
-                SimpleCheckpointStrategy::run(&b).map(|s| s.solution(searcher))
-            });
+            solution_set.spawn_blocking(move || solve_book(&b, searcher));
         });
 
         let mut solutions = Vec::new();
@@ -255,10 +274,15 @@ impl<TP: TaskSpawner + 'static, V: BundleValidatorHandle> MatchingManager<TP, V>
 pub async fn manager_thread<TP: TaskSpawner + 'static, V: BundleValidatorHandle>(
     mut input: Receiver<MatcherCommand>,
     tp: Arc<TP>,
-    validation_handle: V
+    validation_handle: V,
+    self_trade_prevention: SelfTradePrevention
 ) {
-    let manager =
-        MatchingManager { _futures: FuturesUnordered::default(), _tp: tp, validation_handle };
+    let manager = MatchingManager {
+        _futures: FuturesUnordered::default(),
+        _tp: tp,
+        validation_handle,
+        self_trade_prevention
+    };
 
     while let Some(c) = input.recv().await {
         match c {
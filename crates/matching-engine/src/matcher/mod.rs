@@ -3,8 +3,22 @@ use angstrom_types::{
     matching::SqrtPriceX96,
     orders::{OrderPrice, OrderVolume}
 };
+use serde::Deserialize;
 pub use volume::VolumeFillMatcher;
 
+/// How the matcher treats a sender with orders on both sides of a book. Every
+/// node has to run the same mode, or they won't agree on the solutions.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfTradePrevention {
+    /// orders are matched regardless of who sent them
+    #[default]
+    Allow,
+    /// an order is never matched against an order of its own sender, the next
+    /// order on the other side is tried instead
+    SkipCounterparty
+}
+
 /// Preliminary implementation of a struct that captures all the information
 /// we'd want to get out of a finished match for us to use for heurestics and
 /// evaluation
@@ -1,16 +1,17 @@
 use std::{cell::Cell, cmp::Ordering};
 
-use alloy::primitives::U256;
+use alloy::primitives::{Address, U256};
 use angstrom_types::{
-    matching::{uniswap::PoolPrice, Ray, SqrtPriceX96},
+    matching::{uniswap::PoolPrice, Ray},
     orders::{NetAmmOrder, OrderFillState, OrderOutcome, PoolSolution},
     sol_bindings::{
         grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
-        rpc_orders::TopOfBlockOrder
+        rpc_orders::TopOfBlockOrder,
+        RawPoolOrder
     }
 };
 
-use super::Solution;
+use super::{SelfTradePrevention, Solution};
 use crate::book::{
     order::{OrderContainer, OrderExclusion},
     OrderBook
@@ -30,14 +31,20 @@ pub enum VolumeFillMatchEndReason {
 pub struct VolumeFillMatcher<'a> {
     book:             &'a OrderBook,
     bid_idx:          Cell<usize>,
+    /// first bid that isn't filled yet
+    bid_head:         Cell<usize>,
     pub bid_outcomes: Vec<OrderFillState>,
     bid_xpool:        Vec<Option<OrderExclusion>>,
     ask_idx:          Cell<usize>,
+    /// first ask that isn't filled yet
+    ask_head:         Cell<usize>,
     pub ask_outcomes: Vec<OrderFillState>,
     ask_xpool:        Vec<Option<OrderExclusion>>,
     amm_price:        Option<PoolPrice<'a>>,
     amm_outcome:      Option<NetAmmOrder>,
     current_partial:  Option<OrderWithStorageData<GroupedVanillaOrder>>,
+    /// senders whose bids are left with only their own asks to match
+    passed_bidders:   Vec<Address>,
     results:          Solution,
     // A checkpoint should never have a checkpoint stored within itself, otherwise this gets gnarly
     checkpoint:       Option<Box<Self>>
@@ -60,14 +67,17 @@ impl<'a> VolumeFillMatcher<'a> {
         Self {
             book,
             bid_idx: Cell::new(0),
+            bid_head: Cell::new(0),
             bid_outcomes,
             bid_xpool,
             ask_idx: Cell::new(0),
+            ask_head: Cell::new(0),
             ask_outcomes,
             ask_xpool,
             amm_price,
             amm_outcome: None,
             current_partial: None,
+            passed_bidders: Vec::new(),
             results: Solution::default(),
             checkpoint: None
         }
@@ -82,14 +92,17 @@ impl<'a> VolumeFillMatcher<'a> {
         let checkpoint = Self {
             book:            self.book,
             bid_idx:         self.bid_idx.clone(),
+            bid_head:        self.bid_head.clone(),
             bid_outcomes:    self.bid_outcomes.clone(),
             bid_xpool:       self.bid_xpool.clone(),
             ask_idx:         self.ask_idx.clone(),
+            ask_head:        self.ask_head.clone(),
             ask_outcomes:    self.ask_outcomes.clone(),
             ask_xpool:       self.ask_xpool.clone(),
             amm_price:       self.amm_price.clone(),
             amm_outcome:     self.amm_outcome.clone(),
             current_partial: self.current_partial.clone(),
+            passed_bidders:  self.passed_bidders.clone(),
             results:         self.results.clone(),
             checkpoint:      None
         };
@@ -109,31 +122,65 @@ impl<'a> VolumeFillMatcher<'a> {
             return false;
         };
         let Self {
-            bid_idx, bid_outcomes, ask_idx, ask_outcomes, amm_price, current_partial, ..
+            bid_idx,
+            bid_head,
+            bid_outcomes,
+            ask_idx,
+            ask_head,
+            ask_outcomes,
+            amm_price,
+            current_partial,
+            passed_bidders,
+            ..
         } = *checkpoint;
         self.bid_idx = bid_idx;
+        self.bid_head = bid_head;
         self.bid_outcomes = bid_outcomes;
         self.ask_idx = ask_idx;
+        self.ask_head = ask_head;
         self.ask_outcomes = ask_outcomes;
         self.amm_price = amm_price;
         self.current_partial = current_partial;
+        self.passed_bidders = passed_bidders;
         true
     }
 
     pub fn fill(&mut self) -> VolumeFillMatchEndReason {
+        let skip_self_trades =
+            self.book.self_trade_prevention() == SelfTradePrevention::SkipCounterparty;
         {
             loop {
                 let bid = match self.current_partial {
                     Some(ref o) if o.is_bid => OrderContainer::BookOrderFragment(o),
                     _ => {
+                        // a partially filled ask can't take a bid of its own sender
+                        let excluded_sender = self
+                            .current_partial
+                            .as_ref()
+                            .filter(|_| skip_self_trades)
+                            .map(|o| o.from());
+                        let passed_bidders = &self.passed_bidders;
                         if let Some(o) = Self::next_order_from_book(
                             true,
                             &self.bid_idx,
+                            &self.bid_head,
                             self.book.bids(),
                             &self.bid_outcomes,
-                            self.amm_price.as_ref()
+                            self.amm_price.as_ref(),
+                            |sender| {
+                                excluded_sender == Some(sender) || passed_bidders.contains(&sender)
+                            }
                         ) {
                             o
+                        } else if excluded_sender.is_some()
+                            && self
+                                .current_partial
+                                .as_ref()
+                                .is_some_and(|o| o.is_partial())
+                        {
+                            // the bids left are the ask's own, other asks may still take them
+                            self.current_partial = None;
+                            continue
                         } else {
                             return VolumeFillMatchEndReason::NoMoreBids
                         }
@@ -142,14 +189,31 @@ impl<'a> VolumeFillMatcher<'a> {
                 let ask = match self.current_partial {
                     Some(ref o) if !o.is_bid => OrderContainer::BookOrderFragment(o),
                     _ => {
+                        let excluded_sender = bid.sender().filter(|_| skip_self_trades);
                         if let Some(o) = Self::next_order_from_book(
                             false,
                             &self.ask_idx,
+                            &self.ask_head,
                             self.book.asks(),
                             &self.ask_outcomes,
-                            self.amm_price.as_ref()
+                            self.amm_price.as_ref(),
+                            |sender| excluded_sender == Some(sender)
                         ) {
                             o
+                        } else if let Some(sender) = excluded_sender {
+                            // the asks left are the bidder's own, none of its bids can be
+                            // matched anymore but the bids of other senders still can
+                            // an exact bid can't be left partially filled
+                            if self
+                                .current_partial
+                                .as_ref()
+                                .is_some_and(|o| !o.is_partial())
+                            {
+                                return VolumeFillMatchEndReason::NoMoreAsks
+                            }
+                            self.current_partial = None;
+                            self.passed_bidders.push(sender);
+                            continue
                         } else {
                             return VolumeFillMatchEndReason::NoMoreBids
                         }
@@ -267,27 +331,39 @@ impl<'a> VolumeFillMatcher<'a> {
         }
     }
 
+    /// The next order of a side, either from the book or the AMM. Book orders
+    /// of senders `skip_sender` returns true for are passed over and stay
+    /// unfilled, so they are found again for the next counterparty. `head` is
+    /// the first unfilled order of the side, the scan starts there.
     fn next_order_from_book<'b>(
         is_bid: bool,
         index: &Cell<usize>,
+        head: &Cell<usize>,
         book: &'a [OrderWithStorageData<GroupedVanillaOrder>],
         fill_state: &[OrderFillState],
-        amm: Option<&PoolPrice<'a>>
+        amm: Option<&PoolPrice<'a>>,
+        skip_sender: impl Fn(Address) -> bool
     ) -> Option<OrderContainer<'a, 'b>> {
-        let mut cur_idx = index.get();
+        let mut cur_idx = head.get();
+        while fill_state
+            .get(cur_idx)
+            .is_some_and(|state| !matches!(state, OrderFillState::Unfilled))
+        {
+            cur_idx += 1;
+        }
+        head.set(cur_idx);
         // Find the next unfilled order - we need to work with the index separately
         while cur_idx < fill_state.len() {
             match &fill_state[cur_idx] {
-                OrderFillState::Unfilled => break,
+                OrderFillState::Unfilled if !skip_sender(book[cur_idx].from()) => break,
                 _ => cur_idx += 1
             }
         }
         let book_order = book.get(cur_idx);
         // See if our AMM takes precedence
         amm.and_then(|amm_price| {
-            let target_price = book_order
-                .map(|o| SqrtPriceX96::from(Ray::from(*OrderContainer::BookOrder(o).price())));
-            amm_price.order_to_target(target_price, !is_bid)
+            let book_price = book_order.map(|o| Ray::from(*OrderContainer::BookOrder(o).price()));
+            amm_price.order_to_book_price(book_price, !is_bid)
         })
         .map(OrderContainer::AMM)
         .or_else(|| {
@@ -328,17 +404,21 @@ impl<'a> VolumeFillMatcher<'a> {
 mod tests {
     use std::cell::Cell;
 
-    use alloy::primitives::Uint;
+    use alloy::{primitives::Uint, signers::local::PrivateKeySigner};
     use angstrom_types::{
         matching::Ray,
         orders::OrderFillState,
-        primitive::PoolId,
+        primitive::{PoolId, ANGSTROM_DOMAIN},
         sol_bindings::grouped_orders::{GroupedVanillaOrder, OrderWithStorageData}
     };
-    use testing_tools::type_generator::orders::UserOrderBuilder;
+    use testing_tools::type_generator::orders::{SigningInfo, UserOrderBuilder};
 
     use super::VolumeFillMatcher;
-    use crate::book::OrderBook;
+    use crate::{
+        book::{order::OrderContainer, OrderBook},
+        matcher::SelfTradePrevention,
+        simulation::amm::single_position_amm
+    };
 
     #[test]
     fn runs_cleanly_on_empty_book() {
@@ -410,6 +490,114 @@ mod tests {
         );
     }
 
+    #[test]
+    fn self_trade_prevention_skips_own_orders() {
+        let pool_id = PoolId::random();
+        let high_price = Ray::from(Uint::from(1_000_000_000_u128));
+        let mid_price = Ray::from(Uint::from(1_000_000_u128));
+        let low_price = Ray::from(Uint::from(1_000_u128));
+        let (alice, bob) = (PrivateKeySigner::random(), PrivateKeySigner::random());
+        let signed_by = |wallet: &PrivateKeySigner| {
+            Some(SigningInfo {
+                domain:  ANGSTROM_DOMAIN,
+                address: wallet.address(),
+                key:     wallet.credential().clone()
+            })
+        };
+        let order = |wallet: &PrivateKeySigner, price: Ray, is_bid: bool| {
+            UserOrderBuilder::new()
+                .exact()
+                .amount(10)
+                .min_price(price)
+                .signing_key(signed_by(wallet))
+                .with_storage()
+                .is_bid(is_bid)
+                .build()
+        };
+        let bid = order(&alice, high_price, true);
+        // alice's own ask is the cheapest one
+        let own_ask = order(&alice, low_price, false);
+        let other_ask = order(&bob, mid_price, false);
+
+        let filled = |mode: SelfTradePrevention| {
+            let book = OrderBook::new(
+                pool_id,
+                None,
+                vec![bid.clone()],
+                vec![own_ask.clone(), other_ask.clone()],
+                None
+            )
+            .with_self_trade_prevention(mode);
+            let mut matcher = VolumeFillMatcher::new(&book);
+            matcher.fill();
+            matcher
+                .solution(None)
+                .limit
+                .into_iter()
+                .filter(|outcome| outcome.outcome == OrderFillState::CompleteFill)
+                .map(|outcome| outcome.id)
+                .collect::<Vec<_>>()
+        };
+
+        let allowed = filled(SelfTradePrevention::Allow);
+        assert!(allowed.contains(&own_ask.order_id));
+        assert!(!allowed.contains(&other_ask.order_id));
+
+        let prevented = filled(SelfTradePrevention::SkipCounterparty);
+        assert!(prevented.contains(&bid.order_id));
+        assert!(prevented.contains(&other_ask.order_id));
+        assert!(!prevented.contains(&own_ask.order_id));
+    }
+
+    #[test]
+    fn self_trade_prevention_moves_on_to_the_next_bid() {
+        let pool_id = PoolId::random();
+        let high_price = Ray::from(Uint::from(1_000_000_000_u128));
+        let mid_price = Ray::from(Uint::from(1_000_000_u128));
+        let low_price = Ray::from(Uint::from(1_000_u128));
+        let (alice, bob) = (PrivateKeySigner::random(), PrivateKeySigner::random());
+        let order = |wallet: &PrivateKeySigner, price: Ray, is_bid: bool| {
+            UserOrderBuilder::new()
+                .exact()
+                .amount(10)
+                .min_price(price)
+                .signing_key(Some(SigningInfo {
+                    domain:  ANGSTROM_DOMAIN,
+                    address: wallet.address(),
+                    key:     wallet.credential().clone()
+                }))
+                .with_storage()
+                .is_bid(is_bid)
+                .build()
+        };
+        // the only ask is alice's own, so her better priced bid has to be passed over
+        let own_bid = order(&alice, high_price, true);
+        let other_bid = order(&bob, mid_price, true);
+        let ask = order(&alice, low_price, false);
+
+        let book = OrderBook::new(
+            pool_id,
+            None,
+            vec![own_bid.clone(), other_bid.clone()],
+            vec![ask.clone()],
+            None
+        )
+        .with_self_trade_prevention(SelfTradePrevention::SkipCounterparty);
+        let mut matcher = VolumeFillMatcher::new(&book);
+        matcher.fill();
+        let filled = matcher
+            .solution(None)
+            .limit
+            .into_iter()
+            .filter(|outcome| outcome.outcome == OrderFillState::CompleteFill)
+            .map(|outcome| outcome.id)
+            .collect::<Vec<_>>();
+
+        assert!(filled.contains(&other_bid.order_id));
+        assert!(filled.contains(&ask.order_id));
+        assert!(!filled.contains(&own_bid.order_id));
+    }
+
     fn basic_order_book(
         is_bid: bool,
         count: usize,
@@ -436,8 +624,66 @@ mod tests {
         let fill_state: Vec<OrderFillState> =
             book.iter().map(|_| OrderFillState::Unfilled).collect();
         let amm = None;
-        let next_order =
-            VolumeFillMatcher::next_order_from_book(is_bid, &index, &book, &fill_state, amm);
+        let next_order = VolumeFillMatcher::next_order_from_book(
+            is_bid,
+            &index,
+            &index,
+            &book,
+            &fill_state,
+            amm,
+            |_| false
+        );
         assert!(next_order.is_none())
     }
+
+    #[test]
+    fn pool_fee_stops_amm_short_of_book_price() {
+        let book = basic_order_book(true, 1, Ray::from(0.9_f64), 0);
+        let fill_state = vec![OrderFillState::Unfilled];
+        let amm_end_price = |fee_in_e6: u32| {
+            let snapshot = single_position_amm(0, 10000, 2e36 as u128)
+                .unwrap()
+                .with_fee(fee_in_e6);
+            let amm = snapshot.current_price();
+            let next = VolumeFillMatcher::next_order_from_book(
+                true,
+                &Cell::new(0),
+                &Cell::new(0),
+                &book,
+                &fill_state,
+                Some(&amm),
+                |_| false
+            );
+            let Some(OrderContainer::AMM(amm_order)) = next else {
+                panic!("AMM should have been ahead of the book order")
+            };
+            *amm_order.end_bound.price()
+        };
+
+        let no_fee = amm_end_price(0);
+        let with_fee = amm_end_price(3000);
+        assert!(with_fee > no_fee, "AMM bid should stop at a higher price once the fee is paid");
+    }
+
+    #[test]
+    fn pool_fee_is_charged_on_amm_price_and_quantity() {
+        let amm_price_and_quantity = |fee_in_e6: u32| {
+            let snapshot = single_position_amm(0, 10000, 2e36 as u128)
+                .unwrap()
+                .with_fee(fee_in_e6);
+            let amm = snapshot
+                .current_price()
+                .order_to_target(None, false)
+                .unwrap();
+            let limit = (*amm.end_bound.price()).into();
+            let amm = OrderContainer::AMM(amm);
+            (amm.price(), amm.quantity(limit))
+        };
+
+        let (no_fee_price, no_fee_quantity) = amm_price_and_quantity(0);
+        let (fee_price, fee_quantity) = amm_price_and_quantity(3000);
+        // a bidding AMM pays its fee out of the price book asks get
+        assert!(fee_price < no_fee_price);
+        assert!(fee_quantity < no_fee_quantity);
+    }
 }
@@ -0,0 +1,211 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration
+};
+
+use alloy::primitives::BlockNumber;
+use angstrom_types::{orders::PoolSolution, primitive::PoolId};
+use futures::Stream;
+use tokio::{
+    sync::{
+        broadcast,
+        mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender}
+    },
+    time::{interval_at, Instant, Interval, MissedTickBehavior}
+};
+
+/// number of match outcomes a lagging subscriber can fall behind by
+const MATCH_OUTCOME_BUFFER: usize = 100;
+
+/// When the matching step runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchingSchedule {
+    /// once for every new block, following the chain's cadence
+    #[default]
+    PerBlock,
+    /// at a fixed cadence independent of blocks, for intra-block auctions
+    Interval(Duration),
+    /// only when triggered through [`MatchingSchedulerHandle::trigger_match`]
+    OnDemand
+}
+
+/// What caused a matching run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchTrigger {
+    Block(BlockNumber),
+    Interval,
+    /// a manual run, only the given pool is matched
+    Manual(PoolId)
+}
+
+impl MatchTrigger {
+    /// the pool to match, `None` if all pools are matched
+    pub fn pool_id(&self) -> Option<PoolId> {
+        match self {
+            Self::Manual(pool_id) => Some(*pool_id),
+            Self::Block(_) | Self::Interval => None
+        }
+    }
+}
+
+/// The solutions of a single matching run.
+#[derive(Debug, Clone)]
+pub struct MatchOutcome {
+    pub trigger:   MatchTrigger,
+    pub solutions: Vec<PoolSolution>
+}
+
+/// Decides when matching runs. Yields a [`MatchTrigger`] every time a run is
+/// due, whoever polls it runs the matching and hands the solutions back
+/// through [`MatchingScheduler::publish`].
+///
+/// Manual triggers are honoured whatever the schedule is.
+pub struct MatchingScheduler {
+    schedule:      MatchingSchedule,
+    /// created on first poll so the scheduler can be built outside a runtime
+    interval:      Option<Interval>,
+    pending_block: Option<BlockNumber>,
+    manual:        UnboundedReceiver<PoolId>,
+    outcomes:      broadcast::Sender<MatchOutcome>
+}
+
+impl MatchingScheduler {
+    pub fn new() -> (Self, MatchingSchedulerHandle) {
+        let (manual_tx, manual_rx) = unbounded_channel();
+        let (outcomes, _) = broadcast::channel(MATCH_OUTCOME_BUFFER);
+        let handle = MatchingSchedulerHandle { manual: manual_tx, outcomes: outcomes.clone() };
+
+        let this = Self {
+            schedule: MatchingSchedule::default(),
+            interval: None,
+            pending_block: None,
+            manual: manual_rx,
+            outcomes
+        };
+
+        (this, handle)
+    }
+
+    pub fn with_schedule(mut self, schedule: MatchingSchedule) -> Self {
+        self.schedule = schedule;
+        self.interval = None;
+        self
+    }
+
+    pub fn schedule(&self) -> MatchingSchedule {
+        self.schedule
+    }
+
+    /// a run is only scheduled for the block when matching per block, if
+    /// several blocks arrive before the scheduler is polled only the latest
+    /// one is matched
+    pub fn on_new_block(&mut self, block_number: BlockNumber) {
+        if self.schedule == MatchingSchedule::PerBlock {
+            self.pending_block = Some(block_number);
+        }
+    }
+
+    /// hands the solutions of a run to the subscribers
+    pub fn publish(&self, trigger: MatchTrigger, solutions: Vec<PoolSolution>) {
+        let _ = self.outcomes.send(MatchOutcome { trigger, solutions });
+    }
+}
+
+impl Stream for MatchingScheduler {
+    type Item = MatchTrigger;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if let Poll::Ready(Some(pool_id)) = this.manual.poll_recv(cx) {
+            return Poll::Ready(Some(MatchTrigger::Manual(pool_id)))
+        }
+
+        match this.schedule {
+            MatchingSchedule::PerBlock => {
+                if let Some(block_number) = this.pending_block.take() {
+                    return Poll::Ready(Some(MatchTrigger::Block(block_number)))
+                }
+            }
+            MatchingSchedule::Interval(period) => {
+                let interval = this.interval.get_or_insert_with(|| {
+                    let mut interval = interval_at(Instant::now() + period, period);
+                    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                    interval
+                });
+                if interval.poll_tick(cx).is_ready() {
+                    return Poll::Ready(Some(MatchTrigger::Interval))
+                }
+            }
+            MatchingSchedule::OnDemand => {}
+        }
+
+        Poll::Pending
+    }
+}
+
+/// Triggers manual matching runs and subscribes to their results.
+#[derive(Debug, Clone)]
+pub struct MatchingSchedulerHandle {
+    manual:   UnboundedSender<PoolId>,
+    outcomes: broadcast::Sender<MatchOutcome>
+}
+
+impl MatchingSchedulerHandle {
+    /// runs matching for the pool right away, regardless of the schedule.
+    /// Returns false if the scheduler is gone
+    pub fn trigger_match(&self, pool_id: PoolId) -> bool {
+        self.manual.send(pool_id).is_ok()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<MatchOutcome> {
+        self.outcomes.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use angstrom_types::primitive::PoolId;
+    use futures::{FutureExt, StreamExt};
+
+    use super::{MatchTrigger, MatchingSchedule, MatchingScheduler};
+
+    #[tokio::test]
+    async fn test_manual_trigger_runs_on_any_schedule() {
+        let (scheduler, handle) = MatchingScheduler::new();
+        let mut scheduler = scheduler.with_schedule(MatchingSchedule::OnDemand);
+        let pool_id = PoolId::random();
+
+        scheduler.on_new_block(10);
+        assert!(scheduler.next().now_or_never().is_none());
+
+        assert!(handle.trigger_match(pool_id));
+        assert_eq!(scheduler.next().await, Some(MatchTrigger::Manual(pool_id)));
+    }
+
+    #[tokio::test]
+    async fn test_per_block_runs_once_for_latest_block() {
+        let (mut scheduler, _handle) = MatchingScheduler::new();
+
+        scheduler.on_new_block(10);
+        scheduler.on_new_block(11);
+        assert_eq!(scheduler.next().await, Some(MatchTrigger::Block(11)));
+        assert!(scheduler.next().now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_interval_runs_without_blocks() {
+        let (scheduler, _handle) = MatchingScheduler::new();
+        let mut scheduler =
+            scheduler.with_schedule(MatchingSchedule::Interval(Duration::from_millis(50)));
+
+        scheduler.on_new_block(10);
+        assert!(scheduler.next().now_or_never().is_none());
+
+        let trigger = tokio::time::timeout(Duration::from_secs(1), scheduler.next()).await;
+        assert_eq!(trigger, Ok(Some(MatchTrigger::Interval)));
+    }
+}
@@ -62,7 +62,8 @@ pub fn order_distribution(
                 },
                 pool_id: FixedBytes::default(),
                 valid_block: 0,
-                tob_reward: U256::ZERO
+                tob_reward: U256::ZERO,
+                filled_amount: 0
             }
         })
         .take(number)
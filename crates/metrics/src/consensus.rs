@@ -1,6 +1,6 @@
 use std::{collections::HashMap, time::Instant};
 
-use prometheus::{IntGauge, IntGaugeVec};
+use prometheus::{IntCounter, IntGauge, IntGaugeVec};
 
 use crate::METRICS_ENABLED;
 
@@ -14,6 +14,8 @@ struct ConsensusMetrics {
     proposal_build_time_per_block: IntGaugeVec,
     // time (ms) it takes proposal verification per block
     proposal_verification_time_per_block: IntGaugeVec,
+    // number of rounds that gave up on their block before it was committed
+    timeouts: IntCounter,
     // map of block numbers to their consensus start times
     block_consensus_start_times: HashMap<u64, Instant>
 }
@@ -45,11 +47,18 @@ impl Default for ConsensusMetrics {
         )
         .unwrap();
 
+        let timeouts = prometheus::register_int_counter!(
+            "consensus_timeouts",
+            "number of rounds that gave up on their block before it was committed",
+        )
+        .unwrap();
+
         Self {
             block_height,
             proposal_build_time_per_block,
             completion_time_per_block,
             proposal_verification_time_per_block,
+            timeouts,
             block_consensus_start_times: HashMap::default()
         }
     }
@@ -77,6 +86,10 @@ impl ConsensusMetrics {
             .set(time as i64);
     }
 
+    pub fn increment_timeouts(&self) {
+        self.timeouts.inc();
+    }
+
     pub fn set_block_height(&mut self, block_number: u64) {
         self.block_height.set(block_number as i64);
         self.block_consensus_start_times
@@ -135,6 +148,12 @@ impl ConsensusMetricsWrapper {
         }
     }
 
+    pub fn increment_timeouts(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.increment_timeouts()
+        }
+    }
+
     pub fn set_block_height(&mut self, block_number: u64) {
         if let Some(this) = self.0.as_mut() {
             this.set_block_height(block_number)
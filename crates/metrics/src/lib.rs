@@ -12,4 +12,7 @@ pub use order_pool::*;
 mod consensus;
 pub use consensus::*;
 
+mod validation;
+pub use validation::*;
+
 pub static METRICS_ENABLED: OnceLock<bool> = OnceLock::new();
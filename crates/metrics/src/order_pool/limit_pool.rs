@@ -1,5 +1,5 @@
 use angstrom_types::primitive::PoolId;
-use prometheus::{IntGauge, IntGaugeVec};
+use prometheus::{GaugeVec, IntGauge, IntGaugeVec};
 
 use crate::METRICS_ENABLED;
 
@@ -14,7 +14,9 @@ struct VanillaLimitOrderPoolMetrics {
     // number of pending orders per pool
     pending_orders:       IntGaugeVec,
     // number of parked orders per pool
-    parked_orders:        IntGaugeVec
+    parked_orders:        IntGaugeVec,
+    // (bid - ask) / (bid + ask) notional of the pending orders per pool
+    book_imbalance:       GaugeVec
 }
 
 impl Default for VanillaLimitOrderPoolMetrics {
@@ -51,12 +53,20 @@ impl Default for VanillaLimitOrderPoolMetrics {
         )
         .unwrap();
 
+        let book_imbalance = prometheus::register_gauge_vec!(
+            "vanilla_limit_order_pool_book_imbalance",
+            "bid/ask notional imbalance of the pending orders per pool",
+            &["pool_id"]
+        )
+        .unwrap();
+
         Self {
             total_orders,
             parked_orders,
             pending_orders,
             total_parked_orders,
-            total_pending_orders
+            total_pending_orders,
+            book_imbalance
         }
     }
 }
@@ -121,6 +131,13 @@ impl VanillaLimitOrderPoolMetrics {
             .sub(count as i64);
         self.decr_total_pending_orders(count);
     }
+
+    pub fn set_book_imbalance(&self, pool_id: PoolId, imbalance: f64) {
+        self.book_imbalance
+            .get_metric_with_label_values(&[&pool_id.to_string()])
+            .unwrap()
+            .set(imbalance);
+    }
 }
 
 #[derive(Clone)]
@@ -166,6 +183,12 @@ impl VanillaLimitOrderPoolMetricsWrapper {
             this.decr_pending_orders(pool_id, count)
         }
     }
+
+    pub fn set_book_imbalance(&self, pool_id: PoolId, imbalance: f64) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_book_imbalance(pool_id, imbalance)
+        }
+    }
 }
 
 #[derive(Clone)]
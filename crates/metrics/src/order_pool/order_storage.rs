@@ -17,7 +17,9 @@ struct OrderStorageMetrics {
     // number of cancelled composable orders
     cancelled_composable_orders: IntGauge,
     // number of cancelled searcher orders
-    cancelled_searcher_orders:   IntGauge
+    cancelled_searcher_orders:   IntGauge,
+    // number of orders across all pools
+    total_orders:                IntGauge
 }
 
 impl Default for OrderStorageMetrics {
@@ -64,6 +66,12 @@ impl Default for OrderStorageMetrics {
         )
         .unwrap();
 
+        let total_orders = prometheus::register_int_gauge!(
+            "order_storage_total_orders",
+            "number of orders across all pools",
+        )
+        .unwrap();
+
         Self {
             vanilla_limit_orders,
             searcher_orders,
@@ -71,7 +79,8 @@ impl Default for OrderStorageMetrics {
             composable_limit_orders,
             cancelled_vanilla_orders,
             cancelled_composable_orders,
-            cancelled_searcher_orders
+            cancelled_searcher_orders,
+            total_orders
         }
     }
 }
@@ -120,6 +129,10 @@ impl OrderStorageMetrics {
     pub fn incr_cancelled_searcher_orders(&self, count: usize) {
         self.cancelled_searcher_orders.add(count as i64);
     }
+
+    pub fn set_total_orders(&self, count: usize) {
+        self.total_orders.set(count as i64);
+    }
 }
 
 #[derive(Clone)]
@@ -207,4 +220,10 @@ impl OrderStorageMetricsWrapper {
             this.decr_pending_finalization_orders(count)
         }
     }
+
+    pub fn set_total_orders(&self, count: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_total_orders(count)
+        }
+    }
 }
@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+use prometheus::{Histogram, IntCounter, IntGauge};
+
+use crate::METRICS_ENABLED;
+
+#[derive(Clone)]
+struct ValidationMetrics {
+    // number of order signatures that were recovered
+    signature_verifications: IntCounter,
+    // number of order signatures that were served from the cache
+    signature_cache_hits:    IntCounter,
+    // number of order validations answered from the result cache
+    result_cache_hits:       IntCounter,
+    // number of order validations that weren't in the result cache
+    result_cache_misses:     IntCounter,
+    // number of validations currently running
+    validations_in_flight:   IntGauge,
+    // number of validations waiting to start
+    validations_queued:      IntGauge,
+    // number of pairs the token price generator holds prices for
+    priced_pairs:            IntGauge,
+    // time (s) an order waits from reaching the validator until its
+    // validation starts
+    queue_wait:              Histogram,
+    // time (s) validating an order takes once started
+    processing_time:         Histogram
+}
+
+impl Default for ValidationMetrics {
+    fn default() -> Self {
+        let signature_verifications = prometheus::register_int_counter!(
+            "validation_signature_verifications",
+            "number of order signatures that were recovered",
+        )
+        .unwrap();
+
+        let signature_cache_hits = prometheus::register_int_counter!(
+            "validation_signature_cache_hits",
+            "number of order signatures that were served from the cache",
+        )
+        .unwrap();
+
+        let result_cache_hits = prometheus::register_int_counter!(
+            "validation_result_cache_hits",
+            "number of order validations answered from the result cache",
+        )
+        .unwrap();
+
+        let result_cache_misses = prometheus::register_int_counter!(
+            "validation_result_cache_misses",
+            "number of order validations that weren't in the result cache",
+        )
+        .unwrap();
+
+        let validations_in_flight = prometheus::register_int_gauge!(
+            "validation_in_flight",
+            "number of validations currently running",
+        )
+        .unwrap();
+
+        let validations_queued = prometheus::register_int_gauge!(
+            "validation_queued",
+            "number of validations waiting to start",
+        )
+        .unwrap();
+
+        let priced_pairs = prometheus::register_int_gauge!(
+            "validation_priced_pairs",
+            "number of pairs the token price generator holds prices for",
+        )
+        .unwrap();
+
+        let queue_wait = prometheus::register_histogram!(
+            "validation_queue_wait_seconds",
+            "time (s) an order waits from reaching the validator until its validation starts",
+        )
+        .unwrap();
+
+        let processing_time = prometheus::register_histogram!(
+            "validation_processing_seconds",
+            "time (s) validating an order takes once started",
+        )
+        .unwrap();
+
+        Self {
+            signature_verifications,
+            signature_cache_hits,
+            result_cache_hits,
+            result_cache_misses,
+            validations_in_flight,
+            validations_queued,
+            priced_pairs,
+            queue_wait,
+            processing_time
+        }
+    }
+}
+
+impl ValidationMetrics {
+    pub fn incr_signature_verifications(&self) {
+        self.signature_verifications.inc();
+    }
+
+    pub fn incr_signature_cache_hits(&self) {
+        self.signature_cache_hits.inc();
+    }
+
+    pub fn incr_result_cache_hits(&self) {
+        self.result_cache_hits.inc();
+    }
+
+    pub fn incr_result_cache_misses(&self) {
+        self.result_cache_misses.inc();
+    }
+
+    pub fn set_validations_in_flight(&self, in_flight: usize) {
+        self.validations_in_flight.set(in_flight as i64);
+    }
+
+    pub fn set_validations_queued(&self, queued: usize) {
+        self.validations_queued.set(queued as i64);
+    }
+
+    pub fn set_priced_pairs(&self, pairs: usize) {
+        self.priced_pairs.set(pairs as i64);
+    }
+
+    pub fn observe_queue_wait(&self, wait: Duration) {
+        self.queue_wait.observe(wait.as_secs_f64());
+    }
+
+    pub fn observe_processing_time(&self, time: Duration) {
+        self.processing_time.observe(time.as_secs_f64());
+    }
+}
+
+#[derive(Clone)]
+pub struct ValidationMetricsWrapper(Option<ValidationMetrics>);
+
+impl Default for ValidationMetricsWrapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ValidationMetricsWrapper {
+    pub fn new() -> Self {
+        Self(
+            METRICS_ENABLED
+                .get()
+                .copied()
+                .unwrap_or_default()
+                .then(ValidationMetrics::default)
+        )
+    }
+
+    pub fn incr_signature_verifications(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_signature_verifications()
+        }
+    }
+
+    pub fn incr_signature_cache_hits(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_signature_cache_hits()
+        }
+    }
+
+    pub fn incr_result_cache_hits(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_result_cache_hits()
+        }
+    }
+
+    pub fn incr_result_cache_misses(&self) {
+        if let Some(this) = self.0.as_ref() {
+            this.incr_result_cache_misses()
+        }
+    }
+
+    pub fn set_validations_in_flight(&self, in_flight: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_validations_in_flight(in_flight)
+        }
+    }
+
+    pub fn set_validations_queued(&self, queued: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_validations_queued(queued)
+        }
+    }
+
+    pub fn set_priced_pairs(&self, pairs: usize) {
+        if let Some(this) = self.0.as_ref() {
+            this.set_priced_pairs(pairs)
+        }
+    }
+
+    pub fn observe_queue_wait(&self, wait: Duration) {
+        if let Some(this) = self.0.as_ref() {
+            this.observe_queue_wait(wait)
+        }
+    }
+
+    pub fn observe_processing_time(&self, time: Duration) {
+        if let Some(this) = self.0.as_ref() {
+            this.observe_processing_time(time)
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use alloy::primitives::Address;
+use angstrom_types::{orders::OrderOrigin, sol_bindings::ext::RawPoolOrder};
+use validation::order::rejection::{Rejection, RejectionReason};
+
+/// Why an order was turned away by an [`AcceptancePolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum RejectReason {
+    #[error("sender {0} is not allowed to submit orders")]
+    SenderNotAllowed(Address),
+    #[error("token {0} is not allowed")]
+    TokenNotAllowed(Address),
+    #[error("{0}")]
+    Other(String)
+}
+
+impl From<&RejectReason> for Rejection {
+    fn from(reason: &RejectReason) -> Self {
+        let code = match reason {
+            RejectReason::SenderNotAllowed(_) => RejectionReason::SenderNotAllowed,
+            RejectReason::TokenNotAllowed(_) => RejectionReason::TokenNotPermitted,
+            RejectReason::Other(_) => RejectionReason::PolicyRejected
+        };
+
+        Self::new(code, reason.to_string())
+    }
+}
+
+/// Operator defined policy that every new order has to pass before it is sent
+/// to validation. Used for allowlists, token blocks or any other gating an
+/// operator wants without having to touch the validator.
+pub trait AcceptancePolicy: Send + Sync + Unpin + 'static {
+    fn check(&self, order: &impl RawPoolOrder, origin: OrderOrigin) -> Result<(), RejectReason>;
+}
+
+/// Default policy, accepts every order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAll;
+
+impl AcceptancePolicy for AllowAll {
+    fn check(&self, _: &impl RawPoolOrder, _: OrderOrigin) -> Result<(), RejectReason> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::address;
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    struct DenyToken(Address);
+
+    impl AcceptancePolicy for DenyToken {
+        fn check(&self, order: &impl RawPoolOrder, _: OrderOrigin) -> Result<(), RejectReason> {
+            if order.token_in() == self.0 || order.token_out() == self.0 {
+                return Err(RejectReason::TokenNotAllowed(self.0))
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_policy_rejects_denied_token() {
+        let denied = address!("1111111111111111111111111111111111111111");
+        let policy = DenyToken(denied);
+
+        let order = UserOrderBuilder::new().asset_in(denied).build();
+        assert_eq!(
+            policy.check(&order, OrderOrigin::External),
+            Err(RejectReason::TokenNotAllowed(denied))
+        );
+
+        let order = UserOrderBuilder::new().build();
+        assert!(policy.check(&order, OrderOrigin::External).is_ok());
+        assert!(AllowAll.check(&order, OrderOrigin::Local).is_ok());
+    }
+
+    #[test]
+    fn test_reject_reasons_map_to_rejection_codes() {
+        let cases = [
+            (RejectReason::SenderNotAllowed(Address::ZERO), RejectionReason::SenderNotAllowed),
+            (RejectReason::TokenNotAllowed(Address::ZERO), RejectionReason::TokenNotPermitted),
+            (RejectReason::Other("closed".to_string()), RejectionReason::PolicyRejected)
+        ];
+        for (reason, code) in cases {
+            assert_eq!(Rejection::from(&reason), Rejection::new(code, reason.to_string()));
+        }
+    }
+}
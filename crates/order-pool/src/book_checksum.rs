@@ -0,0 +1,41 @@
+use alloy::primitives::{keccak256, B256};
+
+/// Hash over the sorted order hashes of a book. Two nodes with the same
+/// orders get the same checksum no matter in which order they received them,
+/// so comparing checksums tells them whether they need to exchange hashes.
+///
+/// An empty book hashes to the keccak of no input.
+pub fn book_checksum(order_hashes: impl IntoIterator<Item = B256>) -> B256 {
+    let mut order_hashes = order_hashes.into_iter().collect::<Vec<_>>();
+    order_hashes.sort_unstable();
+    order_hashes.dedup();
+
+    keccak256(
+        order_hashes
+            .iter()
+            .flat_map(|hash| hash.0)
+            .collect::<Vec<u8>>()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use alloy::primitives::KECCAK256_EMPTY;
+
+    use super::*;
+
+    #[test]
+    fn test_checksum_ignores_arrival_order() {
+        let hashes = [B256::random(), B256::random(), B256::random()];
+        let mut reversed = hashes;
+        reversed.reverse();
+
+        assert_eq!(book_checksum(hashes), book_checksum(reversed));
+        assert_ne!(book_checksum(hashes), book_checksum(hashes[..2].to_vec()));
+    }
+
+    #[test]
+    fn test_empty_book_checksum() {
+        assert_eq!(book_checksum([]), KECCAK256_EMPTY);
+    }
+}
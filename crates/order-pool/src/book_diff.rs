@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, B256};
+use angstrom_types::{
+    orders::{OrderId, OrderSet},
+    primitive::PoolId,
+    sol_bindings::RespendAvoidanceMethod
+};
+
+/// Orders that changed between two states of the book, by order hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookDiff {
+    /// in the current book but not the previous one
+    pub added:    Vec<B256>,
+    /// in the previous book but gone from the current one
+    pub removed:  Vec<B256>,
+    /// `(previous, current)` orders of a user that took the same nonce or
+    /// flash block slot in the same pool
+    pub replaced: Vec<(B256, B256)>
+}
+
+/// The slot a replacement order takes over from the one it replaces.
+type ReplacementKey = (Address, PoolId, RespendAvoidanceMethod);
+
+impl BookDiff {
+    pub fn between<Limit, Searcher>(
+        previous: &OrderSet<Limit, Searcher>,
+        current: &OrderSet<Limit, Searcher>
+    ) -> Self {
+        let previous = ids(previous);
+        let current = ids(current);
+
+        let mut removed_by_key: HashMap<ReplacementKey, B256> = previous
+            .values()
+            .filter(|id| !current.contains_key(&id.hash))
+            .map(|id| (replacement_key(id), id.hash))
+            .collect();
+
+        let mut diff = Self::default();
+        for id in current
+            .values()
+            .filter(|id| !previous.contains_key(&id.hash))
+        {
+            match removed_by_key.remove(&replacement_key(id)) {
+                Some(replaced) => diff.replaced.push((replaced, id.hash)),
+                None => diff.added.push(id.hash)
+            }
+        }
+        diff.removed = removed_by_key.into_values().collect();
+
+        // keep the output stable regardless of the book's iteration order
+        diff.added.sort_unstable();
+        diff.removed.sort_unstable();
+        diff.replaced.sort_unstable();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.replaced.is_empty()
+    }
+}
+
+fn ids<Limit, Searcher>(set: &OrderSet<Limit, Searcher>) -> HashMap<B256, OrderId> {
+    set.limit
+        .iter()
+        .map(|order| order.order_id)
+        .chain(set.searcher.iter().map(|order| order.order_id))
+        .map(|id| (id.hash, id))
+        .collect()
+}
+
+fn replacement_key(id: &OrderId) -> ReplacementKey {
+    (id.address, id.pool_id, id.reuse_avoidance)
+}
+
+#[cfg(test)]
+mod test {
+    use angstrom_types::sol_bindings::grouped_orders::OrderWithStorageData;
+
+    use super::*;
+
+    fn id(address: Address, nonce: u64) -> OrderId {
+        OrderId {
+            address,
+            hash: B256::random(),
+            reuse_avoidance: RespendAvoidanceMethod::Nonce(nonce),
+            ..Default::default()
+        }
+    }
+
+    fn book(limit: &[OrderId]) -> OrderSet<(), ()> {
+        let limit = limit
+            .iter()
+            .map(|id| OrderWithStorageData { order_id: *id, ..Default::default() })
+            .collect();
+
+        OrderSet { limit, searcher: vec![] }
+    }
+
+    #[test]
+    fn test_diff_of_identical_books_is_empty() {
+        let orders = [id(Address::random(), 0), id(Address::random(), 0)];
+        assert!(BookDiff::between(&book(&orders), &book(&orders)).is_empty());
+    }
+
+    #[test]
+    fn test_diff_splits_added_removed_and_replaced() {
+        let user = Address::random();
+        let kept = id(Address::random(), 0);
+        let gone = id(user, 0);
+        let replaced = id(user, 1);
+        let replacement = OrderId { hash: B256::random(), ..replaced };
+        let new = id(Address::random(), 3);
+
+        let previous = book(&[kept, gone, replaced]);
+        let current = book(&[kept, replacement, new]);
+        let diff = BookDiff::between(&previous, &current);
+
+        assert_eq!(diff.added, vec![new.hash]);
+        assert_eq!(diff.removed, vec![gone.hash]);
+        assert_eq!(diff.replaced, vec![(replaced.hash, replacement.hash)]);
+    }
+}
@@ -0,0 +1,335 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, VecDeque}
+};
+
+use alloy::primitives::{Address, B256};
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// lowest false positive rate a filter is sized for, a rate of 0 would take
+/// an unbounded filter
+const MIN_FALSE_POSITIVE_RATE: f64 = 1e-9;
+
+/// How the hashes of cancelled orders are remembered until their cancellation
+/// expires. Configured as `"exact"` or `{ bloom = { .. } }`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelledOrdersMode {
+    /// every hash is kept exactly
+    #[default]
+    Exact,
+    /// only the most recent cancellations are kept exactly, older ones are
+    /// moved into a counting bloom filter
+    Bloom(CancelledOrdersBloom)
+}
+
+/// Sizing of the bloom filter backed cancelled orders set.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CancelledOrdersBloom {
+    /// cancellations kept exactly before they are moved into the filter
+    pub exact_capacity:      usize,
+    /// cancellations the filter is sized for
+    pub expected_items:      usize,
+    /// share of uncancelled orders wrongly rejected once the filter holds
+    /// `expected_items`, between 0 and 1 exclusive
+    #[serde(deserialize_with = "false_positive_rate")]
+    pub false_positive_rate: f64
+}
+
+fn false_positive_rate<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    let rate = f64::deserialize(deserializer)?;
+    if !(rate > 0.0 && rate < 1.0) {
+        return Err(D::Error::custom(format!("false positive rate {rate} isn't between 0 and 1")))
+    }
+
+    Ok(rate)
+}
+
+impl Default for CancelledOrdersBloom {
+    fn default() -> Self {
+        Self {
+            exact_capacity:      10_000,
+            expected_items:      250_000,
+            false_positive_rate: 0.00001
+        }
+    }
+}
+
+pub(crate) struct CancelRequest {
+    /// The address of the entity requesting the cancellation.
+    pub from:         Address,
+    // The time until the cancellation request is valid.
+    pub valid_until:  u64,
+    /// the cancellation arrived before the order it cancels
+    pub before_order: bool
+}
+
+/// Hashes of cancelled orders, so an order that propagates after its
+/// cancellation isn't accepted again.
+pub(crate) struct CancelledOrders {
+    exact:          HashMap<B256, CancelRequest>,
+    /// hashes of `exact` oldest first, only kept when there is a filter.
+    /// Entries that already expired are skipped when evicting
+    insertion:      VecDeque<B256>,
+    exact_capacity: usize,
+    filter:         Option<CountingBloomFilter>
+}
+
+impl CancelledOrders {
+    pub(crate) fn new(mode: CancelledOrdersMode) -> Self {
+        let (exact_capacity, filter) = match mode {
+            CancelledOrdersMode::Exact => (usize::MAX, None),
+            CancelledOrdersMode::Bloom(config) => {
+                (config.exact_capacity, Some(CountingBloomFilter::new(&config)))
+            }
+        };
+
+        Self { exact: HashMap::new(), insertion: VecDeque::new(), exact_capacity, filter }
+    }
+
+    pub(crate) fn insert(&mut self, order_hash: B256, from: Address, valid_until: u64) {
+        self.insert_request(order_hash, CancelRequest { from, valid_until, before_order: false });
+    }
+
+    /// a cancellation of an order that hasn't arrived yet, see
+    /// [`Self::take_before_order`]
+    pub(crate) fn insert_before_order(
+        &mut self,
+        order_hash: B256,
+        from: Address,
+        valid_until: u64
+    ) {
+        self.insert_request(order_hash, CancelRequest { from, valid_until, before_order: true });
+    }
+
+    /// Forgets the cancellation if `from` sent it before the order arrived,
+    /// returning whether it did. Cancellations moved into the filter can't
+    /// be told apart and are kept.
+    pub(crate) fn take_before_order(&mut self, order_hash: &B256, from: Address) -> bool {
+        let early = self
+            .exact
+            .get(order_hash)
+            .is_some_and(|request| request.before_order && request.from == from);
+        if early {
+            self.exact.remove(order_hash);
+        }
+
+        early
+    }
+
+    fn insert_request(&mut self, order_hash: B256, request: CancelRequest) {
+        let is_new = self.exact.insert(order_hash, request).is_none();
+        let Some(filter) = self.filter.as_mut() else { return };
+        if is_new {
+            self.insertion.push_back(order_hash);
+        }
+
+        while self.exact.len() > self.exact_capacity {
+            let Some(oldest) = self.insertion.pop_front() else { break };
+            if let Some(request) = self.exact.remove(&oldest) {
+                filter.insert(&oldest, request.valid_until);
+            }
+        }
+    }
+
+    pub(crate) fn contains(&self, order_hash: &B256) -> bool {
+        self.exact.contains_key(order_hash)
+            || self
+                .filter
+                .as_ref()
+                .is_some_and(|filter| filter.contains(order_hash))
+    }
+
+    /// whether `from` cancelled the order. The filter doesn't know who
+    /// cancelled, any order it reports is treated as cancelled by its sender
+    pub(crate) fn is_cancelled_by(&self, order_hash: &B256, from: Address) -> bool {
+        match self.exact.get(order_hash) {
+            Some(request) => request.from == from,
+            None => self
+                .filter
+                .as_ref()
+                .is_some_and(|filter| filter.contains(order_hash))
+        }
+    }
+
+    /// forgets the cancellations that were valid until before `now`
+    pub(crate) fn remove_expired(&mut self, now: u64) {
+        self.exact.retain(|_, request| request.valid_until >= now);
+        if let Some(filter) = self.filter.as_mut() {
+            self.insertion
+                .retain(|order_hash| self.exact.contains_key(order_hash));
+            filter.remove_expired(now);
+        }
+    }
+}
+
+/// Bloom filter with small counters instead of bits, so entries can be taken
+/// out again once they expire. Only a digest and the expiry of each entry are
+/// kept, a fraction of what the exact set needs per hash.
+struct CountingBloomFilter {
+    counters: Vec<u8>,
+    hashes:   u64,
+    /// (valid_until, digest) of every entry, soonest to expire first
+    expiries: BinaryHeap<Reverse<(u64, [u64; 2])>>
+}
+
+impl CountingBloomFilter {
+    fn new(config: &CancelledOrdersBloom) -> Self {
+        let items = config.expected_items.max(1) as f64;
+        let rate = config.false_positive_rate.max(MIN_FALSE_POSITIVE_RATE);
+        let ln2 = std::f64::consts::LN_2;
+        let size = (-items * rate.ln() / (ln2 * ln2)).ceil() as usize;
+        let hashes = ((size as f64 / items) * ln2).round().max(1.0) as u64;
+
+        Self { counters: vec![0; size.max(1)], hashes, expiries: BinaryHeap::new() }
+    }
+
+    fn digest(order_hash: &B256) -> [u64; 2] {
+        let first = u64::from_be_bytes(order_hash[..8].try_into().unwrap());
+        let second = u64::from_be_bytes(order_hash[8..16].try_into().unwrap());
+        [first, second | 1]
+    }
+
+    /// double hashing over the two halves of the digest
+    fn indices(&self, [first, second]: [u64; 2]) -> impl Iterator<Item = usize> + '_ {
+        let size = self.counters.len() as u64;
+        (0..self.hashes).map(move |i| (first.wrapping_add(i.wrapping_mul(second)) % size) as usize)
+    }
+
+    fn insert(&mut self, order_hash: &B256, valid_until: u64) {
+        let digest = Self::digest(order_hash);
+        for index in self.indices(digest).collect::<Vec<_>>() {
+            self.counters[index] = self.counters[index].saturating_add(1);
+        }
+        self.expiries.push(Reverse((valid_until, digest)));
+    }
+
+    fn contains(&self, order_hash: &B256) -> bool {
+        self.indices(Self::digest(order_hash))
+            .all(|index| self.counters[index] > 0)
+    }
+
+    fn remove_expired(&mut self, now: u64) {
+        while let Some(Reverse((valid_until, digest))) = self.expiries.peek().copied() {
+            if valid_until >= now {
+                break
+            }
+            self.expiries.pop();
+
+            for index in self.indices(digest).collect::<Vec<_>>() {
+                // a saturated counter can't tell how many entries share it, so
+                // it is never decremented
+                if self.counters[index] != u8::MAX {
+                    self.counters[index] -= 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn bloom(exact_capacity: usize) -> CancelledOrdersMode {
+        CancelledOrdersMode::Bloom(CancelledOrdersBloom {
+            exact_capacity,
+            expected_items: 1_000,
+            false_positive_rate: 0.0001
+        })
+    }
+
+    #[test]
+    fn test_old_cancellations_move_to_filter() {
+        let mut cancelled = CancelledOrders::new(bloom(2));
+        let from = Address::random();
+        let hashes = (0..10).map(|_| B256::random()).collect::<Vec<_>>();
+
+        hashes
+            .iter()
+            .for_each(|hash| cancelled.insert(*hash, from, 100));
+
+        assert_eq!(cancelled.exact.len(), 2);
+        assert!(hashes.iter().all(|hash| cancelled.contains(hash)));
+        // the filter can't tell who cancelled
+        assert!(cancelled.is_cancelled_by(&hashes[0], Address::random()));
+        assert!(!cancelled.is_cancelled_by(&hashes[9], Address::random()));
+        assert!(!cancelled.contains(&B256::random()));
+    }
+
+    #[test]
+    fn test_filtered_cancellations_expire() {
+        let mut cancelled = CancelledOrders::new(bloom(1));
+        let from = Address::random();
+        let (expiring, kept, recent) = (B256::random(), B256::random(), B256::random());
+
+        cancelled.insert(expiring, from, 100);
+        cancelled.insert(kept, from, 300);
+        cancelled.insert(recent, from, 300);
+
+        cancelled.remove_expired(200);
+        assert!(!cancelled.contains(&expiring));
+        assert!(cancelled.contains(&kept));
+        assert!(cancelled.contains(&recent));
+    }
+
+    #[test]
+    fn test_only_cancellations_sent_before_the_order_are_taken() {
+        let mut cancelled = CancelledOrders::new(CancelledOrdersMode::Exact);
+        let from = Address::random();
+        let (early, late) = (B256::random(), B256::random());
+
+        cancelled.insert_before_order(early, from, 100);
+        cancelled.insert(late, from, 100);
+
+        assert!(!cancelled.take_before_order(&early, Address::random()));
+        assert!(!cancelled.take_before_order(&late, from));
+        assert!(cancelled.take_before_order(&early, from));
+        assert!(!cancelled.contains(&early));
+        assert!(cancelled.contains(&late));
+    }
+
+    #[test]
+    fn test_false_positive_rate_is_validated() {
+        let mode = |rate: &str| {
+            serde_json::from_str::<CancelledOrdersMode>(&format!(
+                r#"{{"bloom": {{"false_positive_rate": {rate}}}}}"#
+            ))
+        };
+
+        assert!(mode("0").is_err());
+        assert!(mode("1").is_err());
+        assert!(mode("-0.1").is_err());
+        assert_eq!(
+            mode("0.001").unwrap(),
+            CancelledOrdersMode::Bloom(CancelledOrdersBloom {
+                false_positive_rate: 0.001,
+                ..Default::default()
+            })
+        );
+        let exact = serde_json::from_str::<CancelledOrdersMode>(r#""exact""#).unwrap();
+        assert_eq!(exact, CancelledOrdersMode::Exact);
+
+        // a filter built in code with a rate of 0 is still bounded
+        let bloom = CancelledOrdersBloom {
+            exact_capacity:      0,
+            expected_items:      1_000,
+            false_positive_rate: 0.0
+        };
+        let mut cancelled = CancelledOrders::new(CancelledOrdersMode::Bloom(bloom));
+        let hash = B256::random();
+        cancelled.insert(hash, Address::random(), 100);
+        assert!(cancelled.contains(&hash));
+    }
+
+    #[test]
+    fn test_exact_mode_keeps_every_hash() {
+        let mut cancelled = CancelledOrders::new(CancelledOrdersMode::Exact);
+        let from = Address::random();
+
+        (0..100).for_each(|_| cancelled.insert(B256::random(), from, 100));
+        assert_eq!(cancelled.exact.len(), 100);
+        assert!(cancelled.insertion.is_empty());
+    }
+}
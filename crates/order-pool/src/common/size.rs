@@ -24,8 +24,23 @@ impl SizeTracker {
         }
     }
 
-    #[allow(dead_code)]
+    /// whether `size` fits, without taking it
+    pub fn fits(&self, size: usize) -> bool {
+        self.fits_after_removing(0, size)
+    }
+
+    /// whether `size` would fit once `freed` is removed, without taking it
+    pub fn fits_after_removing(&self, freed: usize, size: usize) -> bool {
+        self.max
+            .map_or(true, |max| self.current.saturating_sub(freed) + size <= max)
+    }
+
+    /// takes `size` for an order whose fit was already checked
+    pub fn add_order(&mut self, size: usize) {
+        self.current += size;
+    }
+
     pub fn remove_order(&mut self, size: usize) {
-        self.current -= size;
+        self.current = self.current.saturating_sub(size);
     }
 }
@@ -1,5 +1,9 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use angstrom_types::primitive::PoolId;
 
+use crate::{cancelled_orders::CancelledOrdersMode, order_lifetime::MinOrderLifetime};
+
 /// Guarantees max orders per sender
 pub const ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER: usize = 16;
 
@@ -15,35 +19,56 @@ pub const SEARCHER_SUBPOOL_MAX_ORDERS_DEFAULT: usize = 100;
 /// The default maximum allowed size of the searcher subpool.
 pub const SEARCHER_SUBPOOL_MAX_SIZE_MB_DEFAULT: usize = 5;
 
+/// The default maximum allowed number of orders across all pools.
+pub const MAX_TOTAL_ORDERS_DEFAULT: usize = 100_000;
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
     /// pool ids
-    pub ids:               Vec<PoolId>,
+    pub ids:                 Vec<PoolId>,
     /// Max number of transaction in the pending sub-pool
-    pub lo_pending_limit:  LimitSubPoolLimit,
+    pub lo_pending_limit:    LimitSubPoolLimit,
     /// Max number of transaction in the queued sub-pool
-    pub lo_queued_limit:   LimitSubPoolLimit,
+    pub lo_queued_limit:     LimitSubPoolLimit,
     /// Max number of transaction in the parked sub-pool
-    pub lo_parked_limit:   LimitSubPoolLimit,
+    pub lo_parked_limit:     LimitSubPoolLimit,
     /// Max number of transaction in the composable limit sub-pool
-    pub cl_pending_limit:  LimitSubPoolLimit,
+    pub cl_pending_limit:    LimitSubPoolLimit,
     /// Max number of transaction in the searcher & composable searcher sub-pool
-    pub s_pending_limit:   SearcherSubPoolLimit,
+    pub s_pending_limit:     SearcherSubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
-    pub max_account_slots: usize
+    pub max_account_slots:   usize,
+    /// Max number of orders across all pools and sub-pools
+    pub max_total_orders:    usize,
+    /// Number of blocks of order events to keep for historical queries,
+    /// disabled if `None`
+    pub history_blocks:      Option<u64>,
+    /// How the hashes of cancelled orders are remembered
+    pub cancelled_orders:    CancelledOrdersMode,
+    /// How long orders of a pool rest before they can be cancelled, orders
+    /// of pools not in here can be cancelled right away
+    pub min_order_lifetimes: HashMap<PoolId, MinOrderLifetime>,
+    /// Write-ahead log of the book, replayed on startup so resting orders
+    /// survive a crash. Disabled if `None`
+    pub order_wal:           Option<PathBuf>
 }
 
 impl Default for PoolConfig {
     fn default() -> Self {
         Self {
-            ids:               vec![],
-            lo_pending_limit:  Default::default(),
-            lo_queued_limit:   Default::default(),
-            lo_parked_limit:   Default::default(),
-            cl_pending_limit:  Default::default(),
-            s_pending_limit:   Default::default(),
-            max_account_slots: ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER
+            ids:                 vec![],
+            lo_pending_limit:    Default::default(),
+            lo_queued_limit:     Default::default(),
+            lo_parked_limit:     Default::default(),
+            cl_pending_limit:    Default::default(),
+            s_pending_limit:     Default::default(),
+            max_account_slots:   ORDER_POOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_total_orders:    MAX_TOTAL_ORDERS_DEFAULT,
+            history_blocks:      None,
+            cancelled_orders:    CancelledOrdersMode::Exact,
+            min_order_lifetimes: HashMap::new(),
+            order_wal:           None
         }
     }
 }
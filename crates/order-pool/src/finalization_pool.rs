@@ -48,6 +48,10 @@ impl FinalizationPool {
         self.id_to_orders.contains_key(order)
     }
 
+    pub fn get_order(&self, order: &FixedBytes<32>) -> Option<&OrderWithStorageData<AllOrders>> {
+        self.id_to_orders.get(order)
+    }
+
     pub fn reorg(
         &mut self,
         orders: Vec<FixedBytes<32>>
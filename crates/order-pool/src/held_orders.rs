@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use alloy::primitives::{Address, BlockNumber, B256};
+use angstrom_types::{
+    primitive::PoolId,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+
+/// orders held for pools that aren't ready, across all pools
+const MAX_HELD_ORDERS: usize = 1024;
+/// orders held for a single pool, so orders for one pool that may never be
+/// initialized can't crowd out those of pools that are about to sync
+const MAX_HELD_ORDERS_PER_POOL: usize = 256;
+/// orders held for a single sender across all pools
+pub const MAX_HELD_ORDERS_PER_SENDER: usize = 16;
+/// blocks an order is held before it's dropped, about ten minutes
+const HELD_ORDER_BLOCKS: u64 = 50;
+
+/// Why an order couldn't be held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum HoldError {
+    #[error("too many orders are held for pools that aren't ready")]
+    Full,
+    #[error("too many orders are held for the pool")]
+    PoolFull,
+    #[error("the sender has too many orders held for pools that aren't ready")]
+    SenderFull
+}
+
+struct HeldOrder {
+    order:      AllOrders,
+    held_until: BlockNumber
+}
+
+/// Orders for pools validation hasn't synced yet or that aren't initialized,
+/// validated again once the initialization watcher reports the pool. Held
+/// orders are capped per pool and per sender and dropped after
+/// [`HELD_ORDER_BLOCKS`].
+#[derive(Default)]
+pub(crate) struct HeldOrders {
+    pools:     HashMap<PoolId, HashMap<B256, HeldOrder>>,
+    by_sender: HashMap<Address, usize>,
+    len:       usize
+}
+
+impl HeldOrders {
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// whether orders are held for the pool
+    pub(crate) fn is_holding(&self, pool_id: &PoolId) -> bool {
+        self.pools.contains_key(pool_id)
+    }
+
+    /// holds the order until its pool is ready or `block` is
+    /// [`HELD_ORDER_BLOCKS`] behind
+    pub(crate) fn hold(
+        &mut self,
+        pool_id: PoolId,
+        order: AllOrders,
+        block: BlockNumber
+    ) -> Result<(), HoldError> {
+        let hash = order.order_hash();
+        let held = self.pools.get(&pool_id);
+        if held.is_some_and(|held| held.contains_key(&hash)) {
+            return Ok(())
+        }
+        if self.len >= MAX_HELD_ORDERS {
+            return Err(HoldError::Full)
+        }
+        if held.is_some_and(|held| held.len() >= MAX_HELD_ORDERS_PER_POOL) {
+            return Err(HoldError::PoolFull)
+        }
+        let sender = order.from();
+        if self.by_sender.get(&sender).copied().unwrap_or_default() >= MAX_HELD_ORDERS_PER_SENDER {
+            return Err(HoldError::SenderFull)
+        }
+
+        *self.by_sender.entry(sender).or_default() += 1;
+        self.len += 1;
+        self.pools
+            .entry(pool_id)
+            .or_default()
+            .insert(hash, HeldOrder { order, held_until: block + HELD_ORDER_BLOCKS });
+
+        Ok(())
+    }
+
+    /// the pool is ready, returns the orders held for it
+    pub(crate) fn release(&mut self, pool_id: &PoolId) -> Vec<AllOrders> {
+        self.pools
+            .remove(pool_id)
+            .unwrap_or_default()
+            .into_values()
+            .map(|held| {
+                self.forget(held.order.from());
+                held.order
+            })
+            .collect()
+    }
+
+    /// Drops the orders held for too long and flash orders for a block before
+    /// `block`, they can't become valid anymore. Returns the dropped orders'
+    /// hashes.
+    pub(crate) fn expire(&mut self, block: BlockNumber) -> Vec<B256> {
+        let mut expired = vec![];
+        for orders in self.pools.values_mut() {
+            orders.retain(|hash, held| {
+                let keep = held.held_until > block
+                    && held.order.flash_block().map_or(true, |b| b >= block);
+                if !keep {
+                    expired.push((*hash, held.order.from()));
+                }
+                keep
+            });
+        }
+        self.pools.retain(|_, orders| !orders.is_empty());
+
+        expired
+            .into_iter()
+            .map(|(hash, sender)| {
+                self.forget(sender);
+                hash
+            })
+            .collect()
+    }
+
+    fn forget(&mut self, sender: Address) {
+        self.len -= 1;
+        if let Some(count) = self.by_sender.get_mut(&sender) {
+            *count -= 1;
+            if *count == 0 {
+                self.by_sender.remove(&sender);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use angstrom_types::sol_bindings::{
+        grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder
+    };
+
+    use super::*;
+
+    fn order(from: Address, nonce: u64) -> AllOrders {
+        let mut order = ExactStandingOrder { nonce, ..Default::default() };
+        order.meta.from = from;
+        AllOrders::Standing(StandingVariants::Exact(order))
+    }
+
+    #[test]
+    fn test_orders_are_capped_per_sender_and_pool() {
+        let mut held = HeldOrders::default();
+        let (pool_id, other_pool) = (PoolId::random(), PoolId::random());
+        let sender = Address::random();
+
+        for nonce in 0..MAX_HELD_ORDERS_PER_SENDER as u64 {
+            let pool = if nonce % 2 == 0 { pool_id } else { other_pool };
+            held.hold(pool, order(sender, nonce), 1).unwrap();
+        }
+        let over = order(sender, MAX_HELD_ORDERS_PER_SENDER as u64);
+        assert_eq!(held.hold(pool_id, over, 1), Err(HoldError::SenderFull));
+
+        // the sender's orders for the other pool no longer count once it's
+        // ready
+        assert_eq!(held.release(&other_pool).len(), MAX_HELD_ORDERS_PER_SENDER / 2);
+        let over = order(sender, MAX_HELD_ORDERS_PER_SENDER as u64);
+        assert!(held.hold(pool_id, over, 1).is_ok());
+
+        let crowded = PoolId::random();
+        let limit = MAX_HELD_ORDERS_PER_POOL as u64;
+        for nonce in 0..limit {
+            held.hold(crowded, order(Address::random(), nonce), 1)
+                .unwrap();
+        }
+        let over = order(Address::random(), limit);
+        assert_eq!(held.hold(crowded, over, 1), Err(HoldError::PoolFull));
+        // other pools still take orders
+        assert!(held
+            .hold(other_pool, order(Address::random(), 0), 1)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_orders_held_too_long_expire() {
+        let mut held = HeldOrders::default();
+        let pool_id = PoolId::random();
+        let sender = Address::random();
+        let early = order(sender, 0);
+        let late = order(sender, 1);
+
+        held.hold(pool_id, early.clone(), 1).unwrap();
+        held.hold(pool_id, late, 10).unwrap();
+
+        assert!(held.expire(HELD_ORDER_BLOCKS).is_empty());
+        assert_eq!(held.expire(HELD_ORDER_BLOCKS + 1), vec![early.order_hash()]);
+        assert_eq!(held.len(), 1);
+        assert_eq!(held.by_sender[&sender], 1);
+
+        held.expire(HELD_ORDER_BLOCKS + 10);
+        assert_eq!(held.len(), 0);
+        assert!(!held.is_holding(&pool_id));
+        assert!(held.by_sender.is_empty());
+    }
+}
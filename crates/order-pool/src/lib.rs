@@ -1,54 +1,163 @@
+mod acceptance;
+mod book_checksum;
+mod book_diff;
+mod cancelled_orders;
 mod common;
 mod config;
 mod finalization_pool;
+mod held_orders;
 mod limit;
+mod order_history;
 mod order_indexer;
+mod order_lifetime;
 pub mod order_storage;
+mod order_wal;
+mod paused_pools;
 
 mod searcher;
 mod validator;
 
 use std::future::Future;
 
-use alloy::primitives::{Address, FixedBytes, B256};
+pub use acceptance::{AcceptancePolicy, AllowAll, RejectReason};
+use alloy::primitives::{Address, FixedBytes, B256, U256};
 use angstrom_types::{
-    orders::{OrderLocation, OrderOrigin, OrderStatus},
+    orders::{OrderLocation, OrderOrigin, OrderStatus, SignedCancellation, TransactionId},
     sol_bindings::grouped_orders::{AllOrders, OrderWithStorageData}
 };
 pub use angstrom_utils::*;
+pub use book_checksum::book_checksum;
+pub use book_diff::BookDiff;
+pub use cancelled_orders::{CancelledOrdersBloom, CancelledOrdersMode};
 pub use config::PoolConfig;
+pub use held_orders::MAX_HELD_ORDERS_PER_SENDER;
+pub use order_history::{OrderEvent, OrderEventKind};
 pub use order_indexer::*;
+pub use order_lifetime::{CancelOrderError, MinOrderLifetime};
+pub use order_wal::{replay, OrderWal, WalEntry};
+pub use paused_pools::PausedPools;
 use tokio_stream::wrappers::BroadcastStream;
+use validation::order::{
+    rejection::Rejection,
+    state::pools::{PoolInitParams, TrackedPool}
+};
 
 #[derive(Debug, Clone)]
 pub enum PoolManagerUpdate {
     NewOrder(OrderWithStorageData<AllOrders>),
     FilledOrder(u64, OrderWithStorageData<AllOrders>),
     UnfilledOrders(OrderWithStorageData<AllOrders>),
-    CancelledOrder { user: Address, pool_id: FixedBytes<32>, order_hash: B256 }
+    /// A fill-or-kill order that could not be fully executed in its block and
+    /// was dropped from the pool.
+    KilledOrder(u64, OrderWithStorageData<AllOrders>),
+    CancelledOrder {
+        user:       Address,
+        pool_id:    FixedBytes<32>,
+        order_hash: B256
+    },
+    /// A resting order that was dropped from the full pool to make room for
+    /// an order that tips more.
+    EvictedOrder(OrderWithStorageData<AllOrders>),
+    /// Orders are held for a pool whose uniswap pool isn't initialized yet,
+    /// sent once per pool with what angstrom's `initializePool` takes.
+    PoolInitRequired(PoolInitParams)
+}
+
+/// Pool side counters reported as part of the node status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// connected strom peers
+    pub peer_count:     usize,
+    /// orders currently held by the pool
+    pub pending_orders: usize
+}
+
+/// Notional of the pending limit orders on each side of a pool's book, both
+/// in the pool's quote token (token1).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BookNotional {
+    pub bid_notional: U256,
+    pub ask_notional: U256
+}
+
+impl BookNotional {
+    /// `(bid - ask) / (bid + ask)`, from -1 for a book of only asks to 1 for
+    /// one of only bids. `None` when both sides are empty.
+    pub fn imbalance(&self) -> Option<f64> {
+        let total = self.bid_notional.saturating_add(self.ask_notional);
+        if total.is_zero() {
+            return None
+        }
+
+        // scale both down to 64 bits, the precision of the f64 is lower anyway
+        let shift = total.bit_len().saturating_sub(64);
+        let to_f64 = |value: U256| (value >> shift).to::<u64>() as f64;
+        Some((to_f64(self.bid_notional) - to_f64(self.ask_notional)) / to_f64(total))
+    }
 }
 
 /// The OrderPool Trait is how other processes can interact with the orderpool
 /// asyncly. This allows for requesting data and providing data from different
-/// threads efficiently.
+/// threads efficiently. Implemented for `Arc`s of handles too, so one handle
+/// can be shared between components without cloning it for each.
+#[auto_impl::auto_impl(Arc)]
 pub trait OrderPoolHandle: Send + Sync + Clone + Unpin + 'static {
-    fn new_order(&self, origin: OrderOrigin, order: AllOrders)
-        -> impl Future<Output = bool> + Send;
+    /// submits the order to the pool, errors with why it was turned away if
+    /// it wasn't accepted
+    fn new_order(
+        &self,
+        origin: OrderOrigin,
+        order: AllOrders
+    ) -> impl Future<Output = Result<(), Rejection>> + Send;
 
     fn subscribe_orders(&self) -> BroadcastStream<PoolManagerUpdate>;
 
     fn pending_orders(&self, sender: Address) -> impl Future<Output = Vec<AllOrders>> + Send;
 
-    fn cancel_order(&self, sender: Address, order_hash: B256) -> impl Future<Output = bool> + Send;
+    /// the sender's orders held in the pool along with their storage data
+    fn orders_by_sender(
+        &self,
+        sender: Address
+    ) -> impl Future<Output = Vec<OrderWithStorageData<AllOrders>>> + Send;
 
+    /// cancels the order of the cancellation's signer and forwards the
+    /// cancellation to peers. Errors if the order hasn't rested for its
+    /// pool's minimum lifetime yet
+    fn cancel_order(
+        &self,
+        cancellation: SignedCancellation
+    ) -> impl Future<Output = Result<bool, CancelOrderError>> + Send;
+
+    /// the pool's orders at the location, only the best `depth` of each side
+    /// if a depth is given
     fn fetch_orders_from_pool(
         &self,
         pool_id: FixedBytes<32>,
-        location: OrderLocation
+        location: OrderLocation,
+        depth: Option<usize>
     ) -> impl Future<Output = Vec<AllOrders>> + Send;
 
+    /// the order's status and the id it's tracked under, `None` if the pool
+    /// doesn't hold the order
     fn fetch_order_status(
         &self,
         order_hash: B256
-    ) -> impl Future<Output = Option<OrderStatus>> + Send;
+    ) -> impl Future<Output = Option<(OrderStatus, TransactionId)>> + Send;
+
+    /// why the order was turned away, `None` if it wasn't or the rejection is
+    /// too old to be remembered
+    fn order_rejection(&self, order_hash: B256) -> impl Future<Output = Option<Rejection>> + Send;
+
+    fn pool_status(&self) -> impl Future<Output = PoolStatus> + Send;
+
+    /// bid and ask notional of the pool's pending limit orders, `None` for an
+    /// unknown pool
+    fn book_notional(
+        &self,
+        pool_id: FixedBytes<32>
+    ) -> impl Future<Output = Option<BookNotional>> + Send;
+
+    /// the angstrom pools the node tracks, including ones initialized since
+    /// it started
+    fn pools(&self) -> impl Future<Output = Vec<TrackedPool>> + Send;
 }
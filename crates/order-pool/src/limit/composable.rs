@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use alloy::primitives::U256;
 use angstrom_metrics::ComposableLimitOrderPoolMetricsWrapper;
 use angstrom_types::{
     primitive::{NewInitializedPool, PoolId},
@@ -57,6 +58,18 @@ impl ComposableLimitPool {
             .owned_map(|| self.metrics.decr_all_orders(pool_id, 1))
     }
 
+    /// the order that tips the least across all pools. Each pool tips in its
+    /// own token0, so `tip` has to bring them to a common unit
+    pub fn lowest_tip_order(
+        &self,
+        tip: impl Fn(&OrderWithStorageData<GroupedComposableOrder>) -> U256
+    ) -> Option<&OrderWithStorageData<GroupedComposableOrder>> {
+        self.map
+            .values()
+            .filter_map(|pool| pool.lowest_tip_order())
+            .min_by_key(|order| tip(order))
+    }
+
     pub fn new_pool(&mut self, pool: NewInitializedPool) {
         let old_is_none = self.map.insert(pool.id, PendingPool::new()).is_none();
         assert!(old_is_none);
@@ -1,17 +1,21 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, ops::RangeInclusive};
 
-use alloy::primitives::{FixedBytes, B256};
+use alloy::primitives::{FixedBytes, B256, U256};
 use angstrom_types::{
     orders::{OrderId, OrderStatus},
     primitive::{NewInitializedPool, PoolId},
-    sol_bindings::grouped_orders::{
-        AllOrders, GroupedComposableOrder, GroupedUserOrder, GroupedVanillaOrder,
-        OrderWithStorageData
+    sol_bindings::{
+        grouped_orders::{
+            AllOrders, GroupedComposableOrder, GroupedUserOrder, GroupedVanillaOrder,
+            OrderWithStorageData
+        },
+        RawPoolOrder
     }
 };
+use validation::common::TokenPriceSnapshot;
 
 use self::{composable::ComposableLimitPool, standard::LimitPool};
-use crate::common::SizeTracker;
+use crate::{common::SizeTracker, BookNotional};
 mod composable;
 mod parked;
 mod pending;
@@ -24,7 +28,9 @@ pub struct LimitOrderPool {
     /// Sub-pool of all composable orders
     composable_orders: ComposableLimitPool,
     /// The size of the current transactions.
-    size:              SizeTracker
+    size:              SizeTracker,
+    /// prices tips are compared at, each pool tips in its own token0
+    token_prices:      TokenPriceSnapshot
 }
 
 impl LimitOrderPool {
@@ -32,10 +38,16 @@ impl LimitOrderPool {
         Self {
             composable_orders: ComposableLimitPool::new(ids),
             limit_orders:      LimitPool::new(ids),
-            size:              SizeTracker { max: max_size, current: 0 }
+            size:              SizeTracker { max: max_size, current: 0 },
+            token_prices:      TokenPriceSnapshot::default()
         }
     }
 
+    /// compares tips at `token_prices` when picking the order to evict
+    pub fn set_token_prices(&mut self, token_prices: TokenPriceSnapshot) {
+        self.token_prices = token_prices;
+    }
+
     pub fn get_order(&self, id: &OrderId) -> Option<OrderWithStorageData<GroupedUserOrder>> {
         self.limit_orders
             .get_order(id.pool_id, id.hash)
@@ -59,34 +71,99 @@ impl LimitOrderPool {
         self.limit_orders.get_order_status(order_hash)
     }
 
+    /// Adds the order, evicting the resting order that tips the least if the
+    /// pool is full. Returns the evicted order
     pub fn add_composable_order(
         &mut self,
         order: OrderWithStorageData<GroupedComposableOrder>
-    ) -> Result<(), LimitPoolError> {
+    ) -> Result<Option<OrderWithStorageData<GroupedUserOrder>>, LimitPoolError> {
         let size = order.size();
-        if !self.size.has_space(size) {
-            return Err(LimitPoolError::MaxSize)
-        }
+        let to_evict = self.order_to_evict(size, self.eth_tip_per_gas(&order))?;
+        self.composable_orders.add_order(order)?;
 
-        self.composable_orders.add_order(order)
+        Ok(self.take_room(size, to_evict))
     }
 
+    /// Adds the order, evicting the resting order that tips the least if the
+    /// pool is full. Returns the evicted order
     pub fn add_vanilla_order(
         &mut self,
         order: OrderWithStorageData<GroupedVanillaOrder>
-    ) -> Result<(), LimitPoolError> {
+    ) -> Result<Option<OrderWithStorageData<GroupedUserOrder>>, LimitPoolError> {
         let size = order.size();
-        if !self.size.has_space(size) {
+        let to_evict = self.order_to_evict(size, self.eth_tip_per_gas(&order))?;
+        self.limit_orders.add_order(order)?;
+
+        Ok(self.take_room(size, to_evict))
+    }
+
+    /// The order to evict so a new order of `size` tipping `tip` wei per gas
+    /// fits, `None` if it fits as is. A full pool only makes room by evicting
+    /// its lowest tipping order, and only for an order that tips more than
+    /// it. Nothing is changed, so the pool stays as is if the new order
+    /// can't be added
+    fn order_to_evict(&self, size: usize, tip: U256) -> Result<Option<OrderId>, LimitPoolError> {
+        if self.size.fits(size) {
+            return Ok(None)
+        }
+
+        let vanilla = self
+            .limit_orders
+            .lowest_tip_order(|order| self.eth_tip_per_gas(order))
+            .map(|order| (self.eth_tip_per_gas(order), order.order_id, order.size()));
+        let composable = self
+            .composable_orders
+            .lowest_tip_order(|order| self.eth_tip_per_gas(order))
+            .map(|order| (self.eth_tip_per_gas(order), order.order_id, order.size()));
+
+        let Some((_, id, freed)) = vanilla
+            .into_iter()
+            .chain(composable)
+            .min_by_key(|(lowest_tip, ..)| *lowest_tip)
+            .filter(|(lowest_tip, ..)| *lowest_tip < tip)
+        else {
+            return Err(LimitPoolError::MaxSize)
+        };
+        if !self.size.fits_after_removing(freed, size) {
             return Err(LimitPoolError::MaxSize)
         }
 
-        self.limit_orders.add_order(order)
+        Ok(Some(id))
+    }
+
+    /// the order's tip per gas in wei. Orders whose token0 has no price tip
+    /// nothing that can be compared, so they're the first to be evicted and
+    /// can't evict others
+    fn eth_tip_per_gas<O: RawPoolOrder>(&self, order: &OrderWithStorageData<O>) -> U256 {
+        let (token0, token1) = if order.token_in() < order.token_out() {
+            (order.token_in(), order.token_out())
+        } else {
+            (order.token_out(), order.token_in())
+        };
+
+        self.token_prices
+            .eth_value(token0, token1, order.effective_tip_per_gas())
+            .unwrap_or_default()
+    }
+
+    /// accounts for a newly added order of `size`, evicting the order picked
+    /// by [`Self::order_to_evict`]. Returns the evicted order
+    fn take_room(
+        &mut self,
+        size: usize,
+        to_evict: Option<OrderId>
+    ) -> Option<OrderWithStorageData<GroupedUserOrder>> {
+        let evicted = to_evict.and_then(|id| self.remove_order(&id));
+        self.size.add_order(size);
+
+        evicted
     }
 
     pub fn remove_order(&mut self, id: &OrderId) -> Option<OrderWithStorageData<GroupedUserOrder>> {
         self.limit_orders
             .remove_order(id.pool_id, id.hash)
             .and_then(|value| {
+                self.size.remove_order(value.size());
                 value
                     .try_map_inner(|this| Ok(GroupedUserOrder::Vanilla(this)))
                     .ok()
@@ -95,6 +172,7 @@ impl LimitOrderPool {
                 self.composable_orders
                     .remove_order(id.pool_id, id.hash)
                     .and_then(|value| {
+                        self.size.remove_order(value.size());
                         value
                             .try_map_inner(|this| Ok(GroupedUserOrder::Composable(this)))
                             .ok()
@@ -119,6 +197,49 @@ impl LimitOrderPool {
             .unwrap_or_default()
     }
 
+    /// the pool's best `depth` pending bids followed by its best `depth`
+    /// pending asks
+    pub fn top_orders_from_pool(&self, pool: FixedBytes<32>, depth: usize) -> Vec<AllOrders> {
+        self.limit_orders
+            .pending_orders
+            .get(&pool)
+            .map(|pool| {
+                pool.bids()
+                    .take(depth)
+                    .chain(pool.asks().take(depth))
+                    .map(|p| p.order.clone().into())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn sorted_bids(
+        &self,
+        pool_id: PoolId
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        self.limit_orders.sorted_bids(pool_id)
+    }
+
+    pub fn sorted_asks(
+        &self,
+        pool_id: PoolId
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        self.limit_orders.sorted_asks(pool_id)
+    }
+
+    /// pending vanilla orders of the pool priced within `prices`
+    pub fn orders_in_price_range(
+        &self,
+        pool_id: PoolId,
+        prices: RangeInclusive<U256>
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        self.limit_orders.orders_in_price_range(pool_id, prices)
+    }
+
+    pub fn book_notional(&self, pool_id: PoolId) -> Result<BookNotional, LimitPoolError> {
+        self.limit_orders.book_notional(pool_id)
+    }
+
     pub fn park_order(&mut self, id: &OrderId) {
         self.limit_orders.park_order(id);
     }
@@ -133,8 +254,170 @@ impl LimitOrderPool {
 pub enum LimitPoolError {
     #[error("Pool has reached max size, and order doesn't satisify replacment requirements")]
     MaxSize,
+    #[error("Order pool is holding the max of {0} orders")]
+    MaxOrders(usize),
     #[error("No pool was found for address: {0} ")]
     NoPool(PoolId),
     #[error(transparent)]
     Unknown(#[from] eyre::Error)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy::primitives::Address;
+    use angstrom_types::{
+        orders::OrderPriorityData,
+        sol_bindings::{grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder}
+    };
+    use validation::common::WETH_ADDRESS;
+
+    use super::*;
+
+    /// an order tipping in wei, its pool's token0 is weth
+    fn tipped_order(pool_id: PoolId, max_gas: u128) -> OrderWithStorageData<GroupedVanillaOrder> {
+        priced_order(pool_id, WETH_ADDRESS, max_gas)
+    }
+
+    /// an order trading `token` against weth, tipping
+    /// `(max_gas - 100) / 10` of the pair's token0 per gas
+    fn priced_order(
+        pool_id: PoolId,
+        token: Address,
+        max_gas: u128
+    ) -> OrderWithStorageData<GroupedVanillaOrder> {
+        let inner = ExactStandingOrder {
+            max_extra_fee_asset0: max_gas,
+            asset_in: token,
+            asset_out: WETH_ADDRESS,
+            ..Default::default()
+        };
+        OrderWithStorageData {
+            order: GroupedVanillaOrder::Standing(StandingVariants::Exact(inner)),
+            pool_id,
+            is_currently_valid: true,
+            priority_data: OrderPriorityData {
+                gas: U256::from(100),
+                gas_units: 10,
+                ..Default::default()
+            },
+            order_id: OrderId { hash: B256::random(), pool_id, ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn full_pool_evicts_lowest_tip_for_higher_tip() {
+        let pool_id = PoolId::random();
+        let size = std::mem::size_of::<GroupedVanillaOrder>();
+        let mut pool = LimitOrderPool::new(&[pool_id], Some(2 * size));
+
+        let low = tipped_order(pool_id, 200);
+        let high = tipped_order(pool_id, 1_100);
+        assert!(pool.add_vanilla_order(low.clone()).unwrap().is_none());
+        assert!(pool.add_vanilla_order(high.clone()).unwrap().is_none());
+
+        // tips no more than the lowest resting order
+        assert!(matches!(
+            pool.add_vanilla_order(tipped_order(pool_id, 200)),
+            Err(LimitPoolError::MaxSize)
+        ));
+
+        let outbidding = tipped_order(pool_id, 600);
+        let evicted = pool.add_vanilla_order(outbidding.clone()).unwrap().unwrap();
+        assert_eq!(evicted.order_id.hash, low.order_id.hash);
+        assert!(pool.get_order(&low.order_id).is_none());
+        assert!(pool.get_order(&outbidding.order_id).is_some());
+        assert!(pool.get_order(&high.order_id).is_some());
+
+        // removing an order frees its space
+        pool.remove_order(&high.order_id);
+        assert!(pool
+            .add_vanilla_order(tipped_order(pool_id, 0))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn tips_of_different_pools_are_compared_in_eth() {
+        // worth 2 eth, so a tip of 30 is worth 60 wei
+        let token = Address::with_last_byte(1);
+        let unpriced = Address::with_last_byte(2);
+        let token_prices = TokenPriceSnapshot::default();
+        token_prices.publish(HashMap::from([((token, WETH_ADDRESS), U256::from(5e17))]));
+
+        let (token_pool, weth_pool, unpriced_pool) =
+            (PoolId::random(), PoolId::random(), PoolId::random());
+        let size = std::mem::size_of::<GroupedVanillaOrder>();
+        let mut pool = LimitOrderPool::new(&[token_pool, weth_pool, unpriced_pool], Some(2 * size));
+        pool.set_token_prices(token_prices);
+
+        let token_tip = priced_order(token_pool, token, 400);
+        let weth_tip = tipped_order(weth_pool, 600);
+        assert!(pool.add_vanilla_order(token_tip.clone()).unwrap().is_none());
+        assert!(pool.add_vanilla_order(weth_tip.clone()).unwrap().is_none());
+
+        // tips 55 wei, more than the 50 wei order but less than the one tipping 30
+        // of the token
+        let outbidding = tipped_order(weth_pool, 650);
+        let evicted = pool.add_vanilla_order(outbidding.clone()).unwrap().unwrap();
+        assert_eq!(evicted.order_id.hash, weth_tip.order_id.hash);
+        assert!(pool.get_order(&token_tip.order_id).is_some());
+
+        // a tip that can't be priced doesn't outbid anything
+        assert!(matches!(
+            pool.add_vanilla_order(priced_order(unpriced_pool, unpriced, 100_000)),
+            Err(LimitPoolError::MaxSize)
+        ));
+    }
+
+    #[test]
+    fn failed_add_keeps_the_order_it_would_have_evicted() {
+        let pool_id = PoolId::random();
+        let size = std::mem::size_of::<GroupedVanillaOrder>();
+        let mut pool = LimitOrderPool::new(&[pool_id], Some(2 * size));
+
+        let low = tipped_order(pool_id, 200);
+        assert!(pool.add_vanilla_order(low.clone()).unwrap().is_none());
+        assert!(pool
+            .add_vanilla_order(tipped_order(pool_id, 1_100))
+            .unwrap()
+            .is_none());
+
+        // outbids the lowest order but targets a pool that isn't tracked
+        let unknown_pool = PoolId::random();
+        assert!(matches!(
+            pool.add_vanilla_order(tipped_order(unknown_pool, 600)),
+            Err(LimitPoolError::NoPool(id)) if id == unknown_pool
+        ));
+        assert!(pool.get_order(&low.order_id).is_some());
+
+        // the failed add didn't take any space, a single eviction makes room
+        let outbidding = tipped_order(pool_id, 600);
+        let evicted = pool.add_vanilla_order(outbidding.clone()).unwrap().unwrap();
+        assert_eq!(evicted.order_id.hash, low.order_id.hash);
+        assert!(pool.get_order(&outbidding.order_id).is_some());
+    }
+
+    #[test]
+    fn top_orders_keep_the_best_of_each_side() {
+        let pool_id = PoolId::random();
+        let mut pool = LimitOrderPool::new(&[pool_id], None);
+
+        let orders = (1..=6u64)
+            .map(|price| {
+                let mut order = tipped_order(pool_id, 1_000 + price as u128);
+                order.priority_data.price = U256::from(price);
+                order.is_bid = price <= 3;
+                pool.add_vanilla_order(order.clone()).unwrap();
+                AllOrders::from(order.order)
+            })
+            .collect::<Vec<_>>();
+
+        let expected = [2, 1, 3, 4].map(|i| orders[i].clone());
+        assert_eq!(pool.top_orders_from_pool(pool_id, 2), expected);
+        assert_eq!(pool.top_orders_from_pool(pool_id, 10).len(), 6);
+        assert!(pool.top_orders_from_pool(PoolId::random(), 2).is_empty());
+    }
+}
@@ -25,6 +25,12 @@ impl ParkedPool {
         self.0.remove(&order_id)
     }
 
+    pub fn lowest_tip_order(&self) -> Option<&OrderWithStorageData<GroupedVanillaOrder>> {
+        self.0
+            .values()
+            .min_by_key(|order| order.effective_tip_per_gas())
+    }
+
     pub fn new_order(&mut self, order: OrderWithStorageData<GroupedVanillaOrder>) {
         self.0.insert(order.hash(), order);
     }
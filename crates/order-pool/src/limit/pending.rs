@@ -1,25 +1,31 @@
 use std::{
-    cmp::Reverse,
-    collections::{BTreeMap, HashMap}
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive
 };
 
-use alloy::primitives::FixedBytes;
+use alloy::primitives::{FixedBytes, U256};
 use angstrom_types::{
-    orders::OrderPriorityData, sol_bindings::grouped_orders::OrderWithStorageData
+    matching::Ray,
+    sol_bindings::{grouped_orders::OrderWithStorageData, RawPoolOrder}
 };
 
+use crate::BookNotional;
+
 pub struct PendingPool<Order: Clone> {
     /// all order hashes
     orders: HashMap<FixedBytes<32>, OrderWithStorageData<Order>>,
-    /// bids are sorted descending by price, TODO: This should be binned into
-    /// ticks based off of the underlying pools params
-    bids:   BTreeMap<Reverse<OrderPriorityData>, FixedBytes<32>>,
-    /// asks are sorted ascending by price,  TODO: This should be binned into
-    /// ticks based off of the underlying pools params
-    asks:   BTreeMap<OrderPriorityData, FixedBytes<32>>
+    /// bids keyed by price, iterated in reverse so the best bid comes first.
+    /// orders at the same price are ordered by effective tip, highest first,
+    /// then by arrival. TODO: This should be binned into ticks based off of
+    /// the underlying pools params
+    bids:   BTreeMap<U256, Vec<FixedBytes<32>>>,
+    /// asks keyed by price, the best ask comes first. orders at the same price
+    /// are ordered like the bids. TODO: This should be binned into ticks based
+    /// off of the underlying pools params
+    asks:   BTreeMap<U256, Vec<FixedBytes<32>>>
 }
 
-impl<Order: Clone> PendingPool<Order> {
+impl<Order: Clone + RawPoolOrder> PendingPool<Order> {
     #[allow(unused)]
     pub fn new() -> Self {
         Self { orders: HashMap::new(), bids: BTreeMap::new(), asks: BTreeMap::new() }
@@ -30,29 +36,267 @@ impl<Order: Clone> PendingPool<Order> {
     }
 
     pub fn add_order(&mut self, order: OrderWithStorageData<Order>) {
-        if order.is_bid {
-            self.bids
-                .insert(Reverse(order.priority_data), order.order_id.hash);
-        } else {
-            self.asks.insert(order.priority_data, order.order_id.hash);
-        }
+        let side = if order.is_bid { &mut self.bids } else { &mut self.asks };
+        let level = side.entry(order.priority_data.price).or_default();
+
+        // goes behind every order at this price that tips at least as much, so
+        // equal tips stay in arrival order
+        let tip = order.effective_tip_per_gas();
+        let position = level
+            .iter()
+            .position(|hash| self.orders[hash].effective_tip_per_gas() < tip)
+            .unwrap_or(level.len());
+        level.insert(position, order.order_id.hash);
+
         self.orders.insert(order.order_id.hash, order);
     }
 
     pub fn remove_order(&mut self, id: FixedBytes<32>) -> Option<OrderWithStorageData<Order>> {
         let order = self.orders.remove(&id)?;
 
-        if order.is_bid {
-            self.bids.remove(&Reverse(order.priority_data))?;
-        } else {
-            self.asks.remove(&order.priority_data)?;
+        let side = if order.is_bid { &mut self.bids } else { &mut self.asks };
+        let level = side.get_mut(&order.priority_data.price)?;
+        level.retain(|hash| *hash != id);
+        if level.is_empty() {
+            side.remove(&order.priority_data.price);
         }
 
         // probably fine to strip extra data here
         Some(order)
     }
 
+    /// the order that tips the least, `None` if the pool is empty
+    pub fn lowest_tip_order(&self) -> Option<&OrderWithStorageData<Order>> {
+        self.orders
+            .values()
+            .min_by_key(|order| order.effective_tip_per_gas())
+    }
+
+    /// notional of both sides, from what is left to fill of each order
+    pub fn book_notional(&self) -> BookNotional {
+        self.orders
+            .values()
+            .fold(BookNotional::default(), |mut notional, order| {
+                let side = if order.is_bid {
+                    &mut notional.bid_notional
+                } else {
+                    &mut notional.ask_notional
+                };
+                *side = side.saturating_add(Self::order_notional(order));
+                notional
+            })
+    }
+
+    /// the order's remaining size in token1. bids are already denominated in
+    /// it, asks sell token0 so they're converted at their limit price
+    fn order_notional(order: &OrderWithStorageData<Order>) -> U256 {
+        let amount = order.remaining_amount();
+        if order.is_bid {
+            return amount
+        }
+
+        Ray::from(order.limit_price()).mul_quantity(amount)
+    }
+
     pub fn get_all_orders(&self) -> Vec<OrderWithStorageData<Order>> {
         self.orders.values().cloned().collect()
     }
+
+    /// bids from the highest to the lowest price
+    pub fn bids(&self) -> impl Iterator<Item = &OrderWithStorageData<Order>> + '_ {
+        self.bids
+            .values()
+            .rev()
+            .flatten()
+            .filter_map(|hash| self.orders.get(hash))
+    }
+
+    /// asks from the lowest to the highest price
+    pub fn asks(&self) -> impl Iterator<Item = &OrderWithStorageData<Order>> + '_ {
+        self.asks
+            .values()
+            .flatten()
+            .filter_map(|hash| self.orders.get(hash))
+    }
+
+    pub fn best_bid(&self) -> Option<&OrderWithStorageData<Order>> {
+        self.bids().next()
+    }
+
+    pub fn best_ask(&self) -> Option<&OrderWithStorageData<Order>> {
+        self.asks().next()
+    }
+
+    /// all orders on both sides with a price within `prices`. bids are returned
+    /// first, each side ordered best price first.
+    pub fn orders_in_price_range(
+        &self,
+        prices: RangeInclusive<U256>
+    ) -> Vec<OrderWithStorageData<Order>> {
+        let bids = self.bids.range(prices.clone()).rev().flat_map(|(_, h)| h);
+        let asks = self.asks.range(prices).flat_map(|(_, h)| h);
+
+        bids.chain(asks)
+            .filter_map(|hash| self.orders.get(hash).cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::B256;
+    use angstrom_types::{
+        orders::{OrderId, OrderPriorityData},
+        sol_bindings::{
+            grouped_orders::{GroupedVanillaOrder, StandingVariants},
+            rpc_orders::ExactStandingOrder
+        }
+    };
+
+    use super::*;
+
+    fn order(price: u64, is_bid: bool) -> OrderWithStorageData<GroupedVanillaOrder> {
+        tipped_order(price, is_bid, 0)
+    }
+
+    fn tipped_order(
+        price: u64,
+        is_bid: bool,
+        max_gas: u128
+    ) -> OrderWithStorageData<GroupedVanillaOrder> {
+        let inner = ExactStandingOrder { max_extra_fee_asset0: max_gas, ..Default::default() };
+        OrderWithStorageData {
+            order: GroupedVanillaOrder::Standing(StandingVariants::Exact(inner)),
+            is_bid,
+            priority_data: OrderPriorityData {
+                price: U256::from(price),
+                gas: U256::from(100),
+                gas_units: 10,
+                ..Default::default()
+            },
+            order_id: OrderId { hash: B256::random(), ..Default::default() },
+            ..Default::default()
+        }
+    }
+
+    fn prices<'a>(
+        orders: impl Iterator<Item = &'a OrderWithStorageData<GroupedVanillaOrder>>
+    ) -> Vec<U256> {
+        orders.map(|o| o.priority_data.price).collect()
+    }
+
+    #[test]
+    fn iterates_sides_best_price_first() {
+        let mut pool = PendingPool::new();
+        for price in [5, 1, 3] {
+            pool.add_order(order(price, true));
+            pool.add_order(order(price, false));
+        }
+
+        assert_eq!(prices(pool.bids()), [5, 3, 1].map(U256::from));
+        assert_eq!(prices(pool.asks()), [1, 3, 5].map(U256::from));
+        assert_eq!(pool.best_bid().unwrap().priority_data.price, U256::from(5));
+        assert_eq!(pool.best_ask().unwrap().priority_data.price, U256::from(1));
+    }
+
+    #[test]
+    fn keeps_orders_at_the_same_price() {
+        let mut pool = PendingPool::new();
+        let first = order(2, true);
+        let second = order(2, true);
+        pool.add_order(first.clone());
+        pool.add_order(second.clone());
+
+        assert_eq!(pool.bids().count(), 2);
+
+        pool.remove_order(first.order_id.hash).unwrap();
+        let remaining = pool.bids().map(|o| o.order_id.hash).collect::<Vec<_>>();
+        assert_eq!(remaining, vec![second.order_id.hash]);
+
+        pool.remove_order(second.order_id.hash).unwrap();
+        assert!(pool.best_bid().is_none());
+    }
+
+    #[test]
+    fn queries_orders_in_price_range() {
+        let mut pool = PendingPool::new();
+        for price in 1..=6 {
+            pool.add_order(order(price, price % 2 == 0));
+        }
+
+        let in_range = pool.orders_in_price_range(U256::from(2)..=U256::from(5));
+        assert_eq!(prices(in_range.iter()), [4, 2, 3, 5].map(U256::from));
+    }
+
+    #[test]
+    fn prefers_higher_tip_at_the_same_price() {
+        let mut pool = PendingPool::new();
+        let low_tip = tipped_order(2, true, 200);
+        let high_tip = tipped_order(2, true, 500);
+        let same_low_tip = tipped_order(2, true, 200);
+        let better_price = tipped_order(3, true, 0);
+        for order in [&low_tip, &high_tip, &same_low_tip, &better_price] {
+            pool.add_order(order.clone());
+        }
+
+        let order = pool.bids().map(|o| o.order_id.hash).collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            vec![
+                better_price.order_id.hash,
+                high_tip.order_id.hash,
+                low_tip.order_id.hash,
+                same_low_tip.order_id.hash,
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_notional_of_both_sides() {
+        // asks are converted at their price, 1.0 here
+        let sized = |amount: u128, is_bid: bool| {
+            let mut order = order(1, is_bid);
+            let GroupedVanillaOrder::Standing(StandingVariants::Exact(inner)) = &mut order.order
+            else {
+                unreachable!()
+            };
+            inner.amount = amount;
+            inner.min_price = U256::from(10).pow(U256::from(27));
+            order
+        };
+
+        let mut pool = PendingPool::new();
+        assert_eq!(pool.book_notional().imbalance(), None);
+
+        let bid = sized(300, true);
+        let bid_hash = bid.order_id.hash;
+        pool.add_order(bid);
+        pool.add_order(sized(100, true));
+        pool.add_order(sized(200, false));
+        assert_eq!(pool.book_notional().bid_notional, U256::from(400));
+        assert_eq!(pool.book_notional().ask_notional, U256::from(200));
+        assert_eq!(pool.book_notional().imbalance(), Some(1.0 / 3.0));
+
+        pool.remove_order(bid_hash);
+        assert_eq!(pool.book_notional().imbalance(), Some(-1.0 / 3.0));
+    }
+
+    #[test]
+    fn notional_follows_recorded_fills() {
+        let mut pool = PendingPool::new();
+        let mut bid = order(1, true);
+        let GroupedVanillaOrder::Standing(StandingVariants::Exact(inner)) = &mut bid.order else {
+            unreachable!()
+        };
+        inner.amount = 300;
+        let hash = bid.order_id.hash;
+        pool.add_order(bid);
+
+        pool.orders.get_mut(&hash).unwrap().record_fill(100);
+        assert_eq!(pool.book_notional().bid_notional, U256::from(200));
+
+        // removing the order takes out what was left of it
+        pool.remove_order(hash);
+        assert_eq!(pool.book_notional(), BookNotional::default());
+    }
 }
@@ -1,6 +1,6 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, ops::RangeInclusive};
 
-use alloy::primitives::B256;
+use alloy::primitives::{B256, U256};
 use angstrom_metrics::VanillaLimitOrderPoolMetricsWrapper;
 use angstrom_types::{
     orders::{OrderId, OrderStatus},
@@ -10,7 +10,7 @@ use angstrom_types::{
 use angstrom_utils::map::OwnedMap;
 
 use super::{parked::ParkedPool, pending::PendingPool};
-use crate::limit::LimitPoolError;
+use crate::{limit::LimitPoolError, BookNotional};
 
 #[derive(Default)]
 pub struct LimitPool {
@@ -73,11 +73,11 @@ impl LimitPool {
         let err = || LimitPoolError::NoPool(pool_id);
 
         if order.is_currently_valid {
-            self.pending_orders
-                .get_mut(&pool_id)
-                .ok_or_else(err)?
-                .add_order(order);
+            let pool = self.pending_orders.get_mut(&pool_id).ok_or_else(err)?;
+            pool.add_order(order);
             self.metrics.incr_pending_orders(pool_id, 1);
+            self.metrics
+                .set_book_imbalance(pool_id, pool.book_notional().imbalance().unwrap_or_default());
         } else {
             self.parked_orders
                 .get_mut(&pool_id)
@@ -97,8 +97,13 @@ impl LimitPool {
         self.pending_orders
             .get_mut(&pool_id)
             .and_then(|pool| {
-                pool.remove_order(order_id)
-                    .owned_map(|| self.metrics.decr_pending_orders(pool_id, 1))
+                pool.remove_order(order_id).owned_map(|| {
+                    self.metrics.decr_pending_orders(pool_id, 1);
+                    self.metrics.set_book_imbalance(
+                        pool_id,
+                        pool.book_notional().imbalance().unwrap_or_default()
+                    );
+                })
             })
             .or_else(|| {
                 self.parked_orders.get_mut(&pool_id).and_then(|pool| {
@@ -115,6 +120,61 @@ impl LimitPool {
             .collect()
     }
 
+    /// the pending or parked order that tips the least across all pools. Each
+    /// pool tips in its own token0, so `tip` has to bring them to a common unit
+    pub fn lowest_tip_order(
+        &self,
+        tip: impl Fn(&OrderWithStorageData<GroupedVanillaOrder>) -> U256
+    ) -> Option<&OrderWithStorageData<GroupedVanillaOrder>> {
+        self.pending_orders
+            .values()
+            .filter_map(|pool| pool.lowest_tip_order())
+            .chain(
+                self.parked_orders
+                    .values()
+                    .filter_map(|pool| pool.lowest_tip_order())
+            )
+            .min_by_key(|order| tip(order))
+    }
+
+    /// pending bids of the pool, best price first
+    pub fn sorted_bids(
+        &self,
+        pool_id: PoolId
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        Ok(self.pending_pool(pool_id)?.bids().cloned().collect())
+    }
+
+    /// pending asks of the pool, best price first
+    pub fn sorted_asks(
+        &self,
+        pool_id: PoolId
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        Ok(self.pending_pool(pool_id)?.asks().cloned().collect())
+    }
+
+    pub fn orders_in_price_range(
+        &self,
+        pool_id: PoolId,
+        prices: RangeInclusive<U256>
+    ) -> Result<Vec<OrderWithStorageData<GroupedVanillaOrder>>, LimitPoolError> {
+        Ok(self.pending_pool(pool_id)?.orders_in_price_range(prices))
+    }
+
+    /// notional of the pending bids and asks of the pool
+    pub fn book_notional(&self, pool_id: PoolId) -> Result<BookNotional, LimitPoolError> {
+        Ok(self.pending_pool(pool_id)?.book_notional())
+    }
+
+    fn pending_pool(
+        &self,
+        pool_id: PoolId
+    ) -> Result<&PendingPool<GroupedVanillaOrder>, LimitPoolError> {
+        self.pending_orders
+            .get(&pool_id)
+            .ok_or(LimitPoolError::NoPool(pool_id))
+    }
+
     pub fn park_order(&mut self, order_id: &OrderId) {
         let Some(mut order) = self.remove_order(order_id.pool_id, order_id.hash) else { return };
         order.is_currently_valid = false;
@@ -134,3 +194,23 @@ impl LimitPool {
         assert!(old_is_none);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_queries_on_unknown_pool_fail() {
+        let pool = LimitPool::new(&[]);
+        let pool_id = PoolId::random();
+
+        assert!(matches!(
+            pool.sorted_bids(pool_id),
+            Err(LimitPoolError::NoPool(id)) if id == pool_id
+        ));
+        assert!(matches!(
+            pool.orders_in_price_range(pool_id, U256::ZERO..=U256::MAX),
+            Err(LimitPoolError::NoPool(_))
+        ));
+    }
+}
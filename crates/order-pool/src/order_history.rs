@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use alloy::primitives::{BlockNumber, B256};
+use angstrom_types::primitive::PoolId;
+
+/// What happened to an order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderEventKind {
+    Validated,
+    Filled,
+    Cancelled,
+    Killed,
+    /// pushed out of the full pool by an order that tips more
+    Evicted
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrderEvent {
+    pub block:      BlockNumber,
+    pub order_hash: B256,
+    pub pool_id:    PoolId,
+    pub kind:       OrderEventKind
+}
+
+/// Append only log of order events over the last `max_blocks` blocks. Used to
+/// answer "what happened to my order" without an external indexer.
+#[derive(Debug)]
+pub struct OrderHistory {
+    events:     VecDeque<OrderEvent>,
+    max_blocks: u64
+}
+
+impl OrderHistory {
+    pub fn new(max_blocks: u64) -> Self {
+        Self { events: VecDeque::new(), max_blocks }
+    }
+
+    pub fn record(&mut self, event: OrderEvent) {
+        // the event's block is the newest of the `max_blocks` kept
+        let oldest_kept = (event.block + 1).saturating_sub(self.max_blocks);
+        self.events.push_back(event);
+
+        while self
+            .events
+            .front()
+            .is_some_and(|event| event.block < oldest_kept)
+        {
+            self.events.pop_front();
+        }
+    }
+
+    /// all recorded events with `from <= block <= to`
+    pub fn orders_in_range(&self, from: BlockNumber, to: BlockNumber) -> Vec<OrderEvent> {
+        self.events
+            .iter()
+            .filter(|event| (from..=to).contains(&event.block))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(block: BlockNumber, kind: OrderEventKind) -> OrderEvent {
+        OrderEvent { block, order_hash: B256::random(), pool_id: PoolId::random(), kind }
+    }
+
+    #[test]
+    fn test_history_returns_events_in_range() {
+        let mut history = OrderHistory::new(100);
+        history.record(event(1, OrderEventKind::Validated));
+        history.record(event(2, OrderEventKind::Filled));
+        history.record(event(3, OrderEventKind::Cancelled));
+        history.record(event(4, OrderEventKind::Killed));
+
+        let events = history.orders_in_range(2, 3);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, OrderEventKind::Filled);
+        assert_eq!(events[1].kind, OrderEventKind::Cancelled);
+    }
+
+    #[test]
+    fn test_history_drops_blocks_past_bound() {
+        let mut history = OrderHistory::new(2);
+        history.record(event(1, OrderEventKind::Validated));
+        history.record(event(2, OrderEventKind::Validated));
+        history.record(event(4, OrderEventKind::Validated));
+
+        let events = history.orders_in_range(0, 4);
+        assert_eq!(events.iter().map(|e| e.block).collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    fn test_history_keeps_exactly_max_blocks() {
+        let blocks = |history: &OrderHistory| {
+            history
+                .orders_in_range(0, u64::MAX)
+                .iter()
+                .map(|e| e.block)
+                .collect::<Vec<_>>()
+        };
+        let mut history = OrderHistory::new(2);
+        history.record(event(1, OrderEventKind::Validated));
+        history.record(event(2, OrderEventKind::Validated));
+        history.record(event(2, OrderEventKind::Filled));
+        assert_eq!(blocks(&history), vec![1, 2, 2]);
+
+        // block 3 pushes block 1 out
+        history.record(event(3, OrderEventKind::Validated));
+        assert_eq!(blocks(&history), vec![2, 2, 3]);
+    }
+}
@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    path::PathBuf,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -8,7 +9,7 @@ use std::{
 
 use alloy::primitives::{Address, BlockNumber, FixedBytes, B256, U256};
 use angstrom_types::{
-    orders::{OrderId, OrderLocation, OrderOrigin, OrderSet, OrderStatus},
+    orders::{OrderId, OrderLocation, OrderOrigin, OrderSet, OrderStatus, TransactionId},
     primitive::{NewInitializedPool, PeerId, PoolId},
     sol_bindings::{
         grouped_orders::{AllOrders, OrderWithStorageData, *},
@@ -17,40 +18,49 @@ use angstrom_types::{
     }
 };
 use futures_util::{Stream, StreamExt};
+use schnellru::{ByLength, LruMap};
 use tokio::sync::oneshot::Sender;
 use tracing::{error, trace};
 use validation::order::{
-    state::{account::user::UserAddress, pools::AngstromPoolsTracker},
+    rejection::{Rejection, RejectionReason},
+    state::{
+        account::user::UserAddress,
+        pools::{AngstromPoolsTracker, TrackedPool}
+    },
     OrderValidationResults, OrderValidatorHandle
 };
 
 use crate::{
+    cancelled_orders::{CancelledOrders, CancelledOrdersMode},
+    held_orders::HeldOrders,
+    order_history::{OrderEvent, OrderEventKind, OrderHistory},
+    order_lifetime::{CancelOrderError, MinOrderLifetime, OrderLifetimes},
     order_storage::OrderStorage,
+    order_wal::{replay, OrderWal, WalEntry},
     validator::{OrderValidator, OrderValidatorRes},
-    PoolManagerUpdate
+    BookNotional, PoolManagerUpdate
 };
 
 /// This is used to remove validated orders. During validation
 /// the same check wil be ran but with more accuracy
-const ETH_BLOCK_TIME: Duration = Duration::from_secs(12);
+pub const ETH_BLOCK_TIME: Duration = Duration::from_secs(12);
 /// mostly arbitrary
 const SEEN_INVALID_ORDERS_CAPACITY: usize = 10000;
 /// represents the maximum number of blocks that we allow for new orders to not
 /// propagate (again mostly arbitrary)
 const MAX_NEW_ORDER_DELAY_PROPAGATION: u64 = 7000;
-
-struct CancelOrderRequest {
-    /// The address of the entity requesting the cancellation.
-    pub from:        Address,
-    // The time until the cancellation request is valid.
-    pub valid_until: u64
-}
+/// number of accepted orders we remember the validation result of, so that
+/// retried submissions can be answered without validating them again
+const RECENTLY_ACCEPTED_CAPACITY: u32 = 1024;
+/// number of rejected orders we remember the rejection of, for status queries
+const RECENTLY_REJECTED_CAPACITY: u32 = 4096;
 
 pub struct OrderIndexer<V: OrderValidatorHandle> {
     /// order storage
     order_storage:          Arc<OrderStorage>,
-    /// Address to order id, used for eoa invalidation
-    address_to_orders:      HashMap<Address, Vec<OrderId>>,
+    /// Sender to the hashes of their orders, used for eoa invalidation and
+    /// sender lookups
+    address_to_orders:      HashMap<Address, HashSet<B256>>,
     /// current block_number
     block_number:           u64,
     /// Order hash to order id, used for order inclusion lookups
@@ -60,7 +70,14 @@ pub struct OrderIndexer<V: OrderValidatorHandle> {
     /// Used to avoid unnecessary computation on order spam
     seen_invalid_orders:    HashSet<B256>,
     /// Used to protect against late order propagation
-    cancelled_orders:       HashMap<B256, CancelOrderRequest>,
+    cancelled_orders:       CancelledOrders,
+    /// When orders of pools with a minimum lifetime were accepted
+    order_lifetimes:        OrderLifetimes,
+    /// Validation results of recently accepted orders, used to answer
+    /// resubmissions of the same order
+    recently_accepted:      LruMap<B256, OrderWithStorageData<AllOrders>, ByLength>,
+    /// Why recently rejected orders were rejected
+    recently_rejected:      LruMap<B256, Rejection, ByLength>,
     /// Order Validator
     validator:              OrderValidator<V>,
     /// a mapping of tokens to pool_id
@@ -68,7 +85,14 @@ pub struct OrderIndexer<V: OrderValidatorHandle> {
     /// List of subscribers for order validation result
     order_validation_subs:  HashMap<B256, Vec<Sender<OrderValidationResults>>>,
     /// List of subscribers for order state change notifications
-    orders_subscriber_tx:   tokio::sync::broadcast::Sender<PoolManagerUpdate>
+    orders_subscriber_tx:   tokio::sync::broadcast::Sender<PoolManagerUpdate>,
+    /// Recent order events, only kept if enabled
+    order_history:          Option<OrderHistory>,
+    /// Accepted and removed orders, only logged if enabled
+    order_wal:              Option<OrderWal>,
+    /// orders for pools validation hasn't synced yet, validated again once
+    /// the initialization watcher reports the pool
+    held_orders:            HeldOrders
 }
 
 impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
@@ -87,70 +111,269 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             order_hash_to_peer_id: HashMap::new(),
             seen_invalid_orders: HashSet::with_capacity(SEEN_INVALID_ORDERS_CAPACITY),
             pool_id_map: angstrom_pools,
-            cancelled_orders: HashMap::new(),
+            cancelled_orders: CancelledOrders::new(CancelledOrdersMode::default()),
+            order_lifetimes: OrderLifetimes::default(),
+            recently_accepted: LruMap::new(ByLength::new(RECENTLY_ACCEPTED_CAPACITY)),
+            recently_rejected: LruMap::new(ByLength::new(RECENTLY_REJECTED_CAPACITY)),
             order_validation_subs: HashMap::new(),
             validator: OrderValidator::new(validator),
-            orders_subscriber_tx
+            orders_subscriber_tx,
+            order_history: None,
+            order_wal: None,
+            held_orders: HeldOrders::default()
         }
     }
 
-    pub fn pending_orders_for_address(
-        &self,
-        address: Address
-    ) -> Vec<OrderWithStorageData<AllOrders>> {
-        let mut orders = Vec::new();
-        if let Some(order_ids) = self.address_to_orders.get(&address) {
-            for order_id in order_ids {
-                let order = match order_id.location {
-                    angstrom_types::orders::OrderLocation::Limit => self
-                        .order_storage
-                        .limit_orders
-                        .lock()
-                        .expect("lock poisoned")
-                        .get_order(order_id)
-                        .and_then(|order| order.try_map_inner(|inner| Ok(inner.into())).ok()),
-                    angstrom_types::orders::OrderLocation::Searcher => self
-                        .order_storage
-                        .searcher_orders
-                        .lock()
-                        .expect("lock poisoned")
-                        .get_order(order_id.pool_id, order_id.hash)
-                        .and_then(|order| {
-                            order.try_map_inner(|inner| Ok(AllOrders::TOB(inner))).ok()
-                        })
-                };
+    /// keeps the order events of the last `history_blocks` blocks around for
+    /// [`Self::orders_in_range`].
+    pub fn with_order_history(mut self, history_blocks: Option<u64>) -> Self {
+        self.order_history = history_blocks.map(OrderHistory::new);
+        self
+    }
 
-                if let Some(order) = order {
-                    orders.push(order);
-                }
+    /// logs the orders accepted into and removed from the pool to the
+    /// write-ahead log at `path`. The orders still resting in it from the last
+    /// run are validated again, so a restart picks up the book where it left
+    /// off.
+    pub fn with_order_wal(mut self, path: Option<PathBuf>) -> Self {
+        let Some(path) = path else { return self };
+
+        let restored = replay(&path, vec![]).and_then(|orders| {
+            let mut wal = OrderWal::open(path.clone())?;
+            wal.truncate(orders.clone());
+            Ok((wal, orders))
+        });
+        match restored {
+            Ok((wal, orders)) => {
+                tracing::info!(
+                    orders = orders.len(),
+                    path = %path.display(),
+                    "validating the orders of the order wal again"
+                );
+                self.order_wal = Some(wal);
+                orders
+                    .into_iter()
+                    .for_each(|order| self.new_order(None, OrderOrigin::Local, order, None));
             }
+            Err(error) => {
+                error!(%error, path = %path.display(), "failed to restore the order wal");
+            }
+        }
+
+        self
+    }
+
+    /// how cancelled order hashes are remembered, see [`CancelledOrdersMode`]
+    pub fn with_cancelled_orders_mode(mut self, mode: CancelledOrdersMode) -> Self {
+        self.cancelled_orders = CancelledOrders::new(mode);
+        self
+    }
+
+    /// orders of the pools in `min_lifetimes` can't be cancelled until they
+    /// rested in the pool for the pool's lifetime
+    pub fn with_min_order_lifetimes(
+        mut self,
+        min_lifetimes: HashMap<PoolId, MinOrderLifetime>
+    ) -> Self {
+        self.order_lifetimes = OrderLifetimes::new(min_lifetimes);
+        self
+    }
+
+    /// number of orders held back until their uniswap pool syncs
+    pub fn orders_awaiting_pool_sync(&self) -> usize {
+        self.held_orders.len()
+    }
+
+    /// Order events recorded between `from` and `to` (inclusive). Empty if
+    /// history isn't enabled.
+    pub fn orders_in_range(&self, from: BlockNumber, to: BlockNumber) -> Vec<OrderEvent> {
+        self.order_history
+            .as_ref()
+            .map(|history| history.orders_in_range(from, to))
+            .unwrap_or_default()
+    }
+
+    fn record_order_event(
+        &mut self,
+        block: BlockNumber,
+        order_hash: B256,
+        pool_id: PoolId,
+        kind: OrderEventKind
+    ) {
+        if let Some(history) = self.order_history.as_mut() {
+            history.record(OrderEvent { block, order_hash, pool_id, kind });
+        }
+        // accepted orders are logged along with the order by the caller
+        if kind != OrderEventKind::Validated {
+            self.append_to_wal(WalEntry::Removed { order_hash });
+        }
+    }
+
+    fn append_to_wal(&mut self, entry: WalEntry) {
+        if let Some(wal) = self.order_wal.as_mut() {
+            wal.append(entry);
+        }
+    }
+
+    /// Replaces the write-ahead log with the orders resting in the pool once
+    /// more entries were appended since the last compaction than orders rest
+    /// in it, which keeps the log within twice the size of the book.
+    fn compact_order_wal(&mut self) {
+        let resting = self.order_hash_to_order_id.len();
+        if self
+            .order_wal
+            .as_ref()
+            .map_or(true, |wal| wal.appended() <= resting)
+        {
+            return
+        }
+        let resting = self
+            .order_hash_to_order_id
+            .values()
+            .filter_map(|order_id| self.get_order(order_id))
+            .map(|order| order.order)
+            .collect::<Vec<_>>();
+
+        if let Some(wal) = self.order_wal.as_mut() {
+            wal.truncate(resting);
+        }
+    }
+
+    /// the orders of `sender` that are currently held in storage
+    pub fn orders_by_sender(&self, sender: Address) -> Vec<OrderWithStorageData<AllOrders>> {
+        self.address_to_orders
+            .get(&sender)
+            .map(|hashes| {
+                hashes
+                    .iter()
+                    .filter_map(|hash| self.order_hash_to_order_id.get(hash))
+                    .filter_map(|order_id| self.get_order(order_id))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// the orders we hold out of the given hashes, unknown hashes are skipped
+    pub fn orders_by_hash(&self, order_hashes: &[B256]) -> Vec<AllOrders> {
+        order_hashes
+            .iter()
+            .filter_map(|hash| self.order_hash_to_order_id.get(hash))
+            .filter_map(|order_id| self.get_order(order_id))
+            .map(|order| order.order)
+            .collect()
+    }
+
+    fn get_order(&self, order_id: &OrderId) -> Option<OrderWithStorageData<AllOrders>> {
+        match order_id.location {
+            OrderLocation::Limit => self
+                .order_storage
+                .limit_orders
+                .lock()
+                .expect("lock poisoned")
+                .get_order(order_id)
+                .and_then(|order| order.try_map_inner(|inner| Ok(inner.into())).ok()),
+            OrderLocation::Searcher => self
+                .order_storage
+                .searcher_orders
+                .lock()
+                .expect("lock poisoned")
+                .get_order(order_id.pool_id, order_id.hash)
+                .and_then(|order| order.try_map_inner(|inner| Ok(AllOrders::TOB(inner))).ok())
         }
-        orders
     }
 
+    /// the pool's orders at the location. With a `depth` only the best
+    /// `depth` orders of each side are returned, bids first
     pub fn orders_by_pool(
         &self,
         pool_id: FixedBytes<32>,
-        order_location: OrderLocation
+        order_location: OrderLocation,
+        depth: Option<usize>
     ) -> Vec<AllOrders> {
-        match order_location {
-            OrderLocation::Limit => self
+        match (order_location, depth) {
+            (OrderLocation::Limit, None) => self
                 .order_storage
                 .limit_orders
                 .lock()
                 .expect("poisoned")
                 .get_all_orders_from_pool(pool_id),
-            OrderLocation::Searcher => self
+            (OrderLocation::Limit, Some(depth)) => self
+                .order_storage
+                .limit_orders
+                .lock()
+                .expect("poisoned")
+                .top_orders_from_pool(pool_id, depth),
+            (OrderLocation::Searcher, None) => self
                 .order_storage
                 .searcher_orders
                 .lock()
                 .expect("poisoned")
-                .get_all_orders_from_pool(pool_id)
+                .get_all_orders_from_pool(pool_id),
+            (OrderLocation::Searcher, Some(depth)) => self
+                .order_storage
+                .searcher_orders
+                .lock()
+                .expect("poisoned")
+                .top_orders_from_pool(pool_id, depth)
         }
     }
 
-    pub fn order_status(&self, order_hash: B256) -> Option<OrderStatus> {
-        self.order_storage.fetch_status_of_order(order_hash)
+    /// the status of the order along with the id the pool tracks it under
+    pub fn order_status(&self, order_hash: B256) -> Option<(OrderStatus, TransactionId)> {
+        let Some(order_id) = self.order_hash_to_order_id.get(&order_hash) else {
+            // filled orders are no longer tracked but are held until their block is
+            // finalized
+            return self
+                .order_storage
+                .fetch_filled_order(&order_hash)
+                .map(|order| (OrderStatus::Filled, order.order_id.transaction_id()))
+        };
+        let status = self.order_storage.fetch_status_of_order(order_hash)?;
+
+        Some((status, order_id.transaction_id()))
+    }
+
+    /// why the order was rejected, if it was rejected recently and hasn't
+    /// been accepted since
+    pub fn rejection(&self, order_hash: &B256) -> Option<Rejection> {
+        self.recently_rejected.peek(order_hash).cloned()
+    }
+
+    /// number of orders currently held in storage
+    pub fn total_orders(&self) -> usize {
+        self.order_storage.total_orders()
+    }
+
+    pub fn book_notional(&self, pool_id: FixedBytes<32>) -> Option<BookNotional> {
+        self.order_storage.book_notional(pool_id)
+    }
+
+    /// all angstrom pools orders can be placed in
+    pub fn pools(&self) -> Vec<TrackedPool> {
+        self.pool_id_map.all_pools()
+    }
+
+    /// checksum over the pool's live orders, peers holding the same orders
+    /// get the same one
+    pub fn book_checksum(&self, pool_id: PoolId) -> B256 {
+        self.order_storage.book_checksum(pool_id)
+    }
+
+    /// hashes of the pool's live orders
+    pub fn book_order_hashes(&self, pool_id: PoolId) -> Vec<B256> {
+        self.order_storage.book_order_hashes(pool_id)
+    }
+
+    /// the hashes of orders we don't hold out of a peer's book, leaving out
+    /// those we already know are invalid or cancelled
+    pub fn missing_orders(&self, order_hashes: &[B256]) -> Vec<B256> {
+        order_hashes
+            .iter()
+            .filter(|hash| {
+                self.is_missing(hash) && !self.is_seen_invalid(hash) && !self.is_cancelled(hash)
+            })
+            .copied()
+            .collect()
     }
 
     fn is_missing(&self, order_hash: &B256) -> bool {
@@ -161,8 +384,16 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.seen_invalid_orders.contains(order_hash)
     }
 
-    fn is_cancelled(&self, order_hash: &B256) -> bool {
-        self.cancelled_orders.contains_key(order_hash)
+    /// the sender of the order, `None` if it isn't in the pool
+    pub fn order_owner(&self, order_hash: &B256) -> Option<Address> {
+        self.order_hash_to_order_id
+            .get(order_hash)
+            .map(|order_id| order_id.address)
+    }
+
+    /// whether a cancellation for the order has been applied and not expired
+    pub fn is_cancelled(&self, order_hash: &B256) -> bool {
+        self.cancelled_orders.contains(order_hash)
     }
 
     fn is_duplicate(&self, order_hash: &B256) -> bool {
@@ -181,20 +412,50 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         order: AllOrders,
         validation_tx: tokio::sync::oneshot::Sender<OrderValidationResults>
     ) {
+        let hash = order.order_hash();
+        // a client retrying a submission gets the status of the first one instead of
+        // the order going through validation again
+        if let Some(subscribers) = self.order_validation_subs.get_mut(&hash) {
+            trace!(?hash, "order resubmitted while still being validated");
+            subscribers.push(validation_tx);
+            return
+        }
+        if let Some(accepted) = self.recently_accepted_order(&hash) {
+            trace!(?hash, "order resubmitted after being accepted");
+            let _ = validation_tx.send(OrderValidationResults::Valid(accepted));
+            return
+        }
+
         self.new_order(None, origin, order, Some(validation_tx))
     }
 
+    /// The validation result of an order we accepted recently and that is
+    /// still resting in the pool.
+    fn recently_accepted_order(&mut self, hash: &B256) -> Option<OrderWithStorageData<AllOrders>> {
+        if !self.order_hash_to_order_id.contains_key(hash) {
+            self.recently_accepted.remove(hash);
+            return None
+        }
+
+        self.recently_accepted.get(hash).cloned()
+    }
+
     pub fn new_network_order(&mut self, peer_id: PeerId, origin: OrderOrigin, order: AllOrders) {
         self.new_order(Some(peer_id), origin, order, None)
     }
 
-    pub fn cancel_order(&mut self, from: Address, order_hash: B256) -> bool {
+    pub fn cancel_order(
+        &mut self,
+        from: Address,
+        order_hash: B256
+    ) -> Result<bool, CancelOrderError> {
         if self.is_seen_invalid(&order_hash) || self.is_cancelled(&order_hash) {
-            return true
+            return Ok(true)
         }
 
         // the cancel arrived before the new order request
         // nothing more needs to be done, since new_order() will return early
+        // unless the order's pool has a minimum lifetime
         if self.is_missing(&order_hash) {
             // optimistically assuming that orders won't take longer than a day to propagate
             let deadline = SystemTime::now()
@@ -202,23 +463,37 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 .unwrap()
                 .as_secs()
                 + MAX_NEW_ORDER_DELAY_PROPAGATION * ETH_BLOCK_TIME.as_secs();
-            self.insert_cancel_request_with_deadline(from, &order_hash, Some(U256::from(deadline)));
+            self.cancelled_orders
+                .insert_before_order(order_hash, from, deadline);
 
-            return true
+            return Ok(true)
         }
 
         let order_id = self.order_hash_to_order_id.get(&order_hash).unwrap();
         if order_id.address != from {
-            return false
+            return Ok(false)
         }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.order_lifetimes
+            .check_cancel(order_hash, self.block_number, now)?;
 
         let removed = self.order_storage.cancel_order(order_id);
         let removed_from_storage = removed.is_some();
         if removed_from_storage {
             let order = removed.unwrap();
-            self.order_hash_to_order_id.remove(&order_hash);
+            self.validator.cancel_order(from, order_hash);
+            self.untrack_order(&order_hash);
             self.order_hash_to_peer_id.remove(&order_hash);
             self.insert_cancel_request_with_deadline(from, &order_hash, order.deadline());
+            self.record_order_event(
+                self.block_number,
+                order_hash,
+                order.pool_id,
+                OrderEventKind::Cancelled
+            );
 
             self.notify_order_subscribers(PoolManagerUpdate::CancelledOrder {
                 order_hash: order.order_hash(),
@@ -227,7 +502,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             });
         }
 
-        removed_from_storage
+        Ok(removed_from_storage)
     }
 
     fn insert_cancel_request_with_deadline(
@@ -251,8 +526,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 u64::from_le_bytes(bytes[..8].try_into().unwrap())
             }
         );
-        self.cancelled_orders
-            .insert(*order_hash, CancelOrderRequest { from, valid_until });
+        self.cancelled_orders.insert(*order_hash, from, valid_until);
     }
 
     fn new_order(
@@ -263,11 +537,30 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         validation_res_sub: Option<Sender<OrderValidationResults>>
     ) {
         let hash = order.order_hash();
-        let cancel_request = self.cancelled_orders.get(&hash);
-        let is_valid_cancel_request =
-            cancel_request.is_some() && cancel_request.unwrap().from == order.from();
+        // a cancel that arrived before the order would let it skip its pool's
+        // minimum lifetime, the order is taken and has to be cancelled again
+        if self
+            .pool_id_map
+            .get_poolid(order.token_in(), order.token_out())
+            .is_some_and(|pool_id| self.order_lifetimes.applies_to(&pool_id))
+            && self.cancelled_orders.take_before_order(&hash, order.from())
+        {
+            tracing::debug!(
+                ?hash,
+                "dropped a cancel sent before the order, its pool has a minimum lifetime"
+            );
+        }
+        let is_valid_cancel_request = self.cancelled_orders.is_cancelled_by(&hash, order.from());
         // network spammers will get penalized only once
         if self.is_duplicate(&hash) || is_valid_cancel_request {
+            let rejection = if is_valid_cancel_request {
+                Rejection::new(RejectionReason::Cancelled, format!("order {hash:?} was cancelled"))
+            } else {
+                Rejection::new(
+                    RejectionReason::DuplicateOrder,
+                    format!("order {hash:?} is already known")
+                )
+            };
             if is_valid_cancel_request {
                 self.insert_cancel_request_with_deadline(order.from(), &hash, order.deadline());
 
@@ -283,7 +576,30 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                 }
                 self.order_storage.log_cancel_order(&order);
             }
-            self.notify_validation_subscribers(&hash, OrderValidationResults::Invalid(hash));
+            self.reject_order(hash, rejection, validation_res_sub);
+            return
+        }
+
+        if let Some(pool_id) = self
+            .pool_id_map
+            .get_poolid(order.token_in(), order.token_out())
+            .filter(|pool_id| self.order_storage.paused_pools().is_paused(pool_id))
+        {
+            tracing::debug!(?hash, ?pool_id, "pool paused, rejecting order");
+            let rejection =
+                Rejection::new(RejectionReason::PoolPaused, format!("pool {pool_id:?} is paused"));
+            self.reject_order(hash, rejection, validation_res_sub);
+            return
+        }
+
+        // apply backpressure before spending any validation work on the order.
+        if self.order_storage.is_full() {
+            tracing::debug!(
+                ?hash,
+                total_orders = self.order_storage.total_orders(),
+                "order pool is full, rejecting order"
+            );
+            self.reject_order(hash, Self::pool_full(), validation_res_sub);
             return
         }
 
@@ -320,16 +636,12 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .collect::<Vec<_>>();
 
         // TODO: notify rpc of dead orders
-        let _expired_orders = hashes
+        let expired_ids = hashes
             .iter()
-            // remove hash from id
-            .map(|hash| self.order_hash_to_order_id.remove(hash).unwrap())
-            .inspect(|order_id| {
-                self.address_to_orders
-                    .values_mut()
-                    // remove from address to orders
-                    .for_each(|v| v.retain(|o| o != order_id));
-            })
+            .filter_map(|hash| self.untrack_order(hash))
+            .collect::<Vec<_>>();
+        let expired_orders = expired_ids
+            .into_iter()
             // remove from all underlying pools
             .filter_map(|id| match id.location {
                 angstrom_types::orders::OrderLocation::Searcher => {
@@ -341,14 +653,31 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             })
             .collect::<Vec<_>>();
 
+        // fill-or-kill orders that weren't filled in their block are killed
+        expired_orders.into_iter().for_each(|order| {
+            if !order.is_fill_or_kill() {
+                self.append_to_wal(WalEntry::Removed { order_hash: order.order_hash() });
+                return
+            }
+            self.record_order_event(
+                block_number,
+                order.order_hash(),
+                order.pool_id,
+                OrderEventKind::Killed
+            );
+            self.notify_order_subscribers(PoolManagerUpdate::KilledOrder(block_number, order))
+        });
+
         hashes
     }
 
     fn eoa_state_change(&mut self, eoas: &[Address]) {
         eoas.iter()
             .filter_map(|eoa| self.address_to_orders.remove(eoa))
-            .for_each(|order_ids| {
-                order_ids.into_iter().for_each(|id| {
+            .for_each(|hashes| {
+                hashes.into_iter().for_each(|hash| {
+                    // tracked again once the order is revalidated
+                    let Some(id) = self.order_hash_to_order_id.remove(&hash) else { return };
                     let Some(order) = (match id.location {
                         angstrom_types::orders::OrderLocation::Limit => {
                             self.order_storage.remove_limit_order(&id)
@@ -360,6 +689,8 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                         return
                     };
 
+                    // logged as accepted again if it's still valid
+                    self.append_to_wal(WalEntry::Removed { order_hash: hash });
                     self.validator
                         .validate_order(OrderOrigin::Local, order.order);
                 })
@@ -368,6 +699,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
 
     pub fn finalized_block(&mut self, block_number: BlockNumber) {
         self.order_storage.finalized_block(block_number);
+        self.compact_order_wal();
     }
 
     pub fn reorg(&mut self, orders: Vec<B256>) {
@@ -387,9 +719,12 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             return
         }
 
-        let filled_orders = orders
+        let filled_ids = orders
             .iter()
-            .filter_map(|hash| self.order_hash_to_order_id.remove(hash))
+            .filter_map(|hash| self.untrack_order(hash))
+            .collect::<Vec<_>>();
+        let filled_orders = filled_ids
+            .into_iter()
             .filter_map(|order_id| match order_id.location {
                 angstrom_types::orders::OrderLocation::Limit => {
                     self.order_storage.remove_limit_order(&order_id)
@@ -401,6 +736,12 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
             .collect::<Vec<OrderWithStorageData<AllOrders>>>();
 
         filled_orders.iter().for_each(|order| {
+            self.record_order_event(
+                block_number,
+                order.order_hash(),
+                order.pool_id,
+                OrderEventKind::Filled
+            );
             self.notify_order_subscribers(PoolManagerUpdate::FilledOrder(
                 block_number,
                 order.clone()
@@ -411,13 +752,42 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
     }
 
     /// Given the nonce ordering rule. Sometimes new transactions can park old
-    /// transactions.
+    /// transactions. Fill-or-kill orders are never parked, they are killed
+    /// instead.
     fn park_transactions(&mut self, txes: &[B256]) {
-        let order_info = txes
+        let (to_kill, to_park): (Vec<_>, Vec<_>) = txes
             .iter()
-            .filter_map(|tx_hash| self.order_hash_to_order_id.get(tx_hash))
-            .collect::<Vec<_>>();
-        self.order_storage.park_orders(order_info);
+            .filter_map(|tx_hash| self.order_hash_to_order_id.get(tx_hash).copied())
+            .partition(|order_id| {
+                order_id.flash_block.is_some()
+                    && self
+                        .order_storage
+                        .limit_orders
+                        .lock()
+                        .expect("poisoned")
+                        .get_order(order_id)
+                        .map(|order| AllOrders::from(order.order).is_fill_or_kill())
+                        .unwrap_or_default()
+            });
+
+        to_kill.into_iter().for_each(|order_id| {
+            self.untrack_order(&order_id.hash);
+
+            if let Some(order) = self.order_storage.remove_limit_order(&order_id) {
+                self.record_order_event(
+                    self.block_number,
+                    order_id.hash,
+                    order.pool_id,
+                    OrderEventKind::Killed
+                );
+                self.notify_order_subscribers(PoolManagerUpdate::KilledOrder(
+                    self.block_number,
+                    order
+                ));
+            }
+        });
+
+        self.order_storage.park_orders(to_park.iter().collect());
     }
 
     fn handle_validated_order(
@@ -430,50 +800,162 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
 
                 // what about the deadline?
                 if valid.valid_block != self.block_number {
-                    self.notify_validation_subscribers(
-                        &hash,
-                        OrderValidationResults::Invalid(hash)
+                    let rejection = Rejection::new(
+                        RejectionReason::StaleBlock,
+                        format!(
+                            "validated for block {} where current is {}",
+                            valid.valid_block, self.block_number
+                        )
                     );
+                    self.reject_order(hash, rejection, None);
 
                     self.seen_invalid_orders.insert(hash);
                     let peers = self.order_hash_to_peer_id.remove(&hash).unwrap_or_default();
                     return Ok(PoolInnerEvent::BadOrderMessages(peers))
                 }
 
+                // the pool may have filled up while the order was being validated
+                if self.order_storage.is_full() {
+                    tracing::debug!(?hash, "order pool is full, dropping validated order");
+                    self.reject_order(hash, Self::pool_full(), None);
+                    self.order_hash_to_peer_id.remove(&hash);
+                    return Ok(PoolInnerEvent::None)
+                }
+
+                self.record_order_event(
+                    self.block_number,
+                    hash,
+                    valid.pool_id,
+                    OrderEventKind::Validated
+                );
+                self.append_to_wal(WalEntry::Accepted { order: valid.order.clone() });
                 self.notify_order_subscribers(PoolManagerUpdate::NewOrder(valid.clone()));
                 self.notify_validation_subscribers(
                     &hash,
                     OrderValidationResults::Valid(valid.clone())
                 );
 
-                let to_propagate = valid.order.clone();
+                let to_propagate = valid.clone();
+                self.recently_accepted.insert(hash, valid.clone());
+                self.recently_rejected.remove(&hash);
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs();
+                self.order_lifetimes
+                    .accepted(hash, valid.pool_id, self.block_number, now);
                 self.update_order_tracking(&hash, valid.from(), valid.order_id);
                 self.park_transactions(&valid.invalidates);
-                self.insert_order(valid)?;
+                if let Some(evicted) = self.insert_order(valid)? {
+                    self.evicted_order(evicted);
+                }
 
                 Ok(PoolInnerEvent::Propagation(to_propagate))
             }
-            OrderValidationResults::Invalid(bad_hash) => {
-                self.notify_validation_subscribers(
-                    &bad_hash,
-                    OrderValidationResults::Invalid(bad_hash)
-                );
+            OrderValidationResults::Invalid(bad_hash, rejection) => {
+                self.reject_order(bad_hash, rejection, None);
                 let peers = self
                     .order_hash_to_peer_id
                     .remove(&bad_hash)
                     .unwrap_or_default();
                 Ok(PoolInnerEvent::BadOrderMessages(peers))
             }
+            OrderValidationResults::PoolNotSynced { order, pool_id } => {
+                let rejection = Rejection::new(
+                    RejectionReason::PoolNotSynced,
+                    format!("pool {pool_id:?} hasn't been synced yet")
+                );
+                let result =
+                    OrderValidationResults::PoolNotSynced { order: order.clone(), pool_id };
+                self.hold_until_pool_syncs(order, pool_id, result, rejection);
+
+                Ok(PoolInnerEvent::None)
+            }
+            OrderValidationResults::PoolNotInitialized { order, init } => {
+                let rejection = Rejection::new(
+                    RejectionReason::PoolNotInitialized,
+                    format!("pool {:?} isn't initialized yet", init.pool_id)
+                );
+                // the first order held for the pool announces what initializing
+                // it takes
+                let first_held = !self.held_orders.is_holding(&init.pool_id);
+                let result =
+                    OrderValidationResults::PoolNotInitialized { order: order.clone(), init };
+                let held = self.hold_until_pool_syncs(order, init.pool_id, result, rejection);
+                if held && first_held {
+                    tracing::info!(
+                        pool_id = ?init.pool_id,
+                        asset0 = ?init.asset0,
+                        asset1 = ?init.asset1,
+                        store_index = init.store_index,
+                        "holding orders for a pool that needs to be initialized"
+                    );
+                    self.notify_order_subscribers(PoolManagerUpdate::PoolInitRequired(init));
+                }
+
+                Ok(PoolInnerEvent::None)
+            }
             OrderValidationResults::TransitionedToBlock => Ok(PoolInnerEvent::None)
         }
     }
 
+    /// holds the order until the initialization watcher reports its pool,
+    /// rejecting it with `rejection` if too many orders are held already for
+    /// the pool or its sender. Returns whether the order is held
+    fn hold_until_pool_syncs(
+        &mut self,
+        order: AllOrders,
+        pool_id: PoolId,
+        result: OrderValidationResults,
+        rejection: Rejection
+    ) -> bool {
+        let hash = order.order_hash();
+        // the sender couldn't have known the pool isn't ready here, so a
+        // relaying peer isn't penalized
+        self.order_hash_to_peer_id.remove(&hash);
+
+        if let Err(e) = self.held_orders.hold(pool_id, order, self.block_number) {
+            tracing::debug!(?hash, ?pool_id, %e, "not holding order until its pool syncs");
+            let rejection = Rejection::new(rejection.code, format!("{}, {e}", rejection.message));
+            self.reject_order(hash, rejection, None);
+            return false
+        }
+        self.notify_validation_subscribers(&hash, result);
+
+        true
+    }
+
     fn notify_order_subscribers(&mut self, update: PoolManagerUpdate) {
         if let Err(e) = self.orders_subscriber_tx.send(update) {
             error!("could not send order update {:?}", e)
         }
     }
 
+    /// tells the order's validation subscribers, and `subscriber` if given,
+    /// why the order was rejected and remembers it for status queries
+    fn reject_order(
+        &mut self,
+        hash: B256,
+        rejection: Rejection,
+        subscriber: Option<Sender<OrderValidationResults>>
+    ) {
+        if let Some(subscriber) = subscriber {
+            self.order_validation_subs
+                .entry(hash)
+                .or_default()
+                .push(subscriber);
+        }
+        // a known order keeps its status, or the reason it was first rejected for
+        if rejection.code != RejectionReason::DuplicateOrder {
+            self.recently_rejected.insert(hash, rejection.clone());
+        }
+        self.notify_validation_subscribers(&hash, OrderValidationResults::Invalid(hash, rejection));
+    }
+
+    fn pool_full() -> Rejection {
+        Rejection::new(RejectionReason::PoolFull, "order pool is full")
+    }
+
     fn notify_validation_subscribers(&mut self, hash: &B256, result: OrderValidationResults) {
         if let Some(subscribers) = self.order_validation_subs.remove(hash) {
             for subscriber in subscribers {
@@ -484,7 +966,11 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         }
     }
 
-    fn insert_order(&mut self, res: OrderWithStorageData<AllOrders>) -> eyre::Result<()> {
+    /// Returns the order evicted from the full pool to make room for `res`
+    fn insert_order(
+        &mut self,
+        res: OrderWithStorageData<AllOrders>
+    ) -> eyre::Result<Option<OrderWithStorageData<AllOrders>>> {
         match res.order_id.location {
             angstrom_types::orders::OrderLocation::Searcher => self
                 .order_storage
@@ -495,6 +981,7 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
                     })
                     .expect("should be unreachable")
                 )
+                .map(|_| None)
                 .map_err(|e| eyre::anyhow!("{:?}", e)),
             angstrom_types::orders::OrderLocation::Limit => self
                 .order_storage
@@ -516,20 +1003,68 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         }
     }
 
+    /// Removes a resting order from the pool to make room, e.g. because the
+    /// peer that relayed it is over its cap. Returns whether the order was in
+    /// the pool.
+    pub fn evict_order(&mut self, order_hash: &B256) -> bool {
+        let Some(id) = self.order_hash_to_order_id.get(order_hash).copied() else { return false };
+        let Some(order) = (match id.location {
+            OrderLocation::Limit => self.order_storage.remove_limit_order(&id),
+            OrderLocation::Searcher => self.order_storage.remove_searcher_order(&id)
+        }) else {
+            return false
+        };
+
+        self.evicted_order(order);
+        true
+    }
+
+    /// a resting order that was pushed out of the pool, by one that tips more
+    /// when the pool is full or through [`Self::evict_order`]
+    fn evicted_order(&mut self, order: OrderWithStorageData<AllOrders>) {
+        let hash = order.order_hash();
+        tracing::debug!(?hash, "evicted resting order from the pool");
+
+        self.untrack_order(&hash);
+        self.record_order_event(self.block_number, hash, order.pool_id, OrderEventKind::Evicted);
+        self.notify_order_subscribers(PoolManagerUpdate::EvictedOrder(order));
+    }
+
     fn update_order_tracking(&mut self, hash: &B256, user: UserAddress, id: OrderId) {
         self.order_hash_to_peer_id.remove(hash);
         self.order_hash_to_order_id.insert(*hash, id);
-        // nonce overlap is checked during validation so its ok we
-        // don't check for duplicates
-        self.address_to_orders.entry(user).or_default().push(id);
+        self.address_to_orders
+            .entry(user)
+            .or_default()
+            .insert(*hash);
+    }
+
+    /// Stops tracking the order under its hash and sender, returns its id if
+    /// it was tracked
+    fn untrack_order(&mut self, hash: &B256) -> Option<OrderId> {
+        self.order_lifetimes.removed(hash);
+        let order_id = self.order_hash_to_order_id.remove(hash)?;
+        if let Some(hashes) = self.address_to_orders.get_mut(&order_id.address) {
+            hashes.remove(hash);
+            if hashes.is_empty() {
+                self.address_to_orders.remove(&order_id.address);
+            }
+        }
+
+        Some(order_id)
     }
 
     pub fn get_all_orders(&self) -> OrderSet<GroupedVanillaOrder, TopOfBlockOrder> {
         self.order_storage.get_all_orders()
     }
 
-    pub fn new_pool(&self, pool: NewInitializedPool) {
+    pub fn new_pool(&mut self, pool: NewInitializedPool) {
+        self.pool_id_map.on_new_pool(pool);
         self.order_storage.new_pool(pool);
+
+        for order in self.held_orders.release(&pool.id) {
+            self.validator.validate_order(OrderOrigin::Local, order);
+        }
     }
 
     pub fn start_new_block_processing(
@@ -555,13 +1090,20 @@ impl<V: OrderValidatorHandle<Order = AllOrders>> OrderIndexer<V> {
         self.filled_orders(block_number, &completed_orders);
         // add expired orders to completed
         completed_orders.extend(self.remove_expired_orders(block_number));
+        // orders held for too long or for a past block are dropped
+        for hash in self.held_orders.expire(block_number) {
+            let rejection = Rejection::new(
+                RejectionReason::Expired,
+                format!("order {hash:?} expired before its pool was ready")
+            );
+            self.reject_order(hash, rejection, None);
+        }
 
         let time_now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
-        self.cancelled_orders
-            .retain(|_, request| request.valid_until >= time_now);
+        self.cancelled_orders.remove_expired(time_now);
         self.validator.notify_validation_on_changes(
             block_number,
             completed_orders,
@@ -601,7 +1143,7 @@ where
 }
 
 pub enum PoolInnerEvent {
-    Propagation(AllOrders),
+    Propagation(OrderWithStorageData<AllOrders>),
     BadOrderMessages(Vec<PeerId>),
     HasTransitionedToNewBlock(u64),
     None
@@ -619,3 +1161,34 @@ pub enum PoolError {
     #[error("Duplicate order")]
     DuplicateOrder
 }
+
+impl From<&PoolError> for Rejection {
+    fn from(error: &PoolError) -> Self {
+        let code = match error {
+            PoolError::MaxSize => RejectionReason::PoolFull,
+            PoolError::NoPool(_) => RejectionReason::NoPool,
+            PoolError::DuplicateNonce(_) => RejectionReason::DuplicateNonce,
+            PoolError::DuplicateOrder => RejectionReason::DuplicateOrder
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pool_errors_map_to_rejection_codes() {
+        let cases = [
+            (PoolError::MaxSize, RejectionReason::PoolFull),
+            (PoolError::NoPool(PoolId::random()), RejectionReason::NoPool),
+            (PoolError::DuplicateNonce(OrderId::default()), RejectionReason::DuplicateNonce),
+            (PoolError::DuplicateOrder, RejectionReason::DuplicateOrder)
+        ];
+        for (error, code) in cases {
+            assert_eq!(Rejection::from(&error), Rejection::new(code, error.to_string()));
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::{collections::HashMap, time::Duration};
+
+use alloy::primitives::{BlockNumber, B256};
+use angstrom_types::primitive::PoolId;
+use serde::{Deserialize, Deserializer};
+use validation::order::rejection::{Rejection, RejectionReason};
+
+/// How long an order has to rest in the pool after it was accepted before its
+/// sender may cancel it. Deters submitting quotes only to cancel them right
+/// away. Configured as `{ blocks = n }` or `{ seconds = n }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MinOrderLifetime {
+    Blocks(u64),
+    #[serde(rename = "seconds", deserialize_with = "duration_from_secs")]
+    Duration(Duration)
+}
+
+fn duration_from_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_secs)
+}
+
+/// Why a cancellation of a resting order was refused.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CancelOrderError {
+    #[error("order {order_hash} can't be cancelled before it rested for {min_lifetime:?}")]
+    TooSoonToCancel { order_hash: B256, min_lifetime: MinOrderLifetime }
+}
+
+impl From<&CancelOrderError> for Rejection {
+    fn from(error: &CancelOrderError) -> Self {
+        let code = match error {
+            CancelOrderError::TooSoonToCancel { .. } => RejectionReason::TooSoonToCancel
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+struct Accepted {
+    min_lifetime: MinOrderLifetime,
+    block:        BlockNumber,
+    /// unix timestamp in seconds
+    timestamp:    u64
+}
+
+/// When the orders of pools with a minimum lifetime were accepted. Orders of
+/// other pools aren't tracked.
+#[derive(Default)]
+pub(crate) struct OrderLifetimes {
+    min_lifetimes: HashMap<PoolId, MinOrderLifetime>,
+    accepted:      HashMap<B256, Accepted>
+}
+
+impl OrderLifetimes {
+    pub(crate) fn new(min_lifetimes: HashMap<PoolId, MinOrderLifetime>) -> Self {
+        Self { min_lifetimes, accepted: HashMap::new() }
+    }
+
+    /// Starts the order's lifetime. An order that is accepted again, e.g.
+    /// after being revalidated, keeps the time it was first accepted at.
+    pub(crate) fn accepted(
+        &mut self,
+        order_hash: B256,
+        pool_id: PoolId,
+        block: BlockNumber,
+        timestamp: u64
+    ) {
+        let Some(min_lifetime) = self.min_lifetimes.get(&pool_id).copied() else { return };
+        self.accepted
+            .entry(order_hash)
+            .or_insert(Accepted { min_lifetime, block, timestamp });
+    }
+
+    /// whether orders of the pool have a minimum lifetime
+    pub(crate) fn applies_to(&self, pool_id: &PoolId) -> bool {
+        self.min_lifetimes.contains_key(pool_id)
+    }
+
+    pub(crate) fn removed(&mut self, order_hash: &B256) {
+        self.accepted.remove(order_hash);
+    }
+
+    /// errors if the order hasn't rested for its pool's minimum lifetime at
+    /// `block` and `timestamp` yet
+    pub(crate) fn check_cancel(
+        &self,
+        order_hash: B256,
+        block: BlockNumber,
+        timestamp: u64
+    ) -> Result<(), CancelOrderError> {
+        let Some(accepted) = self.accepted.get(&order_hash) else { return Ok(()) };
+        let rested = match accepted.min_lifetime {
+            MinOrderLifetime::Blocks(blocks) => block.saturating_sub(accepted.block) >= blocks,
+            MinOrderLifetime::Duration(duration) => {
+                Duration::from_secs(timestamp.saturating_sub(accepted.timestamp)) >= duration
+            }
+        };
+
+        if rested {
+            Ok(())
+        } else {
+            Err(CancelOrderError::TooSoonToCancel {
+                order_hash,
+                min_lifetime: accepted.min_lifetime
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_orders_rest_for_their_pools_lifetime() {
+        let (blocks_pool, seconds_pool) = (PoolId::random(), PoolId::random());
+        let mut lifetimes = OrderLifetimes::new(HashMap::from([
+            (blocks_pool, MinOrderLifetime::Blocks(2)),
+            (seconds_pool, MinOrderLifetime::Duration(Duration::from_secs(30)))
+        ]));
+        let (by_blocks, by_seconds, untracked) = (B256::random(), B256::random(), B256::random());
+        lifetimes.accepted(by_blocks, blocks_pool, 10, 1_000);
+        lifetimes.accepted(by_seconds, seconds_pool, 10, 1_000);
+        lifetimes.accepted(untracked, PoolId::random(), 10, 1_000);
+
+        let too_soon = lifetimes.check_cancel(by_blocks, 11, 5_000).unwrap_err();
+        assert_eq!(Rejection::from(&too_soon).code, RejectionReason::TooSoonToCancel);
+        assert!(lifetimes.check_cancel(by_blocks, 12, 1_000).is_ok());
+        assert!(lifetimes.check_cancel(by_seconds, 20, 1_029).is_err());
+        assert!(lifetimes.check_cancel(by_seconds, 10, 1_030).is_ok());
+        assert!(lifetimes.check_cancel(untracked, 10, 1_000).is_ok());
+
+        // being accepted again doesn't restart the lifetime
+        lifetimes.accepted(by_blocks, blocks_pool, 12, 1_000);
+        assert!(lifetimes.check_cancel(by_blocks, 12, 1_000).is_ok());
+
+        lifetimes.removed(&by_seconds);
+        assert!(lifetimes.check_cancel(by_seconds, 10, 1_000).is_ok());
+    }
+
+    #[test]
+    fn test_lifetimes_deserialize_from_blocks_or_seconds() {
+        let blocks: MinOrderLifetime = serde_json::from_str(r#"{ "blocks": 2 }"#).unwrap();
+        assert_eq!(blocks, MinOrderLifetime::Blocks(2));
+
+        let seconds: MinOrderLifetime = serde_json::from_str(r#"{ "seconds": 30 }"#).unwrap();
+        assert_eq!(seconds, MinOrderLifetime::Duration(Duration::from_secs(30)));
+    }
+}
@@ -2,7 +2,10 @@ use std::{
     collections::HashMap,
     default::Default,
     fmt::Debug,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex
+    },
     time::Instant
 };
 
@@ -12,16 +15,20 @@ use angstrom_types::{
     orders::{OrderId, OrderLocation, OrderSet, OrderStatus},
     primitive::{NewInitializedPool, PoolId},
     sol_bindings::{
-        grouped_orders::{AllOrders, GroupedUserOrder, GroupedVanillaOrder, OrderWithStorageData},
+        grouped_orders::{
+            AllOrders, GroupedUserOrder, GroupedVanillaOrder, OrderWithStorageData, TobRank
+        },
         rpc_orders::TopOfBlockOrder
     }
 };
+use validation::common::TokenPriceSnapshot;
 
 use crate::{
+    book_checksum,
     finalization_pool::FinalizationPool,
     limit::{LimitOrderPool, LimitPoolError},
     searcher::{SearcherPool, SearcherPoolError},
-    PoolConfig
+    BookDiff, BookNotional, PausedPools, PoolConfig
 };
 
 /// The Storage of all verified orders.
@@ -33,6 +40,11 @@ pub struct OrderStorage {
     /// we store filled order hashes until they are expired time wise to ensure
     /// we don't waste processing power in the validator.
     pub filled_orders:               Arc<Mutex<HashMap<B256, Instant>>>,
+    /// number of orders held across the limit and searcher pools.
+    total_orders:                    Arc<AtomicUsize>,
+    max_total_orders:                usize,
+    /// pools that neither take new orders nor are matched
+    paused_pools:                    PausedPools,
     pub metrics:                     OrderStorageMetricsWrapper
 }
 
@@ -59,21 +71,64 @@ impl OrderStorage {
             limit_orders,
             searcher_orders,
             pending_finalization_orders,
+            total_orders: Arc::new(AtomicUsize::new(0)),
+            max_total_orders: config.max_total_orders,
+            paused_pools: PausedPools::default(),
             metrics: OrderStorageMetricsWrapper::default()
         }
     }
 
+    /// shares `paused_pools` with whoever pauses and resumes pools
+    pub fn with_paused_pools(mut self, paused_pools: PausedPools) -> Self {
+        self.paused_pools = paused_pools;
+        self
+    }
+
+    /// compares the tips of limit orders for different pools at
+    /// `token_prices` when the pool is full
+    pub fn with_token_prices(self, token_prices: TokenPriceSnapshot) -> Self {
+        self.limit_orders
+            .lock()
+            .expect("poisoned")
+            .set_token_prices(token_prices);
+        self
+    }
+
+    pub fn paused_pools(&self) -> &PausedPools {
+        &self.paused_pools
+    }
+
+    /// number of orders currently held across all pools
+    pub fn total_orders(&self) -> usize {
+        self.total_orders.load(Ordering::SeqCst)
+    }
+
+    /// whether the storage has hit the configured max total orders. New
+    /// orders are rejected until some are filled, cancelled or expire.
+    pub fn is_full(&self) -> bool {
+        self.total_orders() >= self.max_total_orders
+    }
+
+    fn incr_total_orders(&self) {
+        let total = self.total_orders.fetch_add(1, Ordering::SeqCst) + 1;
+        self.metrics.set_total_orders(total);
+    }
+
+    fn decr_total_orders(&self) {
+        // saturates rather than wrapping around to a pool that looks full
+        let previous = self
+            .total_orders
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |total| Some(total.saturating_sub(1)))
+            .unwrap_or_else(|previous| previous);
+        self.metrics.set_total_orders(previous.saturating_sub(1));
+    }
+
     pub fn fetch_status_of_order(&self, order: B256) -> Option<OrderStatus> {
         if self
-            .filled_orders
+            .pending_finalization_orders
             .lock()
             .expect("poisoned")
-            .contains_key(&order)
-            && self
-                .pending_finalization_orders
-                .lock()
-                .expect("poisoned")
-                .has_order(&order)
+            .has_order(&order)
         {
             return Some(OrderStatus::Filled)
         }
@@ -93,6 +148,15 @@ impl OrderStorage {
             .get_order_status(order)
     }
 
+    /// a filled order that is waiting for its block to be finalized
+    pub fn fetch_filled_order(&self, order: &B256) -> Option<OrderWithStorageData<AllOrders>> {
+        self.pending_finalization_orders
+            .lock()
+            .expect("poisoned")
+            .get_order(order)
+            .cloned()
+    }
+
     // unfortunately, any other solution is just as ugly
     // this needs to be revisited once composable orders are in place
     pub fn log_cancel_order(&self, order: &AllOrders) {
@@ -113,7 +177,7 @@ impl OrderStorage {
             return None
         }
 
-        match order_id.location {
+        let order = match order_id.location {
             angstrom_types::orders::OrderLocation::Limit => self
                 .limit_orders
                 .lock()
@@ -139,7 +203,13 @@ impl OrderStorage {
                         .try_map_inner(|inner| Ok(AllOrders::TOB(inner)))
                         .unwrap()
                 })
+        };
+
+        if order.is_some() {
+            self.decr_total_orders();
         }
+
+        order
     }
 
     /// moves all orders to the parked location if there not already.
@@ -168,7 +238,7 @@ impl OrderStorage {
             .get_orders_for_pool(pool_id)
             .unwrap_or_else(|| panic!("pool {} does not exist", pool_id))
             .iter()
-            .max_by_key(|order| order.tob_reward)
+            .max_by(|a, b| TobRank(a).cmp(&TobRank(b)))
             .cloned()
     }
 
@@ -185,11 +255,17 @@ impl OrderStorage {
         top_orders
     }
 
+    /// Adds the order, returning the order evicted to make room for it if the
+    /// limit pool was full
     pub fn add_new_limit_order(
         &self,
         order: OrderWithStorageData<GroupedUserOrder>
-    ) -> Result<(), LimitPoolError> {
-        if order.is_vanilla() {
+    ) -> Result<Option<OrderWithStorageData<AllOrders>>, LimitPoolError> {
+        if self.is_full() {
+            return Err(LimitPoolError::MaxOrders(self.max_total_orders))
+        }
+
+        let evicted = if order.is_vanilla() {
             let mapped_order = order.try_map_inner(|this| {
                 let GroupedUserOrder::Vanilla(order) = this else {
                     return Err(eyre::eyre!("unreachable"))
@@ -197,11 +273,13 @@ impl OrderStorage {
                 Ok(order)
             })?;
 
-            self.limit_orders
+            let evicted = self
+                .limit_orders
                 .lock()
                 .expect("lock poisoned")
                 .add_vanilla_order(mapped_order)?;
             self.metrics.incr_vanilla_limit_orders(1);
+            evicted
         } else {
             let mapped_order = order.try_map_inner(|this| {
                 let GroupedUserOrder::Composable(order) = this else {
@@ -210,26 +288,43 @@ impl OrderStorage {
                 Ok(order)
             })?;
 
-            self.limit_orders
+            let evicted = self
+                .limit_orders
                 .lock()
                 .expect("lock poisoned")
                 .add_composable_order(mapped_order)?;
             self.metrics.incr_composable_limit_orders(1);
-        }
+            evicted
+        };
+        self.incr_total_orders();
+
+        Ok(evicted.and_then(|order| {
+            if order.is_vanilla() {
+                self.metrics.decr_vanilla_limit_orders(1);
+            } else if order.is_composable() {
+                self.metrics.decr_composable_limit_orders(1);
+            }
+            self.decr_total_orders();
 
-        Ok(())
+            order.try_map_inner(|inner| Ok(inner.into())).ok()
+        }))
     }
 
     pub fn add_new_searcher_order(
         &self,
         order: OrderWithStorageData<TopOfBlockOrder>
     ) -> Result<(), SearcherPoolError> {
+        if self.is_full() {
+            return Err(SearcherPoolError::MaxOrders(self.max_total_orders))
+        }
+
         self.searcher_orders
             .lock()
             .expect("lock poisoned")
             .add_searcher_order(order)?;
 
         self.metrics.incr_searcher_orders(1);
+        self.incr_total_orders();
 
         Ok(())
     }
@@ -285,6 +380,10 @@ impl OrderStorage {
                     .unwrap()
             });
 
+        if order.is_some() {
+            self.decr_total_orders();
+        }
+
         order
     }
 
@@ -299,11 +398,21 @@ impl OrderStorage {
                 } else if order.is_composable() {
                     self.metrics.decr_composable_limit_orders(1);
                 }
+                self.decr_total_orders();
 
                 order.try_map_inner(|inner| Ok(inner.into())).ok()
             })
     }
 
+    /// notional of the pool's pending limit orders, `None` for an unknown pool
+    pub fn book_notional(&self, pool_id: PoolId) -> Option<BookNotional> {
+        self.limit_orders
+            .lock()
+            .expect("poisoned")
+            .book_notional(pool_id)
+            .ok()
+    }
+
     pub fn get_all_orders(&self) -> OrderSet<GroupedVanillaOrder, TopOfBlockOrder> {
         let limit = self.limit_orders.lock().expect("poisoned").get_all_orders();
         let searcher = self.top_tob_orders();
@@ -311,6 +420,38 @@ impl OrderStorage {
         OrderSet { limit, searcher }
     }
 
+    /// what changed in the live book since `previous`, a set earlier taken
+    /// with [`Self::get_all_orders`]
+    pub fn diff(&self, previous: &OrderSet<GroupedVanillaOrder, TopOfBlockOrder>) -> BookDiff {
+        BookDiff::between(previous, &self.get_all_orders())
+    }
+
+    /// hashes of the pool's live orders
+    pub fn book_order_hashes(&self, pool_id: PoolId) -> Vec<B256> {
+        let limit = self
+            .limit_orders
+            .lock()
+            .expect("poisoned")
+            .get_all_orders_from_pool(pool_id);
+        let searcher = self
+            .searcher_orders
+            .lock()
+            .expect("poisoned")
+            .get_all_orders_from_pool(pool_id);
+
+        limit
+            .iter()
+            .chain(&searcher)
+            .map(|order| order.order_hash())
+            .collect()
+    }
+
+    /// checksum over the hashes of the pool's live orders, see
+    /// [`book_checksum`]
+    pub fn book_checksum(&self, pool_id: PoolId) -> B256 {
+        book_checksum(self.book_order_hashes(pool_id))
+    }
+
     pub fn new_pool(&self, pool: NewInitializedPool) {
         self.limit_orders.lock().expect("poisoned").new_pool(pool);
         self.searcher_orders
@@ -319,3 +460,53 @@ impl OrderStorage {
             .new_pool(pool);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    fn limit_order(pool_id: PoolId, nonce: u64) -> OrderWithStorageData<GroupedUserOrder> {
+        UserOrderBuilder::new()
+            .standing()
+            .nonce(nonce)
+            .with_storage()
+            .pool_id(pool_id)
+            .build()
+            .try_map_inner(|order| Ok(GroupedUserOrder::Vanilla(order)))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_orders_over_the_max_are_rejected_until_one_leaves() {
+        let pool_id = PoolId::random();
+        let storage = OrderStorage::new(&PoolConfig {
+            ids: vec![pool_id],
+            max_total_orders: 1,
+            ..Default::default()
+        });
+
+        let first = limit_order(pool_id, 0);
+        let first_id = first.order_id;
+        storage.add_new_limit_order(first).unwrap();
+        assert!(storage.is_full());
+        assert!(matches!(
+            storage.add_new_limit_order(limit_order(pool_id, 1)),
+            Err(LimitPoolError::MaxOrders(1))
+        ));
+
+        assert!(storage.remove_limit_order(&first_id).is_some());
+        assert_eq!(storage.total_orders(), 0);
+        assert!(storage.add_new_limit_order(limit_order(pool_id, 1)).is_ok());
+    }
+
+    #[test]
+    fn test_total_orders_does_not_wrap_below_zero() {
+        let storage = OrderStorage::new(&PoolConfig::default());
+
+        storage.decr_total_orders();
+        assert_eq!(storage.total_orders(), 0);
+        assert!(!storage.is_full());
+    }
+}
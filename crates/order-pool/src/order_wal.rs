@@ -0,0 +1,236 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread::{self, JoinHandle}
+};
+
+use alloy::primitives::B256;
+use angstrom_types::sol_bindings::{grouped_orders::AllOrders, RawPoolOrder};
+use serde::{Deserialize, Serialize};
+
+/// A change to the book, one json object per line of the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum WalEntry {
+    /// the order was accepted into the pool
+    Accepted { order: AllOrders },
+    /// the order left the pool, filled, cancelled, killed or evicted
+    Removed { order_hash: B256 }
+}
+
+/// Write-ahead log of the orders accepted into and removed from the pool, so
+/// the book can be rebuilt after a crash with [`replay`].
+///
+/// Entries are written by a dedicated thread, appending only hands them over,
+/// so the pool never waits on the disk. The log is compacted through
+/// [`Self::truncate`], which replaces it with the orders resting at that
+/// point, so it only grows by what happened since. Dropping the log waits for
+/// the entries handed over so far to be written.
+#[derive(Debug)]
+pub struct OrderWal {
+    path:     PathBuf,
+    /// entries appended since the log was last compacted
+    appended: usize,
+    commands: Option<mpsc::Sender<WalCommand>>,
+    writer:   Option<JoinHandle<()>>
+}
+
+#[derive(Debug)]
+enum WalCommand {
+    Append(WalEntry),
+    Truncate(Vec<AllOrders>)
+}
+
+impl OrderWal {
+    /// opens the log at `path`, entries are appended to what's already in it
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let (commands, rx) = mpsc::channel();
+        let writer_path = path.clone();
+        let writer = thread::Builder::new()
+            .name("order-wal".into())
+            .spawn(move || write_log(writer_path, file, rx))?;
+
+        Ok(Self { path, appended: 0, commands: Some(commands), writer: Some(writer) })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// number of entries appended since the log was last compacted
+    pub fn appended(&self) -> usize {
+        self.appended
+    }
+
+    pub fn append(&mut self, entry: WalEntry) {
+        self.appended += 1;
+        self.send(WalCommand::Append(entry));
+    }
+
+    /// Replaces the log with `snapshot`, the orders resting in the pool. The
+    /// new log is written next to the old one and moved over it, so a crash
+    /// halfway leaves the old log in place.
+    pub fn truncate(&mut self, snapshot: Vec<AllOrders>) {
+        self.appended = 0;
+        self.send(WalCommand::Truncate(snapshot));
+    }
+
+    fn send(&self, command: WalCommand) {
+        let sent = self
+            .commands
+            .as_ref()
+            .is_some_and(|commands| commands.send(command).is_ok());
+        if !sent {
+            tracing::error!(path = %self.path.display(), "order wal writer stopped");
+        }
+    }
+}
+
+impl Drop for OrderWal {
+    fn drop(&mut self) {
+        // closing the channel stops the writer once it wrote what's queued
+        self.commands.take();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+fn write_log(path: PathBuf, mut file: File, commands: mpsc::Receiver<WalCommand>) {
+    for command in commands {
+        let written = match command {
+            WalCommand::Append(entry) => write_entry(&mut file, &entry),
+            WalCommand::Truncate(snapshot) => {
+                compact(&path, &snapshot).map(|compacted| file = compacted)
+            }
+        };
+        if let Err(error) = written {
+            tracing::error!(%error, path = %path.display(), "failed to write the order wal");
+        }
+    }
+}
+
+fn compact(path: &Path, snapshot: &[AllOrders]) -> io::Result<File> {
+    let compacted = path.with_extension("compacting");
+    let mut file = File::create(&compacted)?;
+    for order in snapshot {
+        write_entry(&mut file, &WalEntry::Accepted { order: order.clone() })?;
+    }
+    file.sync_all()?;
+    std::fs::rename(&compacted, path)?;
+
+    OpenOptions::new().append(true).open(path)
+}
+
+fn write_entry(file: &mut File, entry: &WalEntry) -> io::Result<()> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    file.write_all(&line)
+}
+
+/// Rebuilds the resting orders from `snapshot` and the entries of the log at
+/// `wal` that followed it, in the order they were accepted. A missing log
+/// leaves the snapshot as is. Lines that don't parse, like one cut short by a
+/// crash, are skipped.
+pub fn replay(wal: &Path, snapshot: Vec<AllOrders>) -> io::Result<Vec<AllOrders>> {
+    let mut resting = snapshot
+        .into_iter()
+        .enumerate()
+        .map(|(position, order)| (order.order_hash(), (position, order)))
+        .collect::<HashMap<_, _>>();
+    let mut position = resting.len();
+
+    let file = match File::open(wal) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(sorted(resting)),
+        Err(e) => return Err(e)
+    };
+    for line in BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+
+        match serde_json::from_str::<WalEntry>(&line) {
+            Ok(WalEntry::Accepted { order }) => {
+                resting
+                    .entry(order.order_hash())
+                    .or_insert((position, order));
+                position += 1;
+            }
+            Ok(WalEntry::Removed { order_hash }) => {
+                resting.remove(&order_hash);
+            }
+            Err(error) => {
+                tracing::warn!(%error, path = %wal.display(), "skipping malformed order wal entry");
+            }
+        }
+    }
+
+    Ok(sorted(resting))
+}
+
+fn sorted(resting: HashMap<B256, (usize, AllOrders)>) -> Vec<AllOrders> {
+    let mut orders = resting.into_values().collect::<Vec<_>>();
+    orders.sort_by_key(|(position, _)| *position);
+    orders.into_iter().map(|(_, order)| order).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use angstrom_types::sol_bindings::{
+        grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder
+    };
+
+    use super::*;
+
+    fn order(nonce: u64) -> AllOrders {
+        let order = ExactStandingOrder { nonce, ..Default::default() };
+        AllOrders::Standing(StandingVariants::Exact(order))
+    }
+
+    #[test]
+    fn test_replay_rebuilds_the_book() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.wal");
+        let (snapshotted, filled, resting) = (order(0), order(1), order(2));
+
+        let mut wal = OrderWal::open(path.clone()).unwrap();
+        wal.append(WalEntry::Accepted { order: filled.clone() });
+        wal.append(WalEntry::Accepted { order: resting.clone() });
+        wal.append(WalEntry::Removed { order_hash: filled.order_hash() });
+        drop(wal);
+
+        let orders = replay(&path, vec![snapshotted.clone()]).unwrap();
+        assert_eq!(orders, vec![snapshotted, resting]);
+
+        // a missing log is an empty one
+        assert!(replay(&dir.path().join("missing.wal"), vec![])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_truncate_keeps_only_the_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.wal");
+        let (resting, later) = (order(0), order(1));
+
+        let mut wal = OrderWal::open(path.clone()).unwrap();
+        wal.append(WalEntry::Accepted { order: order(3) });
+        wal.append(WalEntry::Accepted { order: resting.clone() });
+        wal.truncate(vec![resting.clone()]);
+        wal.append(WalEntry::Accepted { order: later.clone() });
+        drop(wal);
+
+        // an entry cut short by a crash is skipped
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"kind\":\"accep").unwrap();
+
+        assert_eq!(replay(&path, vec![]).unwrap(), vec![resting, later]);
+    }
+}
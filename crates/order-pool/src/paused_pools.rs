@@ -0,0 +1,110 @@
+use std::{
+    collections::HashSet,
+    io,
+    path::PathBuf,
+    sync::{Arc, RwLock}
+};
+
+use angstrom_types::primitive::PoolId;
+
+/// Pools an operator has paused. New orders for a paused pool are rejected
+/// and the pool is left out of matching, other pools are unaffected. Unlike a
+/// circuit breaker halt, a pause only ends when the operator lifts it.
+///
+/// Clones share the same set, so it can be handed out before the components
+/// that check it are built.
+#[derive(Debug, Clone, Default)]
+pub struct PausedPools {
+    paused: Arc<RwLock<HashSet<PoolId>>>,
+    /// written with the paused set on every change so it survives restarts
+    path:   Option<Arc<PathBuf>>
+}
+
+impl PausedPools {
+    /// Loads the paused set from `path`, one pool id per line. A missing file
+    /// is an empty set. Every later change is written back to it.
+    pub fn load(path: PathBuf) -> io::Result<Self> {
+        let paused = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(|line| {
+                    line.parse::<PoolId>()
+                        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+                })
+                .collect::<io::Result<HashSet<_>>>()?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(e)
+        };
+
+        Ok(Self { paused: Arc::new(RwLock::new(paused)), path: Some(Arc::new(path)) })
+    }
+
+    pub fn is_paused(&self, pool_id: &PoolId) -> bool {
+        self.paused.read().expect("poisoned").contains(pool_id)
+    }
+
+    pub fn paused(&self) -> HashSet<PoolId> {
+        self.paused.read().expect("poisoned").clone()
+    }
+
+    /// returns false if the pool was already paused
+    pub fn pause(&self, pool_id: PoolId) -> bool {
+        let mut paused = self.paused.write().expect("poisoned");
+        let newly_paused = paused.insert(pool_id);
+        if newly_paused {
+            tracing::warn!(?pool_id, "pool paused by operator");
+            self.persist(&paused);
+        }
+
+        newly_paused
+    }
+
+    /// returns false if the pool wasn't paused
+    pub fn resume(&self, pool_id: &PoolId) -> bool {
+        let mut paused = self.paused.write().expect("poisoned");
+        let resumed = paused.remove(pool_id);
+        if resumed {
+            tracing::info!(?pool_id, "pool resumed by operator");
+            self.persist(&paused);
+        }
+
+        resumed
+    }
+
+    fn persist(&self, paused: &HashSet<PoolId>) {
+        let Some(path) = self.path.as_ref() else { return };
+
+        let contents = paused
+            .iter()
+            .map(|pool_id| format!("{pool_id}\n"))
+            .collect::<String>();
+        if let Err(error) = std::fs::write(path.as_path(), contents) {
+            tracing::error!(%error, path = %path.display(), "failed to persist paused pools");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pause_survives_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("paused_pools");
+        let (paused, running) = (PoolId::random(), PoolId::random());
+
+        let pools = PausedPools::load(path.clone()).unwrap();
+        assert!(pools.pause(paused));
+        assert!(pools.pause(running));
+        assert!(!pools.pause(paused));
+        assert!(pools.resume(&running));
+
+        let reloaded = PausedPools::load(path).unwrap();
+        assert!(reloaded.is_paused(&paused));
+        assert!(!reloaded.is_paused(&running));
+        assert!(!reloaded.resume(&running));
+    }
+}
@@ -48,6 +48,20 @@ impl SearcherPool {
             .unwrap_or_default()
     }
 
+    /// the pool's best `depth` bids followed by its best `depth` asks
+    pub fn top_orders_from_pool(&self, pool: FixedBytes<32>, depth: usize) -> Vec<AllOrders> {
+        self.searcher_orders
+            .get(&pool)
+            .map(|pool| {
+                pool.bids()
+                    .take(depth)
+                    .chain(pool.asks().take(depth))
+                    .map(|p| p.order.clone().into())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    }
+
     pub fn has_order(&self, order_hash: B256) -> bool {
         self.searcher_orders
             .values()
@@ -126,6 +140,8 @@ impl SearcherPool {
 pub enum SearcherPoolError {
     #[error("Pool has reached max size, and order doesn't satisify replacment requirements")]
     MaxSize,
+    #[error("Order pool is holding the max of {0} orders")]
+    MaxOrders(usize),
     #[error("No pool was found for address: {0} ")]
     NoPool(PoolId),
     #[error(transparent)]
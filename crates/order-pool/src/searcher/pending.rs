@@ -60,4 +60,14 @@ impl PendingPool {
         // TODO:  This should maybe only return the one best Searcher order we've seen?
         self.orders.values().cloned().collect()
     }
+
+    /// bids from the highest to the lowest price
+    pub fn bids(&self) -> impl Iterator<Item = &OrderWithStorageData<TopOfBlockOrder>> + '_ {
+        self.bids.values().filter_map(|hash| self.orders.get(hash))
+    }
+
+    /// asks from the lowest to the highest price
+    pub fn asks(&self) -> impl Iterator<Item = &OrderWithStorageData<TopOfBlockOrder>> + '_ {
+        self.asks.values().filter_map(|hash| self.orders.get(hash))
+    }
 }
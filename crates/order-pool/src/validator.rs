@@ -62,7 +62,7 @@ where
         block_number: u64,
         completed_orders: Vec<B256>,
         revalidation_addresses: Vec<Address>
-    ) -> Self {
+    ) {
         assert!(
             !self.is_transitioning(),
             "already clearing for new block. if this gets triggered, means we have a big runtime \
@@ -70,21 +70,14 @@ where
         );
         let Self::RegularProcessing { validator, remaining_futures } = self else { unreachable!() };
 
-        let rem_futures = remaining_futures.into_iter().map(|fut| unsafe {
-            std::mem::transmute::<_, ValidationFuture>(Box::pin(fut)
-                as Pin<
-                    Box<dyn futures_util::Future<Output = OrderValidationResults> + Send + Sync>
-                >)
-        });
-
-        Self::ClearingForNewBlock {
+        *self = Self::ClearingForNewBlock {
             validator: validator.clone(),
             waiting_for_new_block: VecDeque::default(),
-            remaining_futures: FuturesUnordered::from_iter(rem_futures),
+            remaining_futures: std::mem::take(remaining_futures),
             completed_orders,
             revalidation_addresses,
             block_number
-        }
+        };
     }
 
     pub fn notify_validation_on_changes(
@@ -130,6 +123,16 @@ where
         }
     }
 
+    /// tells validation the user's order left the pool through a cancel
+    pub fn cancel_order(&self, user: Address, order_hash: B256) {
+        match self {
+            Self::ClearingForNewBlock { validator, .. }
+            | Self::WaitingForStorageCleanup { validator, .. }
+            | Self::InformState { validator, .. }
+            | Self::RegularProcessing { validator, .. } => validator.cancel_order(user, order_hash)
+        }
+    }
+
     fn is_transitioning(&self) -> bool {
         matches!(self, Self::ClearingForNewBlock { .. } | Self::InformState { .. })
     }
@@ -0,0 +1,75 @@
+//! Fixtures shared by the order indexer tests
+#![allow(dead_code)]
+
+use std::{
+    sync::Arc,
+    task::{Context, Poll}
+};
+
+use alloy::primitives::Address;
+use angstrom_types::{
+    contract_payloads::angstrom::AngstromPoolConfigStore,
+    primitive::PoolId,
+    sol_bindings::grouped_orders::{AllOrders, OrderWithStorageData}
+};
+use futures::{task::noop_waker_ref, StreamExt};
+use order_pool::{order_storage::OrderStorage, OrderIndexer, PoolConfig, PoolManagerUpdate};
+use testing_tools::{mocks::validator::MockValidator, type_generator::orders::UserOrderBuilder};
+use tokio::sync::broadcast;
+use validation::order::state::pools::AngstromPoolsTracker;
+
+/// the block the indexers are created at
+pub const BLOCK: u64 = 10;
+
+/// drives the indexer until it has no more work ready
+pub fn poll(indexer: &mut OrderIndexer<MockValidator>) {
+    let mut cx = Context::from_waker(noop_waker_ref());
+    while let Poll::Ready(Some(_)) = indexer.poll_next_unpin(&mut cx) {}
+}
+
+/// an indexer storing orders for `pool_ids` with no angstrom pools
+/// configured, and the receiver of the updates it sends
+pub fn indexer(
+    validator: &MockValidator,
+    pool_ids: Vec<PoolId>
+) -> (OrderIndexer<MockValidator>, broadcast::Receiver<PoolManagerUpdate>) {
+    let pools =
+        AngstromPoolsTracker::new(Address::random(), Arc::new(AngstromPoolConfigStore::default()));
+
+    indexer_with_pools(validator, pool_ids, pools)
+}
+
+/// an indexer storing orders for `pool_ids` that tracks `pools`, and the
+/// receiver of the updates it sends
+pub fn indexer_with_pools(
+    validator: &MockValidator,
+    pool_ids: Vec<PoolId>,
+    pools: AngstromPoolsTracker
+) -> (OrderIndexer<MockValidator>, broadcast::Receiver<PoolManagerUpdate>) {
+    let order_storage =
+        Arc::new(OrderStorage::new(&PoolConfig { ids: pool_ids, ..Default::default() }));
+    let (sub_tx, sub_rx) = broadcast::channel(100);
+
+    (OrderIndexer::new(validator.clone(), order_storage, BLOCK, sub_tx, pools), sub_rx)
+}
+
+/// the order built by `order` as stored for `pool_id`, validated at
+/// `valid_block`
+pub fn stored_order(
+    order: UserOrderBuilder,
+    pool_id: PoolId,
+    valid_block: u64
+) -> OrderWithStorageData<AllOrders> {
+    order
+        .with_storage()
+        .pool_id(pool_id)
+        .valid_block(valid_block)
+        .build()
+        .try_map_inner(|order| Ok(AllOrders::from(order)))
+        .unwrap()
+}
+
+/// a standing order for `pool_id` validated at [`BLOCK`]
+pub fn valid_order(pool_id: PoolId) -> OrderWithStorageData<AllOrders> {
+    stored_order(UserOrderBuilder::new().standing(), pool_id, BLOCK)
+}
@@ -0,0 +1,128 @@
+mod common;
+
+use alloy::primitives::{Address, FixedBytes, B256};
+use angstrom_types::{
+    orders::OrderOrigin,
+    primitive::NewInitializedPool,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use common::{indexer, poll, valid_order, BLOCK};
+use order_pool::{OrderIndexer, PoolManagerUpdate, MAX_HELD_ORDERS_PER_SENDER};
+use testing_tools::{mocks::validator::MockValidator, type_generator::orders::UserOrderBuilder};
+use validation::order::{
+    rejection::RejectionReason, state::pools::PoolInitParams, OrderValidationResults
+};
+
+/// submits an order the validator holds for the uninitialized pool `init`
+fn submit_for_uninitialized_pool(
+    indexer: &mut OrderIndexer<MockValidator>,
+    validator: &MockValidator,
+    init: PoolInitParams,
+    nonce: u64
+) -> B256 {
+    let order = AllOrders::from(UserOrderBuilder::new().standing().nonce(nonce).build());
+    let hash = order.order_hash();
+    validator.add_order(
+        order.from(),
+        OrderValidationResults::PoolNotInitialized { order: order.clone(), init }
+    );
+
+    let (tx, _rx) = tokio::sync::oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, order, tx);
+    poll(indexer);
+
+    hash
+}
+
+fn init_params() -> PoolInitParams {
+    PoolInitParams {
+        pool_id:     FixedBytes::random(),
+        asset0:      Address::random(),
+        asset1:      Address::random(),
+        store_index: 0
+    }
+}
+
+#[test]
+fn orders_for_uninitialized_pools_are_capped_per_sender() {
+    let validator = MockValidator::default();
+    let (mut indexer, mut updates) = indexer(&validator, vec![]);
+    let init = init_params();
+
+    for nonce in 0..MAX_HELD_ORDERS_PER_SENDER as u64 {
+        submit_for_uninitialized_pool(&mut indexer, &validator, init, nonce);
+    }
+    assert_eq!(indexer.orders_awaiting_pool_sync(), MAX_HELD_ORDERS_PER_SENDER);
+
+    let over_cap = submit_for_uninitialized_pool(
+        &mut indexer,
+        &validator,
+        init,
+        MAX_HELD_ORDERS_PER_SENDER as u64
+    );
+    let rejection = indexer
+        .rejection(&over_cap)
+        .expect("orders over the cap are rejected");
+    assert_eq!(rejection.code, RejectionReason::PoolNotInitialized);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), MAX_HELD_ORDERS_PER_SENDER);
+
+    // the pool is announced once, by its first held order
+    let announced = std::iter::from_fn(|| updates.try_recv().ok())
+        .filter_map(|update| match update {
+            PoolManagerUpdate::PoolInitRequired(params) => Some(params),
+            _ => None
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(announced, vec![init]);
+}
+
+#[test]
+fn held_orders_are_validated_again_once_their_pool_is_ready() {
+    let validator = MockValidator::default();
+    let (mut indexer, _updates) = indexer(&validator, vec![]);
+    let pool = NewInitializedPool {
+        currency_in:  Address::random(),
+        currency_out: Address::random(),
+        id:           FixedBytes::random()
+    };
+    let valid = valid_order(pool.id);
+    let hash = valid.order_hash();
+
+    validator.add_order(
+        valid.from(),
+        OrderValidationResults::PoolNotSynced { order: valid.order.clone(), pool_id: pool.id }
+    );
+    let (tx, _rx) = tokio::sync::oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, valid.order.clone(), tx);
+    poll(&mut indexer);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), 1);
+    assert!(indexer.order_owner(&hash).is_none());
+
+    // the pool syncs and the order passes validation this time
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+    indexer.new_pool(pool);
+    poll(&mut indexer);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), 0);
+    assert_eq!(indexer.order_owner(&hash), Some(valid.from()));
+    assert!(indexer.rejection(&hash).is_none());
+}
+
+#[test]
+fn orders_held_for_too_long_expire() {
+    let validator = MockValidator::default();
+    let (mut indexer, _updates) = indexer(&validator, vec![]);
+    let held = submit_for_uninitialized_pool(&mut indexer, &validator, init_params(), 0);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), 1);
+
+    indexer.start_new_block_processing(BLOCK + 1, vec![], vec![]);
+    poll(&mut indexer);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), 1);
+
+    indexer.start_new_block_processing(BLOCK + 50, vec![], vec![]);
+    poll(&mut indexer);
+    assert_eq!(indexer.orders_awaiting_pool_sync(), 0);
+    let rejection = indexer
+        .rejection(&held)
+        .expect("expired orders are rejected");
+    assert_eq!(rejection.code, RejectionReason::Expired);
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy::{
+    primitives::{keccak256, Address},
+    sol_types::SolValue
+};
+use angstrom_types::{
+    contract_payloads::angstrom::AngstromPoolConfigStore,
+    orders::OrderOrigin,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use common::{indexer_with_pools, poll, stored_order, BLOCK};
+use order_pool::{CancelOrderError, MinOrderLifetime, OrderIndexer};
+use testing_tools::{mocks::validator::MockValidator, type_generator::orders::UserOrderBuilder};
+use validation::order::{
+    rejection::RejectionReason, state::pools::AngstromPoolsTracker, OrderValidationResults
+};
+
+/// a config store holding an angstrom pool for the sorted pair
+fn config_store(token0: Address, token1: Address) -> AngstromPoolConfigStore {
+    let mut entry = vec![0u8];
+    entry.extend_from_slice(&keccak256((token0, token1).abi_encode())[5..]);
+    entry.extend_from_slice(&60u16.to_be_bytes());
+    entry.extend_from_slice(&3000u32.to_be_bytes()[1..]);
+
+    AngstromPoolConfigStore::try_from(entry.as_slice()).unwrap()
+}
+
+struct Setup {
+    indexer: OrderIndexer<MockValidator>,
+    order:   AllOrders
+}
+
+/// an indexer with a single pool, whose orders rest for `min_lifetime` if
+/// set, and an order for it
+fn setup(min_lifetime: Option<MinOrderLifetime>) -> Setup {
+    let (token0, token1) = {
+        let (a, b) = (Address::random(), Address::random());
+        (a.min(b), a.max(b))
+    };
+    let store = Arc::new(config_store(token0, token1));
+    let pools = AngstromPoolsTracker::new(Address::random(), store);
+    let pool_id = pools.get_poolid(token0, token1).unwrap();
+
+    let validator = MockValidator::default();
+    let (indexer, _updates) = indexer_with_pools(&validator, vec![pool_id], pools);
+    let min_lifetimes = min_lifetime
+        .map(|lifetime| HashMap::from([(pool_id, lifetime)]))
+        .unwrap_or_default();
+    let indexer = indexer.with_min_order_lifetimes(min_lifetimes);
+
+    let valid = stored_order(
+        UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1),
+        pool_id,
+        BLOCK
+    );
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+
+    Setup { indexer, order: valid.order }
+}
+
+fn submit(indexer: &mut OrderIndexer<MockValidator>, order: AllOrders) -> OrderValidationResults {
+    let (tx, mut rx) = tokio::sync::oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, order, tx);
+    poll(indexer);
+
+    rx.try_recv().expect("the order was answered")
+}
+
+#[test]
+fn resting_orders_cannot_be_cancelled_before_their_lifetime() {
+    let Setup { mut indexer, order } = setup(Some(MinOrderLifetime::Blocks(2)));
+    assert!(matches!(submit(&mut indexer, order.clone()), OrderValidationResults::Valid(_)));
+
+    let cancelled = indexer.cancel_order(order.from(), order.order_hash());
+    assert!(matches!(cancelled, Err(CancelOrderError::TooSoonToCancel { .. })));
+    assert!(indexer.order_owner(&order.order_hash()).is_some());
+}
+
+#[test]
+fn cancels_sent_before_the_order_do_not_skip_its_lifetime() {
+    let Setup { mut indexer, order } = setup(Some(MinOrderLifetime::Blocks(2)));
+    let hash = order.order_hash();
+
+    // the cancel outruns the order
+    assert!(indexer.cancel_order(order.from(), hash).unwrap());
+    assert!(matches!(submit(&mut indexer, order.clone()), OrderValidationResults::Valid(_)));
+
+    let cancelled = indexer.cancel_order(order.from(), hash);
+    assert!(matches!(cancelled, Err(CancelOrderError::TooSoonToCancel { .. })));
+}
+
+#[test]
+fn cancels_sent_before_the_order_apply_without_a_lifetime() {
+    let Setup { mut indexer, order } = setup(None);
+
+    assert!(indexer
+        .cancel_order(order.from(), order.order_hash())
+        .unwrap());
+    match submit(&mut indexer, order.clone()) {
+        OrderValidationResults::Invalid(_, rejection) => {
+            assert_eq!(rejection.code, RejectionReason::Cancelled)
+        }
+        other => panic!("expected the order to be rejected, got {other:?}")
+    }
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use alloy::primitives::FixedBytes;
+use angstrom_types::orders::{OrderOrigin, OrderStatus};
+use common::{indexer, poll, valid_order, BLOCK};
+use testing_tools::mocks::validator::MockValidator;
+use validation::order::OrderValidationResults;
+
+#[test]
+fn filled_orders_report_their_status() {
+    let pool_id = FixedBytes::random();
+    let validator = MockValidator::default();
+    let (mut indexer, _updates) = indexer(&validator, vec![pool_id]);
+
+    let valid = valid_order(pool_id);
+    let hash = valid.order_hash();
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+
+    let (tx, _rx) = tokio::sync::oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, valid.order.clone(), tx);
+    poll(&mut indexer);
+    assert!(matches!(indexer.order_status(hash), Some((OrderStatus::Pending, _))));
+
+    indexer.start_new_block_processing(BLOCK + 1, vec![hash], vec![]);
+    poll(&mut indexer);
+
+    let (status, id) = indexer
+        .order_status(hash)
+        .expect("filled orders keep a status");
+    assert!(matches!(status, OrderStatus::Filled));
+    assert_eq!(id, valid.order_id.transaction_id());
+}
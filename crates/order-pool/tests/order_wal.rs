@@ -0,0 +1,72 @@
+mod common;
+
+use std::collections::HashSet;
+
+use alloy::primitives::{FixedBytes, B256, U256};
+use angstrom_types::{
+    orders::OrderOrigin,
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
+use common::{indexer, poll, stored_order, BLOCK};
+use order_pool::{replay, OrderIndexer, WalEntry};
+use testing_tools::{mocks::validator::MockValidator, type_generator::orders::UserOrderBuilder};
+use validation::order::OrderValidationResults;
+
+/// submits a standing order that is accepted into the pool, expiring ones
+/// expire before the next block
+fn submit(
+    indexer: &mut OrderIndexer<MockValidator>,
+    validator: &MockValidator,
+    pool_id: FixedBytes<32>,
+    nonce: u64,
+    expiring: bool
+) -> AllOrders {
+    let mut valid = stored_order(UserOrderBuilder::new().standing().nonce(nonce), pool_id, BLOCK);
+    if expiring {
+        valid.order_id.deadline = Some(U256::from(1));
+    }
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+
+    let (tx, _rx) = tokio::sync::oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, valid.order.clone(), tx);
+    poll(indexer);
+
+    valid.order
+}
+
+#[test]
+fn filled_cancelled_and_expired_orders_are_logged_as_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("orders.wal");
+    let pool_id = FixedBytes::random();
+    let validator = MockValidator::default();
+    let (indexer, _updates) = indexer(&validator, vec![pool_id]);
+    let mut indexer = indexer.with_order_wal(Some(path.clone()));
+
+    let filled = submit(&mut indexer, &validator, pool_id, 0, false);
+    let resting = submit(&mut indexer, &validator, pool_id, 1, false);
+    let cancelled = submit(&mut indexer, &validator, pool_id, 2, false);
+    let expired = submit(&mut indexer, &validator, pool_id, 3, true);
+
+    assert!(indexer
+        .cancel_order(cancelled.from(), cancelled.order_hash())
+        .unwrap());
+    indexer.start_new_block_processing(BLOCK + 1, vec![filled.order_hash()], vec![]);
+    poll(&mut indexer);
+    // waits for the log to be written
+    drop(indexer);
+
+    let removed = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| match serde_json::from_str(line).unwrap() {
+            WalEntry::Removed { order_hash } => Some(order_hash),
+            WalEntry::Accepted { .. } => None
+        })
+        .collect::<HashSet<B256>>();
+    assert_eq!(
+        removed,
+        HashSet::from([filled.order_hash(), cancelled.order_hash(), expired.order_hash()])
+    );
+    assert_eq!(replay(&path, vec![]).unwrap(), vec![resting]);
+}
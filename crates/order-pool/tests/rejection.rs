@@ -0,0 +1,75 @@
+mod common;
+
+use alloy::primitives::FixedBytes;
+use angstrom_types::{orders::OrderOrigin, sol_bindings::grouped_orders::AllOrders};
+use common::{indexer, poll, stored_order, valid_order, BLOCK};
+use order_pool::OrderIndexer;
+use testing_tools::{mocks::validator::MockValidator, type_generator::orders::UserOrderBuilder};
+use tokio::sync::oneshot;
+use validation::order::{
+    rejection::{Rejection, RejectionReason},
+    OrderValidationResults
+};
+
+/// submits the order over rpc, returns what its submitter is answered
+fn submit(
+    indexer: &mut OrderIndexer<MockValidator>,
+    order: AllOrders
+) -> oneshot::Receiver<OrderValidationResults> {
+    let (tx, rx) = oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, order, tx);
+    poll(indexer);
+
+    rx
+}
+
+fn rejection_code(rx: &mut oneshot::Receiver<OrderValidationResults>) -> Option<RejectionReason> {
+    match rx.try_recv() {
+        Ok(OrderValidationResults::Invalid(_, rejection)) => Some(rejection.code),
+        _ => None
+    }
+}
+
+#[test]
+fn rejected_orders_report_why_until_they_are_accepted() {
+    let pool_id = FixedBytes::random();
+    let validator = MockValidator::default();
+    let (mut indexer, _updates) = indexer(&validator, vec![pool_id]);
+
+    let valid = valid_order(pool_id);
+    let hash = valid.order_hash();
+    assert!(indexer.rejection(&hash).is_none());
+
+    let rejection = Rejection::new(RejectionReason::NoPriceForToken, "no price for the token");
+    validator.add_order(valid.from(), OrderValidationResults::Invalid(hash, rejection.clone()));
+    let mut rx = submit(&mut indexer, valid.order.clone());
+    assert_eq!(rejection_code(&mut rx), Some(RejectionReason::NoPriceForToken));
+    assert_eq!(indexer.rejection(&hash), Some(rejection));
+
+    // the order can be submitted again and is accepted this time
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+    let mut rx = submit(&mut indexer, valid.order.clone());
+    assert!(matches!(rx.try_recv(), Ok(OrderValidationResults::Valid(_))));
+    assert!(indexer.rejection(&hash).is_none());
+}
+
+#[test]
+fn duplicates_keep_the_first_rejection() {
+    let pool_id = FixedBytes::random();
+    let validator = MockValidator::default();
+    let (mut indexer, _updates) = indexer(&validator, vec![pool_id]);
+
+    // validated against a block that isn't current anymore
+    let stale = stored_order(UserOrderBuilder::new().standing(), pool_id, BLOCK - 1);
+    let hash = stale.order_hash();
+    validator.add_order(stale.from(), OrderValidationResults::Valid(stale.clone()));
+    let mut rx = submit(&mut indexer, stale.order.clone());
+    assert_eq!(rejection_code(&mut rx), Some(RejectionReason::StaleBlock));
+
+    let mut rx = submit(&mut indexer, stale.order.clone());
+    assert_eq!(rejection_code(&mut rx), Some(RejectionReason::DuplicateOrder));
+    assert_eq!(
+        indexer.rejection(&hash).map(|rejection| rejection.code),
+        Some(RejectionReason::StaleBlock)
+    );
+}
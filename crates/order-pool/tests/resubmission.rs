@@ -0,0 +1,101 @@
+mod common;
+
+use alloy::primitives::FixedBytes;
+use angstrom_types::{
+    orders::OrderOrigin,
+    sol_bindings::{
+        grouped_orders::{AllOrders, OrderWithStorageData},
+        RawPoolOrder
+    }
+};
+use common::{indexer, poll, valid_order, BLOCK};
+use order_pool::OrderIndexer;
+use testing_tools::mocks::validator::MockValidator;
+use tokio::sync::oneshot;
+use validation::order::{
+    rejection::{Rejection, RejectionReason},
+    OrderValidationResults
+};
+
+/// an indexer for a single pool and an order for it the validator accepts
+/// once, validating it a second time panics
+fn setup(
+    validator: &MockValidator
+) -> (OrderIndexer<MockValidator>, OrderWithStorageData<AllOrders>) {
+    let pool_id = FixedBytes::random();
+    let (indexer, _updates) = indexer(validator, vec![pool_id]);
+
+    let valid = valid_order(pool_id);
+    validator.add_order(valid.from(), OrderValidationResults::Valid(valid.clone()));
+
+    (indexer, valid)
+}
+
+fn submit(
+    indexer: &mut OrderIndexer<MockValidator>,
+    order: AllOrders
+) -> oneshot::Receiver<OrderValidationResults> {
+    let (tx, rx) = oneshot::channel();
+    indexer.new_rpc_order(OrderOrigin::External, order, tx);
+
+    rx
+}
+
+fn is_valid(rx: &mut oneshot::Receiver<OrderValidationResults>) -> bool {
+    matches!(rx.try_recv(), Ok(OrderValidationResults::Valid(_)))
+}
+
+#[test]
+fn orders_resubmitted_during_validation_share_its_result() {
+    let validator = MockValidator::default();
+    let (mut indexer, valid) = setup(&validator);
+
+    let mut first = submit(&mut indexer, valid.order.clone());
+    let mut retry = submit(&mut indexer, valid.order.clone());
+    poll(&mut indexer);
+
+    assert!(is_valid(&mut first));
+    assert!(is_valid(&mut retry));
+    assert_eq!(indexer.order_owner(&valid.order_hash()), Some(valid.from()));
+}
+
+#[test]
+fn accepted_orders_are_answered_without_validating_them_again() {
+    let validator = MockValidator::default();
+    let (mut indexer, valid) = setup(&validator);
+
+    let mut first = submit(&mut indexer, valid.order.clone());
+    poll(&mut indexer);
+    assert!(is_valid(&mut first));
+
+    // answered right away, before the indexer is polled
+    let mut retry = submit(&mut indexer, valid.order.clone());
+    match retry.try_recv() {
+        Ok(OrderValidationResults::Valid(accepted)) => {
+            assert_eq!(accepted.order_hash(), valid.order_hash())
+        }
+        other => panic!("expected the stored result, got {other:?}")
+    }
+}
+
+#[test]
+fn orders_no_longer_in_the_pool_are_validated_again() {
+    let validator = MockValidator::default();
+    let (mut indexer, valid) = setup(&validator);
+    let hash = valid.order_hash();
+
+    let mut first = submit(&mut indexer, valid.order.clone());
+    poll(&mut indexer);
+    assert!(is_valid(&mut first));
+
+    indexer.start_new_block_processing(BLOCK + 1, vec![hash], vec![]);
+    poll(&mut indexer);
+    assert!(indexer.order_owner(&hash).is_none());
+
+    // the stored result is dropped, the order goes through validation again
+    let rejection = Rejection::new(RejectionReason::NoPriceForToken, "no price for the token");
+    validator.add_order(valid.from(), OrderValidationResults::Invalid(hash, rejection));
+    let mut retry = submit(&mut indexer, valid.order.clone());
+    poll(&mut indexer);
+    assert!(!is_valid(&mut retry));
+}
@@ -0,0 +1,42 @@
+use alloy_primitives::{Address, FixedBytes};
+use angstrom_types::sol_bindings::grouped_orders::AllOrders;
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use validation::order::trace::ValidationTrace;
+
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "angstrom_admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "angstrom_admin"))]
+#[async_trait::async_trait]
+pub trait AdminApi {
+    /// Loads the balances and approvals of `addresses` for `tokens` into the
+    /// validator so their next orders validate without fetching state.
+    #[method(name = "prewarm")]
+    async fn prewarm(&self, addresses: Vec<Address>, tokens: Vec<Address>) -> RpcResult<()>;
+
+    /// Runs matching for the pool right away, whatever the node's matching
+    /// schedule is.
+    #[method(name = "triggerMatch")]
+    async fn trigger_match(&self, pool_id: FixedBytes<32>) -> RpcResult<()>;
+
+    /// Stops taking new orders for the pool and leaves it out of matching
+    /// until it's resumed. Returns false if it was already paused.
+    #[method(name = "pausePool")]
+    async fn pause_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool>;
+
+    /// Lifts a pause, returns false if the pool wasn't paused.
+    #[method(name = "resumePool")]
+    async fn resume_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool>;
+
+    /// The pools the circuit breaker halted after an abnormal price move.
+    #[method(name = "haltedPools")]
+    async fn halted_pools(&self) -> RpcResult<Vec<FixedBytes<32>>>;
+
+    /// Lifts a circuit breaker halt before the pool's price stabilizes,
+    /// returns false if the pool wasn't halted.
+    #[method(name = "resumeHaltedPool")]
+    async fn resume_halted_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool>;
+
+    /// Validates the order without adding it to the pool and reports each
+    /// check it went through, for debugging why an order is rejected.
+    #[method(name = "traceOrder")]
+    async fn trace_order(&self, order: AllOrders) -> RpcResult<ValidationTrace>;
+}
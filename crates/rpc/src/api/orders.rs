@@ -2,24 +2,26 @@ use std::collections::HashSet;
 
 use alloy_primitives::{Address, FixedBytes, B256, U256};
 use angstrom_types::{
-    orders::{OrderLocation, OrderStatus},
-    primitive::Signature,
-    sol_bindings::grouped_orders::AllOrders
+    orders::{OrderLocation, SignedCancellation},
+    sol_bindings::grouped_orders::{AllOrders, OrderWithStorageData}
 };
+use consensus::ValidatorInfo;
 use futures::StreamExt;
 use jsonrpsee::{
     core::{RpcResult, Serialize},
     proc_macros::rpc
 };
 use serde::Deserialize;
+use validation::order::{rejection::Rejection, SimulationResult};
 
-use crate::types::{OrderSubscriptionFilter, OrderSubscriptionKind};
+use crate::types::{
+    BookImbalance, NodeStatus, OrderStatusResult, OrderSubscriptionFilter, OrderSubscriptionKind,
+    PoolInfo, SubmitResult
+};
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CancelOrderRequest {
-    pub signature: Signature,
-    pub hash:      B256
-}
+/// A cancellation as submitted over rpc, the same signed request that is
+/// gossiped between nodes
+pub type CancelOrderRequest = SignedCancellation;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GasEstimateResponse {
@@ -38,20 +40,63 @@ pub trait OrderApi {
     #[method(name = "pendingOrder")]
     async fn pending_order(&self, from: Address) -> RpcResult<Vec<AllOrders>>;
 
+    /// The sender's open orders along with the pool they rest in and their
+    /// validation data
+    #[method(name = "ordersOf")]
+    async fn orders_of(&self, address: Address) -> RpcResult<Vec<OrderWithStorageData<AllOrders>>>;
+
     #[method(name = "cancelOrder")]
     async fn cancel_order(&self, request: CancelOrderRequest) -> RpcResult<bool>;
 
     #[method(name = "estimateGas")]
     async fn estimate_gas(&self, order: AllOrders) -> RpcResult<GasEstimateResponse>;
 
+    /// Dry runs the order through validation without submitting it
+    #[method(name = "simulateOrder")]
+    async fn simulate_order(&self, order: AllOrders) -> RpcResult<SimulationResult>;
+
+    /// Lowest nonce that isn't used on chain or by one of the users pending
+    /// orders
+    #[method(name = "nextNonce")]
+    async fn next_nonce(&self, address: Address) -> RpcResult<u64>;
+
+    /// Sync and pool state of the node, for liveness and readiness checks
+    #[method(name = "nodeStatus")]
+    async fn node_status(&self) -> RpcResult<NodeStatus>;
+
+    /// The validators consensus runs its rounds with and their voting power,
+    /// empty until consensus is started
+    #[method(name = "validatorSet")]
+    async fn validator_set(&self) -> RpcResult<Vec<ValidatorInfo>>;
+
+    /// Bid/ask notional imbalance of the pool's pending limit orders, `None`
+    /// for an unknown pool
+    #[method(name = "bookImbalance")]
+    async fn book_imbalance(&self, pool_id: FixedBytes<32>) -> RpcResult<Option<BookImbalance>>;
+
+    /// All angstrom pools the node accepts orders for, including pools
+    /// initialized after it started
+    #[method(name = "pools")]
+    async fn pools(&self) -> RpcResult<Vec<PoolInfo>>;
+
+    /// The order's status and the id the pool tracks it under
     #[method(name = "orderStatus")]
-    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatus>>;
+    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatusResult>>;
 
+    /// Why the order was turned away, `None` if it wasn't or the node no
+    /// longer remembers it. Only the most recent rejections are kept
+    #[method(name = "orderRejection")]
+    async fn order_rejection(&self, order_hash: B256) -> RpcResult<Option<Rejection>>;
+
+    /// The pair's orders at the location. With a `depth` only the best
+    /// `depth` bids and the best `depth` asks are returned, otherwise the
+    /// whole book
     #[method(name = "ordersByPair")]
     async fn orders_by_pair(
         &self,
         pair: FixedBytes<32>,
-        location: OrderLocation
+        location: OrderLocation,
+        depth: Option<usize>
     ) -> RpcResult<Vec<AllOrders>>;
 
     #[subscription(
@@ -77,6 +122,11 @@ pub trait OrderApi {
             .collect::<RpcResult<Vec<_>>>()
     }
 
+    /// Submits a batch of orders in one call. Every order gets its own result,
+    /// in the order they were given, so one bad order doesn't fail the batch
+    #[method(name = "submitOrders")]
+    async fn submit_orders(&self, orders: Vec<serde_json::Value>) -> RpcResult<Vec<SubmitResult>>;
+
     #[method(name = "pendingOrders")]
     async fn pending_orders(&self, from: Vec<Address>) -> RpcResult<Vec<AllOrders>> {
         Ok(futures::stream::iter(from.into_iter())
@@ -120,7 +170,7 @@ pub trait OrderApi {
     async fn status_of_orders(
         &self,
         order_hashes: Vec<B256>
-    ) -> RpcResult<Vec<Option<OrderStatus>>> {
+    ) -> RpcResult<Vec<Option<OrderStatusResult>>> {
         futures::stream::iter(order_hashes.into_iter())
             .map(|order| async move { self.order_status(order).await })
             .buffered(3)
@@ -133,10 +183,11 @@ pub trait OrderApi {
     #[method(name = "ordersByPairs")]
     async fn orders_by_pairs(
         &self,
-        pair_with_location: Vec<(FixedBytes<32>, OrderLocation)>
+        pair_with_location: Vec<(FixedBytes<32>, OrderLocation)>,
+        depth: Option<usize>
     ) -> RpcResult<Vec<AllOrders>> {
         Ok(futures::stream::iter(pair_with_location.into_iter())
-            .map(|(pair, location)| async move { self.orders_by_pair(pair, location).await })
+            .map(|(pair, location)| async move { self.orders_by_pair(pair, location, depth).await })
             .buffered(3)
             .collect::<Vec<_>>()
             .await
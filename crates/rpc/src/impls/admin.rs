@@ -0,0 +1,187 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant}
+};
+
+use alloy_primitives::{Address, FixedBytes};
+use angstrom_types::{primitive::PoolId, sol_bindings::grouped_orders::AllOrders};
+use jsonrpsee::core::RpcResult;
+use matching_engine::MatchingSchedulerHandle;
+use order_pool::PausedPools;
+use uniswap_v4::uniswap::circuit_breaker::PoolCircuitBreaker;
+use validation::order::{trace::ValidationTrace, OrderValidatorHandle};
+
+use crate::{api::AdminApiServer, invalid_params_rpc_err, rpc_err};
+
+/// most (address, token) pairs a single prewarm call may load
+pub const MAX_PREWARM_ENTRIES: usize = 10_000;
+
+/// most `traceOrder` calls served per second unless configured otherwise,
+/// each one runs a gas simulation
+pub const DEFAULT_MAX_TRACES_PER_SECOND: u32 = 10;
+
+pub struct AdminApi<Validator> {
+    validator:          Validator,
+    matching_scheduler: Option<MatchingSchedulerHandle>,
+    paused_pools:       Option<PausedPools>,
+    circuit_breaker:    Option<Arc<PoolCircuitBreaker<PoolId>>>,
+    trace_limit:        Mutex<RateLimit>
+}
+
+impl<Validator> AdminApi<Validator> {
+    pub fn new(validator: Validator) -> Self {
+        Self {
+            validator,
+            matching_scheduler: None,
+            paused_pools: None,
+            circuit_breaker: None,
+            trace_limit: Mutex::new(RateLimit::new(DEFAULT_MAX_TRACES_PER_SECOND))
+        }
+    }
+
+    /// serves at most `max_per_second` `traceOrder` calls a second, the rest
+    /// are turned away
+    pub fn with_max_traces_per_second(mut self, max_per_second: u32) -> Self {
+        self.trace_limit = Mutex::new(RateLimit::new(max_per_second));
+        self
+    }
+
+    pub fn with_paused_pools(mut self, paused_pools: PausedPools) -> Self {
+        self.paused_pools = Some(paused_pools);
+        self
+    }
+
+    fn paused_pools(&self) -> RpcResult<&PausedPools> {
+        self.paused_pools
+            .as_ref()
+            .ok_or_else(|| invalid_params_rpc_err("pool pausing is not enabled on this node"))
+    }
+
+    pub fn with_circuit_breaker(
+        mut self,
+        circuit_breaker: Arc<PoolCircuitBreaker<PoolId>>
+    ) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    fn circuit_breaker(&self) -> RpcResult<&PoolCircuitBreaker<PoolId>> {
+        self.circuit_breaker
+            .as_deref()
+            .ok_or_else(|| invalid_params_rpc_err("circuit breaker is not enabled on this node"))
+    }
+
+    pub fn with_matching_scheduler(mut self, matching_scheduler: MatchingSchedulerHandle) -> Self {
+        self.matching_scheduler = Some(matching_scheduler);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<Validator> AdminApiServer for AdminApi<Validator>
+where
+    Validator: OrderValidatorHandle
+{
+    async fn prewarm(&self, addresses: Vec<Address>, tokens: Vec<Address>) -> RpcResult<()> {
+        let entries = addresses.len().saturating_mul(tokens.len());
+        if entries > MAX_PREWARM_ENTRIES {
+            return Err(invalid_params_rpc_err(format!(
+                "prewarm of {entries} entries exceeds the limit of {MAX_PREWARM_ENTRIES}"
+            )))
+        }
+
+        self.validator.prewarm(addresses, tokens);
+        Ok(())
+    }
+
+    async fn trigger_match(&self, pool_id: FixedBytes<32>) -> RpcResult<()> {
+        let Some(matching_scheduler) = self.matching_scheduler.as_ref() else {
+            return Err(invalid_params_rpc_err("matching scheduler is not enabled on this node"))
+        };
+
+        if !matching_scheduler.trigger_match(pool_id) {
+            return Err(rpc_err(
+                jsonrpsee::types::error::INTERNAL_ERROR_CODE,
+                "matching scheduler has shut down",
+                None
+            ))
+        }
+
+        Ok(())
+    }
+
+    async fn pause_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool> {
+        Ok(self.paused_pools()?.pause(pool_id))
+    }
+
+    async fn resume_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool> {
+        Ok(self.paused_pools()?.resume(&pool_id))
+    }
+
+    async fn halted_pools(&self) -> RpcResult<Vec<FixedBytes<32>>> {
+        Ok(self.circuit_breaker()?.halted_pools().into_iter().collect())
+    }
+
+    async fn resume_halted_pool(&self, pool_id: FixedBytes<32>) -> RpcResult<bool> {
+        Ok(self.circuit_breaker()?.resume_pool(&pool_id))
+    }
+
+    async fn trace_order(&self, order: AllOrders) -> RpcResult<ValidationTrace> {
+        if !self.trace_limit.lock().unwrap().try_acquire(Instant::now()) {
+            return Err(rpc_err(
+                jsonrpsee::types::error::SERVER_IS_BUSY_CODE,
+                "too many traceOrder calls, try again in a second",
+                None
+            ))
+        }
+
+        Ok(self.validator.trace_order(order).await)
+    }
+}
+
+/// Counts calls in one second windows.
+struct RateLimit {
+    max_per_second: u32,
+    window_start:   Instant,
+    calls:          u32
+}
+
+impl RateLimit {
+    fn new(max_per_second: u32) -> Self {
+        Self { max_per_second, window_start: Instant::now(), calls: 0 }
+    }
+
+    /// whether a call at `now` fits the limit, counting it if it does
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.window_start) >= Duration::from_secs(1) {
+            self.window_start = now;
+            self.calls = 0;
+        }
+        if self.calls >= self.max_per_second {
+            return false
+        }
+
+        self.calls += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_calls_are_rate_limited() {
+        let start = Instant::now();
+        let mut limit = RateLimit::new(2);
+
+        assert!(limit.try_acquire(start));
+        assert!(limit.try_acquire(start + Duration::from_millis(10)));
+        assert!(!limit.try_acquire(start + Duration::from_millis(20)));
+
+        // the next window starts over
+        assert!(limit.try_acquire(start + Duration::from_secs(1)));
+        assert!(limit.try_acquire(start + Duration::from_secs(1)));
+        assert!(!limit.try_acquire(start + Duration::from_secs(1)));
+    }
+}
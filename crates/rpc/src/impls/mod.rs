@@ -1,7 +1,9 @@
+mod admin;
 mod consensus;
 mod orders;
 mod quoting;
 
+pub use admin::*;
 pub use consensus::*;
 pub use orders::*;
 pub use quoting::*;
@@ -1,31 +1,84 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, io};
 
 use alloy_primitives::{Address, FixedBytes, B256};
 use angstrom_types::{
-    orders::{OrderLocation, OrderOrigin, OrderStatus},
-    sol_bindings::grouped_orders::AllOrders
+    orders::{OrderLocation, OrderOrigin, TransactionId},
+    sol_bindings::{
+        ext::RawPoolOrder,
+        grouped_orders::{AllOrders, OrderWithStorageData}
+    }
 };
+use consensus::ValidatorInfo;
 use futures::StreamExt;
 use jsonrpsee::{core::RpcResult, PendingSubscriptionSink, SubscriptionMessage};
-use order_pool::{OrderPoolHandle, PoolManagerUpdate};
+use order_pool::{CancelOrderError, OrderPoolHandle, PoolManagerUpdate};
 use reth_tasks::TaskSpawner;
-use validation::order::OrderValidatorHandle;
+use serde::Serialize;
+use validation::order::{rejection::Rejection, OrderValidatorHandle, SimulationResult};
 
 use crate::{
     api::{CancelOrderRequest, GasEstimateResponse, OrderApiServer},
-    types::{OrderSubscriptionFilter, OrderSubscriptionKind, OrderSubscriptionResult},
-    OrderApiError::{GasEstimationError, SignatureRecoveryError}
+    types::{
+        BookImbalance, NodeStatus, NodeStatusSources, OrderStatusResult, OrderSubscriptionFilter,
+        OrderSubscriptionKind, OrderSubscriptionResult, PoolInfo, SubmitResult
+    },
+    OrderApiError::{
+        CancellationError, GasEstimationError, ResponseTooLarge, SignatureRecoveryError,
+        ValidatorUnavailable
+    }
 };
 
+/// most orders a single `submitOrders` call may carry
+const MAX_SUBMIT_BATCH: usize = 256;
+
+/// error code of a query whose response is over the node's size limit, kept
+/// apart from jsonrpsee's transport codes so callers can tell the two apart
+pub const RESPONSE_TOO_LARGE_CODE: i32 = -32050;
+
 pub struct OrderApi<OrderPool, Spawner, Validator> {
-    pool:         OrderPool,
-    task_spawner: Spawner,
-    validator:    Validator
+    pool:              OrderPool,
+    task_spawner:      Spawner,
+    validator:         Validator,
+    status_sources:    NodeStatusSources,
+    /// most bytes the serialized response of a query may take
+    max_response_size: Option<usize>
 }
 
 impl<OrderPool, Spawner, Validator> OrderApi<OrderPool, Spawner, Validator> {
     pub fn new(pool: OrderPool, task_spawner: Spawner, validator: Validator) -> Self {
-        Self { pool, task_spawner, validator }
+        Self {
+            pool,
+            task_spawner,
+            validator,
+            status_sources: NodeStatusSources::default(),
+            max_response_size: None
+        }
+    }
+
+    /// rejects queries whose serialized response takes more than
+    /// `max_response_size` bytes instead of answering them
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = Some(max_response_size);
+        self
+    }
+
+    /// the response if it fits the size limit. Serializing stops as soon as
+    /// the limit is exceeded, so an oversized response is never built in full
+    fn bounded<T: Serialize>(&self, response: T) -> RpcResult<T> {
+        let Some(limit) = self.max_response_size else { return Ok(response) };
+
+        let mut counter = SizeCounter { written: 0, limit };
+        if serde_json::to_writer(&mut counter, &response).is_err() && counter.written > limit {
+            return Err(ResponseTooLarge(limit).into())
+        }
+
+        Ok(response)
+    }
+
+    /// reads the block heights reported by `nodeStatus` from `status_sources`
+    pub fn with_status_sources(mut self, status_sources: NodeStatusSources) -> Self {
+        self.status_sources = status_sources;
+        self
     }
 }
 
@@ -37,21 +90,62 @@ where
     Validator: OrderValidatorHandle
 {
     async fn send_order(&self, order: AllOrders) -> RpcResult<bool> {
-        Ok(self.pool.new_order(OrderOrigin::External, order).await)
+        Ok(self
+            .pool
+            .new_order(OrderOrigin::External, order)
+            .await
+            .is_ok())
     }
 
     async fn pending_order(&self, from: Address) -> RpcResult<Vec<AllOrders>> {
-        Ok(self.pool.pending_orders(from).await)
+        self.bounded(self.pool.pending_orders(from).await)
+    }
+
+    async fn pending_orders(&self, from: Vec<Address>) -> RpcResult<Vec<AllOrders>> {
+        let orders =
+            futures::future::join_all(from.into_iter().map(|from| self.pool.pending_orders(from)))
+                .await;
+        self.bounded(orders.into_iter().flatten().collect())
+    }
+
+    async fn orders_of(&self, address: Address) -> RpcResult<Vec<OrderWithStorageData<AllOrders>>> {
+        self.bounded(self.pool.orders_by_sender(address).await)
+    }
+
+    async fn submit_orders(&self, orders: Vec<serde_json::Value>) -> RpcResult<Vec<SubmitResult>> {
+        if orders.len() > MAX_SUBMIT_BATCH {
+            return Err(invalid_params_rpc_err(format!(
+                "batch of {} orders exceeds the limit of {MAX_SUBMIT_BATCH}",
+                orders.len()
+            )))
+        }
+
+        // all orders are handed to the pool at once, in the order given, so
+        // orders of the same sender are validated in sequence
+        let submissions = orders.into_iter().map(|order| async move {
+            let order = match serde_json::from_value::<AllOrders>(order) {
+                Ok(order) => order,
+                Err(e) => return SubmitResult::Malformed { error: e.to_string() }
+            };
+            let order_hash = order.order_hash();
+            let transaction_id = TransactionId::of(&order);
+            match self.pool.new_order(OrderOrigin::External, order).await {
+                Ok(()) => SubmitResult::Accepted { order_hash, transaction_id },
+                Err(reason) => SubmitResult::Rejected { order_hash, reason }
+            }
+        });
+
+        Ok(futures::future::join_all(submissions).await)
     }
 
     async fn cancel_order(&self, request: CancelOrderRequest) -> RpcResult<bool> {
-        let sender = request
-            .signature
-            .recover_signer_full_public_key(request.hash)
-            .map(|s| Address::from_raw_public_key(&*s))
-            .map_err(|_| SignatureRecoveryError)?;
+        request.recover_signer().ok_or(SignatureRecoveryError)?;
 
-        Ok(self.pool.cancel_order(sender, request.hash).await)
+        Ok(self
+            .pool
+            .cancel_order(request)
+            .await
+            .map_err(CancellationError)?)
     }
 
     async fn estimate_gas(&self, order: AllOrders) -> RpcResult<GasEstimateResponse> {
@@ -63,16 +157,91 @@ where
         Ok(GasEstimateResponse { gas, gas_units: gas_limit })
     }
 
-    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatus>> {
-        Ok(self.pool.fetch_order_status(order_hash).await)
+    async fn simulate_order(&self, order: AllOrders) -> RpcResult<SimulationResult> {
+        Ok(self.validator.simulate_order(order).await)
+    }
+
+    async fn next_nonce(&self, address: Address) -> RpcResult<u64> {
+        Ok(self
+            .validator
+            .next_nonce(address)
+            .await
+            .ok_or(ValidatorUnavailable)?)
+    }
+
+    async fn node_status(&self) -> RpcResult<NodeStatus> {
+        let pool_status = self.pool.pool_status().await;
+        Ok(self.status_sources.status(pool_status))
+    }
+
+    async fn validator_set(&self) -> RpcResult<Vec<ValidatorInfo>> {
+        Ok(self.status_sources.validator_set.validator_set())
+    }
+
+    async fn book_imbalance(&self, pool_id: FixedBytes<32>) -> RpcResult<Option<BookImbalance>> {
+        Ok(self.pool.book_notional(pool_id).await.map(Into::into))
+    }
+
+    async fn pools(&self) -> RpcResult<Vec<PoolInfo>> {
+        self.bounded(
+            self.pool
+                .pools()
+                .await
+                .into_iter()
+                .map(Into::into)
+                .collect()
+        )
+    }
+
+    async fn order_status(&self, order_hash: B256) -> RpcResult<Option<OrderStatusResult>> {
+        let status = self.pool.fetch_order_status(order_hash).await;
+        Ok(status.map(|(status, transaction_id)| OrderStatusResult { status, transaction_id }))
+    }
+
+    async fn status_of_orders(
+        &self,
+        order_hashes: Vec<B256>
+    ) -> RpcResult<Vec<Option<OrderStatusResult>>> {
+        let statuses = futures::future::join_all(
+            order_hashes
+                .into_iter()
+                .map(|order_hash| self.order_status(order_hash))
+        )
+        .await
+        .into_iter()
+        .collect::<RpcResult<Vec<_>>>()?;
+        self.bounded(statuses)
+    }
+
+    async fn order_rejection(&self, order_hash: B256) -> RpcResult<Option<Rejection>> {
+        Ok(self.pool.order_rejection(order_hash).await)
     }
 
     async fn orders_by_pair(
         &self,
         pair: FixedBytes<32>,
-        location: OrderLocation
+        location: OrderLocation,
+        depth: Option<usize>
+    ) -> RpcResult<Vec<AllOrders>> {
+        self.bounded(
+            self.pool
+                .fetch_orders_from_pool(pair, location, depth)
+                .await
+        )
+    }
+
+    async fn orders_by_pairs(
+        &self,
+        pair_with_location: Vec<(FixedBytes<32>, OrderLocation)>,
+        depth: Option<usize>
     ) -> RpcResult<Vec<AllOrders>> {
-        Ok(self.pool.fetch_orders_from_pool(pair, location).await)
+        let orders = futures::future::join_all(
+            pair_with_location
+                .into_iter()
+                .map(|(pair, location)| self.pool.fetch_orders_from_pool(pair, location, depth))
+        )
+        .await;
+        self.bounded(orders.into_iter().flatten().collect())
     }
 
     async fn subscribe_orders(
@@ -119,7 +288,13 @@ pub enum OrderApiError {
     #[error("failed to recover signer from signature")]
     SignatureRecoveryError,
     #[error("failed to estimate gas: {0}")]
-    GasEstimationError(String)
+    GasEstimationError(String),
+    #[error("{0}")]
+    CancellationError(CancelOrderError),
+    #[error("response exceeds the limit of {0} bytes, narrow your query")]
+    ResponseTooLarge(usize),
+    #[error("the validator is unavailable, the node may be shutting down")]
+    ValidatorUnavailable
 }
 
 impl From<OrderApiError> for jsonrpsee::types::ErrorObjectOwned {
@@ -127,7 +302,16 @@ impl From<OrderApiError> for jsonrpsee::types::ErrorObjectOwned {
         match error {
             OrderApiError::InvalidSignature => invalid_params_rpc_err(error.to_string()),
             OrderApiError::SignatureRecoveryError => invalid_params_rpc_err(error.to_string()),
-            OrderApiError::GasEstimationError(e) => invalid_params_rpc_err(e)
+            OrderApiError::GasEstimationError(e) => invalid_params_rpc_err(e),
+            OrderApiError::CancellationError(e) => invalid_params_rpc_err(e.to_string()),
+            OrderApiError::ResponseTooLarge(limit) => jsonrpsee::types::ErrorObject::owned(
+                RESPONSE_TOO_LARGE_CODE,
+                error.to_string(),
+                Some(serde_json::json!({ "limit": limit }))
+            ),
+            OrderApiError::ValidatorUnavailable => {
+                rpc_err(jsonrpsee::types::error::INTERNAL_ERROR_CODE, error.to_string(), None)
+            }
         }
     }
 }
@@ -151,6 +335,28 @@ pub fn rpc_err(
     )
 }
 
+/// Counts the bytes of a response as it is serialized, erroring once it grows
+/// past the limit
+struct SizeCounter {
+    written: usize,
+    limit:   usize
+}
+
+impl io::Write for SizeCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.written += buf.len();
+        if self.written > self.limit {
+            return Err(io::Error::other("response size limit exceeded"))
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 trait OrderFilterMatching {
     fn filter_out_order(
         self,
@@ -198,6 +404,14 @@ impl OrderFilterMatching for PoolManagerUpdate {
             {
                 Some(OrderSubscriptionResult::CancelledOrder(order_hash))
             }
+            PoolManagerUpdate::KilledOrder(block, order)
+                if kind.contains(&OrderSubscriptionKind::KilledOrders)
+                    && (filter.contains(&OrderSubscriptionFilter::ByPair(order.pool_id))
+                        || filter.contains(&OrderSubscriptionFilter::ByAddress(order.from()))
+                        || filter.contains(&OrderSubscriptionFilter::None)) =>
+            {
+                Some(OrderSubscriptionResult::KilledOrder(block, order.order))
+            }
             _ => None
         }
     }
@@ -205,20 +419,30 @@ impl OrderFilterMatching for PoolManagerUpdate {
 
 #[cfg(test)]
 mod tests {
-    use std::{future, future::Future};
+    use std::{
+        future,
+        future::Future,
+        sync::{atomic::Ordering, Arc}
+    };
 
     use alloy_primitives::{Address, B256, U256};
     use angstrom_network::pool_manager::OrderCommand;
     use angstrom_types::{
-        orders::{OrderOrigin, OrderStatus},
-        sol_bindings::grouped_orders::{AllOrders, FlashVariants, StandingVariants}
+        orders::{OrderOrigin, OrderStatus, SignedCancellation},
+        sol_bindings::{
+            ext::{RawPoolOrder, RespendAvoidanceMethod},
+            grouped_orders::{AllOrders, FlashVariants, OrderWithStorageData, StandingVariants}
+        }
     };
     use futures::FutureExt;
-    use order_pool::PoolManagerUpdate;
+    use order_pool::{BookNotional, PoolManagerUpdate, PoolStatus};
     use reth_tasks::TokioTaskExecutor;
     use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
     use tokio_stream::wrappers::BroadcastStream;
-    use validation::order::{GasEstimationFuture, ValidationFuture};
+    use validation::order::{
+        rejection::RejectionReason, state::pools::TrackedPool, GasEstimationFuture, NonceFuture,
+        SimulationFuture, ValidationFuture
+    };
 
     use super::*;
 
@@ -258,6 +482,216 @@ mod tests {
         assert!(api.send_order(tob_order).await.expect("to not throw error"));
     }
 
+    #[tokio::test]
+    async fn test_shared_pool_handle() {
+        let (to_pool, _pool_rx) = unbounded_channel();
+        let pool_handle = Arc::new(MockOrderPoolHandle::new(to_pool));
+        let api = OrderApi::new(pool_handle.clone(), TokioTaskExecutor::default(), MockValidator);
+
+        assert!(api
+            .send_order(create_standing_order())
+            .await
+            .expect("to not throw error"));
+        assert_eq!(Arc::strong_count(&pool_handle), 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_orders_reports_each_order() {
+        let (mut handle, api) = setup_order_api();
+
+        let standing_order = create_standing_order();
+        let orders = vec![
+            serde_json::to_value(&standing_order).unwrap(),
+            serde_json::json!({ "not": "an order" }),
+            serde_json::to_value(create_tob_order()).unwrap(),
+        ];
+
+        let results = api.submit_orders(orders).await.expect("to not throw error");
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[0],
+            SubmitResult::Accepted {
+                order_hash:     standing_order.order_hash(),
+                transaction_id: TransactionId::of(&standing_order)
+            }
+        );
+        assert!(matches!(results[1], SubmitResult::Malformed { .. }));
+        assert!(results[2].is_accepted());
+
+        // only the decodable orders reach the pool
+        assert!(handle._from_api.try_recv().is_ok());
+        assert!(handle._from_api.try_recv().is_ok());
+        assert!(handle._from_api.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_orders_of_sender() {
+        let (_handle, api) = setup_order_api();
+
+        let orders = api
+            .orders_of(Address::ZERO)
+            .await
+            .expect("to not throw error");
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].order, create_standing_order());
+
+        let orders = api
+            .orders_of(Address::random())
+            .await
+            .expect("to not throw error");
+        assert!(orders.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_simulate_order_does_not_submit() {
+        let (mut handle, api) = setup_order_api();
+
+        let result = api
+            .simulate_order(create_flash_order())
+            .await
+            .expect("to not throw error");
+        assert!(result.would_validate);
+        assert!(result.rejection_reason.is_none());
+
+        // a dry run must never reach the pool
+        assert!(handle._from_api.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_node_status_waits_for_pools_to_sync() {
+        let (_handle, api) = setup_order_api();
+        let sources = NodeStatusSources::default();
+        let api = api.with_status_sources(sources.clone());
+
+        sources.validation_block.store(100, Ordering::SeqCst);
+        sources.pools_synced_block.store(98, Ordering::SeqCst);
+        let status = api.node_status().await.expect("to not throw error");
+        assert_eq!(status.current_block, 100);
+        assert_eq!(status.validation_lag, 2);
+        assert!(!status.pools_synced);
+        assert_eq!((status.peer_count, status.pending_orders), (3, 7));
+        assert_eq!(status.validations_queued, 0);
+
+        sources.pools_synced_block.store(100, Ordering::SeqCst);
+        let status = api.node_status().await.expect("to not throw error");
+        assert_eq!(status.validation_lag, 0);
+        assert!(status.pools_synced);
+
+        sources.validations_queued.store(12, Ordering::Relaxed);
+        let status = api.node_status().await.expect("to not throw error");
+        assert_eq!(status.validations_queued, 12);
+    }
+
+    #[tokio::test]
+    async fn test_book_imbalance() {
+        let (_handle, api) = setup_order_api();
+
+        let book = api
+            .book_imbalance(FixedBytes::ZERO)
+            .await
+            .expect("to not throw error")
+            .expect("pool is known");
+        assert_eq!(book.imbalance, Some(0.5));
+
+        let unknown = api
+            .book_imbalance(FixedBytes::repeat_byte(1))
+            .await
+            .expect("to not throw error");
+        assert!(unknown.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_order_status_carries_transaction_id() {
+        let (_handle, api) = setup_order_api();
+
+        let status = api
+            .order_status(B256::ZERO)
+            .await
+            .expect("to not throw error")
+            .expect("order is held");
+        assert!(matches!(status.status, OrderStatus::Pending));
+        assert_eq!(status.transaction_id.reuse_avoidance, RespendAvoidanceMethod::Nonce(3));
+
+        let unknown = api
+            .order_status(B256::repeat_byte(1))
+            .await
+            .expect("to not throw error");
+        assert!(unknown.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_order_rejection_carries_code() {
+        let (_handle, api) = setup_order_api();
+
+        let rejection = api
+            .order_rejection(B256::ZERO)
+            .await
+            .expect("to not throw error")
+            .expect("order was rejected");
+        assert_eq!(rejection.code, RejectionReason::PoolFull);
+
+        let unknown = api
+            .order_rejection(B256::repeat_byte(1))
+            .await
+            .expect("to not throw error");
+        assert!(unknown.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pools() {
+        let (_handle, api) = setup_order_api();
+
+        let pools = api.pools().await.expect("to not throw error");
+        assert_eq!(pools.len(), 1);
+        assert_eq!((pools[0].fee, pools[0].tick_spacing), (3000, 60));
+        assert_eq!(pools[0].token1, Address::repeat_byte(1));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_queries_are_rejected() {
+        let (_handle, api) = setup_order_api();
+        let api = api.with_max_response_size(64);
+
+        let error = api.orders_of(Address::ZERO).await.unwrap_err();
+        assert_eq!(error, OrderApiError::ResponseTooLarge(64).into());
+        assert_eq!(error.code(), RESPONSE_TOO_LARGE_CODE);
+        assert_eq!(
+            error.data().map(|data| data.get()),
+            Some(serde_json::json!({ "limit": 64 }).to_string().as_str())
+        );
+        assert_eq!(api.pools().await.unwrap_err(), OrderApiError::ResponseTooLarge(64).into());
+
+        // responses within the limit are answered as usual
+        let orders = api
+            .orders_of(Address::random())
+            .await
+            .expect("to not throw error");
+        assert!(orders.is_empty());
+
+        let (_handle, api) = setup_order_api();
+        let api = api.with_max_response_size(1024 * 1024);
+        assert_eq!(api.pools().await.expect("to not throw error").len(), 1);
+    }
+
+    #[test]
+    fn test_killed_order_subscription() {
+        let order = OrderWithStorageData::<()>::default()
+            .try_map_inner(|_| Ok(create_flash_order()))
+            .unwrap();
+        let filter = HashSet::from([OrderSubscriptionFilter::None]);
+
+        let update = PoolManagerUpdate::KilledOrder(10, order.clone());
+        assert!(update
+            .filter_out_order(&HashSet::from([OrderSubscriptionKind::FilledOrders]), &filter)
+            .is_none());
+
+        let update = PoolManagerUpdate::KilledOrder(10, order.clone());
+        assert_eq!(
+            update.filter_out_order(&HashSet::from([OrderSubscriptionKind::KilledOrders]), &filter),
+            Some(OrderSubscriptionResult::KilledOrder(10, order.order))
+        );
+    }
+
     fn setup_order_api(
     ) -> (OrderApiTestHandle, OrderApi<MockOrderPoolHandle, TokioTaskExecutor, MockValidator>) {
         let (to_pool, pool_rx) = unbounded_channel();
@@ -287,7 +721,8 @@ mod tests {
         fn fetch_orders_from_pool(
             &self,
             _: FixedBytes<32>,
-            _: OrderLocation
+            _: OrderLocation,
+            _: Option<usize>
         ) -> impl Future<Output = Vec<AllOrders>> + Send {
             future::ready(vec![])
         }
@@ -296,13 +731,13 @@ mod tests {
             &self,
             origin: OrderOrigin,
             order: AllOrders
-        ) -> impl Future<Output = bool> + Send {
+        ) -> impl Future<Output = Result<(), Rejection>> + Send {
             let (tx, _) = tokio::sync::oneshot::channel();
             let _ = self
                 .sender
                 .send(OrderCommand::NewOrder(origin, order, tx))
                 .is_ok();
-            future::ready(true)
+            future::ready(Ok(()))
         }
 
         fn subscribe_orders(&self) -> BroadcastStream<PoolManagerUpdate> {
@@ -311,15 +746,14 @@ mod tests {
 
         fn cancel_order(
             &self,
-            from: Address,
-            order_hash: B256
-        ) -> impl Future<Output = bool> + Send {
+            cancellation: SignedCancellation
+        ) -> impl Future<Output = Result<bool, CancelOrderError>> + Send {
             let (tx, _) = tokio::sync::oneshot::channel();
             let _ = self
                 .sender
-                .send(OrderCommand::CancelOrder(from, order_hash, tx))
+                .send(OrderCommand::CancelOrder(cancellation, tx))
                 .is_ok();
-            future::ready(true)
+            future::ready(Ok(true))
         }
 
         fn pending_orders(&self, address: Address) -> impl Future<Output = Vec<AllOrders>> + Send {
@@ -331,8 +765,62 @@ mod tests {
             rx.map(|res| res.unwrap_or_default())
         }
 
-        fn fetch_order_status(&self, _: B256) -> impl Future<Output = Option<OrderStatus>> + Send {
-            future::ready(None)
+        fn orders_by_sender(
+            &self,
+            sender: Address
+        ) -> impl Future<Output = Vec<OrderWithStorageData<AllOrders>>> + Send {
+            let order = OrderWithStorageData::<()>::default()
+                .try_map_inner(|_| Ok(create_standing_order()))
+                .unwrap();
+            future::ready(
+                (sender == Address::ZERO)
+                    .then_some(order)
+                    .into_iter()
+                    .collect()
+            )
+        }
+
+        fn fetch_order_status(
+            &self,
+            order_hash: B256
+        ) -> impl Future<Output = Option<(OrderStatus, TransactionId)>> + Send {
+            let id = TransactionId {
+                sender:          Address::ZERO,
+                reuse_avoidance: RespendAvoidanceMethod::Nonce(3)
+            };
+            future::ready((order_hash == B256::ZERO).then_some((OrderStatus::Pending, id)))
+        }
+
+        fn order_rejection(
+            &self,
+            order_hash: B256
+        ) -> impl Future<Output = Option<Rejection>> + Send {
+            let rejection = Rejection::new(RejectionReason::PoolFull, "pool is full");
+            future::ready((order_hash == B256::ZERO).then_some(rejection))
+        }
+
+        fn pool_status(&self) -> impl Future<Output = PoolStatus> + Send {
+            future::ready(PoolStatus { peer_count: 3, pending_orders: 7 })
+        }
+
+        fn book_notional(
+            &self,
+            pool_id: FixedBytes<32>
+        ) -> impl Future<Output = Option<BookNotional>> + Send {
+            future::ready((pool_id == FixedBytes::ZERO).then_some(BookNotional {
+                bid_notional: U256::from(300),
+                ask_notional: U256::from(100)
+            }))
+        }
+
+        fn pools(&self) -> impl Future<Output = Vec<TrackedPool>> + Send {
+            future::ready(vec![TrackedPool {
+                pool_id:      FixedBytes::ZERO,
+                token0:       Address::ZERO,
+                token1:       Address::repeat_byte(1),
+                fee_in_e6:    3000,
+                tick_spacing: 60
+            }])
         }
     }
 
@@ -358,5 +846,19 @@ mod tests {
         fn estimate_gas(&self, _order: AllOrders) -> GasEstimationFuture {
             Box::pin(future::ready(Ok((21_000u64, U256::from(250_000u64)))))
         }
+
+        fn simulate_order(&self, order: AllOrders) -> SimulationFuture {
+            Box::pin(future::ready(SimulationResult {
+                would_validate:   true,
+                estimated_fill:   order.amount_in(),
+                required_balance: U256::from(order.amount_in()),
+                hook_gas:         None,
+                rejection_reason: None
+            }))
+        }
+
+        fn next_nonce(&self, _user: Address) -> NonceFuture {
+            Box::pin(future::ready(Some(0)))
+        }
     }
 }
@@ -0,0 +1,25 @@
+use alloy_primitives::U256;
+use order_pool::BookNotional;
+use serde::{Deserialize, Serialize};
+
+/// Bid/ask size of a pool's pending limit orders, see
+/// `angstrom_bookImbalance`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BookImbalance {
+    /// pending bids, in the pool's quote token (token1)
+    pub bid_notional: U256,
+    /// pending asks converted at their limit price, in token1
+    pub ask_notional: U256,
+    /// `(bid - ask) / (bid + ask)`, `None` when the book is empty
+    pub imbalance:    Option<f64>
+}
+
+impl From<BookNotional> for BookImbalance {
+    fn from(notional: BookNotional) -> Self {
+        Self {
+            bid_notional: notional.bid_notional,
+            ask_notional: notional.ask_notional,
+            imbalance:    notional.imbalance()
+        }
+    }
+}
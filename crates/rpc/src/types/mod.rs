@@ -1,5 +1,13 @@
+pub mod book;
+pub mod pools;
 pub mod quoting;
+pub mod status;
+pub mod submit;
 pub mod subscriptions;
 
+pub use book::*;
+pub use pools::*;
 pub use quoting::*;
+pub use status::*;
+pub use submit::*;
 pub use subscriptions::*;
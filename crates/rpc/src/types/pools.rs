@@ -0,0 +1,27 @@
+use alloy_primitives::{Address, FixedBytes};
+use serde::{Deserialize, Serialize};
+use validation::order::state::pools::TrackedPool;
+
+/// An angstrom pool orders can be placed in, see `angstrom_pools`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PoolInfo {
+    pub pool_id:      FixedBytes<32>,
+    /// the lower of the two token addresses
+    pub token0:       Address,
+    pub token1:       Address,
+    /// swap fee in millionths
+    pub fee:          u32,
+    pub tick_spacing: u16
+}
+
+impl From<TrackedPool> for PoolInfo {
+    fn from(pool: TrackedPool) -> Self {
+        Self {
+            pool_id:      pool.pool_id,
+            token0:       pool.token0,
+            token1:       pool.token1,
+            fee:          pool.fee_in_e6,
+            tick_spacing: pool.tick_spacing
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::{
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+    Arc
+};
+
+use consensus::ValidatorSetHandle;
+use order_pool::PoolStatus;
+use serde::{Deserialize, Serialize};
+
+/// Operational state of the node, see `angstrom_nodeStatus`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NodeStatus {
+    /// block validation is currently running on
+    pub current_block:      u64,
+    /// whether the uniswap pools have caught up with validation, readiness
+    /// probes should gate on this
+    pub pools_synced:       bool,
+    /// how many blocks the uniswap pools trail validation by
+    pub validation_lag:     u64,
+    /// connected strom peers
+    pub peer_count:         usize,
+    /// orders currently held by the pool
+    pub pending_orders:     usize,
+    /// validations waiting to start, a growing number means validation is
+    /// backed up
+    pub validations_queued: usize
+}
+
+/// Block heights and queue depth published by validation and the uniswap pool
+/// manager, and the validator set published by consensus. The rpc is built
+/// before any of them is started, so they're handed the same counters once
+/// they are.
+#[derive(Debug, Clone, Default)]
+pub struct NodeStatusSources {
+    pub validation_block:   Arc<AtomicU64>,
+    pub pools_synced_block: Arc<AtomicU64>,
+    pub validations_queued: Arc<AtomicUsize>,
+    pub validator_set:      ValidatorSetHandle
+}
+
+impl NodeStatusSources {
+    pub fn status(&self, pool: PoolStatus) -> NodeStatus {
+        let current_block = self.validation_block.load(Ordering::SeqCst);
+        let pools_synced_block = self.pools_synced_block.load(Ordering::SeqCst);
+        let validation_lag = current_block.saturating_sub(pools_synced_block);
+
+        NodeStatus {
+            current_block,
+            // nothing has been synced before validation reports a block
+            pools_synced: current_block != 0 && validation_lag == 0,
+            validation_lag,
+            peer_count: pool.peer_count,
+            pending_orders: pool.pending_orders,
+            validations_queued: self.validations_queued.load(Ordering::Relaxed)
+        }
+    }
+}
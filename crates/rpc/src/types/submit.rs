@@ -0,0 +1,30 @@
+use alloy_primitives::B256;
+use angstrom_types::orders::{OrderStatus, TransactionId};
+use serde::{Deserialize, Serialize};
+use validation::order::rejection::Rejection;
+
+/// Outcome of a single order of an `angstrom_submitOrders` batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum SubmitResult {
+    /// the order passed validation and was added to the pool. Orders of the
+    /// same sender that share a `transaction_id` use the same nonce
+    Accepted { order_hash: B256, transaction_id: TransactionId },
+    /// the order failed validation or wasn't admitted to the pool
+    Rejected { order_hash: B256, reason: Rejection },
+    /// the order couldn't be decoded
+    Malformed { error: String }
+}
+
+/// Status of an order held by the pool, see `angstrom_orderStatus`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderStatusResult {
+    pub status:         OrderStatus,
+    pub transaction_id: TransactionId
+}
+
+impl SubmitResult {
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accepted { .. })
+    }
+}
@@ -37,7 +37,9 @@ pub enum OrderSubscriptionKind {
     /// Any new reorged orders
     UnfilleOrders,
     /// Any new cancelled orders
-    CancelledOrders
+    CancelledOrders,
+    /// Any fill-or-kill orders that were killed
+    KilledOrders
 }
 
 #[derive(
@@ -62,5 +64,6 @@ pub enum OrderSubscriptionResult {
     NewOrder(AllOrders),
     FilledOrder(u64, AllOrders),
     UnfilledOrder(AllOrders),
-    CancelledOrder(B256)
+    CancelledOrder(B256),
+    KilledOrder(u64, AllOrders)
 }
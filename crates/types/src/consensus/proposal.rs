@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use super::PreProposal;
 use crate::{
     orders::PoolSolution,
-    primitive::{PeerId, Signature}
+    primitive::{PeerId, PoolId, Signature}
 };
 
 #[derive(Default, Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -19,6 +19,9 @@ pub struct Proposal {
     pub preproposals: Vec<PreProposal>,
     /// PoolSolutions sorted by PoolId
     pub solutions:    Vec<PoolSolution>,
+    /// Pools the leader left out of matching, halted by its circuit breaker or
+    /// paused by its operator, sorted
+    pub halted_pools: Vec<PoolId>,
     /// This signature is over (etheruem_block | hash(vanilla_bundle) |
     /// hash(order_buffer) | hash(lower_bound))
     pub signature:    Signature
@@ -30,10 +33,12 @@ impl Proposal {
         source: PeerId,
         preproposals: Vec<PreProposal>,
         mut solutions: Vec<PoolSolution>,
+        mut halted_pools: Vec<PoolId>,
         sk: &SecretKey
     ) -> Self {
         // Sort our solutions
         solutions.sort_by_key(|sol| sol.id);
+        halted_pools.sort();
 
         // Build our hash and sign
         let mut buf = Vec::new();
@@ -41,6 +46,7 @@ impl Proposal {
         buf.extend(*source);
         buf.extend(bincode::serialize(&preproposals).unwrap());
         buf.extend(bincode::serialize(&solutions).unwrap());
+        buf.extend(bincode::serialize(&halted_pools).unwrap());
 
         let hash = keccak256(buf);
         let sig = reth_primitives::sign_message(sk.secret_bytes().into(), hash).unwrap();
@@ -50,6 +56,7 @@ impl Proposal {
             source,
             preproposals,
             solutions,
+            halted_pools,
             signature: Signature(sig)
         }
     }
@@ -63,6 +70,14 @@ impl Proposal {
         if !self.preproposals.iter().all(|i| i.is_valid()) {
             return false
         }
+        // Halted pools can't be solved
+        if self
+            .solutions
+            .iter()
+            .any(|solution| self.halted_pools.contains(&solution.id))
+        {
+            return false
+        }
         // Then our own signature has to be valid
         let hash = keccak256(self.payload());
         let Ok(source) = self.signature.recover_signer_full_public_key(hash) else {
@@ -77,6 +92,7 @@ impl Proposal {
         buf.extend(*self.source);
         buf.extend(bincode::serialize(&self.preproposals).unwrap());
         buf.extend(bincode::serialize(&self.solutions).unwrap());
+        buf.extend(bincode::serialize(&self.halted_pools).unwrap());
 
         Bytes::from_iter(buf)
     }
@@ -89,7 +105,7 @@ mod tests {
     use reth_network_peers::pk2id;
     use secp256k1::Secp256k1;
 
-    use super::{Proposal, SecretKey};
+    use super::{PoolId, PoolSolution, Proposal, SecretKey};
 
     #[test]
     fn can_be_constructed() {
@@ -99,7 +115,7 @@ mod tests {
         let solutions = vec![];
         let mut rng = thread_rng();
         let sk = SecretKey::new(&mut rng);
-        Proposal::generate_proposal(ethereum_height, source, preproposals, solutions, &sk);
+        Proposal::generate_proposal(ethereum_height, source, preproposals, solutions, vec![], &sk);
     }
 
     #[test]
@@ -114,9 +130,38 @@ mod tests {
         let pk = sk.public_key(&secp);
         // Grab the source ID from the secret/public keypair
         let source = pk2id(&pk);
-        let proposal =
-            Proposal::generate_proposal(ethereum_height, source, preproposals, solutions, &sk);
+        let proposal = Proposal::generate_proposal(
+            ethereum_height,
+            source,
+            preproposals,
+            solutions,
+            vec![],
+            &sk
+        );
 
         assert!(proposal.is_valid(), "Unable to validate self");
     }
+
+    #[test]
+    fn halted_pools_are_signed_and_left_unsolved() {
+        let sk = SecretKey::new(&mut thread_rng());
+        let source = pk2id(&sk.public_key(&Secp256k1::new()));
+        let (halted, other) = (PoolId::random(), PoolId::random());
+        let solution = |id| PoolSolution { id, ..Default::default() };
+
+        let solutions = vec![solution(other)];
+        let proposal =
+            Proposal::generate_proposal(100, source, vec![], solutions, vec![halted], &sk);
+        assert!(proposal.is_valid());
+
+        // the halted pools can't be swapped out after signing
+        let mut tampered = proposal.clone();
+        tampered.halted_pools = vec![other];
+        assert!(!tampered.is_valid());
+
+        let solutions = vec![solution(halted)];
+        let solves_halted =
+            Proposal::generate_proposal(100, source, vec![], solutions, vec![halted], &sk);
+        assert!(!solves_halted.is_valid());
+    }
 }
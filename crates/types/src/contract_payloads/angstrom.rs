@@ -153,6 +153,12 @@ pub struct StandingValidation {
     deadline: u64
 }
 
+impl StandingValidation {
+    pub fn new(nonce: u64, deadline: u64) -> Self {
+        Self { nonce, deadline }
+    }
+}
+
 #[derive(Debug, PadeEncode, PadeDecode)]
 pub enum OrderQuantities {
     Exact { quantity: u128 },
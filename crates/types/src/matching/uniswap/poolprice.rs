@@ -8,7 +8,10 @@ use eyre::OptionExt;
 use uniswap_v3_math::tick_math::{get_sqrt_ratio_at_tick, get_tick_at_sqrt_ratio};
 
 use super::{liqrange::LiqRangeRef, poolpricevec::PoolPriceVec, Quantity, Tick};
-use crate::matching::SqrtPriceX96;
+use crate::matching::{Ray, SqrtPriceX96};
+
+const FEE_DENOMINATOR: u32 = 1_000_000;
+
 /// Representation of a specific price point in a Uniswap Pool.  Can be operated
 /// on to simulate the behavior of the price withing said pool.
 ///
@@ -123,6 +126,52 @@ impl<'a> PoolPrice<'a> {
         Some(PoolPriceVec::new(self.clone(), end_bound))
     }
 
+    /// Like [`Self::order_to_target`] but with the target given as the price
+    /// of a book order we want to match against.  The pool fee is taken out
+    /// of the AMM's side of the trade, so the AMM only stays competitive with
+    /// the book order while its post-fee price is still better.  That means
+    /// we have to stop short of the raw book price by the fee.
+    pub fn order_to_book_price(
+        &self,
+        book_price: Option<Ray>,
+        buy: bool
+    ) -> Option<PoolPriceVec<'a>> {
+        let target_price = match book_price {
+            Some(p) => Some(SqrtPriceX96::from(self.pre_fee_price(p, buy)?)),
+            None => None
+        };
+        self.order_to_target(target_price, buy)
+    }
+
+    /// Converts a post-fee price into the pool price that produces it.  When
+    /// the AMM is selling (`buy`) the fee is added on top of the pool price,
+    /// when it's buying the fee is taken out of what it pays.  Returns `None`
+    /// if the fee tier would swallow the entire trade.
+    pub fn pre_fee_price(&self, post_fee: Ray, buy: bool) -> Option<Ray> {
+        let fee = self.liq_range.pool_snap.fee_in_e6();
+        if fee == 0 {
+            return Some(post_fee)
+        }
+        if fee >= FEE_DENOMINATOR {
+            return None
+        }
+        let denominator = U256::from(FEE_DENOMINATOR);
+        let remaining = U256::from(FEE_DENOMINATOR - fee);
+        let pre_fee = if buy {
+            post_fee.checked_mul(remaining)? / denominator
+        } else {
+            post_fee.checked_mul(denominator)? / remaining
+        };
+        Some(Ray::from(pre_fee))
+    }
+
+    /// The inverse of [`Self::pre_fee_price`], the price the other side of
+    /// the trade gets out of the pool price `pre_fee` once the fee is paid.
+    pub fn post_fee_price(&self, pre_fee: Ray, buy: bool) -> Option<Ray> {
+        // the fee scales the price the opposite way
+        self.pre_fee_price(pre_fee, !buy)
+    }
+
     pub fn price(&self) -> &SqrtPriceX96 {
         &self.price
     }
@@ -384,6 +384,31 @@ impl<'a> PoolPriceVec<'a> {
         (quantity, price)
     }
 
+    /// Like [`Self::quantity`] with `target_price` being a post-fee price,
+    /// only counts the range up to the pool price that still gives
+    /// `target_price` once the pool fee is paid
+    pub fn quantity_after_fee(&self, target_price: OrderPrice) -> (U256, U256) {
+        match self
+            .start_bound
+            .pre_fee_price(Ray::from(target_price), self.is_buy())
+        {
+            Some(pre_fee) => self.quantity(pre_fee.into()),
+            None => (U256::ZERO, U256::ZERO)
+        }
+    }
+
+    /// The price the range starts at once the pool fee is paid, what book
+    /// orders trading against the AMM actually get
+    pub fn start_price_after_fee(&self) -> OrderPrice {
+        let start = Ray::from(self.start_bound.price);
+        match self.start_bound.post_fee_price(start, self.is_buy()) {
+            Some(price) => price.into(),
+            // the fee takes the whole trade, nobody would trade against it
+            None if self.is_buy() => U256::MAX.into(),
+            None => U256::ZERO.into()
+        }
+    }
+
     // Maybe it's OK that I don't check the price again here because in the matching
     // algo I've only offered a quantity bounded by the price, so we should
     // always be OK?
@@ -1,6 +1,8 @@
 use std::slice::Iter;
 
+use alloy::primitives::{keccak256, B256, U256};
 use eyre::{eyre, Context, OptionExt};
+use thiserror::Error;
 use uniswap_v3_math::tick_math::get_tick_at_sqrt_ratio;
 
 use super::{
@@ -10,6 +12,22 @@ use super::{
 };
 use crate::matching::SqrtPriceX96;
 
+/// Version of the format written by [`PoolSnapshot::persist`]. Bumped
+/// whenever the layout changes, older snapshots are refused on restore.
+pub const POOL_SNAPSHOT_VERSION: u16 = 1;
+
+#[derive(Debug, Error)]
+pub enum SnapshotRestoreError {
+    #[error("snapshot is truncated")]
+    Truncated,
+    #[error("snapshot format version {found} isn't supported, expected {expected}")]
+    UnsupportedVersion { found: u16, expected: u16 },
+    #[error("snapshot checksum {found:?} doesn't match its contents {expected:?}")]
+    ChecksumMismatch { found: B256, expected: B256 },
+    #[error("snapshot contents are invalid: {0}")]
+    Invalid(eyre::Report)
+}
+
 /// Snapshot of a particular Uniswap pool and a map of its liquidity.
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct PoolSnapshot {
@@ -24,7 +42,10 @@ pub struct PoolSnapshot {
     pub(crate) current_tick:   Tick,
     /// Index into the 'ranges' vector for the PoolRange that includes the tick
     /// our current price lives at/in
-    pub(crate) cur_tick_idx:   usize
+    pub(crate) cur_tick_idx:   usize,
+    /// Fee charged by the pool on every swap against it, in millionths (the
+    /// same unit as the Angstrom pool config)
+    pub(crate) fee_in_e6:      u32
 }
 
 impl PoolSnapshot {
@@ -53,7 +74,18 @@ impl PoolSnapshot {
             return Err(eyre!("Unable to find initialized tick window for tick '{}'", current_tick));
         };
 
-        Ok(Self { ranges, sqrt_price_x96, current_tick, cur_tick_idx })
+        Ok(Self { ranges, sqrt_price_x96, current_tick, cur_tick_idx, fee_in_e6: 0 })
+    }
+
+    /// Sets the fee tier charged by this pool, in millionths
+    pub fn with_fee(mut self, fee_in_e6: u32) -> Self {
+        self.fee_in_e6 = fee_in_e6;
+        self
+    }
+
+    /// The fee tier charged by this pool, in millionths
+    pub fn fee_in_e6(&self) -> u32 {
+        self.fee_in_e6
     }
 
     /// Find the PoolRange in this market snapshot that the provided tick lies
@@ -91,4 +123,118 @@ impl PoolSnapshot {
     pub fn liquidity_at_tick(&self, tick: Tick) -> Option<u128> {
         self.get_range_for_tick(tick).map(|range| range.liquidity())
     }
+
+    /// Serializes the snapshot as the format version, a keccak checksum of the
+    /// body and the body itself: price, fee and the liquidity ranges.
+    pub fn persist(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(40 + self.ranges.len() * 24);
+        body.extend_from_slice(&U256::from(self.sqrt_price_x96).to_be_bytes::<32>());
+        body.extend_from_slice(&self.fee_in_e6.to_be_bytes());
+        body.extend_from_slice(&(self.ranges.len() as u32).to_be_bytes());
+        for range in &self.ranges {
+            body.extend_from_slice(&range.lower_tick.to_be_bytes());
+            body.extend_from_slice(&range.upper_tick.to_be_bytes());
+            body.extend_from_slice(&range.liquidity.to_be_bytes());
+        }
+
+        let mut bytes = Vec::with_capacity(34 + body.len());
+        bytes.extend_from_slice(&POOL_SNAPSHOT_VERSION.to_be_bytes());
+        bytes.extend_from_slice(keccak256(&body).as_slice());
+        bytes.extend_from_slice(&body);
+        bytes
+    }
+
+    /// Reads back a snapshot written by [`Self::persist`]. Snapshots of
+    /// another format version or whose body doesn't match the checksum are
+    /// refused, the ranges are checked like in [`Self::new`].
+    pub fn restore(mut bytes: &[u8]) -> Result<Self, SnapshotRestoreError> {
+        let version = u16::from_be_bytes(take(&mut bytes)?);
+        if version != POOL_SNAPSHOT_VERSION {
+            return Err(SnapshotRestoreError::UnsupportedVersion {
+                found:    version,
+                expected: POOL_SNAPSHOT_VERSION
+            })
+        }
+        let found = B256::from(take::<32>(&mut bytes)?);
+        let expected = keccak256(bytes);
+        if found != expected {
+            return Err(SnapshotRestoreError::ChecksumMismatch { found, expected })
+        }
+
+        let sqrt_price_x96 = SqrtPriceX96::from(U256::from_be_bytes(take::<32>(&mut bytes)?));
+        let fee_in_e6 = u32::from_be_bytes(take(&mut bytes)?);
+        let range_count = u32::from_be_bytes(take(&mut bytes)?);
+        let ranges = (0..range_count)
+            .map(|_| {
+                let lower_tick = Tick::from_be_bytes(take(&mut bytes)?);
+                let upper_tick = Tick::from_be_bytes(take(&mut bytes)?);
+                let liquidity = u128::from_be_bytes(take(&mut bytes)?);
+                LiqRange::new(lower_tick, upper_tick, liquidity)
+                    .map_err(SnapshotRestoreError::Invalid)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !bytes.is_empty() {
+            return Err(SnapshotRestoreError::Invalid(eyre!("trailing bytes after the ranges")))
+        }
+
+        Self::new(ranges, sqrt_price_x96)
+            .map(|snapshot| snapshot.with_fee(fee_in_e6))
+            .map_err(SnapshotRestoreError::Invalid)
+    }
+}
+
+/// splits the next `N` bytes off the front of `bytes`
+fn take<const N: usize>(bytes: &mut &[u8]) -> Result<[u8; N], SnapshotRestoreError> {
+    let (head, rest) = bytes
+        .split_first_chunk::<N>()
+        .ok_or(SnapshotRestoreError::Truncated)?;
+    *bytes = rest;
+    Ok(*head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> PoolSnapshot {
+        let ranges = vec![
+            LiqRange::new(-1000, 0, 1_000_000).unwrap(),
+            LiqRange::new(0, 1000, 2_000_000).unwrap(),
+        ];
+        PoolSnapshot::new(ranges, SqrtPriceX96::at_tick(10).unwrap())
+            .unwrap()
+            .with_fee(3000)
+    }
+
+    #[test]
+    fn restores_what_was_persisted() {
+        let snapshot = snapshot();
+        let restored = PoolSnapshot::restore(&snapshot.persist()).unwrap();
+        assert_eq!(restored, snapshot);
+    }
+
+    #[test]
+    fn refuses_corrupt_or_incompatible_snapshots() {
+        let bytes = snapshot().persist();
+
+        let mut corrupt = bytes.clone();
+        *corrupt.last_mut().unwrap() ^= 1;
+        assert!(matches!(
+            PoolSnapshot::restore(&corrupt),
+            Err(SnapshotRestoreError::ChecksumMismatch { .. })
+        ));
+
+        let mut other_version = bytes.clone();
+        other_version[..2].copy_from_slice(&(POOL_SNAPSHOT_VERSION + 1).to_be_bytes());
+        assert!(matches!(
+            PoolSnapshot::restore(&other_version),
+            Err(SnapshotRestoreError::UnsupportedVersion { found, .. })
+                if found == POOL_SNAPSHOT_VERSION + 1
+        ));
+
+        assert!(matches!(
+            PoolSnapshot::restore(&bytes[..20]),
+            Err(SnapshotRestoreError::Truncated)
+        ));
+    }
 }
@@ -0,0 +1,49 @@
+use alloy::primitives::{Address, B256};
+use serde::{Deserialize, Serialize};
+
+use crate::primitive::Signature;
+
+/// A user's request to cancel one of their orders, signed over the order's
+/// hash. Nodes recover the canceller from the signature, so the request can
+/// be relayed by anyone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedCancellation {
+    pub signature: Signature,
+    pub hash:      B256
+}
+
+impl SignedCancellation {
+    /// the address that signed the cancellation, `None` if the signature
+    /// doesn't recover
+    pub fn recover_signer(&self) -> Option<Address> {
+        self.signature
+            .recover_signer_full_public_key(self.hash)
+            .ok()
+            .map(|key| Address::from_raw_public_key(&*key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{keccak256, FixedBytes};
+    use reth_network_peers::pk2id;
+    use secp256k1::SecretKey;
+
+    use super::*;
+
+    #[test]
+    fn signer_is_recovered_from_the_order_hash() {
+        let hash = keccak256([1, 2, 3]);
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let public_key = pk2id(&secret_key.public_key(secp256k1::SECP256K1));
+        let signature = Signature(
+            reth_primitives::sign_message(FixedBytes(secret_key.secret_bytes()), hash).unwrap()
+        );
+
+        let cancellation = SignedCancellation { signature, hash };
+        assert_eq!(cancellation.recover_signer(), Some(Address::from_raw_public_key(&*public_key)));
+
+        let unsigned = SignedCancellation { signature: Signature::default(), hash };
+        assert_eq!(unsigned.recover_signer(), None);
+    }
+}
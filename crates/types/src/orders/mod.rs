@@ -1,12 +1,14 @@
+mod cancellation;
 mod fillstate;
 mod origin;
 use alloy::primitives::U256;
 pub mod orderpool;
 
+pub use cancellation::*;
 pub use fillstate::*;
 pub use orderpool::*;
 pub use origin::*;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 pub type BookID = u128;
 pub type OrderID = u128;
@@ -19,12 +21,29 @@ use crate::{
     sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OrderSet<Limit, Searcher> {
     pub limit:    Vec<OrderWithStorageData<Limit>>,
     pub searcher: Vec<OrderWithStorageData<Searcher>>
 }
 
+impl<Limit, Searcher> OrderSet<Limit, Searcher>
+where
+    Limit: Serialize + DeserializeOwned,
+    Searcher: Serialize + DeserializeOwned
+{
+    /// Wire format of the set. This is the same bincode layout the consensus
+    /// payload hashes are built from, so a decoded set re-hashes identically
+    /// on every node.
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("order set is always serializable")
+    }
+
+    pub fn decode(buf: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(buf)
+    }
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum NetAmmOrder {
     Buy(U256, U256),
@@ -104,3 +123,14 @@ pub struct PoolSolution {
     /// hashes here
     pub limit:        Vec<OrderOutcome>
 }
+
+impl PoolSolution {
+    /// Wire format of the solution, see [`OrderSet::encode`].
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::serialize(self).expect("pool solution is always serializable")
+    }
+
+    pub fn decode(buf: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(buf)
+    }
+}
@@ -44,6 +44,25 @@ impl OrderId {
             location: order.order_location()
         }
     }
+
+    pub fn transaction_id(&self) -> TransactionId {
+        TransactionId { sender: self.address, reuse_avoidance: self.reuse_avoidance }
+    }
+}
+
+/// The sender of an order and the nonce, or block for flash orders, that
+/// keeps it from being replayed. Two standing orders with the same id use
+/// the same nonce, so at most one of them can be filled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TransactionId {
+    pub sender:          Address,
+    pub reuse_avoidance: RespendAvoidanceMethod
+}
+
+impl TransactionId {
+    pub fn of<T: RawPoolOrder>(order: &T) -> Self {
+        Self { sender: order.from(), reuse_avoidance: order.respend_avoidance_strategy() }
+    }
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
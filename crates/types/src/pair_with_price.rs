@@ -1,7 +1,7 @@
 use alloy::primitives::{Address, U256};
 use futures::{Stream, StreamExt};
 use pade::PadeDecode;
-use reth_provider::CanonStateNotificationStream;
+use reth_provider::CanonStateNotification;
 
 use crate::contract_payloads::angstrom::AngstromBundle;
 
@@ -15,6 +15,12 @@ pub struct PairsWithPrice {
 }
 
 impl PairsWithPrice {
+    /// whether more than `max_age_blocks` blocks have passed since this price
+    /// was settled
+    pub fn is_stale(&self, current_block: u64, max_age_blocks: u64) -> bool {
+        current_block.saturating_sub(self.block_num) > max_age_blocks
+    }
+
     /// Decodes the AngstromPayload bundle and allows us to checkout
     /// the prices that the pools settled at. We then can use this for things
     /// such as our eth -> erc-20 gas price calculator
@@ -33,12 +39,12 @@ impl PairsWithPrice {
 
     pub fn into_price_update_stream(
         angstrom_address: Address,
-        stream: CanonStateNotificationStream
+        stream: impl Stream<Item = CanonStateNotification> + 'static
     ) -> impl Stream<Item = Vec<Self>> + 'static {
         stream.map(move |notification| {
             let new_cannon_chain = match notification {
-                reth_provider::CanonStateNotification::Reorg { new, .. } => new,
-                reth_provider::CanonStateNotification::Commit { new } => new
+                CanonStateNotification::Reorg { new, .. } => new,
+                CanonStateNotification::Commit { new } => new
             };
             let block_num = new_cannon_chain.tip().number;
             new_cannon_chain
@@ -1,5 +1,8 @@
 use alloy::{
-    primitives::{FixedBytes, Log},
+    primitives::{
+        aliases::{I24, U24},
+        FixedBytes, Log
+    },
     sol_types::SolValue
 };
 use alloy_primitives::{keccak256, Address};
@@ -11,11 +14,33 @@ use crate::contract_bindings::{
 pub type PoolId = FixedBytes<32>;
 
 impl From<PoolKey> for PoolId {
+    /// `keccak256(abi.encode(key))`, the five words of the key hashed as the
+    /// pool manager and Angstrom's `_toId` do
     fn from(value: PoolKey) -> Self {
-        keccak256(value.abi_encode_packed())
+        keccak256(value.abi_encode())
     }
 }
 
+/// The id the contracts give the pool of the pair with this config. The
+/// tokens may be passed in either order, a pool key always holds them sorted.
+pub fn derive_pool_id(
+    token0: Address,
+    token1: Address,
+    fee: u32,
+    tick_spacing: i32,
+    hook: Address
+) -> PoolId {
+    let (currency0, currency1) = if token0 < token1 { (token0, token1) } else { (token1, token0) };
+
+    PoolId::from(PoolKey {
+        currency0,
+        currency1,
+        fee: U24::from(fee),
+        tickSpacing: I24::unchecked_from(tick_spacing),
+        hooks: hook
+    })
+}
+
 pub type PoolIdWithDirection = (bool, PoolId);
 
 /// just a placeholder type so i can implement the general architecture
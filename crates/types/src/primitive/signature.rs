@@ -4,7 +4,7 @@ use std::{
 };
 
 use alloy::{
-    primitives::{Address, FixedBytes, U256},
+    primitives::{uint, Address, FixedBytes, U256},
     rlp::{Decodable, Encodable, Error}
 };
 use alloy_primitives::{Parity, Signature as ESignature};
@@ -16,6 +16,11 @@ use secp256k1::{
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// Half the order of the secp256k1 curve, the largest s a canonical signature
+/// can have (EIP-2).
+pub const SECP256K1N_HALF: U256 =
+    uint!(0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF5D576E7357A4501DDFE92F46681B20A0_U256);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
 //#[derive(Default)]
@@ -49,6 +54,13 @@ impl Signature {
         let public = SECP256K1.recover_ecdsa(&Message::from_digest_slice(&message[..32])?, &sig)?;
         Ok(pk2id(&public))
     }
+
+    /// whether s is at most [`SECP256K1N_HALF`]. `(r, n - s)` with the other
+    /// parity recovers the same signer, only the low-s one of the two is
+    /// canonical
+    pub fn is_low_s(&self) -> bool {
+        self.s() <= SECP256K1N_HALF
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
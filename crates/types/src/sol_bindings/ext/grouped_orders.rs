@@ -1,4 +1,4 @@
-use std::{hash::Hash, ops::Deref};
+use std::{cmp::Ordering, hash::Hash, ops::Deref};
 
 use alloy::primitives::{Address, Bytes, FixedBytes, TxHash, U256};
 use alloy_primitives::B256;
@@ -119,6 +119,13 @@ impl AllOrders {
             Self::TOB(t) => t.eip712_hash_struct()
         }
     }
+
+    /// Exact flash orders are fill-or-kill. They must be fully executed in the
+    /// block they are valid for, otherwise they are dropped rather than
+    /// resting in the book.
+    pub fn is_fill_or_kill(&self) -> bool {
+        matches!(self, Self::Flash(FlashVariants::Exact(_)))
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -142,7 +149,11 @@ pub struct OrderWithStorageData<Order> {
     pub valid_block:        u64,
     /// holds expiry data
     pub order_id:           OrderId,
-    pub tob_reward:         U256
+    pub tob_reward:         U256,
+    /// amount of the order that has been filled so far. Only tracked locally
+    /// while matching, it isn't part of the consensus encoding
+    #[serde(skip)]
+    pub filled_amount:      u128
 }
 
 impl<Order> Hash for OrderWithStorageData<Order> {
@@ -167,6 +178,32 @@ impl OrderWithStorageData<AllOrders> {
     }
 }
 
+impl<Order: RawPoolOrder> OrderWithStorageData<Order> {
+    /// What the order pays on top of its gas cost, per unit of gas. This is
+    /// what the builder earns from including it. The gas cost is set during
+    /// validation and re-priced every block, so this follows the base fee.
+    pub fn effective_tip_per_gas(&self) -> U256 {
+        let tip = U256::from(self.max_gas_token_0()).saturating_sub(self.priority_data.gas);
+        tip / U256::from(self.priority_data.gas_units.max(1))
+    }
+
+    /// What is left to fill of the order, its signed amount minus the fills
+    /// recorded against it. Everything that sizes a resting or partially
+    /// matched order goes through this.
+    pub fn remaining_amount(&self) -> U256 {
+        U256::from(self.amount_in().saturating_sub(self.filled_amount))
+    }
+
+    pub fn is_fully_filled(&self) -> bool {
+        self.remaining_amount().is_zero()
+    }
+
+    /// records another `quantity` of the order as filled
+    pub fn record_fill(&mut self, quantity: u128) {
+        self.filled_amount = self.filled_amount.saturating_add(quantity);
+    }
+}
+
 impl<Order> Deref for OrderWithStorageData<Order> {
     type Target = Order;
 
@@ -196,11 +233,49 @@ impl<Order> OrderWithStorageData<Order> {
             is_currently_valid: self.is_currently_valid,
             is_valid:           self.is_valid,
             order_id:           self.order_id,
-            tob_reward:         U256::ZERO
+            tob_reward:         self.tob_reward,
+            filled_amount:      self.filled_amount
         })
     }
 }
 
+impl OrderWithStorageData<TopOfBlockOrder> {
+    /// What the order pays the protocol for the top of the block, the reward
+    /// validation calculated for it against the pool.
+    pub fn bid_value(&self) -> U256 {
+        self.tob_reward
+    }
+}
+
+/// Ranks top of block orders by [`OrderWithStorageData::bid_value`], the
+/// greatest wins the top of the block. Equal bids are ranked by order hash,
+/// the lowest hash ranking highest, so every node picks the same winner.
+#[derive(Debug, Clone, Copy)]
+pub struct TobRank<'a>(pub &'a OrderWithStorageData<TopOfBlockOrder>);
+
+impl Ord for TobRank<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .bid_value()
+            .cmp(&other.0.bid_value())
+            .then_with(|| other.0.order_id.hash.cmp(&self.0.order_id.hash))
+    }
+}
+
+impl PartialOrd for TobRank<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for TobRank<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TobRank<'_> {}
+
 #[derive(Debug)]
 pub enum GroupedUserOrder {
     Vanilla(GroupedVanillaOrder),
@@ -306,6 +381,20 @@ impl RawPoolOrder for StandingVariants {
         }
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        match self {
+            StandingVariants::Exact(e) => e.has_canonical_signature(),
+            StandingVariants::Partial(p) => p.has_canonical_signature()
+        }
+    }
+
+    fn signature(&self) -> &[u8] {
+        match self {
+            StandingVariants::Exact(e) => e.signature(),
+            StandingVariants::Partial(p) => p.signature()
+        }
+    }
+
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Limit
     }
@@ -316,6 +405,13 @@ impl RawPoolOrder for StandingVariants {
             StandingVariants::Partial(p) => p.use_internal()
         }
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        match self {
+            StandingVariants::Exact(e) => e.hook_calldata(),
+            StandingVariants::Partial(p) => p.hook_calldata()
+        }
+    }
 }
 
 impl RawPoolOrder for FlashVariants {
@@ -333,6 +429,20 @@ impl RawPoolOrder for FlashVariants {
         }
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        match self {
+            FlashVariants::Exact(e) => e.has_canonical_signature(),
+            FlashVariants::Partial(p) => p.has_canonical_signature()
+        }
+    }
+
+    fn signature(&self) -> &[u8] {
+        match self {
+            FlashVariants::Exact(e) => e.signature(),
+            FlashVariants::Partial(p) => p.signature()
+        }
+    }
+
     fn order_hash(&self) -> TxHash {
         match self {
             FlashVariants::Exact(e) => e.order_hash(),
@@ -413,6 +523,13 @@ impl RawPoolOrder for FlashVariants {
             FlashVariants::Partial(p) => p.use_internal()
         }
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        match self {
+            FlashVariants::Exact(e) => e.hook_calldata(),
+            FlashVariants::Partial(p) => p.hook_calldata()
+        }
+    }
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
@@ -507,6 +624,10 @@ impl GroupedVanillaOrder {
                 | Self::KillOrFill(FlashVariants::Partial(_))
         )
     }
+
+    pub fn is_fill_or_kill(&self) -> bool {
+        matches!(self, Self::KillOrFill(FlashVariants::Exact(_)))
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -530,6 +651,18 @@ impl GroupedComposableOrder {
     }
 }
 
+/// Amount out the order's min price implies for `amount` in. Saturates
+/// instead of panicking on a zero price or one that doesn't fit a u128
+fn min_amount_out(amount: u128, min_price: U256, asset_in: Address, asset_out: Address) -> u128 {
+    // TODO: verify math on this. feels wrong
+    let min_price = u128::try_from(min_price).unwrap_or(u128::MAX);
+    if asset_in < asset_out {
+        amount.saturating_mul(min_price)
+    } else {
+        amount.checked_div(min_price).unwrap_or_default()
+    }
+}
+
 impl RawPoolOrder for TopOfBlockOrder {
     fn max_gas_token_0(&self) -> u128 {
         self.max_gas_asset0
@@ -560,7 +693,11 @@ impl RawPoolOrder for TopOfBlockOrder {
     }
 
     fn limit_price(&self) -> U256 {
-        U256::from(self.amount_in() / self.amount_out_min())
+        U256::from(
+            self.amount_in()
+                .checked_div(self.amount_out_min())
+                .unwrap_or_default()
+        )
     }
 
     fn amount_out_min(&self) -> u128 {
@@ -583,6 +720,14 @@ impl RawPoolOrder for TopOfBlockOrder {
             .unwrap_or_default()
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        Signature::new_from_bytes(&self.meta.signature).is_ok_and(|sig| sig.is_low_s())
+    }
+
+    fn signature(&self) -> &[u8] {
+        &self.meta.signature
+    }
+
     fn order_location(&self) -> OrderLocation {
         OrderLocation::Searcher
     }
@@ -590,6 +735,10 @@ impl RawPoolOrder for TopOfBlockOrder {
     fn use_internal(&self) -> bool {
         self.use_internal
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        &[]
+    }
 }
 
 impl RawPoolOrder for PartialStandingOrder {
@@ -605,6 +754,14 @@ impl RawPoolOrder for PartialStandingOrder {
             .unwrap_or_default()
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        Signature::new_from_bytes(&self.meta.signature).is_ok_and(|sig| sig.is_low_s())
+    }
+
+    fn signature(&self) -> &[u8] {
+        &self.meta.signature
+    }
+
     fn flash_block(&self) -> Option<u64> {
         None
     }
@@ -614,12 +771,7 @@ impl RawPoolOrder for PartialStandingOrder {
     }
 
     fn amount_out_min(&self) -> u128 {
-        // TODO: verify math on this. feels wrong
-        if self.asset_in < self.asset_out {
-            self.min_amount_in * self.min_price.to::<u128>()
-        } else {
-            self.min_amount_in / self.min_price.to::<u128>()
-        }
+        min_amount_out(self.min_amount_in, self.min_price, self.asset_in, self.asset_out)
     }
 
     fn limit_price(&self) -> U256 {
@@ -657,6 +809,10 @@ impl RawPoolOrder for PartialStandingOrder {
     fn use_internal(&self) -> bool {
         self.use_internal
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        &self.hook_data
+    }
 }
 
 impl RawPoolOrder for ExactStandingOrder {
@@ -672,6 +828,14 @@ impl RawPoolOrder for ExactStandingOrder {
             .unwrap_or_default()
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        Signature::new_from_bytes(&self.meta.signature).is_ok_and(|sig| sig.is_low_s())
+    }
+
+    fn signature(&self) -> &[u8] {
+        &self.meta.signature
+    }
+
     fn flash_block(&self) -> Option<u64> {
         None
     }
@@ -681,12 +845,7 @@ impl RawPoolOrder for ExactStandingOrder {
     }
 
     fn amount_out_min(&self) -> u128 {
-        // TODO: verify math on this. feels wrong
-        if self.asset_in < self.asset_out {
-            self.amount * self.min_price.to::<u128>()
-        } else {
-            self.amount / self.min_price.to::<u128>()
-        }
+        min_amount_out(self.amount, self.min_price, self.asset_in, self.asset_out)
     }
 
     fn limit_price(&self) -> U256 {
@@ -724,6 +883,10 @@ impl RawPoolOrder for ExactStandingOrder {
     fn use_internal(&self) -> bool {
         self.use_internal
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        &self.hook_data
+    }
 }
 
 impl RawPoolOrder for PartialFlashOrder {
@@ -739,6 +902,14 @@ impl RawPoolOrder for PartialFlashOrder {
             .unwrap_or_default()
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        Signature::new_from_bytes(&self.meta.signature).is_ok_and(|sig| sig.is_low_s())
+    }
+
+    fn signature(&self) -> &[u8] {
+        &self.meta.signature
+    }
+
     fn flash_block(&self) -> Option<u64> {
         Some(self.valid_for_block)
     }
@@ -764,12 +935,7 @@ impl RawPoolOrder for PartialFlashOrder {
     }
 
     fn amount_out_min(&self) -> u128 {
-        // TODO: verify math on this. feels wrong
-        if self.asset_in < self.asset_out {
-            self.min_amount_in * self.min_price.to::<u128>()
-        } else {
-            self.min_amount_in / self.min_price.to::<u128>()
-        }
+        min_amount_out(self.min_amount_in, self.min_price, self.asset_in, self.asset_out)
     }
 
     fn respend_avoidance_strategy(&self) -> RespendAvoidanceMethod {
@@ -791,6 +957,10 @@ impl RawPoolOrder for PartialFlashOrder {
     fn use_internal(&self) -> bool {
         self.use_internal
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        &self.hook_data
+    }
 }
 
 impl RawPoolOrder for ExactFlashOrder {
@@ -806,6 +976,14 @@ impl RawPoolOrder for ExactFlashOrder {
             .unwrap_or_default()
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        Signature::new_from_bytes(&self.meta.signature).is_ok_and(|sig| sig.is_low_s())
+    }
+
+    fn signature(&self) -> &[u8] {
+        &self.meta.signature
+    }
+
     fn flash_block(&self) -> Option<u64> {
         Some(self.valid_for_block)
     }
@@ -839,12 +1017,7 @@ impl RawPoolOrder for ExactFlashOrder {
     }
 
     fn amount_out_min(&self) -> u128 {
-        // TODO: verify math on this. feels wrong
-        if self.asset_in < self.asset_out {
-            self.amount * self.min_price.to::<u128>()
-        } else {
-            self.amount / self.min_price.to::<u128>()
-        }
+        min_amount_out(self.amount, self.min_price, self.asset_in, self.asset_out)
     }
 
     fn respend_avoidance_strategy(&self) -> RespendAvoidanceMethod {
@@ -858,6 +1031,10 @@ impl RawPoolOrder for ExactFlashOrder {
     fn use_internal(&self) -> bool {
         self.use_internal
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        &self.hook_data
+    }
 }
 
 impl RawPoolOrder for AllOrders {
@@ -877,6 +1054,22 @@ impl RawPoolOrder for AllOrders {
         }
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        match self {
+            AllOrders::Standing(p) => p.has_canonical_signature(),
+            AllOrders::Flash(kof) => kof.has_canonical_signature(),
+            AllOrders::TOB(tob) => tob.has_canonical_signature()
+        }
+    }
+
+    fn signature(&self) -> &[u8] {
+        match self {
+            AllOrders::Standing(p) => p.signature(),
+            AllOrders::Flash(kof) => kof.signature(),
+            AllOrders::TOB(tob) => tob.signature()
+        }
+    }
+
     fn from(&self) -> Address {
         match self {
             AllOrders::Standing(p) => p.from(),
@@ -972,6 +1165,14 @@ impl RawPoolOrder for AllOrders {
             AllOrders::TOB(tob) => tob.use_internal()
         }
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        match self {
+            AllOrders::Standing(p) => p.hook_calldata(),
+            AllOrders::Flash(kof) => kof.hook_calldata(),
+            AllOrders::TOB(tob) => tob.hook_calldata()
+        }
+    }
 }
 
 impl RawPoolOrder for GroupedVanillaOrder {
@@ -989,6 +1190,20 @@ impl RawPoolOrder for GroupedVanillaOrder {
         }
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        match self {
+            GroupedVanillaOrder::Standing(p) => p.has_canonical_signature(),
+            GroupedVanillaOrder::KillOrFill(kof) => kof.has_canonical_signature()
+        }
+    }
+
+    fn signature(&self) -> &[u8] {
+        match self {
+            GroupedVanillaOrder::Standing(p) => p.signature(),
+            GroupedVanillaOrder::KillOrFill(kof) => kof.signature()
+        }
+    }
+
     fn respend_avoidance_strategy(&self) -> RespendAvoidanceMethod {
         match self {
             GroupedVanillaOrder::Standing(p) => p.respend_avoidance_strategy(),
@@ -1072,6 +1287,13 @@ impl RawPoolOrder for GroupedVanillaOrder {
             GroupedVanillaOrder::KillOrFill(kof) => kof.use_internal()
         }
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        match self {
+            GroupedVanillaOrder::Standing(p) => p.hook_calldata(),
+            GroupedVanillaOrder::KillOrFill(kof) => kof.hook_calldata()
+        }
+    }
 }
 
 impl RawPoolOrder for GroupedComposableOrder {
@@ -1159,6 +1381,20 @@ impl RawPoolOrder for GroupedComposableOrder {
         }
     }
 
+    fn has_canonical_signature(&self) -> bool {
+        match self {
+            GroupedComposableOrder::Partial(p) => p.has_canonical_signature(),
+            GroupedComposableOrder::KillOrFill(kof) => kof.has_canonical_signature()
+        }
+    }
+
+    fn signature(&self) -> &[u8] {
+        match self {
+            GroupedComposableOrder::Partial(p) => p.signature(),
+            GroupedComposableOrder::KillOrFill(kof) => kof.signature()
+        }
+    }
+
     fn order_location(&self) -> OrderLocation {
         match &self {
             GroupedComposableOrder::Partial(_) => OrderLocation::Limit,
@@ -1172,4 +1408,53 @@ impl RawPoolOrder for GroupedComposableOrder {
             GroupedComposableOrder::KillOrFill(kof) => kof.use_internal()
         }
     }
+
+    fn hook_calldata(&self) -> &[u8] {
+        match self {
+            GroupedComposableOrder::Partial(p) => p.hook_calldata(),
+            GroupedComposableOrder::KillOrFill(kof) => kof.hook_calldata()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_amount_follows_recorded_fills() {
+        let order = PartialStandingOrder { max_amount_in: 1_000, ..Default::default() };
+        let mut order = OrderWithStorageData {
+            order: GroupedVanillaOrder::Standing(StandingVariants::Partial(order)),
+            ..Default::default()
+        };
+        assert_eq!(order.remaining_amount(), U256::from(1_000));
+
+        order.record_fill(400);
+        assert_eq!(order.remaining_amount(), U256::from(600));
+        assert!(!order.is_fully_filled());
+
+        order.record_fill(700);
+        assert_eq!(order.remaining_amount(), U256::ZERO);
+        assert!(order.is_fully_filled());
+    }
+
+    #[test]
+    fn tob_rank_breaks_ties_by_lowest_hash() {
+        let tob = |reward: u64, hash: u8| OrderWithStorageData {
+            order: TopOfBlockOrder::default(),
+            order_id: OrderId { hash: B256::repeat_byte(hash), ..Default::default() },
+            tob_reward: U256::from(reward),
+            ..Default::default()
+        };
+        let (low, high, tied) = (tob(10, 1), tob(20, 3), tob(20, 2));
+
+        assert!(TobRank(&high) > TobRank(&low));
+        assert!(TobRank(&tied) > TobRank(&high));
+
+        let winner = [&low, &high, &tied]
+            .into_iter()
+            .max_by(|a, b| TobRank(a).cmp(&TobRank(b)));
+        assert_eq!(winner.map(|order| order.order_id.hash), Some(tied.order_id.hash));
+    }
 }
@@ -42,10 +42,65 @@ pub trait RawPoolOrder: fmt::Debug + Send + Sync + Clone + Unpin + 'static {
 
     fn is_valid_signature(&self) -> bool;
 
+    /// whether the signature's s is in the lower half of the curve order
+    /// (EIP-2). Its high-s counterpart recovers the same signer, so every
+    /// order has a second valid signature. Only the low-s one is canonical
+    fn has_canonical_signature(&self) -> bool;
+
+    /// the raw signature bytes, not covered by the order hash
+    fn signature(&self) -> &[u8];
+
     fn order_location(&self) -> OrderLocation;
 
     /// whether to use angstrom balances or not
     fn use_internal(&self) -> bool;
+
+    /// raw hook data of the order, empty if it has no hook
+    fn hook_calldata(&self) -> &[u8];
+
+    /// the contract the order's hook calls, encoded as the first 20 bytes of
+    /// the hook data. `None` if there is no hook or it is too short to hold
+    /// an address
+    fn hook_target(&self) -> Option<Address> {
+        self.hook_calldata().get(..20).map(Address::from_slice)
+    }
+
+    /// Checks the invariants that only depend on the order itself. Validators
+    /// run this before looking at any state
+    fn validate_static(&self) -> Result<(), StaticOrderError> {
+        if self.amount_in() == 0 {
+            return Err(StaticOrderError::ZeroAmountIn)
+        }
+
+        // amounts are unsigned, so an order that sells something has to ask for
+        // something back either as a min amount out or through its price
+        if self.amount_out_min() == 0 && self.limit_price().is_zero() {
+            return Err(StaticOrderError::ZeroAmountOut)
+        }
+
+        if self.token_in() == self.token_out() {
+            return Err(StaticOrderError::SameToken(self.token_in()))
+        }
+
+        if self.deadline().is_none() && self.flash_block().is_none() {
+            return Err(StaticOrderError::MissingExpiry)
+        }
+
+        Ok(())
+    }
+}
+
+/// An order that is malformed regardless of chain state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum StaticOrderError {
+    #[error("order has no amount in")]
+    ZeroAmountIn,
+    #[error("order asks for nothing in return")]
+    ZeroAmountOut,
+    #[error("order trades {0:?} for itself")]
+    SameToken(Address),
+    #[error("order has neither a deadline nor a flash block")]
+    MissingExpiry
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash, Copy)]
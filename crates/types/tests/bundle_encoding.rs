@@ -0,0 +1,240 @@
+//! Golden encoding vectors for [`AngstromBundle`].
+//!
+//! Every fixture below is a fully deterministic bundle whose pade encoding is
+//! committed under `tests/vectors`. Any change to the encoding of a bundle
+//! will show up here before it shows up as a revert on chain.
+//!
+//! A missing vector fails the test like a changed one. New fixtures and
+//! intentional layout changes (made together with the contract) are written
+//! out with `BLESS_VECTORS=1 cargo test -p angstrom-types --test
+//! bundle_encoding`, and the files committed.
+use std::path::PathBuf;
+
+use alloy::{
+    hex,
+    primitives::{address, aliases::I24, Address, Bytes, FixedBytes, U256}
+};
+use angstrom_types::contract_payloads::{
+    angstrom::{AngstromBundle, OrderQuantities, StandingValidation, TopOfBlockOrder, UserOrder},
+    rewards::{PoolUpdate, RewardsUpdate},
+    Asset, Pair, Signature
+};
+use pade::{PadeDecode, PadeEncode};
+
+const TOKEN_0: Address = address!("1111111111111111111111111111111111111111");
+const TOKEN_1: Address = address!("2222222222222222222222222222222222222222");
+const TOKEN_2: Address = address!("3333333333333333333333333333333333333333");
+const RECIPIENT: Address = address!("4444444444444444444444444444444444444444");
+const HOOK: Address = address!("5555555555555555555555555555555555555555");
+
+fn vector_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("vectors")
+        .join(format!("{name}.hex"))
+}
+
+fn assets() -> Vec<Asset> {
+    vec![
+        Asset { addr: TOKEN_0, borrow: 0, save: 1_000, settle: 25_000 },
+        Asset { addr: TOKEN_1, borrow: 500, save: 0, settle: 40_000 },
+        Asset { addr: TOKEN_2, borrow: 0, save: 0, settle: 0 },
+    ]
+}
+
+fn pairs() -> Vec<Pair> {
+    vec![
+        Pair {
+            index0:       0,
+            index1:       1,
+            store_index:  0,
+            price_1over0: U256::from(10).pow(U256::from(27))
+        },
+        Pair {
+            index0:       1,
+            index1:       2,
+            store_index:  1,
+            price_1over0: U256::from(3) * U256::from(10).pow(U256::from(26))
+        },
+    ]
+}
+
+fn ecdsa_signature(seed: u8) -> Signature {
+    Signature::Ecdsa {
+        v: 27 + (seed % 2),
+        r: FixedBytes::repeat_byte(seed),
+        s: FixedBytes::repeat_byte(seed.wrapping_add(0x40))
+    }
+}
+
+fn raw_signature(seed: u8) -> Bytes {
+    ecdsa_signature(seed).pade_encode().into()
+}
+
+fn pool_updates() -> Vec<PoolUpdate> {
+    vec![
+        PoolUpdate {
+            zero_for_one:     true,
+            pair_index:       0,
+            swap_in_quantity: 12_345,
+            rewards_update:   RewardsUpdate::CurrentOnly { amount: 100 }
+        },
+        PoolUpdate {
+            zero_for_one:     false,
+            pair_index:       1,
+            swap_in_quantity: 0,
+            rewards_update:   RewardsUpdate::MultiTick {
+                start_tick:      I24::unchecked_from(-600),
+                start_liquidity: 5_000_000_000,
+                quantities:      vec![10, 20, 30]
+            }
+        },
+    ]
+}
+
+fn searcher_order(seed: u8, zero_for_1: bool) -> TopOfBlockOrder {
+    TopOfBlockOrder {
+        use_internal: seed % 2 == 0,
+        quantity_in: 1_000_000 * seed as u128,
+        quantity_out: 990_000 * seed as u128,
+        max_gas_asset_0: 50_000,
+        gas_used_asset_0: 42_000,
+        pairs_index: 0,
+        zero_for_1,
+        recipient: (seed % 2 == 1).then_some(RECIPIENT),
+        signature: ecdsa_signature(seed)
+    }
+}
+
+fn exact_flash_order(ref_id: u32) -> UserOrder {
+    UserOrder {
+        ref_id,
+        use_internal: false,
+        pair_index: 0,
+        min_price: U256::from(10).pow(U256::from(27)),
+        recipient: None,
+        hook_data: None,
+        zero_for_one: true,
+        standing_validation: None,
+        order_quantities: OrderQuantities::Exact { quantity: 7_500 },
+        max_extra_fee_asset0: 300,
+        extra_fee_asset0: 120,
+        exact_in: true,
+        signature: raw_signature(ref_id as u8)
+    }
+}
+
+fn partial_standing_order(ref_id: u32) -> UserOrder {
+    UserOrder {
+        ref_id,
+        use_internal: true,
+        pair_index: 1,
+        min_price: U256::from(3) * U256::from(10).pow(U256::from(26)),
+        recipient: Some(RECIPIENT),
+        hook_data: None,
+        zero_for_one: false,
+        standing_validation: Some(StandingValidation::new(7, 1_700_000_000)),
+        order_quantities: OrderQuantities::Partial {
+            min_quantity_in: 100,
+            max_quantity_in: 10_000,
+            filled_quantity: 4_200
+        },
+        max_extra_fee_asset0: 250,
+        extra_fee_asset0: 90,
+        exact_in: false,
+        signature: raw_signature(ref_id as u8)
+    }
+}
+
+/// composable orders carry the hook address followed by the hook payload
+fn composable_order(ref_id: u32) -> UserOrder {
+    let hook_data = [HOOK.as_slice(), &[0xde, 0xad, 0xbe, 0xef]].concat();
+
+    UserOrder {
+        hook_data: Some(hook_data.into()),
+        standing_validation: Some(StandingValidation::new(1, 1_800_000_000)),
+        ..exact_flash_order(ref_id)
+    }
+}
+
+fn fixtures() -> Vec<(&'static str, AngstromBundle)> {
+    vec![
+        ("empty", AngstromBundle::new(vec![], vec![], vec![], vec![], vec![])),
+        (
+            "single_limit",
+            AngstromBundle::new(assets(), pairs(), vec![], vec![], vec![exact_flash_order(1)])
+        ),
+        (
+            "multi_limit",
+            AngstromBundle::new(
+                assets(),
+                pairs(),
+                pool_updates(),
+                vec![],
+                vec![exact_flash_order(1), partial_standing_order(2), exact_flash_order(3)]
+            )
+        ),
+        (
+            "searcher_only",
+            AngstromBundle::new(
+                assets(),
+                pairs(),
+                pool_updates(),
+                vec![searcher_order(1, true), searcher_order(2, false)],
+                vec![]
+            )
+        ),
+        (
+            "composable_with_hooks",
+            AngstromBundle::new(
+                assets(),
+                pairs(),
+                vec![],
+                vec![],
+                vec![composable_order(1), composable_order(2)]
+            )
+        ),
+        (
+            "mixed",
+            AngstromBundle::new(
+                assets(),
+                pairs(),
+                pool_updates(),
+                vec![searcher_order(3, true)],
+                vec![exact_flash_order(1), partial_standing_order(2), composable_order(3)]
+            )
+        ),
+    ]
+}
+
+#[test]
+fn bundle_encoding_matches_vectors() {
+    let bless = std::env::var_os("BLESS_VECTORS").is_some();
+
+    for (name, bundle) in fixtures() {
+        let encoded = hex::encode(bundle.pade_encode());
+        let path = vector_path(name);
+
+        if bless {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(&path, format!("{encoded}\n")).unwrap();
+            continue
+        }
+
+        let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+            panic!("no vector for `{name}` bundle, write it with BLESS_VECTORS=1")
+        });
+        assert_eq!(encoded, expected.trim(), "encoding of `{name}` bundle changed");
+    }
+}
+
+#[test]
+fn bundle_vectors_round_trip() {
+    for (name, bundle) in fixtures() {
+        let encoded = bundle.pade_encode();
+        let decoded = AngstromBundle::pade_decode(&mut encoded.as_slice(), None)
+            .unwrap_or_else(|_| panic!("failed to decode `{name}` bundle"));
+
+        assert_eq!(decoded.pade_encode(), encoded, "`{name}` bundle didn't round trip");
+    }
+}
@@ -0,0 +1,248 @@
+//! Golden encoding vectors for the [`OrderSet`] and [`PoolSolution`] consensus
+//! wire format.
+//!
+//! Proposals are hashed over the bincode encoding of these types, so any
+//! change to their layout means nodes on different versions stop agreeing on
+//! proposal hashes. The vectors live under `tests/vectors` next to the bundle
+//! ones. A missing vector fails like a changed one, they're written out with
+//! `BLESS_VECTORS=1 cargo test -p angstrom-types --test order_set_encoding`.
+use std::path::PathBuf;
+
+use alloy::{
+    hex,
+    primitives::{address, aliases::U40, Address, Bytes, FixedBytes, U256}
+};
+use angstrom_types::{
+    matching::Ray,
+    orders::{
+        NetAmmOrder, OrderFillState, OrderId, OrderLocation, OrderOutcome, OrderPriorityData,
+        OrderSet, PoolSolution
+    },
+    primitive::PoolId,
+    sol_bindings::{
+        ext::RespendAvoidanceMethod,
+        grouped_orders::{AllOrders, FlashVariants, OrderWithStorageData, StandingVariants},
+        rpc_orders::{
+            ExactFlashOrder, ExactStandingOrder, OrderMeta, PartialFlashOrder,
+            PartialStandingOrder, TopOfBlockOrder
+        }
+    }
+};
+
+type ConsensusOrderSet = OrderSet<AllOrders, TopOfBlockOrder>;
+
+const TOKEN_0: Address = address!("1111111111111111111111111111111111111111");
+const TOKEN_1: Address = address!("2222222222222222222222222222222222222222");
+const USER: Address = address!("3333333333333333333333333333333333333333");
+const RECIPIENT: Address = address!("4444444444444444444444444444444444444444");
+
+fn vector_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("vectors")
+        .join(format!("{name}.hex"))
+}
+
+fn pool_id() -> PoolId {
+    FixedBytes::repeat_byte(0xaa)
+}
+
+fn meta(seed: u8) -> OrderMeta {
+    OrderMeta { isEcdsa: true, from: USER, signature: Bytes::from(vec![seed; 65]) }
+}
+
+fn with_storage<O>(
+    order: O,
+    seed: u8,
+    is_bid: bool,
+    location: OrderLocation
+) -> OrderWithStorageData<O> {
+    OrderWithStorageData {
+        order,
+        priority_data: OrderPriorityData {
+            price:     U256::from(10).pow(U256::from(27)),
+            volume:    1_000 * seed as u128,
+            gas:       U256::from(21_000),
+            gas_units: 50_000
+        },
+        invalidates: vec![FixedBytes::repeat_byte(seed.wrapping_add(0x80))],
+        pool_id: pool_id(),
+        is_currently_valid: true,
+        is_bid,
+        is_valid: true,
+        valid_block: 100,
+        order_id: OrderId {
+            address: USER,
+            pool_id: pool_id(),
+            hash: FixedBytes::repeat_byte(seed),
+            reuse_avoidance: RespendAvoidanceMethod::Nonce(seed as u64),
+            deadline: Some(U256::from(1_800_000_000u64)),
+            flash_block: Some(100),
+            location
+        },
+        tob_reward: U256::from(seed),
+        filled_amount: 0
+    }
+}
+
+/// one order of every limit variant, with every optional field populated
+fn limit_orders() -> Vec<OrderWithStorageData<AllOrders>> {
+    let standing_exact = ExactStandingOrder {
+        exact_in: true,
+        amount: 1_000,
+        max_extra_fee_asset0: 10,
+        min_price: U256::from(10).pow(U256::from(27)),
+        asset_in: TOKEN_0,
+        asset_out: TOKEN_1,
+        recipient: RECIPIENT,
+        hook_data: Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]),
+        nonce: 1,
+        deadline: U40::from(1_800_000_000u64),
+        meta: meta(1),
+        ..Default::default()
+    };
+    let standing_partial = PartialStandingOrder {
+        min_amount_in: 100,
+        max_amount_in: 2_000,
+        max_extra_fee_asset0: 20,
+        min_price: U256::from(10).pow(U256::from(27)),
+        use_internal: true,
+        asset_in: TOKEN_1,
+        asset_out: TOKEN_0,
+        recipient: RECIPIENT,
+        nonce: 2,
+        deadline: U40::from(1_800_000_000u64),
+        meta: meta(2),
+        ..Default::default()
+    };
+    let flash_exact = ExactFlashOrder {
+        amount: 3_000,
+        min_price: U256::from(10).pow(U256::from(27)),
+        asset_in: TOKEN_0,
+        asset_out: TOKEN_1,
+        valid_for_block: 100,
+        meta: meta(3),
+        ..Default::default()
+    };
+    let flash_partial = PartialFlashOrder {
+        min_amount_in: 10,
+        max_amount_in: 4_000,
+        min_price: U256::from(10).pow(U256::from(27)),
+        asset_in: TOKEN_1,
+        asset_out: TOKEN_0,
+        valid_for_block: 100,
+        meta: meta(4),
+        ..Default::default()
+    };
+
+    vec![
+        (AllOrders::Standing(StandingVariants::Exact(standing_exact)), 1, true),
+        (AllOrders::Standing(StandingVariants::Partial(standing_partial)), 2, false),
+        (AllOrders::Flash(FlashVariants::Exact(flash_exact)), 3, true),
+        (AllOrders::Flash(FlashVariants::Partial(flash_partial)), 4, false),
+    ]
+    .into_iter()
+    .map(|(order, seed, is_bid)| with_storage(order, seed, is_bid, OrderLocation::Limit))
+    .collect()
+}
+
+fn searcher_order(seed: u8) -> OrderWithStorageData<TopOfBlockOrder> {
+    let order = TopOfBlockOrder {
+        quantity_in:     5_000 * seed as u128,
+        quantity_out:    4_900 * seed as u128,
+        max_gas_asset0:  500,
+        use_internal:    seed % 2 == 0,
+        asset_in:        TOKEN_0,
+        asset_out:       TOKEN_1,
+        recipient:       RECIPIENT,
+        valid_for_block: 100,
+        meta:            meta(seed)
+    };
+
+    with_storage(order, seed, true, OrderLocation::Searcher)
+}
+
+fn order_set_fixtures() -> Vec<(&'static str, ConsensusOrderSet)> {
+    vec![
+        ("order_set_empty", OrderSet { limit: vec![], searcher: vec![] }),
+        (
+            "order_set_full",
+            OrderSet {
+                limit:    limit_orders(),
+                searcher: vec![searcher_order(5), searcher_order(6)]
+            }
+        ),
+    ]
+}
+
+fn pool_solution_fixtures() -> Vec<(&'static str, PoolSolution)> {
+    vec![
+        ("pool_solution_empty", PoolSolution::default()),
+        (
+            "pool_solution_full",
+            PoolSolution {
+                id:           pool_id(),
+                ucp:          Ray::from(U256::from(10).pow(U256::from(27))),
+                searcher:     Some(searcher_order(5)),
+                amm_quantity: Some(NetAmmOrder::Sell(U256::from(1_000), U256::from(990))),
+                limit:        [
+                    OrderFillState::Unfilled,
+                    OrderFillState::CompleteFill,
+                    OrderFillState::PartialFill(U256::from(500)),
+                    OrderFillState::Killed
+                ]
+                .into_iter()
+                .zip(limit_orders())
+                .map(|(outcome, order)| OrderOutcome { id: order.order_id, outcome })
+                .collect()
+            }
+        ),
+    ]
+}
+
+fn check_vector(name: &str, encoded: Vec<u8>) {
+    let encoded = hex::encode(encoded);
+    let path = vector_path(name);
+
+    if std::env::var_os("BLESS_VECTORS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, format!("{encoded}\n")).unwrap();
+        return
+    }
+
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("no vector for `{name}`, write it with BLESS_VECTORS=1"));
+    assert_eq!(encoded, expected.trim(), "encoding of `{name}` changed");
+}
+
+#[test]
+fn order_set_encoding_matches_vectors() {
+    for (name, set) in order_set_fixtures() {
+        check_vector(name, set.encode());
+    }
+}
+
+#[test]
+fn pool_solution_encoding_matches_vectors() {
+    for (name, solution) in pool_solution_fixtures() {
+        check_vector(name, solution.encode());
+    }
+}
+
+#[test]
+fn order_sets_round_trip() {
+    for (name, set) in order_set_fixtures() {
+        let decoded = ConsensusOrderSet::decode(&set.encode())
+            .unwrap_or_else(|_| panic!("failed to decode `{name}`"));
+        assert_eq!(decoded, set, "`{name}` didn't round trip");
+    }
+}
+
+#[test]
+fn pool_solutions_round_trip() {
+    for (name, solution) in pool_solution_fixtures() {
+        let decoded = PoolSolution::decode(&solution.encode())
+            .unwrap_or_else(|_| panic!("failed to decode `{name}`"));
+        assert_eq!(decoded, solution, "`{name}` didn't round trip");
+    }
+}
@@ -0,0 +1,99 @@
+//! Pool id vectors. The node has to derive the same id for a pool as the
+//! contracts do, otherwise orders are routed to a pool the contract doesn't
+//! know and get dropped.
+//!
+//! The mainnet vector is the id of a live Uniswap v4 pool, the others are
+//! `keccak256(abi.encode(key))` computed outside of this codebase.
+use alloy::primitives::{
+    address,
+    aliases::{I24, U24},
+    b256, Address, B256
+};
+use angstrom_types::{
+    contract_bindings::angstrom::Angstrom::PoolKey,
+    primitive::{derive_pool_id, PoolId}
+};
+
+const TOKEN_0: Address = address!("1111111111111111111111111111111111111111");
+const TOKEN_1: Address = address!("2222222222222222222222222222222222222222");
+const HOOK: Address = address!("5555555555555555555555555555555555555555");
+
+/// the dynamic fee flag of uniswap v4
+const DYNAMIC_FEE: u32 = 0x800000;
+
+struct Vector {
+    token0:       Address,
+    token1:       Address,
+    fee:          u32,
+    tick_spacing: i32,
+    hook:         Address,
+    pool_id:      B256
+}
+
+fn vectors() -> Vec<Vector> {
+    vec![
+        // ETH/USDC 0.05% on mainnet
+        Vector {
+            token0:       Address::ZERO,
+            token1:       address!("A0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"),
+            fee:          500,
+            tick_spacing: 10,
+            hook:         Address::ZERO,
+            pool_id:      b256!("21c67e77068de97969ba93d4aab21826d33ca12bb9f565d8496e8fda8a82ca27")
+        },
+        Vector {
+            token0:       TOKEN_0,
+            token1:       TOKEN_1,
+            fee:          3000,
+            tick_spacing: 60,
+            hook:         HOOK,
+            pool_id:      b256!("b7f623ad7cfef173218f7c13cdb90f4febbc08447b82eb111360439d2076e722")
+        },
+        Vector {
+            token0:       TOKEN_0,
+            token1:       TOKEN_1,
+            fee:          DYNAMIC_FEE,
+            tick_spacing: 1,
+            hook:         HOOK,
+            pool_id:      b256!("5d754dd268070e7ea57a579b74be675059ac66ad04c345fc186fc9f9b6e2057d")
+        },
+        Vector {
+            token0:       TOKEN_0,
+            token1:       TOKEN_1,
+            fee:          100,
+            tick_spacing: 1,
+            hook:         Address::ZERO,
+            pool_id:      b256!("a094ec379d42be65d4f90fb18e4bb9db9d5e3f0fa53b018f6d7abf5df9563776")
+        },
+    ]
+}
+
+#[test]
+fn derived_pool_ids_match_vectors() {
+    for v in vectors() {
+        let pool_id = derive_pool_id(v.token0, v.token1, v.fee, v.tick_spacing, v.hook);
+        assert_eq!(pool_id, v.pool_id, "pool {} / {} fee {}", v.token0, v.token1, v.fee);
+    }
+}
+
+#[test]
+fn derivation_ignores_token_order() {
+    for v in vectors() {
+        let pool_id = derive_pool_id(v.token1, v.token0, v.fee, v.tick_spacing, v.hook);
+        assert_eq!(pool_id, v.pool_id);
+    }
+}
+
+#[test]
+fn pool_key_conversion_matches_derivation() {
+    for v in vectors() {
+        let key = PoolKey {
+            currency0:   v.token0,
+            currency1:   v.token1,
+            fee:         U24::from(v.fee),
+            tickSpacing: I24::unchecked_from(v.tick_spacing),
+            hooks:       v.hook
+        };
+        assert_eq!(PoolId::from(key), v.pool_id);
+    }
+}
@@ -0,0 +1,189 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    hash::Hash,
+    sync::RwLock
+};
+
+use alloy::primitives::{BlockNumber, U256};
+
+const BPS: u64 = 10_000;
+/// fixed point precision used when comparing sqrt prices
+const PRECISION_BITS: usize = 64;
+
+/// Halts matching on pools whose price moved more than the allowed amount
+/// within a single block. Such a move is more likely manipulation or a bad sync
+/// than real price discovery, so we don't want to execute users into it.
+///
+/// A halted pool resumes on its own once it goes through a block where the
+/// price stays within bounds, or when an operator calls
+/// [`PoolCircuitBreaker::resume_pool`] through the admin rpc.
+#[derive(Debug)]
+pub struct PoolCircuitBreaker<A> {
+    /// max price move in basis points for pools without an override. `None`
+    /// disables the breaker for those pools.
+    default_max_move_bps: Option<u64>,
+    pool_max_move_bps:    HashMap<A, u64>,
+    halted:               RwLock<HashSet<A>>
+}
+
+impl<A> Default for PoolCircuitBreaker<A> {
+    fn default() -> Self {
+        Self {
+            default_max_move_bps: None,
+            pool_max_move_bps:    HashMap::new(),
+            halted:               RwLock::new(HashSet::new())
+        }
+    }
+}
+
+impl<A> PoolCircuitBreaker<A>
+where
+    A: Eq + Hash + Debug + Copy
+{
+    pub fn new(default_max_move_bps: Option<u64>) -> Self {
+        Self { default_max_move_bps, ..Default::default() }
+    }
+
+    pub fn with_pool_max_move_bps(mut self, pool: A, max_move_bps: u64) -> Self {
+        self.pool_max_move_bps.insert(pool, max_move_bps);
+        self
+    }
+
+    pub fn pool_is_halted(&self, pool: &A) -> bool {
+        self.halted.read().unwrap().contains(pool)
+    }
+
+    pub fn halted_pools(&self) -> HashSet<A> {
+        self.halted.read().unwrap().clone()
+    }
+
+    /// lifts the halt on the pool, returns false if it wasn't halted.
+    pub fn resume_pool(&self, pool: &A) -> bool {
+        let resumed = self.halted.write().unwrap().remove(pool);
+        if resumed {
+            tracing::info!(?pool, "pool resumed by operator");
+        }
+
+        resumed
+    }
+
+    /// Called once per block with the sqrt price before and after the block for
+    /// every pool that changed in it. Pools not in `price_moves` didn't move
+    /// and so count as stable.
+    pub fn on_new_block(&self, block: BlockNumber, price_moves: &HashMap<A, (U256, U256)>) {
+        let mut halted = self.halted.write().unwrap();
+
+        halted.retain(|pool| {
+            let is_stable = !price_moves.contains_key(pool);
+            if is_stable {
+                tracing::info!(?pool, block, "pool price stabilized, resuming matching");
+            }
+            !is_stable
+        });
+
+        for (pool, (before, after)) in price_moves {
+            let Some(max_move_bps) = self.max_move_bps(pool) else { continue };
+
+            let move_bps = price_move_bps(*before, *after);
+            if move_bps > max_move_bps {
+                if halted.insert(*pool) {
+                    tracing::warn!(
+                        ?pool,
+                        block,
+                        move_bps,
+                        max_move_bps,
+                        "abnormal price move, halting matching for pool"
+                    );
+                }
+            } else if halted.remove(pool) {
+                tracing::info!(?pool, block, "pool price stabilized, resuming matching");
+            }
+        }
+    }
+
+    fn max_move_bps(&self, pool: &A) -> Option<u64> {
+        self.pool_max_move_bps
+            .get(pool)
+            .copied()
+            .or(self.default_max_move_bps)
+    }
+}
+
+/// The price move between two sqrt prices, in basis points of the starting
+/// price. Saturates at `u64::MAX`.
+pub fn price_move_bps(sqrt_price_before: U256, sqrt_price_after: U256) -> u64 {
+    if sqrt_price_before.is_zero() {
+        return if sqrt_price_after.is_zero() { 0 } else { u64::MAX }
+    }
+
+    let one = U256::from(1) << PRECISION_BITS;
+    let Some(sqrt_ratio) = (sqrt_price_after << PRECISION_BITS).checked_div(sqrt_price_before)
+    else {
+        return u64::MAX
+    };
+    let Some(ratio) = sqrt_ratio
+        .checked_mul(sqrt_ratio)
+        .map(|r| r >> PRECISION_BITS)
+    else {
+        return u64::MAX
+    };
+
+    let delta = if ratio > one { ratio - one } else { one - ratio };
+    let bps = (delta * U256::from(BPS)) >> PRECISION_BITS;
+
+    bps.try_into().unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sqrt_price_at_tick(tick: i32) -> U256 {
+        uniswap_v3_math::tick_math::get_sqrt_ratio_at_tick(tick).unwrap()
+    }
+
+    #[test]
+    fn measures_price_moves() {
+        let start = sqrt_price_at_tick(0);
+        assert_eq!(price_move_bps(start, start), 0);
+
+        // each tick is a 1bp price move
+        let up = price_move_bps(start, sqrt_price_at_tick(100));
+        assert!((100..=101).contains(&up), "{up}");
+        let down = price_move_bps(start, sqrt_price_at_tick(-100));
+        assert!((98..=100).contains(&down), "{down}");
+    }
+
+    #[test]
+    fn halts_and_resumes_on_stable_block() {
+        let breaker = PoolCircuitBreaker::new(Some(500));
+        let pool = 1u8;
+        let start = sqrt_price_at_tick(0);
+
+        breaker.on_new_block(1, &HashMap::from([(pool, (start, sqrt_price_at_tick(100)))]));
+        assert!(!breaker.pool_is_halted(&pool));
+
+        breaker.on_new_block(2, &HashMap::from([(pool, (start, sqrt_price_at_tick(1_000)))]));
+        assert!(breaker.pool_is_halted(&pool));
+
+        // no move at all in the next block
+        breaker.on_new_block(3, &HashMap::new());
+        assert!(!breaker.pool_is_halted(&pool));
+    }
+
+    #[test]
+    fn uses_pool_overrides_and_operator_resume() {
+        let breaker = PoolCircuitBreaker::new(None).with_pool_max_move_bps(2u8, 50);
+        let start = sqrt_price_at_tick(0);
+        let moved = sqrt_price_at_tick(100);
+
+        breaker.on_new_block(1, &HashMap::from([(1u8, (start, moved)), (2u8, (start, moved))]));
+        assert!(!breaker.pool_is_halted(&1));
+        assert!(breaker.pool_is_halted(&2));
+
+        assert!(breaker.resume_pool(&2));
+        assert!(!breaker.pool_is_halted(&2));
+        assert!(!breaker.resume_pool(&2));
+    }
+}
@@ -84,24 +84,28 @@ impl PoolData {
     /// for internal math. this is different than just the raw conversion given
     /// that we don't do any decimal adjustments
     pub fn get_raw_price(&self) -> U256 {
-        let this = SqrtPriceX96::from(self.sqrtPrice);
-        let tick = this.to_tick().expect("should never fail");
-        // TODO: not a fan of this given precision will be lost. could cause problems
-        // down the road.
-        let normalized_price = 1.0001_f64.powi(tick);
-
-        // because this is user set values and we want to upkeep readability on the
-        // frontend, this value is not stored as a RAY and instead is a
-        // unadjusted t1/t0
-        let price = Rational::try_from(normalized_price).unwrap();
-        let (output, _): (Natural, _) =
-            price.rounding_into(malachite::rounding_modes::RoundingMode::Floor);
-        let limbs = output.to_limbs_asc();
-
-        U256::from_limbs_slice(&limbs)
+        raw_price(SqrtPriceX96::from(self.sqrtPrice))
     }
 }
 
+/// [`PoolData::get_raw_price`] of a pool's sqrt price
+pub fn raw_price(sqrt_price: SqrtPriceX96) -> U256 {
+    let tick = sqrt_price.to_tick().expect("should never fail");
+    // TODO: not a fan of this given precision will be lost. could cause problems
+    // down the road.
+    let normalized_price = 1.0001_f64.powi(tick);
+
+    // because this is user set values and we want to upkeep readability on the
+    // frontend, this value is not stored as a RAY and instead is a
+    // unadjusted t1/t0
+    let price = Rational::try_from(normalized_price).unwrap();
+    let (output, _): (Natural, _) =
+        price.rounding_into(malachite::rounding_modes::RoundingMode::Floor);
+    let limbs = output.to_limbs_asc();
+
+    U256::from_limbs_slice(&limbs)
+}
+
 sol! {
     type PoolId is bytes32;
 
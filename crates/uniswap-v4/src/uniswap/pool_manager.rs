@@ -3,7 +3,7 @@ use std::{
     fmt::Debug,
     hash::Hash,
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc, RwLock, RwLockReadGuard
     }
 };
@@ -27,6 +27,7 @@ use tokio::{
 
 use super::{pool::PoolError, pool_providers::PoolMangerBlocks};
 use crate::uniswap::{
+    circuit_breaker::PoolCircuitBreaker,
     pool::EnhancedUniswapPool,
     pool_data_loader::{DataLoader, PoolDataLoader},
     pool_providers::PoolManagerProvider
@@ -45,11 +46,14 @@ where
 {
     pools:               SyncedUniswapPools<A, Loader>,
     latest_synced_block: u64,
+    /// last block the pools were synced to, shared with whoever reports it
+    synced_block:        Arc<AtomicU64>,
     state_change_buffer: usize,
     state_change_cache:  Arc<RwLock<StateChangeCache<Loader, A>>>,
     provider:            Arc<P>,
     block_sync:          BlockSync,
-    sync_started:        AtomicBool
+    sync_started:        AtomicBool,
+    circuit_breaker:     Arc<PoolCircuitBreaker<A>>
 }
 
 impl<P, BlockSync, Loader, A> UniswapPoolManager<P, BlockSync, Loader, A>
@@ -75,14 +79,39 @@ where
         Self {
             pools: Arc::new(rwlock_pools),
             latest_synced_block,
+            synced_block: Arc::new(AtomicU64::new(latest_synced_block)),
             state_change_buffer,
             state_change_cache: Arc::new(RwLock::new(HashMap::new())),
             provider,
             sync_started: AtomicBool::new(false),
-            block_sync
+            block_sync,
+            circuit_breaker: Arc::new(PoolCircuitBreaker::default())
         }
     }
 
+    /// shares `circuit_breaker` with whoever resumes halted pools
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<PoolCircuitBreaker<A>>) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    pub fn circuit_breaker(&self) -> Arc<PoolCircuitBreaker<A>> {
+        self.circuit_breaker.clone()
+    }
+
+    /// Publishes the block the pools are synced to through `synced_block`
+    /// instead of a fresh counter.
+    pub fn with_synced_block(mut self, synced_block: Arc<AtomicU64>) -> Self {
+        synced_block.store(self.latest_synced_block, Ordering::SeqCst);
+        self.synced_block = synced_block;
+        self
+    }
+
+    /// The block the pools are currently synced to.
+    pub fn synced_block(&self) -> Arc<AtomicU64> {
+        self.synced_block.clone()
+    }
+
     pub fn fetch_pool_snapshots(&self) -> HashMap<A, PoolSnapshot> {
         self.pools
             .iter()
@@ -164,6 +193,8 @@ where
         let filter = self.filter();
         let state_change_cache = Arc::clone(&self.state_change_cache);
         let block_sync = self.block_sync.clone();
+        let circuit_breaker = Arc::clone(&self.circuit_breaker);
+        let synced_block = Arc::clone(&self.synced_block);
 
         let updated_pool_handle = tokio::spawn(async move {
             let mut block_stream: BoxStream<Option<_>> = provider.subscribe_blocks();
@@ -207,6 +238,7 @@ where
                 }
 
                 let logs_by_address = Loader::group_logs(logs);
+                let mut price_moves = HashMap::new();
 
                 for (addr, logs) in logs_by_address {
                     if logs.is_empty() {
@@ -221,12 +253,14 @@ where
                     let address = {
                         let mut pool_guard = pool.write().unwrap();
                         let mut state_change_cache = state_change_cache.write().unwrap();
+                        let price_before = pool_guard.sqrt_price;
                         Self::handle_state_changes_from_logs(
                             &mut pool_guard,
                             &mut state_change_cache,
                             logs,
                             chain_head_block_number
                         )?;
+                        price_moves.insert(addr, (price_before, pool_guard.sqrt_price));
                         pool_guard.address()
                     };
 
@@ -238,7 +272,9 @@ where
                     }
                 }
 
+                circuit_breaker.on_new_block(chain_head_block_number, &price_moves);
                 last_synced_block = chain_head_block_number;
+                synced_block.store(last_synced_block, Ordering::SeqCst);
 
                 if is_reorg {
                     block_sync.sign_off_reorg(MODULE_NAME, block_range.unwrap(), None);
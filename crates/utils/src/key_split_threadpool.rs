@@ -1,24 +1,56 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     future::Future,
     hash::Hash,
     pin::Pin,
-    sync::Arc,
-    task::{Poll, Waker}
+    task::{Poll, Waker},
+    time::Duration
 };
 
-use futures::{stream::FuturesUnordered, Stream, StreamExt};
-use tokio::sync::Semaphore;
+use futures::{
+    future::{AbortHandle, Abortable, Aborted},
+    stream::FuturesUnordered,
+    Stream, StreamExt
+};
+
+use crate::sync_pipeline::ThreadPool;
 
-use crate::{sync_pipeline::ThreadPool, PollExt};
+/// The pool was closed through [`KeySplitThreadpool::drain`] or
+/// [`KeySplitThreadpool::shutdown_now`], the task was dropped without being
+/// run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("the thread pool is closed and no longer takes tasks")]
+pub struct PoolClosed;
 
-type PendingFut<F> = Pin<Box<dyn Future<Output = <F as Future>::Output> + Send>>;
+type PendingFut<K, F> =
+    Pin<Box<dyn Future<Output = (u64, Option<K>, Result<<F as Future>::Output, Aborted>)> + Send>>;
 
+/// Runs tasks on the thread pool with at most `permit_size` of them in flight
+/// per key.
+///
+/// Queued tasks are dispatched round-robin over the keys waiting for a slot,
+/// so a key that keeps submitting goes to the back of the line after every
+/// task it gets scheduled and can't starve the keys queued behind it. When
+/// `max_in_flight` is set it also bounds the tasks running over all keys.
+///
+/// Once closed, through [`Self::drain`] or [`Self::shutdown_now`], new tasks
+/// are dropped without being run and [`PoolClosed`] is returned.
 pub struct KeySplitThreadpool<K: PartialEq + Eq + Hash + Clone, F: Future, TP: ThreadPool> {
     tp:              TP,
-    pending_results: FuturesUnordered<PendingFut<F>>,
+    pending_results: FuturesUnordered<PendingFut<K, F>>,
     permit_size:     usize,
-    pending:         HashMap<K, Arc<Semaphore>>,
+    max_in_flight:   usize,
+    /// tasks waiting for a slot, per key
+    queued:          HashMap<K, VecDeque<F>>,
+    /// keys with queued tasks, in the order they get their next turn
+    rotation:        VecDeque<K>,
+    /// tasks of each key that are currently running
+    in_flight:       HashMap<K, usize>,
+    total_in_flight: usize,
+    /// cancels the running task with the id, both keyed and raw ones
+    abort_handles:   HashMap<u64, AbortHandle>,
+    next_task_id:    u64,
+    closed:          bool,
     waker:           Option<Waker>
 }
 
@@ -33,41 +65,119 @@ where
         Self {
             tp: theadpool,
             permit_size,
-            pending: HashMap::default(),
+            max_in_flight: usize::MAX,
+            queued: HashMap::default(),
+            rotation: VecDeque::default(),
+            in_flight: HashMap::default(),
+            total_in_flight: 0,
             pending_results: FuturesUnordered::default(),
+            abort_handles: HashMap::default(),
+            next_task_id: 0,
+            closed: false,
             waker: None
         }
     }
 
-    pub fn spawn_raw(&mut self, fut: F) {
-        let tp_cloned = self.tp.clone();
-        let fut = Box::pin(async move { tp_cloned.spawn(fut).await }) as PendingFut<F>;
+    /// bounds the keyed tasks running at once over all keys. Anything above
+    /// it waits in the round-robin queue.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.max_in_flight = max_in_flight.max(1);
+        self
+    }
 
-        self.pending_results.push(fut);
+    pub fn spawn_raw(&mut self, fut: F) -> Result<(), PoolClosed> {
+        if self.closed {
+            return Err(PoolClosed)
+        }
+        self.spawn(None, fut);
         // if a waker is scheduled. insure we pool
         self.waker.as_ref().inspect(|i| i.wake_by_ref());
-    }
-
-    pub fn add_new_task(&mut self, key: K, fut: F) {
-        // grab semaphore
-        let permit = self
-            .pending
-            .entry(key)
-            .or_insert_with(|| Arc::new(Semaphore::new(self.permit_size)));
-        let permit_cloned = permit.clone();
-        let tp_cloned = self.tp.clone();
 
-        let fut = Box::pin(async move {
-            let permit = permit_cloned.acquire().await.expect("never");
-            let res = tp_cloned.spawn(fut).await;
-            drop(permit);
+        Ok(())
+    }
 
-            res
-        }) as PendingFut<F>;
+    pub fn add_new_task(&mut self, key: K, fut: F) -> Result<(), PoolClosed> {
+        if self.closed {
+            return Err(PoolClosed)
+        }
+        let queue = self.queued.entry(key.clone()).or_default();
+        if queue.is_empty() {
+            self.rotation.push_back(key);
+        }
+        queue.push_back(fut);
 
-        self.pending_results.push(fut);
+        self.dispatch();
         // if a waker is scheduled. insure we pool
         self.waker.as_ref().inspect(|i| i.wake_by_ref());
+
+        Ok(())
+    }
+
+    /// tasks of the key that are waiting for a slot
+    pub fn queue_depth(&self, key: &K) -> usize {
+        self.queued.get(key).map(VecDeque::len).unwrap_or_default()
+    }
+
+    /// waiting tasks of every key that has any
+    pub fn queue_depths(&self) -> impl Iterator<Item = (&K, usize)> + '_ {
+        self.queued.iter().map(|(key, queue)| (key, queue.len()))
+    }
+
+    /// waiting tasks over all keys
+    pub fn total_queued(&self) -> usize {
+        self.queued.values().map(VecDeque::len).sum()
+    }
+
+    /// tasks of the key that are currently running
+    pub fn in_flight(&self, key: &K) -> usize {
+        self.in_flight.get(key).copied().unwrap_or_default()
+    }
+
+    /// tasks currently running over all keys
+    pub fn total_in_flight(&self) -> usize {
+        self.total_in_flight
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Stops accepting tasks and drops the queued ones that haven't started,
+    /// then waits up to `timeout` for the running ones to finish. Whatever is
+    /// still running after it is cancelled as with [`Self::shutdown_now`].
+    /// Returns whether every running task finished in time, their results are
+    /// discarded.
+    pub async fn drain(&mut self, timeout: Duration) -> bool {
+        self.close();
+
+        let finished =
+            tokio::time::timeout(timeout, async { while self.next().await.is_some() {} })
+                .await
+                .is_ok();
+        if !finished {
+            self.shutdown_now();
+        }
+
+        finished
+    }
+
+    /// Stops accepting tasks, drops the queued ones and cancels the running
+    /// ones. A running task stops the next time it yields.
+    pub fn shutdown_now(&mut self) {
+        self.close();
+
+        self.abort_handles
+            .drain()
+            .for_each(|(_, handle)| handle.abort());
+        self.pending_results = FuturesUnordered::default();
+        self.in_flight.clear();
+        self.total_in_flight = 0;
+    }
+
+    fn close(&mut self) {
+        self.closed = true;
+        self.queued.clear();
+        self.rotation.clear();
     }
 
     /// registers waker if its doesn't exist
@@ -76,6 +186,65 @@ where
             self.waker = Some(f());
         }
     }
+
+    /// hands out free slots one task at a time, moving each key that got one
+    /// to the back of the rotation. Stops once every waiting key is at its
+    /// limit or the global limit is hit.
+    fn dispatch(&mut self) {
+        let mut skipped = 0;
+        while self.total_in_flight < self.max_in_flight && skipped < self.rotation.len() {
+            let key = self.rotation.pop_front().expect("checked len");
+            if self.in_flight(&key) >= self.permit_size {
+                self.rotation.push_back(key);
+                skipped += 1;
+                continue
+            }
+            skipped = 0;
+
+            let queue = self
+                .queued
+                .get_mut(&key)
+                .expect("rotation keys have queued tasks");
+            let fut = queue.pop_front().expect("rotation keys have queued tasks");
+            if queue.is_empty() {
+                self.queued.remove(&key);
+            } else {
+                self.rotation.push_back(key.clone());
+            }
+
+            *self.in_flight.entry(key.clone()).or_default() += 1;
+            self.total_in_flight += 1;
+
+            self.spawn(Some(key), fut);
+        }
+    }
+
+    /// runs the task on the thread pool, cancellable through its abort handle
+    fn spawn(&mut self, key: Option<K>, fut: F) {
+        let id = self.next_task_id;
+        self.next_task_id += 1;
+
+        let (handle, registration) = AbortHandle::new_pair();
+        self.abort_handles.insert(id, handle);
+
+        let tp_cloned = self.tp.clone();
+        let fut = Abortable::new(fut, registration);
+        let fut =
+            Box::pin(async move { (id, key, tp_cloned.spawn(fut).await) }) as PendingFut<K, F>;
+        self.pending_results.push(fut);
+    }
+
+    fn on_task_finished(&mut self, key: K) {
+        if let Some(in_flight) = self.in_flight.get_mut(&key) {
+            *in_flight -= 1;
+            if *in_flight == 0 {
+                self.in_flight.remove(&key);
+            }
+        }
+        self.total_in_flight -= 1;
+
+        self.dispatch();
+    }
 }
 
 impl<K: PartialEq + Eq + Hash + Clone, F: Future, TP: ThreadPool> Stream
@@ -83,7 +252,7 @@ impl<K: PartialEq + Eq + Hash + Clone, F: Future, TP: ThreadPool> Stream
 where
     K: Send + Unpin + 'static,
     F: Send + 'static + Unpin,
-    TP: Clone,
+    TP: Clone + Send + 'static + Unpin,
     <F as Future>::Output: Send + 'static + Unpin
 {
     type Item = F::Output;
@@ -92,8 +261,170 @@ where
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>
     ) -> Poll<Option<Self::Item>> {
-        self.pending_results
-            .poll_next_unpin(cx)
-            .filter(|inner| inner.is_some())
+        while let Poll::Ready(next) = self.pending_results.poll_next_unpin(cx) {
+            // an open pool waits for new tasks, a closed one is done once it
+            // ran out of them
+            let Some((id, key, res)) = next else { break };
+            self.abort_handles.remove(&id);
+            if let Some(key) = key {
+                self.on_task_finished(key);
+            }
+
+            // cancelled tasks have no result
+            if let Ok(res) = res {
+                return Poll::Ready(Some(res))
+            }
+        }
+        if self.closed && self.pending_results.is_empty() {
+            return Poll::Ready(None)
+        }
+
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{future::Future, pin::Pin, time::Duration};
+
+    use futures::StreamExt;
+    use tokio::{runtime::Handle, sync::oneshot};
+
+    use super::{KeySplitThreadpool, PoolClosed};
+
+    type Task = Pin<Box<dyn Future<Output = usize> + Send>>;
+
+    /// a task returning `value` once it's released through the sender
+    fn gated(value: usize) -> (oneshot::Sender<()>, Task) {
+        let (tx, rx) = oneshot::channel();
+        let task = Box::pin(async move {
+            let _ = rx.await;
+            value
+        }) as Task;
+
+        (tx, task)
+    }
+
+    async fn next_result(pool: &mut KeySplitThreadpool<u8, Task, Handle>) -> Option<usize> {
+        tokio::time::timeout(Duration::from_secs(1), pool.next())
+            .await
+            .expect("the pool didn't yield within a second")
+    }
+
+    #[tokio::test]
+    async fn test_tasks_of_a_key_run_in_order() {
+        let mut pool = KeySplitThreadpool::new(Handle::current(), 1);
+
+        // earlier tasks take longer, they'd finish last if they ran at once
+        for i in 0..5 {
+            let task = Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10 * (5 - i as u64))).await;
+                i
+            }) as Task;
+            pool.add_new_task(0, task).unwrap();
+        }
+        assert_eq!(pool.in_flight(&0), 1);
+        assert_eq!(pool.queue_depth(&0), 4);
+
+        for i in 0..5 {
+            assert_eq!(next_result(&mut pool).await, Some(i));
+        }
+        assert_eq!(pool.total_in_flight(), 0);
+        assert_eq!(pool.total_queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tasks_beyond_the_limits_wait_for_a_slot() {
+        let mut pool = KeySplitThreadpool::new(Handle::current(), 2).with_max_in_flight(3);
+
+        let mut gates = vec![];
+        for i in 0..4 {
+            let (gate, task) = gated(i);
+            gates.push(gate);
+            pool.add_new_task(0, task).unwrap();
+        }
+        // limited by the per key permits
+        assert_eq!(pool.in_flight(&0), 2);
+        assert_eq!(pool.queue_depth(&0), 2);
+
+        for i in 10..12 {
+            let (gate, task) = gated(i);
+            gates.push(gate);
+            pool.add_new_task(1, task).unwrap();
+        }
+        // limited by the global cap
+        assert_eq!(pool.in_flight(&1), 1);
+        assert_eq!(pool.queue_depth(&1), 1);
+        assert_eq!(pool.total_in_flight(), 3);
+        assert_eq!(pool.total_queued(), 3);
+
+        // a finished task frees a single slot
+        gates.remove(0).send(()).unwrap();
+        assert_eq!(next_result(&mut pool).await, Some(0));
+        assert_eq!(pool.total_in_flight(), 3);
+        assert_eq!(pool.total_queued(), 2);
+        assert!(pool.in_flight(&0) <= 2);
+
+        for gate in gates {
+            let _ = gate.send(());
+        }
+        let mut results = vec![];
+        for _ in 0..5 {
+            results.push(next_result(&mut pool).await.unwrap());
+        }
+        results.sort_unstable();
+        assert_eq!(results, vec![1, 2, 3, 10, 11]);
+        assert_eq!(pool.total_in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_a_limit_of_zero_still_runs_tasks() {
+        let mut pool = KeySplitThreadpool::new(Handle::current(), 1).with_max_in_flight(0);
+
+        let (gate, task) = gated(1);
+        pool.add_new_task(0, task).unwrap();
+        assert_eq!(pool.total_in_flight(), 1);
+
+        gate.send(()).unwrap();
+        assert_eq!(next_result(&mut pool).await, Some(1));
+    }
+
+    #[tokio::test]
+    async fn test_drain_waits_for_running_tasks_and_drops_queued_ones() {
+        let mut pool = KeySplitThreadpool::new(Handle::current(), 1);
+
+        let (gate, running) = gated(0);
+        pool.add_new_task(0, running).unwrap();
+        let (_queued_gate, queued) = gated(1);
+        pool.add_new_task(0, queued).unwrap();
+        // start the running task
+        assert!(futures::poll!(pool.next()).is_pending());
+
+        gate.send(()).unwrap();
+        assert!(pool.drain(Duration::from_secs(1)).await);
+        assert!(pool.is_closed());
+        assert_eq!(pool.total_queued(), 0);
+        assert_eq!(pool.total_in_flight(), 0);
+
+        // a closed pool doesn't take new tasks
+        let (_gate, task) = gated(2);
+        assert_eq!(pool.add_new_task(0, task), Err(PoolClosed));
+        let (_gate, task) = gated(3);
+        assert_eq!(pool.spawn_raw(task), Err(PoolClosed));
+        assert_eq!(pool.total_queued() + pool.total_in_flight(), 0);
+        assert_eq!(next_result(&mut pool).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_drain_cancels_tasks_still_running_after_the_timeout() {
+        let mut pool = KeySplitThreadpool::new(Handle::current(), 1);
+
+        let (_gate, stuck) = gated(0);
+        pool.add_new_task(0, stuck).unwrap();
+        assert!(futures::poll!(pool.next()).is_pending());
+
+        assert!(!pool.drain(Duration::from_millis(50)).await);
+        assert_eq!(pool.total_in_flight(), 0);
+        assert_eq!(next_result(&mut pool).await, None);
     }
 }
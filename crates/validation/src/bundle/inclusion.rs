@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use alloy::primitives::B256;
+use angstrom_types::contract_payloads::angstrom::AngstromBundle;
+use parking_lot::RwLock;
+use serde::Deserialize;
+
+/// An order every bundle has to carry, e.g. the protocol's fee collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct InclusionEntry {
+    pub order_hash: B256,
+    /// index the order has to be at among the bundle's orders, top of block
+    /// orders first. Anywhere in the bundle if not set
+    #[serde(default)]
+    pub position:   Option<usize>,
+    /// block the order is required at. Flash and top of block orders are
+    /// only valid for a single block and their hash commits to it, so their
+    /// entries have to name it. Standing orders hash the same at every block
+    /// and can be required at all of them by leaving it unset
+    #[serde(default)]
+    pub block:      Option<u64>
+}
+
+impl InclusionEntry {
+    fn applies_at(&self, block: u64) -> bool {
+        self.block.map_or(true, |required_at| required_at == block)
+    }
+}
+
+/// Why a bundle doesn't satisfy the inclusion list.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InclusionError {
+    #[error("bundle is missing order {0} of the inclusion list")]
+    Missing(B256),
+    #[error("order {order_hash} is at position {found} of the bundle instead of {required}")]
+    WrongPosition { order_hash: B256, required: usize, found: usize }
+}
+
+/// Orders that have to be part of every bundle, checked before a bundle is
+/// simulated. Clones share their entries, so entries for upcoming blocks can
+/// be added while the validator runs.
+#[derive(Debug, Clone, Default)]
+pub struct InclusionList {
+    entries: Arc<RwLock<Vec<InclusionEntry>>>
+}
+
+impl InclusionList {
+    pub fn new(entries: Vec<InclusionEntry>) -> Self {
+        Self { entries: Arc::new(RwLock::new(entries)) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().is_empty()
+    }
+
+    /// requires the order from now on, or only at the entry's block if it
+    /// names one
+    pub fn insert(&self, entry: InclusionEntry) {
+        self.entries.write().push(entry);
+    }
+
+    /// drops the entries for blocks before `block`, no bundle can carry
+    /// them anymore
+    pub fn prune(&self, block: u64) {
+        self.entries
+            .write()
+            .retain(|entry| entry.block.map_or(true, |required_at| required_at >= block));
+    }
+
+    /// errors on the first entry the bundle, to be executed at `block`,
+    /// doesn't carry where it's required
+    pub fn verify(&self, bundle: &AngstromBundle, block: u64) -> Result<(), InclusionError> {
+        let entries = self.entries.read();
+        if !entries.iter().any(|entry| entry.applies_at(block)) {
+            return Ok(())
+        }
+        let order_hashes = bundle.get_order_hashes(block).collect::<Vec<_>>();
+
+        entries
+            .iter()
+            .filter(|entry| entry.applies_at(block))
+            .try_for_each(|entry| {
+                let found = order_hashes
+                    .iter()
+                    .position(|hash| *hash == entry.order_hash)
+                    .ok_or(InclusionError::Missing(entry.order_hash))?;
+                match entry.position {
+                    Some(required) if required != found => Err(InclusionError::WrongPosition {
+                        order_hash: entry.order_hash,
+                        required,
+                        found
+                    }),
+                    _ => Ok(())
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::contract_payloads::{angstrom::TopOfBlockOrder, Asset, Pair};
+
+    use super::*;
+
+    const BLOCK: u64 = 100;
+
+    /// a bundle of top of block orders that differ in their quantity in
+    fn bundle(quantities: &[u128]) -> AngstromBundle {
+        let assets = vec![Asset::default(), Asset::default()];
+        let pair = Pair { index0: 0, index1: 1, ..Default::default() };
+        let orders = quantities
+            .iter()
+            .map(|&quantity_in| TopOfBlockOrder { quantity_in, ..Default::default() })
+            .collect();
+
+        AngstromBundle::new(assets, vec![pair], vec![], orders, vec![])
+    }
+
+    fn hash_of(quantity_in: u128) -> B256 {
+        hash_at(quantity_in, BLOCK)
+    }
+
+    fn hash_at(quantity_in: u128, block: u64) -> B256 {
+        bundle(&[quantity_in])
+            .get_order_hashes(block)
+            .next()
+            .unwrap()
+    }
+
+    fn entry(quantity_in: u128, position: Option<usize>) -> InclusionEntry {
+        InclusionEntry { order_hash: hash_of(quantity_in), position, block: Some(BLOCK) }
+    }
+
+    #[test]
+    fn present_entries_pass() {
+        let list = InclusionList::new(vec![entry(1, Some(0)), entry(3, None)]);
+
+        assert_eq!(list.verify(&bundle(&[1, 2, 3]), BLOCK), Ok(()));
+        assert_eq!(InclusionList::default().verify(&bundle(&[]), BLOCK), Ok(()));
+    }
+
+    #[test]
+    fn absent_entries_fail() {
+        let list = InclusionList::new(vec![entry(1, None), entry(4, None)]);
+        assert_eq!(
+            list.verify(&bundle(&[1, 2, 3]), BLOCK),
+            Err(InclusionError::Missing(hash_of(4)))
+        );
+    }
+
+    #[test]
+    fn entries_only_apply_at_their_block() {
+        let list = InclusionList::new(vec![entry(1, None)]);
+
+        // a top of block order's hash commits to the block, a bundle for the
+        // next block can't carry it and doesn't have to
+        assert_eq!(list.verify(&bundle(&[2]), BLOCK + 1), Ok(()));
+        assert_eq!(list.verify(&bundle(&[2]), BLOCK), Err(InclusionError::Missing(hash_of(1))));
+
+        // entries without a block apply to every bundle
+        let list = InclusionList::new(vec![InclusionEntry {
+            order_hash: hash_of(1),
+            position:   None,
+            block:      None
+        }]);
+        assert!(list.verify(&bundle(&[1]), BLOCK + 1).is_err());
+    }
+
+    #[test]
+    fn entries_are_updated_per_block() {
+        let list = InclusionList::default();
+        let shared = list.clone();
+        shared.insert(InclusionEntry {
+            order_hash: hash_at(1, BLOCK + 1),
+            position:   None,
+            block:      Some(BLOCK + 1)
+        });
+
+        assert_eq!(list.verify(&bundle(&[2]), BLOCK), Ok(()));
+        assert_eq!(
+            list.verify(&bundle(&[2]), BLOCK + 1),
+            Err(InclusionError::Missing(hash_at(1, BLOCK + 1)))
+        );
+        assert_eq!(list.verify(&bundle(&[1]), BLOCK + 1), Ok(()));
+
+        shared.prune(BLOCK + 2);
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn misordered_entries_fail() {
+        let list = InclusionList::new(vec![entry(1, Some(0))]);
+
+        assert_eq!(
+            list.verify(&bundle(&[2, 1]), BLOCK),
+            Err(InclusionError::WrongPosition {
+                order_hash: hash_of(1),
+                required:   0,
+                found:      1
+            })
+        );
+    }
+}
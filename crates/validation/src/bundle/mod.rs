@@ -2,16 +2,19 @@ use std::{fmt::Debug, pin::Pin, sync::Arc};
 
 use alloy::{primitives::Address, sol_types::SolCall};
 use angstrom_types::contract_payloads::angstrom::{AngstromBundle, BundleGasDetails};
-use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
+use angstrom_utils::key_split_threadpool::{KeySplitThreadpool, PoolClosed};
 use eyre::eyre;
 use futures::{Future, FutureExt};
 use pade::PadeEncode;
+use reth_provider::BlockNumReader;
 use revm::primitives::{EnvWithHandlerCfg, TxKind};
 use tokio::runtime::Handle;
 
-use crate::common::TokenPriceGenerator;
+use crate::common::{ContractCallError, TokenPriceGenerator};
 
+pub mod inclusion;
 pub mod validator;
+pub use inclusion::*;
 pub use validator::*;
 
 pub struct BundleValidator<DB> {
@@ -19,7 +22,9 @@ pub struct BundleValidator<DB> {
     angstrom_address: Address,
     /// the address associated with this node.
     /// this will ensure the  node has access and the simulation can pass
-    node_address:     Address
+    node_address:     Address,
+    /// orders every bundle has to carry
+    inclusion_list:   InclusionList
 }
 
 impl<DB> BundleValidator<DB>
@@ -28,7 +33,14 @@ where
     <DB as revm::DatabaseRef>::Error: Send + Sync + Debug
 {
     pub fn new(db: Arc<DB>, angstrom_address: Address, node_address: Address) -> Self {
-        Self { db, angstrom_address, node_address }
+        Self { db, angstrom_address, node_address, inclusion_list: InclusionList::default() }
+    }
+
+    /// bundles that don't carry the listed orders where required fail
+    /// validation without being simulated
+    pub fn with_inclusion_list(mut self, inclusion_list: InclusionList) -> Self {
+        self.inclusion_list = inclusion_list;
+        self
     }
 
     pub fn simulate_bundle(
@@ -41,7 +53,12 @@ where
             Pin<Box<dyn Future<Output = ()> + Send>>,
             Handle
         >
-    ) {
+    ) -> Result<(), PoolClosed> {
+        if let Err(e) = self.check_inclusion_list(&bundle) {
+            let _ = sender.send(Err(e));
+            return Ok(())
+        }
+
         let node_address = self.node_address;
         let angstrom_address = self.angstrom_address;
         let db = self.db.clone();
@@ -70,10 +87,13 @@ where
                     })
                     .build();
 
-                let result = evm
-                    .transact()
-                    .map_err(|_| eyre!("failed to transact with revm"))
-                    .unwrap();
+                let result = match evm.transact() {
+                    Ok(result) => result,
+                    Err(e) => {
+                        let _ = sender.send(Err(ContractCallError::transact(e).into()));
+                        return
+                    }
+                };
 
                 if !result.result.is_success() {
                     let _ = sender.send(Err(eyre!("transaction simulation failed")));
@@ -86,4 +106,15 @@ where
             .boxed()
         )
     }
+
+    /// bundles are built for the block after the current one
+    fn check_inclusion_list(&self, bundle: &AngstromBundle) -> eyre::Result<()> {
+        if self.inclusion_list.is_empty() {
+            return Ok(())
+        }
+        let block = self.db.best_block_number()? + 1;
+        self.inclusion_list.prune(block);
+
+        Ok(self.inclusion_list.verify(bundle, block)?)
+    }
 }
@@ -0,0 +1,63 @@
+use std::{
+    fmt::Debug,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH}
+};
+
+/// Source of wall clock time for validation. Order deadlines are unix
+/// timestamps so everything is in seconds.
+pub trait Clock: Debug + Send + Sync + 'static {
+    /// seconds since the unix epoch
+    fn now(&self) -> u64;
+}
+
+/// Reads the system time, what the validator uses outside of tests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is before the unix epoch")
+            .as_secs()
+    }
+}
+
+/// Stuck at a single point in time, deadlines of orders validated against a
+/// past block are checked against that block's timestamp.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Clock that only moves when told to, so deadline handling can be tested
+/// deterministically.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    now: AtomicU64
+}
+
+impl MockClock {
+    pub fn new(now: u64) -> Self {
+        Self { now: AtomicU64::new(now) }
+    }
+
+    pub fn set(&self, now: u64) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.fetch_add(by.as_secs(), Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> u64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
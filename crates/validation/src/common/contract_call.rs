@@ -0,0 +1,75 @@
+use alloy::{primitives::Bytes, sol_types::SolCall};
+use revm::primitives::ExecutionResult;
+
+/// Why a contract call simulated during validation didn't produce a usable
+/// result. Returned instead of panicking so a bad response only fails the
+/// request that made the call, not the worker running it.
+#[derive(Debug, thiserror::Error)]
+pub enum ContractCallError {
+    #[error("failed to execute call: {0}")]
+    Transact(String),
+    #[error("call reverted with {0}")]
+    Reverted(Bytes),
+    #[error("call halted: {0}")]
+    Halted(String),
+    #[error("failed to decode call result: {0}")]
+    Decode(#[from] alloy::sol_types::Error)
+}
+
+impl ContractCallError {
+    pub fn transact(error: impl std::fmt::Debug) -> Self {
+        Self::Transact(format!("{error:?}"))
+    }
+}
+
+/// Decodes the return data of a simulated call to `C`.
+pub fn decode_call_result<C: SolCall>(
+    result: &ExecutionResult
+) -> Result<C::Return, ContractCallError> {
+    match result {
+        ExecutionResult::Success { output, .. } => Ok(C::abi_decode_returns(output.data(), false)?),
+        ExecutionResult::Revert { output, .. } => Err(ContractCallError::Reverted(output.clone())),
+        ExecutionResult::Halt { reason, .. } => {
+            Err(ContractCallError::Halted(format!("{reason:?}")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::U256;
+    use angstrom_types::contract_bindings::mintable_mock_erc_20::MintableMockERC20::balanceOfCall;
+    use revm::primitives::{Output, SuccessReason};
+
+    use super::*;
+
+    fn success(output: Bytes) -> ExecutionResult {
+        ExecutionResult::Success {
+            reason:       SuccessReason::Return,
+            gas_used:     0,
+            gas_refunded: 0,
+            logs:         vec![],
+            output:       Output::Call(output)
+        }
+    }
+
+    #[test]
+    fn test_malformed_output_is_an_error() {
+        let balance = U256::from(42);
+        let encoded = balanceOfCall::abi_encode_returns(&(balance,));
+        let decoded = decode_call_result::<balanceOfCall>(&success(encoded.into())).unwrap();
+        assert_eq!(decoded._0, balance);
+
+        let truncated = success(Bytes::from_static(&[1, 2, 3]));
+        assert!(matches!(
+            decode_call_result::<balanceOfCall>(&truncated),
+            Err(ContractCallError::Decode(_))
+        ));
+
+        let reverted = ExecutionResult::Revert { gas_used: 0, output: Bytes::new() };
+        assert!(matches!(
+            decode_call_result::<balanceOfCall>(&reverted),
+            Err(ContractCallError::Reverted(_))
+        ));
+    }
+}
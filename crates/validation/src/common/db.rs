@@ -1,8 +1,10 @@
-use alloy::primitives::{Address, BlockNumber, StorageKey, StorageValue};
+use std::sync::Arc;
+
+use alloy::primitives::{Address, BlockNumber, StorageKey, StorageValue, B256, U256};
 use reth_primitives::Account;
 use reth_provider::{
-    AccountReader, BlockNumReader, ProviderResult, StateProvider, StateProviderBox,
-    StateProviderFactory
+    AccountReader, BlockHashReader, BlockNumReader, HeaderProvider, ProviderError, ProviderResult,
+    StateProvider, StateProviderBox, StateProviderFactory
 };
 
 pub trait BlockStateProvider {
@@ -48,3 +50,54 @@ impl<T: StateProviderFactory> BlockStateProviderFactory for T {
         BlockNumReader::best_block_number(self)
     }
 }
+
+/// Opens the state of past blocks so orders can be validated against them.
+pub trait HistoricalStateFactory: Send + Sync + 'static {
+    /// the state once `block` was executed, along with the block's timestamp
+    fn state_at(&self, block: u64) -> ProviderResult<(HistoricalDb, u64)>;
+}
+
+impl<T> HistoricalStateFactory for T
+where
+    T: StateProviderFactory + HeaderProvider + Send + Sync + 'static
+{
+    fn state_at(&self, block: u64) -> ProviderResult<(HistoricalDb, u64)> {
+        let header = self
+            .header_by_number(block)?
+            .ok_or(ProviderError::HeaderNotFound(block.into()))?;
+        let state = self.state_by_block_id(block.into())?;
+
+        Ok((HistoricalDb(Arc::new(state)), header.timestamp))
+    }
+}
+
+/// The state at a single past block. Read only, nothing is cached between
+/// lookups.
+#[derive(Clone)]
+pub struct HistoricalDb(Arc<StateProviderBox>);
+
+impl revm::DatabaseRef for HistoricalDb {
+    type Error = ProviderError;
+
+    fn basic_ref(
+        &self,
+        address: Address
+    ) -> Result<Option<revm::primitives::AccountInfo>, Self::Error> {
+        Ok(self.0.basic_account(address)?.map(Into::into))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<revm::primitives::Bytecode, Self::Error> {
+        Ok(self.0.bytecode_by_hash(code_hash)?.unwrap_or_default().0)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self
+            .0
+            .storage(address, B256::new(index.to_be_bytes()))?
+            .unwrap_or_default())
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self.0.block_hash(number)?.unwrap_or_default())
+    }
+}
@@ -6,9 +6,18 @@ use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
 use futures::{Future, Stream, StreamExt};
 use tokio::runtime::Handle;
 
+pub mod clock;
+pub use clock::*;
+
+pub mod contract_call;
+pub use contract_call::*;
+
 pub mod db;
 pub use db::*;
 
+pub mod retry_db;
+pub use retry_db::*;
+
 pub mod token_pricing;
 pub use token_pricing::*;
 
@@ -17,7 +26,14 @@ pub use token_pricing::*;
 pub struct SharedTools {
     pub token_pricing:   TokenPriceGenerator,
     token_price_updater: Pin<Box<dyn Stream<Item = Vec<PairsWithPrice>> + 'static>>,
-    pub thread_pool: KeySplitThreadpool<Address, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>
+    /// token price updates applied since startup
+    price_updates:       usize,
+    /// republished with the prices after every update
+    price_snapshot:      TokenPriceSnapshot,
+    pub thread_pool: KeySplitThreadpool<Address, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>,
+    /// bundles are simulated on `thread_pool` if not set
+    bundle_thread_pool:
+        Option<KeySplitThreadpool<Address, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>>
 }
 
 impl SharedTools {
@@ -26,7 +42,40 @@ impl SharedTools {
         token_price_updater: Pin<Box<dyn Stream<Item = Vec<PairsWithPrice>> + 'static>>,
         thread_pool: KeySplitThreadpool<Address, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>
     ) -> Self {
-        Self { token_price_updater, token_pricing, thread_pool }
+        Self {
+            token_price_updater,
+            token_pricing,
+            thread_pool,
+            price_updates: 0,
+            price_snapshot: TokenPriceSnapshot::default(),
+            bundle_thread_pool: None
+        }
+    }
+
+    /// keeps `price_snapshot` up to date with the prices, for whoever reads
+    /// them outside validation
+    pub fn with_price_snapshot(mut self, price_snapshot: TokenPriceSnapshot) -> Self {
+        price_snapshot.publish(self.token_pricing.generate_lookup_map());
+        self.price_snapshot = price_snapshot;
+        self
+    }
+
+    /// simulates bundles on their own thread pool instead of the one shared
+    /// with order validation
+    pub fn with_bundle_thread_pool(
+        mut self,
+        bundle_thread_pool: KeySplitThreadpool<
+            Address,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Self {
+        self.bundle_thread_pool = Some(bundle_thread_pool);
+        self
+    }
+
+    pub fn price_updates(&self) -> usize {
+        self.price_updates
     }
 
     pub fn token_pricing_ref(&self) -> &TokenPriceGenerator {
@@ -39,6 +88,38 @@ impl SharedTools {
         &mut self.thread_pool
     }
 
+    /// the token pricing along with the thread pool bundles are simulated on
+    pub fn bundle_tools(
+        &mut self
+    ) -> (
+        &TokenPriceGenerator,
+        &mut KeySplitThreadpool<Address, Pin<Box<dyn Future<Output = ()> + Send>>, Handle>
+    ) {
+        let thread_pool = self
+            .bundle_thread_pool
+            .as_mut()
+            .unwrap_or(&mut self.thread_pool);
+        (&self.token_pricing, thread_pool)
+    }
+
+    /// validations currently running on the order and bundle thread pools
+    pub fn validations_in_flight(&self) -> usize {
+        self.thread_pool.total_in_flight()
+            + self
+                .bundle_thread_pool
+                .as_ref()
+                .map_or(0, KeySplitThreadpool::total_in_flight)
+    }
+
+    /// validations waiting for a slot on the order and bundle thread pools
+    pub fn validations_queued(&self) -> usize {
+        self.thread_pool.total_queued()
+            + self
+                .bundle_thread_pool
+                .as_ref()
+                .map_or(0, KeySplitThreadpool::total_queued)
+    }
+
     pub fn token_pricing_snapshot(&self) -> TokenPriceGenerator {
         self.token_pricing.clone()
     }
@@ -54,8 +135,19 @@ impl Future for SharedTools {
         self.thread_pool.try_register_waker(|| cx.waker().clone());
         while let Poll::Ready(Some(_)) = self.thread_pool.poll_next_unpin(cx) {}
 
+        if let Some(bundle_thread_pool) = self.bundle_thread_pool.as_mut() {
+            bundle_thread_pool.try_register_waker(|| cx.waker().clone());
+            while let Poll::Ready(Some(_)) = bundle_thread_pool.poll_next_unpin(cx) {}
+        }
+
+        let price_updates = self.price_updates;
         while let Poll::Ready(Some(updates)) = self.token_price_updater.poll_next_unpin(cx) {
             self.token_pricing.apply_update(updates);
+            self.price_updates += 1;
+        }
+        if self.price_updates != price_updates {
+            self.price_snapshot
+                .publish(self.token_pricing.generate_lookup_map());
         }
 
         Poll::Pending
@@ -0,0 +1,264 @@
+use std::{io, thread, time::Duration};
+
+use alloy::primitives::{Address, B256, U256};
+use reth_errors::ProviderError;
+use revm::{
+    primitives::{AccountInfo, Bytecode},
+    DatabaseRef
+};
+use serde::Deserialize;
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// How often and how fast failed db reads are retried.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DbRetryConfig {
+    /// retries after the first attempt, 0 disables retrying
+    #[serde(default = "DbRetryConfig::default_max_retries")]
+    pub max_retries:        u32,
+    /// backoff before the first retry, doubled on every following one
+    #[serde(default = "DbRetryConfig::default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "DbRetryConfig::default_max_backoff_ms")]
+    pub max_backoff_ms:     u64
+}
+
+impl DbRetryConfig {
+    const fn default_max_retries() -> u32 {
+        3
+    }
+
+    const fn default_initial_backoff_ms() -> u64 {
+        10
+    }
+
+    const fn default_max_backoff_ms() -> u64 {
+        200
+    }
+
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff_ms
+            .saturating_mul(1u64.checked_shl(retry).unwrap_or(u64::MAX));
+        Duration::from_millis(backoff.min(self.max_backoff_ms))
+    }
+}
+
+impl Default for DbRetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries:        Self::default_max_retries(),
+            initial_backoff_ms: Self::default_initial_backoff_ms(),
+            max_backoff_ms:     Self::default_max_backoff_ms()
+        }
+    }
+}
+
+/// Db errors that can go away by trying again, such as a dropped connection
+/// to the provider, as opposed to ones that will fail every time.
+pub trait TransientError {
+    fn is_transient(&self) -> bool;
+}
+
+impl TransientError for eyre::Report {
+    fn is_transient(&self) -> bool {
+        self.chain().any(|err| {
+            if let Some(err) = err.downcast_ref::<io::Error>() {
+                return matches!(
+                    err.kind(),
+                    io::ErrorKind::TimedOut
+                        | io::ErrorKind::Interrupted
+                        | io::ErrorKind::WouldBlock
+                        | io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                        | io::ErrorKind::BrokenPipe
+                )
+            }
+
+            matches!(err.downcast_ref::<ProviderError>(), Some(ProviderError::Database(_)))
+        })
+    }
+}
+
+/// Wraps a db and retries reads that fail with a transient error using an
+/// exponential backoff. Permanent errors, and transient ones that outlast the
+/// retries, are returned as is.
+///
+/// Reads are synchronous, so the backoff blocks the calling thread like the
+/// read itself does. On a multi threaded runtime the worker's other tasks are
+/// handed off first, so a retrying read doesn't stall the validations queued
+/// behind it.
+#[derive(Debug, Clone)]
+pub struct RetryDb<DB> {
+    db:     DB,
+    config: DbRetryConfig
+}
+
+impl<DB> RetryDb<DB>
+where
+    DB: DatabaseRef,
+    DB::Error: TransientError
+{
+    pub fn new(db: DB, config: DbRetryConfig) -> Self {
+        Self { db, config }
+    }
+
+    fn with_retry<T>(&self, read: impl Fn(&DB) -> Result<T, DB::Error>) -> Result<T, DB::Error> {
+        let mut retry = 0;
+        loop {
+            match read(&self.db) {
+                Err(err) if err.is_transient() && retry < self.config.max_retries => {
+                    let backoff = self.config.backoff(retry);
+                    retry += 1;
+                    tracing::debug!(retry, ?backoff, "transient db error, retrying read");
+                    sleep(backoff);
+                }
+                res => return res
+            }
+        }
+    }
+}
+
+/// sleeps without holding up the other tasks of the runtime worker it's
+/// called from, if any
+fn sleep(backoff: Duration) {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| thread::sleep(backoff))
+        }
+        _ => thread::sleep(backoff)
+    }
+}
+
+impl<DB> DatabaseRef for RetryDb<DB>
+where
+    DB: DatabaseRef,
+    DB::Error: TransientError
+{
+    type Error = DB::Error;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.with_retry(|db| db.basic_ref(address))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.with_retry(|db| db.code_by_hash_ref(code_hash))
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.with_retry(|db| db.storage_ref(address, index))
+    }
+
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.with_retry(|db| db.block_hash_ref(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum MockError {
+        Transient,
+        Permanent
+    }
+
+    impl TransientError for MockError {
+        fn is_transient(&self) -> bool {
+            matches!(self, MockError::Transient)
+        }
+    }
+
+    /// fails the first `failures` reads with `error`
+    struct FlakyDb {
+        failures: u32,
+        error:    fn() -> MockError,
+        reads:    AtomicU32
+    }
+
+    impl FlakyDb {
+        fn new(failures: u32, error: fn() -> MockError) -> Self {
+            Self { failures, error, reads: AtomicU32::new(0) }
+        }
+    }
+
+    impl DatabaseRef for FlakyDb {
+        type Error = MockError;
+
+        fn basic_ref(&self, _: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            Ok(None)
+        }
+
+        fn code_by_hash_ref(&self, _: B256) -> Result<Bytecode, Self::Error> {
+            Ok(Bytecode::default())
+        }
+
+        fn storage_ref(&self, _: Address, _: U256) -> Result<U256, Self::Error> {
+            if self.reads.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err((self.error)())
+            }
+            Ok(U256::from(1))
+        }
+
+        fn block_hash_ref(&self, _: u64) -> Result<B256, Self::Error> {
+            Ok(B256::ZERO)
+        }
+    }
+
+    fn config(max_retries: u32) -> DbRetryConfig {
+        DbRetryConfig { max_retries, initial_backoff_ms: 1, max_backoff_ms: 2 }
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let db = RetryDb::new(FlakyDb::new(2, || MockError::Transient), config(3));
+        assert_eq!(db.storage_ref(Address::ZERO, U256::ZERO), Ok(U256::from(1)));
+        assert_eq!(db.db.reads.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let db = RetryDb::new(FlakyDb::new(10, || MockError::Transient), config(3));
+        assert_eq!(db.storage_ref(Address::ZERO, U256::ZERO), Err(MockError::Transient));
+        assert_eq!(db.db.reads.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn does_not_retry_permanent_errors() {
+        let db = RetryDb::new(FlakyDb::new(1, || MockError::Permanent), config(3));
+        assert_eq!(db.storage_ref(Address::ZERO, U256::ZERO), Err(MockError::Permanent));
+        assert_eq!(db.db.reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn retrying_does_not_stall_other_tasks() {
+        let config = DbRetryConfig {
+            max_retries:        3,
+            initial_backoff_ms: 500,
+            max_backoff_ms:     500
+        };
+        let db = RetryDb::new(FlakyDb::new(3, || MockError::Transient), config);
+        let read = tokio::spawn(async move { db.storage_ref(Address::ZERO, U256::ZERO) });
+        let other = tokio::spawn(async {});
+
+        // the read holds the only worker for over a second
+        tokio::time::timeout(Duration::from_millis(500), other)
+            .await
+            .expect("the other task ran while the read was backing off")
+            .unwrap();
+        assert_eq!(read.await.unwrap(), Ok(U256::from(1)));
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let config = DbRetryConfig {
+            max_retries:        10,
+            initial_backoff_ms: 10,
+            max_backoff_ms:     50
+        };
+        let backoffs = (0..4).map(|retry| config.backoff(retry).as_millis());
+        assert_eq!(backoffs.collect::<Vec<_>>(), vec![10, 20, 40, 50]);
+    }
+}
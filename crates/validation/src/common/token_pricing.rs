@@ -1,5 +1,7 @@
 use std::{
     collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+    ops::RangeInclusive,
     sync::Arc
 };
 
@@ -10,10 +12,15 @@ use alloy::{
 };
 use angstrom_types::{pair_with_price::PairsWithPrice, primitive::PoolId};
 use futures::StreamExt;
+use parking_lot::RwLock;
 use tracing::warn;
-use uniswap_v4::uniswap::{pool_data_loader::PoolDataLoader, pool_manager::SyncedUniswapPools};
+use uniswap_v4::uniswap::{
+    pool::PoolError, pool_data_loader::PoolDataLoader, pool_manager::SyncedUniswapPools
+};
 
 const BLOCKS_TO_AVG_PRICE: u64 = 5;
+/// pools whose price history is loaded at once by a backfill
+const BACKFILL_CONCURRENCY: usize = 16;
 pub const WETH_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
 
 // crazy that this is a thing
@@ -24,11 +31,34 @@ pub const WETH_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c75
 /// In the case of NON direct eth pairs. we assume that any token liquid enough
 /// to trade on angstrom not with eth will always have a eth pair 1 hop away.
 /// this allows for a simple lookup.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct TokenPriceGenerator {
-    prev_prices:  HashMap<PoolId, VecDeque<PairsWithPrice>>,
-    pair_to_pool: HashMap<(Address, Address), PoolId>,
-    cur_block:    u64
+    prev_prices:   HashMap<PoolId, VecDeque<PairsWithPrice>>,
+    pair_to_pool:  HashMap<(Address, Address), PoolId>,
+    cur_block:     u64,
+    /// when set, pairs whose last settled price is older than this many
+    /// blocks can't be used for conversions
+    max_price_age: Option<u64>,
+    /// prices kept per pair, the conversion price is their average
+    history_len:   usize,
+    /// when set, the least recently priced pairs are dropped beyond this many
+    max_pairs:     Option<NonZeroUsize>,
+    /// a backfill priced every tracked pair
+    seeded:        bool
+}
+
+impl Default for TokenPriceGenerator {
+    fn default() -> Self {
+        Self {
+            prev_prices:   HashMap::default(),
+            pair_to_pool:  HashMap::default(),
+            cur_block:     0,
+            max_price_age: None,
+            history_len:   BLOCKS_TO_AVG_PRICE as usize,
+            max_pairs:     None,
+            seeded:        false
+        }
+    }
 }
 
 impl TokenPriceGenerator {
@@ -53,32 +83,16 @@ impl TokenPriceGenerator {
         let pools = futures::stream::iter(uni.iter())
             .map(|(pool_key, pool)| {
                 let provider = provider.clone();
+                let data_loader = pool.read().unwrap().data_loader();
 
                 async move {
-                    let mut queue = VecDeque::new();
-                    // scoping
-                    let data_loader = {
-                        let pool_read = pool.read().unwrap();
-                        let data_loader = pool_read.data_loader();
-                        drop(pool_read);
-                        data_loader
-                    };
-
-                    for block_number in current_block - BLOCKS_TO_AVG_PRICE..=current_block {
-                        let pool_data = data_loader
-                            .load_pool_data(Some(block_number), provider.clone())
-                            .await
-                            .expect("failed to load historical price for token price conversion");
-
-                        let price = pool_data.get_raw_price();
-
-                        queue.push_back(PairsWithPrice {
-                            token0:         pool_data.tokenA,
-                            token1:         pool_data.tokenB,
-                            block_num:      block_number,
-                            price_1_over_0: price
-                        });
-                    }
+                    let queue = load_price_history(
+                        provider,
+                        data_loader,
+                        current_block - BLOCKS_TO_AVG_PRICE..=current_block
+                    )
+                    .await
+                    .expect("failed to load historical price for token price conversion");
 
                     (*pool_key, queue)
                 }
@@ -90,7 +104,191 @@ impl TokenPriceGenerator {
             })
             .await;
 
-        Ok(Self { prev_prices: pools, cur_block: current_block, pair_to_pool })
+        Ok(Self {
+            prev_prices: pools,
+            cur_block: current_block,
+            pair_to_pool,
+            ..Default::default()
+        })
+    }
+
+    /// Loads the price history of every pool whose pair has no price yet,
+    /// including pools added after the generator was built, from the chain
+    /// at the blocks leading up to the current one. It doesn't depend on the
+    /// pools having synced. Pools whose history can't be loaded are left
+    /// without a price until their first update.
+    pub async fn backfill<P: Provider<T, N>, T: Transport + Clone, N: Network, Loader>(
+        &mut self,
+        provider: Arc<P>,
+        uni: &SyncedUniswapPools<PoolId, Loader>
+    ) where
+        Loader: PoolDataLoader<PoolId> + Default + Clone + Send + Sync + 'static
+    {
+        let first_block = self.cur_block.saturating_sub(self.history_len as u64 - 1);
+        let missing = uni
+            .iter()
+            .filter(|(pool_id, _)| !self.prev_prices.contains_key(*pool_id))
+            .map(|(pool_id, pool)| (*pool_id, pool.read().unwrap().data_loader()))
+            .collect::<Vec<_>>();
+
+        let blocks = first_block..=self.cur_block;
+        let histories = futures::stream::iter(missing)
+            .map(|(pool_id, data_loader)| {
+                let history = load_price_history(provider.clone(), data_loader, blocks.clone());
+                async move { (pool_id, history.await) }
+            })
+            .buffer_unordered(BACKFILL_CONCURRENCY)
+            .filter_map(|(pool_id, history)| async move {
+                history
+                    .inspect_err(|error| {
+                        warn!(?pool_id, %error, "failed to backfill the pool's price history")
+                    })
+                    .ok()
+                    .map(|history| (pool_id, history))
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        self.seed_histories(histories);
+    }
+
+    /// whether the backfill left every tracked pair with a price. False if
+    /// there was none or no pair is tracked
+    pub fn is_seeded(&self) -> bool {
+        self.seeded
+    }
+
+    /// sets the price history of the pairs without a price to `histories`,
+    /// oldest price first
+    fn seed_histories(&mut self, histories: Vec<(PoolId, VecDeque<PairsWithPrice>)>) {
+        for (pool_id, mut history) in histories {
+            let Some(latest) = history.back() else { continue };
+            self.pair_to_pool
+                .entry((latest.token0, latest.token1))
+                .or_insert(pool_id);
+            let prices = self.prev_prices.entry(pool_id).or_default();
+            if prices.is_empty() {
+                let excess = history.len().saturating_sub(self.history_len);
+                history.drain(..excess);
+                *prices = history;
+            }
+        }
+        self.seeded = !self.pair_to_pool.is_empty()
+            && self
+                .pair_to_pool
+                .values()
+                .all(|pool_id| self.prev_prices.contains_key(pool_id));
+        tracing::debug!(pairs = self.prev_prices.len(), seeded = self.seeded, "backfilled prices");
+        self.evict_least_recently_priced();
+    }
+
+    /// Stops using a pair's price for conversions once it hasn't settled for
+    /// more than `max_age_blocks`, so valuations error instead of running on
+    /// a stale price.
+    pub fn with_max_price_age(mut self, max_age_blocks: Option<u64>) -> Self {
+        self.max_price_age = max_age_blocks;
+        self
+    }
+
+    /// averages each pair's price over its last `history_len` blocks
+    /// instead of the last 5
+    pub fn with_history_len(mut self, history_len: Option<usize>) -> Self {
+        self.history_len = history_len.unwrap_or(BLOCKS_TO_AVG_PRICE as usize).max(1);
+        for prices in self.prev_prices.values_mut() {
+            let excess = prices.len().saturating_sub(self.history_len);
+            prices.drain(..excess);
+        }
+        self
+    }
+
+    /// Keeps the prices of at most `max_pairs` pairs, dropping the ones that
+    /// were priced the longest ago. A dropped pair is tracked again once its
+    /// price settles, until then it has no conversion price.
+    pub fn with_max_pairs(mut self, max_pairs: Option<NonZeroUsize>) -> Self {
+        self.max_pairs = max_pairs;
+        self.evict_least_recently_priced();
+        self
+    }
+
+    /// pairs whose prices are currently held
+    pub fn tracked_pairs(&self) -> usize {
+        self.prev_prices.len()
+    }
+
+    fn evict_least_recently_priced(&mut self) {
+        let Some(max_pairs) = self.max_pairs.map(NonZeroUsize::get) else { return };
+        while self.prev_prices.len() > max_pairs {
+            let Some(oldest) = self
+                .prev_prices
+                .iter()
+                .min_by_key(|(_, prices)| prices.back().map(|price| price.block_num))
+                .map(|(pool_id, _)| *pool_id)
+            else {
+                return
+            };
+            tracing::debug!(pool_id = ?oldest, max_pairs, "dropping least recently priced pair");
+            self.prev_prices.remove(&oldest);
+        }
+    }
+
+    /// block the pool's price was last settled at
+    pub fn last_update_block(&self, pool_id: &PoolId) -> Option<u64> {
+        self.prev_prices
+            .get(pool_id)
+            .and_then(|prices| prices.back())
+            .map(|price| price.block_num)
+    }
+
+    /// whether the pool's price hasn't settled in more than `max_age_blocks`.
+    /// Unknown pools are always stale
+    pub fn is_stale(&self, pool_id: &PoolId, max_age_blocks: u64) -> bool {
+        self.prev_prices
+            .get(pool_id)
+            .and_then(|prices| prices.back())
+            .map_or(true, |price| price.is_stale(self.cur_block, max_age_blocks))
+    }
+
+    /// the pool's price of token0 in token1 averaged over its history, `None`
+    /// if it has no usable price
+    pub fn pool_price(&self, pool_id: &PoolId) -> Option<U256> {
+        let prices = self.usable_prices(pool_id)?;
+        if prices.is_empty() {
+            return None
+        }
+
+        Some(
+            prices
+                .iter()
+                .map(|price| price.price_1_over_0)
+                .sum::<U256>()
+                / U256::from(prices.len())
+        )
+    }
+
+    /// a generator that only knows `prices`, each pair's history filled with
+    /// its price
+    #[cfg(test)]
+    pub(crate) fn with_prices(prices: Vec<(PoolId, PairsWithPrice)>) -> Self {
+        let mut this = Self::default();
+        let history_len = this.history_len;
+        let histories = prices
+            .into_iter()
+            .map(|(pool_id, price)| (pool_id, std::iter::repeat(price).take(history_len).collect()))
+            .collect();
+        this.seed_histories(histories);
+        this
+    }
+
+    /// the price history of the pool, unless it's too old to be used
+    fn usable_prices(&self, pool_id: &PoolId) -> Option<&VecDeque<PairsWithPrice>> {
+        if let Some(max_age) = self.max_price_age {
+            if self.is_stale(pool_id, max_age) {
+                warn!(?pool_id, max_age, "price is stale, not using it for conversion");
+                return None
+            }
+        }
+
+        self.prev_prices.get(pool_id)
     }
 
     pub fn generate_lookup_map(&self) -> HashMap<(Address, Address), U256> {
@@ -117,20 +315,21 @@ impl TokenPriceGenerator {
                 .pair_to_pool
                 .get(&(pool_update.token0, pool_update.token1))
                 .expect("got pool update that we don't have stored");
-            let prev_prices = self
-                .prev_prices
-                .get_mut(pool_key)
-                .expect("don't have prev_prices for update");
-            prev_prices.pop_front();
+            // pairs dropped by the pair cap start over
+            let prev_prices = self.prev_prices.entry(*pool_key).or_default();
             prev_prices.push_back(pool_update);
+            let excess = prev_prices.len().saturating_sub(self.history_len);
+            prev_prices.drain(..excess);
         }
         self.cur_block += 1;
+        self.evict_least_recently_priced();
     }
 
     /// NOTE: assumes tokens are properly sorted
     /// returns the conversion ratio of the pair to eth, this looks like
     /// non-weth / weth. This then allows for the simple calcuation of
-    /// gas_in_wei * conversion price in order to get the used token_0
+    /// gas_in_wei * conversion price in order to get the used token_0. `None`
+    /// if the pair has no usable price
     pub fn get_eth_conversion_price(&self, token_0: Address, token_1: Address) -> Option<U256> {
         if token_0 == WETH_ADDRESS {
             return Some(U256::from(1))
@@ -139,15 +338,12 @@ impl TokenPriceGenerator {
         // conversion factor will be 1-1
         if token_1 == WETH_ADDRESS {
             // if so, just pull the price
-            let pool_key = self
-                .pair_to_pool
-                .get(&(token_0, token_1))
-                .expect("got pool update that we don't have stored");
+            let pool_key = self.pair_to_pool.get(&(token_0, token_1))?;
 
-            let prices = self.prev_prices.get(pool_key)?;
+            let prices = self.usable_prices(pool_key)?;
             let size = prices.len() as u64;
 
-            if size != BLOCKS_TO_AVG_PRICE {
+            if size != self.history_len as u64 {
                 warn!("size of loaded blocks doesn't match the value we set");
             }
 
@@ -179,10 +375,10 @@ impl TokenPriceGenerator {
         // check token_0 first for a weth pair. otherwise, check token_1.
         if let Some(key) = self.pair_to_pool.get(&(token_0_hop1, token_1_hop1)) {
             // there is a hop from token_0 to weth
-            let prices = self.prev_prices.get(key)?;
+            let prices = self.usable_prices(key)?;
             let size = prices.len() as u64;
 
-            if size != BLOCKS_TO_AVG_PRICE {
+            if size != self.history_len as u64 {
                 warn!("size of loaded blocks doesn't match the value we set");
             }
 
@@ -204,15 +400,12 @@ impl TokenPriceGenerator {
         } else if let Some(key) = self.pair_to_pool.get(&(token_0_hop2, token_1_hop2)) {
             // because we are going through token1 here and we want token zero, we need to
             // do some extra math
-            let default_pool_key = self
-                .pair_to_pool
-                .get(&(token_0, token_1))
-                .expect("got pool update that we don't have stored");
+            let default_pool_key = self.pair_to_pool.get(&(token_0, token_1))?;
 
-            let prices = self.prev_prices.get(default_pool_key)?;
+            let prices = self.usable_prices(default_pool_key)?;
             let size = prices.len() as u64;
 
-            if size != BLOCKS_TO_AVG_PRICE {
+            if size != self.history_len as u64 {
                 warn!("size of loaded blocks doesn't match the value we set");
             }
             // token 0 / token 1
@@ -226,10 +419,10 @@ impl TokenPriceGenerator {
                 / U256::from(size);
 
             // grab second hop
-            let prices = self.prev_prices.get(key)?;
+            let prices = self.usable_prices(key)?;
             let size = prices.len() as u64;
 
-            if size != BLOCKS_TO_AVG_PRICE {
+            if size != self.history_len as u64 {
                 warn!("size of loaded blocks doesn't match the value we set");
             }
 
@@ -251,14 +444,72 @@ impl TokenPriceGenerator {
             // token 0 / token1 * token1 / weth  = token0 / weth
             Some(first_hop_price * second_hop_price)
         } else {
-            panic!("found a token that doesn't have a 1 hop to WETH")
+            warn!(?token_0, ?token_1, "pair has no 1 hop to WETH, no price to convert with");
+            None
         }
     }
 }
 
+/// The eth conversion price of every priced pair, as of the validator's last
+/// price update. Clones share the same prices, so components outside
+/// validation can compare amounts of different tokens by their worth in eth.
+#[derive(Debug, Clone, Default)]
+pub struct TokenPriceSnapshot {
+    prices: Arc<RwLock<HashMap<(Address, Address), U256>>>
+}
+
+impl TokenPriceSnapshot {
+    /// replaces the prices with `prices`, keyed by the sorted pair like
+    /// [`TokenPriceGenerator::generate_lookup_map`]
+    pub fn publish(&self, prices: HashMap<(Address, Address), U256>) {
+        *self.prices.write() = prices;
+    }
+
+    /// NOTE: assumes tokens are properly sorted
+    /// `amount` of token_0 in wei, `None` if the pair has no price
+    pub fn eth_value(&self, token_0: Address, token_1: Address, amount: U256) -> Option<U256> {
+        if token_0 == WETH_ADDRESS {
+            return Some(amount)
+        }
+
+        // non-weth / weth with 18 decimals of precision
+        let conversion = *self.prices.read().get(&(token_0, token_1))?;
+        (!conversion.is_zero()).then(|| amount.saturating_mul(U256::from(1e18)) / conversion)
+    }
+}
+
+/// the pool's price at each of `blocks`, loaded from the chain
+async fn load_price_history<P: Provider<T, N>, T: Transport + Clone, N: Network, Loader>(
+    provider: Arc<P>,
+    data_loader: Loader,
+    blocks: RangeInclusive<u64>
+) -> Result<VecDeque<PairsWithPrice>, PoolError>
+where
+    Loader: PoolDataLoader<PoolId>
+{
+    let mut queue = VecDeque::new();
+    for block_number in blocks {
+        let pool_data = data_loader
+            .load_pool_data(Some(block_number), provider.clone())
+            .await?;
+
+        queue.push_back(PairsWithPrice {
+            token0:         pool_data.tokenA,
+            token1:         pool_data.tokenB,
+            block_num:      block_number,
+            price_1_over_0: pool_data.get_raw_price()
+        });
+    }
+
+    Ok(queue)
+}
+
 #[cfg(test)]
 pub mod test {
-    use std::collections::{HashMap, VecDeque};
+    use std::{
+        collections::{HashMap, VecDeque},
+        num::NonZeroUsize
+    };
 
     use alloy::{
         node_bindings::WEI_IN_ETHER,
@@ -267,7 +518,7 @@ pub mod test {
     use angstrom_types::pair_with_price::PairsWithPrice;
     use revm::primitives::address;
 
-    use super::TokenPriceGenerator;
+    use super::{TokenPriceGenerator, TokenPriceSnapshot};
 
     const TOKEN0: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
     const TOKEN1: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc3");
@@ -344,7 +595,11 @@ pub mod test {
         let queue = VecDeque::from([pair; 5]);
         prices.insert(FixedBytes::<32>::with_last_byte(4), queue);
 
-        TokenPriceGenerator { cur_block: 0, prev_prices: prices, pair_to_pool: pairs_to_key }
+        TokenPriceGenerator {
+            prev_prices: prices,
+            pair_to_pool: pairs_to_key,
+            ..Default::default()
+        }
     }
 
     #[test]
@@ -388,4 +643,150 @@ pub mod test {
         let expected_rate = U256::from(1600000000000u128);
         assert_eq!(rate, expected_rate)
     }
+
+    #[test]
+    fn pairs_without_a_price_have_no_conversion() {
+        let token_conversion = setup();
+
+        // paired with weth, but the pair isn't tracked
+        assert_eq!(token_conversion.get_eth_conversion_price(TOKEN4, TOKEN0), None);
+        // no hop to weth for either token
+        let (token0, token1) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        assert_eq!(token_conversion.get_eth_conversion_price(token0, token1), None);
+    }
+
+    fn pair1_update(block: u64) -> PairsWithPrice {
+        PairsWithPrice {
+            token0:         TOKEN2,
+            token1:         TOKEN0,
+            block_num:      block,
+            price_1_over_0: U256::from(5) * WEI_IN_ETHER
+        }
+    }
+
+    #[test]
+    fn backfill_prices_pairs_without_a_price() {
+        assert!(!TokenPriceGenerator::default().is_seeded());
+
+        let mut token_conversion = setup();
+        let token = Address::with_last_byte(1);
+        let pool = FixedBytes::<32>::with_last_byte(5);
+        token_conversion.pair_to_pool.insert((token, TOKEN0), pool);
+        assert_eq!(token_conversion.get_eth_conversion_price(token, TOKEN0), None);
+        assert!(!token_conversion.is_seeded());
+
+        let history = |token0, prices: &[u64]| {
+            prices
+                .iter()
+                .enumerate()
+                .map(|(block, price)| PairsWithPrice {
+                    token0,
+                    token1: TOKEN0,
+                    block_num: block as u64,
+                    price_1_over_0: U256::from(*price) * WEI_IN_ETHER
+                })
+                .collect::<VecDeque<_>>()
+        };
+        // the oldest prices beyond the history length are dropped
+        token_conversion.seed_histories(vec![(pool, history(token, &[100, 1, 3, 1, 3, 2]))]);
+        assert!(token_conversion.is_seeded());
+        assert_eq!(token_conversion.prev_prices[&pool].len(), 5);
+        assert_eq!(
+            token_conversion.get_eth_conversion_price(token, TOKEN0),
+            Some(U256::from(1e36) / U256::from(2e18))
+        );
+
+        // pairs that already have a price keep it
+        let priced = FixedBytes::<32>::with_last_byte(1);
+        token_conversion.seed_histories(vec![(priced, history(TOKEN2, &[2]))]);
+        assert_eq!(
+            token_conversion.get_eth_conversion_price(TOKEN2, TOKEN0),
+            Some(U256::from(1e36) / U256::from(5e18))
+        );
+    }
+
+    #[test]
+    fn snapshot_values_amounts_in_eth() {
+        let snapshot = TokenPriceSnapshot::default();
+        assert_eq!(snapshot.eth_value(TOKEN2, TOKEN0, U256::from(100)), None);
+
+        snapshot.publish(setup().generate_lookup_map());
+        // token2 is worth 5 eth
+        assert_eq!(snapshot.eth_value(TOKEN2, TOKEN0, U256::from(100)), Some(U256::from(500)));
+        // weth is worth itself
+        assert_eq!(snapshot.eth_value(TOKEN0, TOKEN1, U256::from(100)), Some(U256::from(100)));
+
+        // clones share what's published
+        snapshot.clone().publish(HashMap::default());
+        assert_eq!(snapshot.eth_value(TOKEN2, TOKEN0, U256::from(100)), None);
+    }
+
+    #[test]
+    fn price_history_is_bounded_per_pair() {
+        let mut token_conversion = setup().with_history_len(Some(2));
+        let pool = FixedBytes::<32>::with_last_byte(1);
+        assert_eq!(token_conversion.prev_prices[&pool].len(), 2);
+
+        for block in 1..=3 {
+            token_conversion.apply_update(vec![pair1_update(block)]);
+        }
+        assert_eq!(token_conversion.prev_prices[&pool].len(), 2);
+        assert_eq!(token_conversion.last_update_block(&pool), Some(3));
+    }
+
+    #[test]
+    fn least_recently_priced_pairs_are_dropped_beyond_the_cap() {
+        let mut token_conversion = setup().with_max_pairs(NonZeroUsize::new(4));
+        assert_eq!(token_conversion.tracked_pairs(), 4);
+
+        // only pair 1 settles a new price, so it's kept
+        token_conversion.apply_update(vec![pair1_update(1)]);
+        let mut token_conversion = token_conversion.with_max_pairs(NonZeroUsize::new(1));
+        assert_eq!(token_conversion.tracked_pairs(), 1);
+        assert!(token_conversion
+            .get_eth_conversion_price(TOKEN2, TOKEN0)
+            .is_some());
+        assert!(token_conversion
+            .get_eth_conversion_price(TOKEN4, TOKEN1)
+            .is_none());
+
+        // a dropped pair comes back once it's priced again
+        token_conversion.apply_update(vec![PairsWithPrice {
+            token0:         TOKEN4,
+            token1:         TOKEN1,
+            block_num:      2,
+            price_1_over_0: U256::from(1e36) / U256::from(8e6)
+        }]);
+        assert_eq!(token_conversion.tracked_pairs(), 1);
+        assert!(token_conversion
+            .last_update_block(&FixedBytes::<32>::with_last_byte(4))
+            .is_some());
+        assert!(token_conversion
+            .last_update_block(&FixedBytes::<32>::with_last_byte(1))
+            .is_none());
+    }
+
+    #[test]
+    fn stale_prices_are_not_used_once_a_max_age_is_set() {
+        let mut token_conversion = setup().with_max_price_age(Some(2));
+        let pool = FixedBytes::<32>::with_last_byte(1);
+
+        token_conversion.apply_update(vec![]);
+        token_conversion.apply_update(vec![]);
+        assert_eq!(token_conversion.last_update_block(&pool), Some(0));
+        assert!(!token_conversion.is_stale(&pool, 2));
+        assert!(token_conversion
+            .get_eth_conversion_price(TOKEN2, TOKEN0)
+            .is_some());
+
+        token_conversion.apply_update(vec![]);
+        assert!(token_conversion.is_stale(&pool, 2));
+        assert!(token_conversion
+            .get_eth_conversion_price(TOKEN2, TOKEN0)
+            .is_none());
+        // multi-hop conversions go through the same stale pair
+        assert!(token_conversion
+            .get_eth_conversion_price(TOKEN2, TOKEN3)
+            .is_none());
+    }
 }
@@ -4,8 +4,13 @@ pub mod order;
 pub mod validator;
 
 use std::{
+    any::Any,
     fmt::Debug,
-    sync::{atomic::AtomicU64, Arc}
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+        Arc
+    }
 };
 
 use alloy::primitives::Address;
@@ -13,11 +18,14 @@ use angstrom_types::{
     contract_payloads::angstrom::AngstromPoolConfigStore, pair_with_price::PairsWithPrice
 };
 use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
-use bundle::BundleValidator;
-use common::SharedTools;
+use bundle::{BundleValidator, InclusionList};
+use common::{
+    HistoricalStateFactory, RetryDb, SharedTools, SystemClock, TokenPriceSnapshot, TransientError
+};
 use futures::StreamExt;
 use reth_provider::CanonStateNotificationStream;
-use tokio::sync::mpsc::UnboundedReceiver;
+use thiserror::Error;
+use tokio::sync::{mpsc::UnboundedReceiver, oneshot};
 use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 use validator::Validator;
 
@@ -26,62 +34,280 @@ use crate::{
     order::{
         order_validator::OrderValidator,
         sim::SimValidation,
-        state::{db_state_utils::FetchUtils, pools::AngstromPoolsTracker}
+        state::{
+            config::ValidationConfig, db_state_utils::FetchUtils, pools::AngstromPoolsTracker
+        }
     },
     validator::{ValidationClient, ValidationRequest}
 };
 
 const MAX_VALIDATION_PER_ADDR: usize = 2;
 
+#[derive(Debug, Error)]
+pub enum ValidationInitError {
+    /// the price feed only reads bundles sent to the angstrom contract, with
+    /// the zero address it would never see a price
+    #[error("angstrom address is the zero address")]
+    ZeroAngstromAddress
+}
+
+impl ValidationInitError {
+    pub fn check_angstrom_address(angstrom_address: Address) -> Result<(), Self> {
+        if angstrom_address.is_zero() {
+            return Err(Self::ZeroAngstromAddress)
+        }
+
+        Ok(())
+    }
+}
+
+/// Why validation stopped. It is meant to run for as long as the node does,
+/// so any exit means orders are no longer validated.
+#[derive(Debug, Clone, Error)]
+pub enum ValidationExit {
+    /// every sender of validation requests was dropped
+    #[error("validation request channel closed")]
+    RequestsClosed,
+    #[error("validation panicked: {0}")]
+    Panicked(String)
+}
+
+impl ValidationExit {
+    /// whether validation stopped because the node is shutting down, the
+    /// request senders are only all dropped then
+    pub fn is_shutdown(&self) -> bool {
+        matches!(self, Self::RequestsClosed)
+    }
+}
+
+/// Tracks the validation thread started by [`init_validation`].
+#[derive(Debug)]
+pub struct ValidationHandle {
+    alive:          Arc<AtomicBool>,
+    exit:           oneshot::Receiver<ValidationExit>,
+    inclusion_list: InclusionList,
+    token_prices:   TokenPriceSnapshot
+}
+
+impl ValidationHandle {
+    /// flag that is cleared once validation stops, for health checks that
+    /// outlive the handle
+    pub fn liveness(&self) -> Arc<AtomicBool> {
+        self.alive.clone()
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+    /// the orders bundles have to carry, shared with the bundle validator so
+    /// entries for upcoming blocks can be added while it runs
+    pub fn inclusion_list(&self) -> InclusionList {
+        self.inclusion_list.clone()
+    }
+
+    /// the token prices validation converts gas with, kept up to date as
+    /// new prices settle
+    pub fn token_prices(&self) -> TokenPriceSnapshot {
+        self.token_prices.clone()
+    }
+
+    /// resolves with the reason once validation stops
+    pub async fn stopped(self) -> ValidationExit {
+        self.exit.await.unwrap_or_else(|_| {
+            ValidationExit::Panicked("validation thread exited without a reason".to_string())
+        })
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    panic
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| panic.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn init_validation<
     DB: Unpin + Clone + 'static + reth_provider::BlockNumReader + revm::DatabaseRef + Send + Sync
 >(
     db: DB,
-    current_block: u64,
-    angstrom_address: Option<Address>,
+    current_block: Arc<AtomicU64>,
+    validations_queued: Arc<AtomicUsize>,
+    angstrom_address: Address,
     node_address: Address,
     state_notification: CanonStateNotificationStream,
     uniswap_pools: SyncedUniswapPools,
     price_generator: TokenPriceGenerator,
     pool_store: Arc<AngstromPoolConfigStore>,
-    validator_rx: UnboundedReceiver<ValidationRequest>
-) where
-    <DB as revm::DatabaseRef>::Error: Send + Sync + Debug
+    validator_rx: UnboundedReceiver<ValidationRequest>,
+    validation_config: ValidationConfig,
+    historical_state: Option<Arc<dyn HistoricalStateFactory>>
+) -> Result<ValidationHandle, ValidationInitError>
+where
+    <DB as revm::DatabaseRef>::Error: Send + Sync + Debug + TransientError
 {
-    let current_block = Arc::new(AtomicU64::new(current_block));
+    ValidationInitError::check_angstrom_address(angstrom_address)?;
+
     let revm_lru = Arc::new(db);
-    let fetch = FetchUtils::new(Address::default(), revm_lru.clone());
+    let retry_db = RetryDb::new(revm_lru.clone(), validation_config.db_retry.clone());
+    let fetch = FetchUtils::new(Address::default(), Arc::new(retry_db));
+    let approval_cache = fetch.approvals.cache();
+
+    let (exit_tx, exit_rx) = oneshot::channel();
+    let alive = Arc::new(AtomicBool::new(true));
+    let liveness = alive.clone();
+    let inclusion_list = InclusionList::new(validation_config.inclusion_list.clone());
+    let bundle_inclusion_list = inclusion_list.clone();
+    let token_prices = TokenPriceSnapshot::default();
+    let published_prices = token_prices.clone();
 
     std::thread::spawn(move || {
-        let rt = tokio::runtime::Builder::new_multi_thread()
-            .enable_all()
-            .worker_threads(4)
-            .build()
-            .unwrap();
-
-        let handle = rt.handle().clone();
-        let pools = AngstromPoolsTracker::new(angstrom_address.unwrap_or_default(), pool_store);
-        // load storage slot state + pools
-        let thread_pool = KeySplitThreadpool::new(handle, MAX_VALIDATION_PER_ADDR);
-        let sim = SimValidation::new(revm_lru.clone(), angstrom_address);
-
-        // load price update stream;
-        let update_stream = PairsWithPrice::into_price_update_stream(
-            angstrom_address.unwrap_or_default(),
-            state_notification
-        )
-        .boxed();
-
-        let order_validator =
-            rt.block_on(OrderValidator::new(sim, current_block, pools, fetch, uniswap_pools));
-
-        let bundle_validator =
-            BundleValidator::new(revm_lru.clone(), angstrom_address.unwrap(), node_address);
-        let shared_utils = SharedTools::new(price_generator, update_stream, thread_pool);
-
-        rt.block_on(async {
-            Validator::new(validator_rx, order_validator, bundle_validator, shared_utils).await
-        })
+        let run = AssertUnwindSafe(move || {
+            let rt = tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .worker_threads(validation_config.runtime.worker_threads.get())
+                .build()
+                .unwrap();
+            // kept alive for as long as the validator runs
+            let bundle_rt = validation_config
+                .runtime
+                .bundle_worker_threads
+                .map(|worker_threads| {
+                    tokio::runtime::Builder::new_multi_thread()
+                        .enable_all()
+                        .worker_threads(worker_threads.get())
+                        .thread_name("bundle-validation")
+                        .build()
+                        .unwrap()
+                });
+
+            let handle = rt.handle().clone();
+            let max_in_flight = validation_config.runtime.max_validations_in_flight.get();
+            let pools = AngstromPoolsTracker::new(angstrom_address, pool_store);
+            // load storage slot state + pools
+            let thread_pool = KeySplitThreadpool::new(handle, MAX_VALIDATION_PER_ADDR)
+                .with_max_in_flight(max_in_flight);
+            let sim = SimValidation::new(revm_lru.clone(), Some(angstrom_address))
+                .with_timeout(validation_config.order_timeout())
+                .with_missing_price(validation_config.missing_price);
+
+            // load price update stream, cached approvals are dropped as the chain moves
+            let state_notification = state_notification
+                .inspect(move |notification| approval_cache.on_canon_state(notification));
+            let update_stream =
+                PairsWithPrice::into_price_update_stream(angstrom_address, state_notification)
+                    .boxed();
+
+            let warmup = validation_config.warmup.clone();
+            let max_price_age = validation_config.max_price_age_blocks;
+            let price_history = validation_config.price_history_blocks;
+            let max_priced_pairs = validation_config.max_priced_pairs;
+            let order_validator = rt.block_on(OrderValidator::new(
+                sim,
+                current_block,
+                pools,
+                fetch,
+                uniswap_pools,
+                validation_config,
+                Arc::new(SystemClock)
+            ));
+            let order_validator = match historical_state {
+                Some(state) => order_validator.with_historical_state(state, angstrom_address),
+                None => order_validator
+            };
+
+            let bundle_validator =
+                BundleValidator::new(revm_lru.clone(), angstrom_address, node_address)
+                    .with_inclusion_list(bundle_inclusion_list);
+            let price_generator = price_generator
+                .with_max_price_age(max_price_age)
+                .with_history_len(price_history)
+                .with_max_pairs(max_priced_pairs);
+            let mut shared_utils = SharedTools::new(price_generator, update_stream, thread_pool)
+                .with_price_snapshot(published_prices);
+            if let Some(bundle_rt) = bundle_rt.as_ref() {
+                let bundle_thread_pool =
+                    KeySplitThreadpool::new(bundle_rt.handle().clone(), MAX_VALIDATION_PER_ADDR)
+                        .with_max_in_flight(max_in_flight);
+                shared_utils = shared_utils.with_bundle_thread_pool(bundle_thread_pool);
+            }
+
+            rt.block_on(async {
+                Validator::new(validator_rx, order_validator, bundle_validator, shared_utils)
+                    .with_warmup(warmup)
+                    .with_queued_counter(validations_queued)
+                    .await
+            })
+        });
+
+        let exit = match std::panic::catch_unwind(run) {
+            Ok(()) => ValidationExit::RequestsClosed,
+            Err(panic) => ValidationExit::Panicked(panic_message(panic))
+        };
+        if exit.is_shutdown() {
+            tracing::info!(%exit, "order validation stopped");
+        } else {
+            tracing::error!(%exit, "order validation stopped");
+        }
+        alive.store(false, Ordering::SeqCst);
+        let _ = exit_tx.send(exit);
     });
+
+    Ok(ValidationHandle { alive: liveness, exit: exit_rx, inclusion_list, token_prices })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle() -> (oneshot::Sender<ValidationExit>, ValidationHandle) {
+        let (exit_tx, exit) = oneshot::channel();
+        let handle = ValidationHandle {
+            alive: Arc::new(AtomicBool::new(true)),
+            exit,
+            inclusion_list: InclusionList::new(vec![]),
+            token_prices: TokenPriceSnapshot::default()
+        };
+
+        (exit_tx, handle)
+    }
+
+    #[tokio::test]
+    async fn test_closed_requests_are_a_shutdown() {
+        let (exit_tx, handle) = handle();
+        exit_tx.send(ValidationExit::RequestsClosed).unwrap();
+
+        assert!(handle.stopped().await.is_shutdown());
+    }
+
+    #[tokio::test]
+    async fn test_exit_without_a_reason_is_a_failure() {
+        let (exit_tx, handle) = handle();
+        drop(exit_tx);
+
+        let exit = handle.stopped().await;
+        assert!(matches!(exit, ValidationExit::Panicked(_)));
+        assert!(!exit.is_shutdown());
+    }
+
+    #[test]
+    fn test_zero_angstrom_address_is_rejected() {
+        assert!(matches!(
+            ValidationInitError::check_angstrom_address(Address::ZERO),
+            Err(ValidationInitError::ZeroAngstromAddress)
+        ));
+        assert!(ValidationInitError::check_angstrom_address(Address::random()).is_ok());
+    }
+
+    #[test]
+    fn test_panic_messages_are_kept() {
+        let panic = std::panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(panic), "static message");
+
+        let block = 7;
+        let panic = std::panic::catch_unwind(|| panic!("formatted at {block}")).unwrap_err();
+        assert_eq!(panic_message(panic), "formatted at 7");
+    }
 }
@@ -1,8 +1,10 @@
-use std::{fmt::Debug, future::Future, pin::Pin};
+use std::{fmt::Debug, future::Future, pin::Pin, time::Instant};
 
 use alloy::primitives::{Address, B256, U256};
 use angstrom_types::{
+    matching::Ray,
     orders::OrderOrigin,
+    primitive::PoolId,
     sol_bindings::{
         ext::RawPoolOrder,
         grouped_orders::{
@@ -11,14 +13,30 @@ use angstrom_types::{
         rpc_orders::TopOfBlockOrder
     }
 };
+use serde::{Deserialize, Serialize};
 use sim::SimValidation;
 use tokio::sync::oneshot::{channel, Sender};
 
-use crate::{common::TokenPriceGenerator, validator::ValidationRequest};
+use crate::{
+    common::TokenPriceGenerator,
+    order::{
+        rejection::{Rejection, RejectionReason},
+        state::{
+            account::UserAccountVerificationError,
+            config::{MissingPricePolicy, ValidationConfig},
+            pools::PoolInitParams
+        },
+        trace::ValidationTrace
+    },
+    validator::ValidationRequest
+};
 
 pub mod order_validator;
+pub mod rejection;
+pub mod result_cache;
 pub mod sim;
 pub mod state;
+pub mod trace;
 
 use crate::validator::ValidationClient;
 
@@ -31,46 +49,83 @@ pub type ValidationsFuture<'a> =
 pub type GasEstimationFuture<'a> =
     Pin<Box<dyn Future<Output = Result<(u64, U256), String>> + Send + Sync + 'a>>;
 
+pub type SimulationFuture<'a> = Pin<Box<dyn Future<Output = SimulationResult> + Send + Sync + 'a>>;
+
+pub type TraceFuture<'a> = Pin<Box<dyn Future<Output = ValidationTrace> + Send + Sync + 'a>>;
+
+/// `None` if the validator is gone
+pub type NonceFuture<'a> = Pin<Box<dyn Future<Output = Option<u64>> + Send + Sync + 'a>>;
+
 pub enum OrderValidationRequest {
-    ValidateOrder(Sender<OrderValidationResults>, AllOrders, OrderOrigin)
+    /// the `Instant` is when the request was sent, the time until its
+    /// validation starts is recorded as its queue wait
+    ValidateOrder(Sender<OrderValidationResults>, AllOrders, OrderOrigin, Instant)
+}
+
+impl OrderValidationRequest {
+    /// when the request was sent to the validator
+    pub fn sent(&self) -> Instant {
+        match self {
+            Self::ValidateOrder(.., sent) => *sent
+        }
+    }
 }
 
 /// TODO: not a fan of all the conversions. can def simplify
 impl From<OrderValidationRequest> for OrderValidation {
     fn from(value: OrderValidationRequest) -> Self {
         match value {
-            OrderValidationRequest::ValidateOrder(tx, order, orign) => match order {
+            OrderValidationRequest::ValidateOrder(tx, order, orign, _) => match order {
                 AllOrders::Standing(p) => {
-                    // TODO: check hook data and deal with composable
-                    // if p.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
-                    // } else {
-                    //
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::Partial(p),
-                    //         orign
-                    //     )
-                    // }
+                    if p.hook_data().is_empty() {
+                        OrderValidation::Limit(tx, GroupedVanillaOrder::Standing(p), orign)
+                    } else {
+                        OrderValidation::LimitComposable(
+                            tx,
+                            GroupedComposableOrder::Partial(p),
+                            orign
+                        )
+                    }
                 }
                 AllOrders::Flash(kof) => {
-                    // TODO: check hook data and deal with composable
-                    // if kof.hook_data.is_empty() {
-                    OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
-                    // } else {
-                    //     OrderValidation::LimitComposable(
-                    //         tx,
-                    //         GroupedComposableOrder::KillOrFill(kof),
-                    //         orign
-                    //     )
-                    // }
+                    if kof.hook_data().is_empty() {
+                        OrderValidation::Limit(tx, GroupedVanillaOrder::KillOrFill(kof), orign)
+                    } else {
+                        OrderValidation::LimitComposable(
+                            tx,
+                            GroupedComposableOrder::KillOrFill(kof),
+                            orign
+                        )
+                    }
                 }
+                // searcher orders carry no hook data so they are never composable
                 AllOrders::TOB(tob) => OrderValidation::Searcher(tx, tob, orign)
             }
         }
     }
 }
 
+/// Outcome of a dry run validation of an order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulationResult {
+    /// whether the order would be accepted into the pool
+    pub would_validate:   bool,
+    /// amount of the input token that the users current balances can fill
+    pub estimated_fill:   u128,
+    /// amount of the input token the user needs to hold or have approved
+    pub required_balance: U256,
+    /// gas used by the order including its hook, only set for composable
+    /// orders
+    pub hook_gas:         Option<u64>,
+    pub rejection_reason: Option<String>
+}
+
+impl SimulationResult {
+    pub fn rejected(required_balance: U256, reason: impl Into<String>) -> Self {
+        Self { required_balance, rejection_reason: Some(reason.into()), ..Default::default() }
+    }
+}
+
 pub enum ValidationMessage {
     ValidationResults(OrderValidationResults)
 }
@@ -78,9 +133,24 @@ pub enum ValidationMessage {
 #[derive(Debug, Clone)]
 pub enum OrderValidationResults {
     Valid(OrderWithStorageData<AllOrders>),
-    // the raw hash to be removed
-    Invalid(B256),
-    TransitionedToBlock
+    /// the raw hash to be removed and why the order was rejected
+    Invalid(B256, Rejection),
+    TransitionedToBlock,
+    /// the order's uniswap pool hasn't synced yet, the order can be validated
+    /// again once it has
+    PoolNotSynced {
+        order:   AllOrders,
+        pool_id: PoolId
+    },
+    /// the order's uniswap pool wasn't initialized yet and the node holds
+    /// such orders, see [`UninitializedPoolPolicy`]. The order can be
+    /// validated again once the pool is initialized
+    ///
+    /// [`UninitializedPoolPolicy`]: crate::order::state::config::UninitializedPoolPolicy
+    PoolNotInitialized {
+        order: AllOrders,
+        init:  PoolInitParams
+    }
 }
 
 impl OrderValidationResults {
@@ -120,8 +190,10 @@ impl OrderValidationResults {
                     SimValidation::calculate_user_gas
                 );
 
-                if res.is_err() {
-                    *self = OrderValidationResults::Invalid(order_hash);
+                if let Err(e) = &res {
+                    tracing::debug!(%e, "failed to simulate order gas");
+                    let rejection = Self::gas_rejection::<GroupedVanillaOrder>(e);
+                    *self = OrderValidationResults::Invalid(order_hash, rejection);
 
                     return
                 }
@@ -139,8 +211,10 @@ impl OrderValidationResults {
                     AllOrders::TOB,
                     SimValidation::calculate_tob_gas
                 );
-                if res.is_err() {
-                    *self = OrderValidationResults::Invalid(order_hash);
+                if let Err(e) = &res {
+                    tracing::debug!(%e, "failed to simulate order gas");
+                    let rejection = Self::gas_rejection::<TopOfBlockOrder>(e);
+                    *self = OrderValidationResults::Invalid(order_hash, rejection);
 
                     return
                 }
@@ -152,6 +226,55 @@ impl OrderValidationResults {
         }
     }
 
+    /// Rejects the order if it's worth less than its pool's minimum notional,
+    /// quoted in token1. Asks sell token0, their amount is valued at the
+    /// pool's average settled price rather than the order's own limit price
+    pub fn check_min_notional(
+        &mut self,
+        config: &ValidationConfig,
+        token_price: &TokenPriceGenerator
+    ) {
+        let Self::Valid(order) = &*self else { return };
+        let Some(&min_notional) = config.min_notional.get(&order.pool_id) else { return };
+        let order_hash = order.order_hash();
+        let amount = U256::from(order.amount_in());
+
+        let notional = if order.is_bid {
+            amount
+        } else {
+            match token_price.pool_price(&order.pool_id) {
+                Some(price) => Ray::from(price).mul_quantity(amount),
+                None if config.missing_price == MissingPricePolicy::Admit => return,
+                None => {
+                    let error = UserAccountVerificationError::<AllOrders>::NoPoolPrice {
+                        order_hash,
+                        pool_id: order.pool_id
+                    };
+                    *self = Self::Invalid(order_hash, Rejection::from(&error));
+                    return
+                }
+            }
+        };
+
+        if notional < min_notional {
+            let error = UserAccountVerificationError::<AllOrders>::BelowMinNotional {
+                order_hash,
+                notional,
+                min_notional
+            };
+            *self = Self::Invalid(order_hash, Rejection::from(&error));
+        }
+    }
+
+    /// the order's typed error if the gas calculation returned one, e.g. for a
+    /// token without a price
+    fn gas_rejection<O: RawPoolOrder>(error: &eyre::Report) -> Rejection {
+        error
+            .downcast_ref::<UserAccountVerificationError<O>>()
+            .map(Rejection::from)
+            .unwrap_or_else(|| Rejection::new(RejectionReason::SimulationFailed, error.to_string()))
+    }
+
     // hmm the structure here is probably overkill to avoid 8 extra lines of code
     fn map_and_process<Old, New, DB>(
         order: OrderWithStorageData<Old>,
@@ -173,12 +296,10 @@ impl OrderValidationResults {
             .try_map_inner(move |order| Ok(map_new(order)))
             .unwrap();
 
-        if let Ok((gas_units, gas_used)) = (calculate_function)(sim, &order, token_price) {
-            order.priority_data.gas += gas_used;
-            order.priority_data.gas_units = gas_units;
-        } else {
-            return Err(eyre::eyre!("not able to process gas"))
-        }
+        let (gas_units, gas_used) = (calculate_function)(sim, &order, token_price)
+            .map_err(|e| eyre::eyre!("not able to process gas: {e}"))?;
+        order.priority_data.gas += gas_used;
+        order.priority_data.gas_units = gas_units;
 
         order.try_map_inner(move |new_order| Ok(map_old(new_order)))
     }
@@ -197,6 +318,20 @@ impl OrderValidation {
             Self::Limit(_, u, _) => u.from()
         }
     }
+
+    /// answers the request with `results` without validating the order
+    pub fn respond(self, results: OrderValidationResults) {
+        let (Self::Limit(tx, ..) | Self::LimitComposable(tx, ..) | Self::Searcher(tx, ..)) = self;
+        let _ = tx.send(results);
+    }
+
+    pub fn order_hash(&self) -> B256 {
+        match &self {
+            Self::Searcher(_, u, _) => u.order_hash(),
+            Self::LimitComposable(_, u, _) => u.order_hash(),
+            Self::Limit(_, u, _) => u.order_hash()
+        }
+    }
 }
 
 /// Provides support for validating transaction at any given state of the chain
@@ -227,6 +362,44 @@ pub trait OrderValidatorHandle: Send + Sync + Clone + Debug + Unpin + 'static {
 
     /// estimates gas usage for order
     fn estimate_gas(&self, order: AllOrders) -> GasEstimationFuture;
+
+    /// runs the order through validation without adding it to the pool or
+    /// updating any validation state
+    fn simulate_order(&self, order: AllOrders) -> SimulationFuture;
+
+    /// [`Self::simulate_order`] against the state of a past block, answers
+    /// whether the order would have been valid then. Rejected by default
+    fn simulate_order_at_block(&self, order: AllOrders, _block: u64) -> SimulationFuture {
+        let result = SimulationResult::rejected(
+            U256::from(order.amount_in()),
+            "validating at a past block isn't supported"
+        );
+        Box::pin(async move { result })
+    }
+
+    /// [`Self::simulate_order`] that reports each check the order went
+    /// through. Rejected by default
+    fn trace_order(&self, _order: AllOrders) -> TraceFuture {
+        let trace = ValidationTrace {
+            steps:     vec![],
+            rejection: Some(Rejection::new(
+                RejectionReason::Unavailable,
+                "tracing orders isn't supported"
+            ))
+        };
+        Box::pin(async move { trace })
+    }
+
+    /// lowest nonce not used on chain or by any of the users pending orders
+    fn next_nonce(&self, user: Address) -> NonceFuture;
+
+    /// hint that orders from `addresses` over `tokens` are about to arrive, so
+    /// their account state can be loaded ahead of time. Ignored by default
+    fn prewarm(&self, _addresses: Vec<Address>, _tokens: Vec<Address>) {}
+
+    /// the pool dropped `user`'s cancelled order, so it no longer counts
+    /// against their balances. Ignored by default
+    fn cancel_order(&self, _user: Address, _order_hash: B256) {}
 }
 
 impl OrderValidatorHandle for ValidationClient {
@@ -247,22 +420,30 @@ impl OrderValidatorHandle for ValidationClient {
                 addresses
             });
 
-            rx.await.unwrap()
+            // a validator that shut down holds no state left to transition
+            rx.await.unwrap_or_else(|_| {
+                tracing::warn!(block_number, "validator is gone, skipping its block transition");
+                OrderValidationResults::TransitionedToBlock
+            })
         })
     }
 
     fn validate_order(&self, origin: OrderOrigin, transaction: Self::Order) -> ValidationFuture {
         Box::pin(async move {
+            let order_hash = transaction.order_hash();
             let (tx, rx) = channel();
             let _ = self
                 .0
                 .send(ValidationRequest::Order(OrderValidationRequest::ValidateOrder(
                     tx,
                     transaction,
-                    origin
+                    origin,
+                    Instant::now()
                 )));
 
-            rx.await.unwrap()
+            rx.await.unwrap_or_else(|_| {
+                OrderValidationResults::Invalid(order_hash, validator_unavailable())
+            })
         })
     }
 
@@ -272,11 +453,323 @@ impl OrderValidatorHandle for ValidationClient {
                 OrderValidationResults::Valid(o) => {
                     Ok((o.priority_data.gas_units, o.priority_data.gas))
                 }
-                OrderValidationResults::Invalid(e) => Err(format!("Invalid order: {}", e)),
+                OrderValidationResults::Invalid(_, rejection) => {
+                    Err(format!("Invalid order: {}", rejection.message))
+                }
                 OrderValidationResults::TransitionedToBlock => {
                     Err("Order transitioned to block".to_string())
                 }
+                OrderValidationResults::PoolNotSynced { pool_id, .. } => {
+                    Err(format!("Pool {pool_id:?} isn't synced yet"))
+                }
+                OrderValidationResults::PoolNotInitialized { init, .. } => {
+                    Err(format!("Pool {:?} isn't initialized yet", init.pool_id))
+                }
             }
         })
     }
+
+    fn simulate_order(&self, order: AllOrders) -> SimulationFuture {
+        Box::pin(async move {
+            let required_balance = U256::from(order.amount_in());
+            let (tx, rx) = channel();
+            let _ = self
+                .0
+                .send(ValidationRequest::Simulate { sender: tx, order, at_block: None });
+
+            rx.await.unwrap_or_else(|_| {
+                SimulationResult::rejected(required_balance, validator_unavailable().message)
+            })
+        })
+    }
+
+    fn simulate_order_at_block(&self, order: AllOrders, block: u64) -> SimulationFuture {
+        Box::pin(async move {
+            let required_balance = U256::from(order.amount_in());
+            let (tx, rx) = channel();
+            let _ = self.0.send(ValidationRequest::Simulate {
+                sender: tx,
+                order,
+                at_block: Some(block)
+            });
+
+            rx.await.unwrap_or_else(|_| {
+                SimulationResult::rejected(required_balance, validator_unavailable().message)
+            })
+        })
+    }
+
+    fn trace_order(&self, order: AllOrders) -> TraceFuture {
+        Box::pin(async move {
+            let (tx, rx) = channel();
+            let _ = self.0.send(ValidationRequest::Trace { sender: tx, order });
+
+            rx.await.unwrap_or_else(|_| ValidationTrace {
+                steps:     vec![],
+                rejection: Some(validator_unavailable())
+            })
+        })
+    }
+
+    fn next_nonce(&self, user: Address) -> NonceFuture {
+        Box::pin(async move {
+            let (tx, rx) = channel();
+            let _ = self
+                .0
+                .send(ValidationRequest::NextNonce { sender: tx, user });
+
+            rx.await.ok()
+        })
+    }
+
+    fn prewarm(&self, addresses: Vec<Address>, tokens: Vec<Address>) {
+        let _ = self
+            .0
+            .send(ValidationRequest::Prewarm { addresses, tokens });
+    }
+
+    fn cancel_order(&self, user: Address, order_hash: B256) {
+        let _ = self
+            .0
+            .send(ValidationRequest::CancelOrder { user, order_hash });
+    }
+}
+
+/// answer to requests the validator dropped, because it shut down or its
+/// thread pool was closed
+fn validator_unavailable() -> Rejection {
+    Rejection::new(RejectionReason::Unavailable, "the validator is shutting down")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use alloy::primitives::{Address, Bytes, U256};
+    use angstrom_types::{
+        matching::Ray,
+        orders::OrderOrigin,
+        pair_with_price::PairsWithPrice,
+        primitive::PoolId,
+        sol_bindings::grouped_orders::{AllOrders, FlashVariants, StandingVariants}
+    };
+    use testing_tools::type_generator::orders::{
+        StoredOrderBuilder, ToBOrderBuilder, UserOrderBuilder
+    };
+    use tokio::sync::{mpsc::unbounded_channel, oneshot::channel};
+
+    use super::{
+        GroupedVanillaOrder, OrderValidation, OrderValidationRequest, OrderValidationResults,
+        OrderValidatorHandle, RawPoolOrder
+    };
+    use crate::{
+        common::TokenPriceGenerator,
+        order::{
+            rejection::RejectionReason,
+            state::config::{MissingPricePolicy, ValidationConfig}
+        },
+        validator::{ValidationClient, ValidationRequest}
+    };
+
+    fn to_validation(order: AllOrders) -> OrderValidation {
+        let (tx, _) = channel();
+        OrderValidationRequest::ValidateOrder(tx, order, OrderOrigin::External, Instant::now())
+            .into()
+    }
+
+    fn with_hook(order: GroupedVanillaOrder) -> AllOrders {
+        let hook = Bytes::from_static(&[0xde, 0xad, 0xbe, 0xef]);
+        match order {
+            GroupedVanillaOrder::Standing(StandingVariants::Exact(mut o)) => {
+                o.hook_data = hook;
+                AllOrders::Standing(StandingVariants::Exact(o))
+            }
+            GroupedVanillaOrder::Standing(StandingVariants::Partial(mut o)) => {
+                o.hook_data = hook;
+                AllOrders::Standing(StandingVariants::Partial(o))
+            }
+            GroupedVanillaOrder::KillOrFill(FlashVariants::Exact(mut o)) => {
+                o.hook_data = hook;
+                AllOrders::Flash(FlashVariants::Exact(o))
+            }
+            GroupedVanillaOrder::KillOrFill(FlashVariants::Partial(mut o)) => {
+                o.hook_data = hook;
+                AllOrders::Flash(FlashVariants::Partial(o))
+            }
+        }
+    }
+
+    #[test]
+    fn routes_orders_without_hooks_to_limit() {
+        let standing = UserOrderBuilder::new().standing().build();
+        let flash = UserOrderBuilder::new().kill_or_fill().build();
+
+        for order in [standing, flash] {
+            let order: AllOrders = order.into();
+            assert!(matches!(to_validation(order), OrderValidation::Limit(..)));
+        }
+    }
+
+    #[test]
+    fn routes_orders_with_hooks_to_limit_composable() {
+        let standing = UserOrderBuilder::new().standing().build();
+        let flash = UserOrderBuilder::new().kill_or_fill().build();
+
+        for order in [standing, flash] {
+            assert!(matches!(
+                to_validation(with_hook(order)),
+                OrderValidation::LimitComposable(..)
+            ));
+        }
+    }
+
+    #[test]
+    fn routes_top_of_block_orders_to_searcher() {
+        let order = AllOrders::TOB(ToBOrderBuilder::new().build());
+        assert!(matches!(to_validation(order), OrderValidation::Searcher(..)));
+    }
+
+    /// a valid order of `amount` in the pool, selling token1 if it's a bid
+    fn valid_order(pool_id: PoolId, amount: u128, is_bid: bool) -> OrderValidationResults {
+        let order = UserOrderBuilder::new().standing().exact().amount(amount);
+        let order = StoredOrderBuilder::from_builder(order)
+            .is_bid(is_bid)
+            .pool_id(pool_id)
+            .build()
+            .try_map_inner(|order| Ok(order.into()))
+            .unwrap();
+
+        OrderValidationResults::Valid(order)
+    }
+
+    fn rejection_code(results: &OrderValidationResults) -> Option<RejectionReason> {
+        match results {
+            OrderValidationResults::Invalid(_, rejection) => Some(rejection.code),
+            _ => None
+        }
+    }
+
+    #[test]
+    fn min_notional_values_asks_at_the_pool_price() {
+        let pool_id = PoolId::random();
+        let mut config = ValidationConfig::default();
+        config.min_notional.insert(pool_id, U256::from(1_000));
+
+        // token0 trades at 2 token1
+        let price = PairsWithPrice {
+            token0:         Address::random(),
+            token1:         Address::random(),
+            price_1_over_0: *Ray::from(2.0),
+            block_num:      0
+        };
+        let prices = TokenPriceGenerator::with_prices(vec![(pool_id, price)]);
+
+        // bids already sell token1
+        let mut bid = valid_order(pool_id, 999, true);
+        bid.check_min_notional(&config, &prices);
+        assert_eq!(rejection_code(&bid), Some(RejectionReason::BelowMinNotional));
+
+        let mut bid = valid_order(pool_id, 1_000, true);
+        bid.check_min_notional(&config, &prices);
+        assert!(matches!(bid, OrderValidationResults::Valid(_)));
+
+        // 500 token0 are worth 1000 token1 at the pool's price, whatever the
+        // order's own limit price
+        let mut ask = valid_order(pool_id, 499, false);
+        ask.check_min_notional(&config, &prices);
+        assert_eq!(rejection_code(&ask), Some(RejectionReason::BelowMinNotional));
+
+        let mut ask = valid_order(pool_id, 500, false);
+        ask.check_min_notional(&config, &prices);
+        assert!(matches!(ask, OrderValidationResults::Valid(_)));
+
+        // pools without a minimum aren't checked
+        let mut other = valid_order(PoolId::random(), 1, true);
+        other.check_min_notional(&config, &prices);
+        assert!(matches!(other, OrderValidationResults::Valid(_)));
+    }
+
+    #[test]
+    fn min_notional_of_unpriced_asks_follows_the_missing_price_policy() {
+        let pool_id = PoolId::random();
+        let mut config = ValidationConfig::default();
+        config.min_notional.insert(pool_id, U256::from(1_000));
+        let prices = TokenPriceGenerator::default();
+
+        let mut ask = valid_order(pool_id, 10_000, false);
+        ask.check_min_notional(&config, &prices);
+        assert_eq!(rejection_code(&ask), Some(RejectionReason::NoPriceForToken));
+
+        config.missing_price = MissingPricePolicy::Admit;
+        let mut ask = valid_order(pool_id, 10_000, false);
+        ask.check_min_notional(&config, &prices);
+        assert!(matches!(ask, OrderValidationResults::Valid(_)));
+    }
+
+    #[tokio::test]
+    async fn requests_the_validator_drops_are_answered_as_unavailable() {
+        let (tx, mut rx) = unbounded_channel();
+        // drops every request unanswered, as the validator does once its
+        // thread pool is closed
+        tokio::spawn(async move { while rx.recv().await.is_some() {} });
+        let client = ValidationClient(tx);
+
+        let order: AllOrders = UserOrderBuilder::new().standing().build().into();
+        let order_hash = order.order_hash();
+        match client
+            .validate_order(OrderOrigin::External, order.clone())
+            .await
+        {
+            OrderValidationResults::Invalid(hash, rejection) => {
+                assert_eq!(hash, order_hash);
+                assert_eq!(rejection.code, RejectionReason::Unavailable);
+            }
+            other => panic!("expected the order to be rejected, got {other:?}")
+        }
+
+        let simulation = client.simulate_order(order.clone()).await;
+        assert!(!simulation.would_validate);
+        assert!(simulation.rejection_reason.is_some());
+
+        let trace = client.trace_order(order).await;
+        assert_eq!(trace.rejection.map(|r| r.code), Some(RejectionReason::Unavailable));
+        assert_eq!(client.next_nonce(Address::random()).await, None);
+    }
+
+    #[tokio::test]
+    async fn orders_are_stamped_when_sent() {
+        let (tx, mut rx) = unbounded_channel();
+        let client = ValidationClient(tx);
+        let order: AllOrders = UserOrderBuilder::new().standing().build().into();
+
+        let before = Instant::now();
+        // nothing answers, the request waits in the channel until it's read
+        let wait = Duration::from_millis(20);
+        let validation = client.validate_order(OrderOrigin::External, order);
+        assert!(tokio::time::timeout(wait, validation).await.is_err());
+
+        let Some(ValidationRequest::Order(request)) = rx.recv().await else {
+            panic!("the order is sent to the validator")
+        };
+        assert!(request.sent() >= before);
+        assert!(request.sent().elapsed() >= wait);
+    }
+
+    #[tokio::test]
+    async fn requests_after_the_validator_stopped_are_answered() {
+        let (tx, rx) = unbounded_channel();
+        drop(rx);
+        let client = ValidationClient(tx);
+
+        let order: AllOrders = UserOrderBuilder::new().standing().build().into();
+        assert_eq!(
+            rejection_code(&client.validate_order(OrderOrigin::External, order).await),
+            Some(RejectionReason::Unavailable)
+        );
+        assert!(matches!(
+            client.new_block(1, vec![], vec![]).await,
+            OrderValidationResults::TransitionedToBlock
+        ));
+        assert_eq!(client.next_nonce(Address::random()).await, None);
+    }
 }
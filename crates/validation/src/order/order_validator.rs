@@ -1,31 +1,55 @@
 use std::{
     pin::Pin,
-    sync::{atomic::AtomicU64, Arc}
+    sync::{atomic::AtomicU64, Arc},
+    time::Instant
 };
 
-use alloy::primitives::{Address, BlockNumber, B256};
-use angstrom_utils::key_split_threadpool::KeySplitThreadpool;
+use alloy::primitives::{Address, BlockNumber, B256, U256};
+use angstrom_metrics::ValidationMetricsWrapper;
+use angstrom_types::sol_bindings::{ext::RawPoolOrder, grouped_orders::AllOrders};
+use angstrom_utils::key_split_threadpool::{KeySplitThreadpool, PoolClosed};
 use futures::Future;
 use tokio::runtime::Handle;
+use tracing::{field, Instrument};
 use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 
 use super::{
+    result_cache::ValidationResultCache,
     sim::SimValidation,
     state::{
-        account::user::UserAddress, db_state_utils::StateFetchUtils, pools::PoolsTracker,
+        account::user::UserAddress,
+        config::ValidationConfig,
+        db_state_utils::{FetchUtils, StateFetchUtils},
+        pools::PoolsTracker,
         StateValidation
     },
+    trace::{ValidationStep, ValidationTrace},
     OrderValidationRequest
 };
 use crate::{
-    common::TokenPriceGenerator,
-    order::{state::account::UserAccountProcessor, OrderValidation}
+    common::{Clock, FixedClock, HistoricalStateFactory, TokenPriceGenerator},
+    order::{
+        state::account::UserAccountProcessor, OrderValidation, OrderValidationResults,
+        SimulationResult
+    }
 };
 
 pub struct OrderValidator<DB, Pools, Fetch> {
     sim:          SimValidation<DB>,
     state:        StateValidation<Pools, Fetch>,
-    block_number: Arc<AtomicU64>
+    block_number: Arc<AtomicU64>,
+    result_cache: ValidationResultCache,
+    config:       Arc<ValidationConfig>,
+    /// unset if the node can't open the state of past blocks
+    historical:   Option<HistoricalValidation>,
+    metrics:      ValidationMetricsWrapper
+}
+
+/// What validating an order against the state of a past block needs.
+#[derive(Clone)]
+struct HistoricalValidation {
+    state:            Arc<dyn HistoricalStateFactory>,
+    angstrom_address: Address
 }
 
 impl<DB, Pools, Fetch> OrderValidator<DB, Pools, Fetch>
@@ -40,11 +64,43 @@ where
         block_number: Arc<AtomicU64>,
         pools: Pools,
         fetch: Fetch,
-        uniswap_pools: SyncedUniswapPools
+        uniswap_pools: SyncedUniswapPools,
+        config: ValidationConfig,
+        clock: Arc<dyn Clock>
+    ) -> Self {
+        let result_cache = ValidationResultCache::new(config.result_cache.effective_capacity());
+        let state = StateValidation::new(
+            UserAccountProcessor::new(fetch)
+                .with_config(config.clone())
+                .with_clock(clock),
+            pools,
+            uniswap_pools
+        );
+
+        Self {
+            state,
+            sim,
+            block_number,
+            result_cache,
+            config: Arc::new(config),
+            historical: None,
+            metrics: ValidationMetricsWrapper::new()
+        }
+    }
+
+    /// lets orders be simulated against the state of a past block, see
+    /// [`Self::simulate_order`]
+    pub fn with_historical_state(
+        mut self,
+        state: Arc<dyn HistoricalStateFactory>,
+        angstrom_address: Address
     ) -> Self {
-        let state = StateValidation::new(UserAccountProcessor::new(fetch), pools, uniswap_pools);
+        self.historical = Some(HistoricalValidation { state, angstrom_address });
+        self
+    }
 
-        Self { state, sim, block_number }
+    pub fn pools_populated(&self) -> bool {
+        self.state.pools_populated()
     }
 
     pub fn on_new_block(
@@ -55,10 +111,12 @@ where
     ) {
         self.block_number
             .store(block_number, std::sync::atomic::Ordering::SeqCst);
+        self.result_cache.clear();
         self.state.new_block(completed_orders, address_changes);
     }
 
-    /// only checks state
+    /// only checks state. The time from when the request was sent until
+    /// validation starts is recorded as its wait
     pub fn validate_order(
         &mut self,
         order: OrderValidationRequest,
@@ -68,32 +126,368 @@ where
             Pin<Box<dyn Future<Output = ()> + Send>>,
             Handle
         >
-    ) {
+    ) -> Result<(), PoolClosed> {
         let block_number = self.block_number.load(std::sync::atomic::Ordering::SeqCst);
+        let sent = order.sent();
         let order_validation: OrderValidation = order.into();
+        let order_hash = order_validation.order_hash();
+        // the outcome can't change until the next block so re-validations are
+        // answered from the cache
+        if let Some(results) = self.result_cache.get(order_hash, block_number) {
+            order_validation.respond(results);
+            return Ok(())
+        }
+
         let user = order_validation.user();
+        let result_cache = self.result_cache.clone();
         let cloned_state = self.state.clone();
         let cloned_sim = self.sim.clone();
+        let config = self.config.clone();
+        let metrics = self.metrics.clone();
+
+        // every log line emitted while validating the order is tagged with it
+        let span = tracing::debug_span!(
+            "validate_order",
+            order_hash = %order_hash,
+            sender = %user,
+            block_number,
+            pool_id = field::Empty,
+            outcome = field::Empty
+        );
+
+        thread_pool.add_new_task(
+            user,
+            Box::pin(
+                async move {
+                    let started = Instant::now();
+                    metrics.observe_queue_wait(started.saturating_duration_since(sent));
+
+                    let (tx, mut results, is_limit) = match order_validation {
+                        OrderValidation::Limit(tx, order, _) => {
+                            (tx, cloned_state.handle_regular_order(order, block_number), true)
+                        }
+                        OrderValidation::LimitComposable(tx, order, _) => {
+                            (tx, cloned_state.handle_regular_order(order, block_number), true)
+                        }
+                        OrderValidation::Searcher(tx, order, _) => {
+                            (tx, cloned_state.handle_regular_order(order, block_number), false)
+                        }
+                    };
+                    results.check_min_notional(&config, &token_conversion);
+                    results.add_gas_cost_or_invalidate(&cloned_sim, &token_conversion, is_limit);
+
+                    let span = tracing::Span::current();
+                    match &results {
+                        OrderValidationResults::Valid(order) => {
+                            span.record("pool_id", field::display(order.pool_id));
+                            span.record("outcome", "valid");
+                        }
+                        OrderValidationResults::PoolNotSynced { pool_id, .. } => {
+                            span.record("pool_id", field::display(pool_id));
+                            span.record("outcome", "pool not synced");
+                        }
+                        OrderValidationResults::PoolNotInitialized { init, .. } => {
+                            span.record("pool_id", field::display(init.pool_id));
+                            span.record("outcome", "pool not initialized");
+                        }
+                        _ => {
+                            span.record("outcome", "invalid");
+                        }
+                    }
+                    tracing::debug!("order validated");
+
+                    // a valid order takes from its user's balances
+                    if matches!(results, OrderValidationResults::Valid(_)) {
+                        result_cache.invalidate_user(user);
+                    }
+                    result_cache.insert(order_hash, block_number, &results);
+                    metrics.observe_processing_time(started.elapsed());
+                    let _ = tx.send(results);
+                }
+                .instrument(span)
+            )
+        )
+    }
+
+    /// The order was cancelled. Its user's cached outcomes are dropped right
+    /// away, what the order reserved is freed in line with their validations.
+    pub fn cancel_order(
+        &self,
+        user: Address,
+        order_hash: B256,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        self.result_cache.invalidate_user(user);
+        let result_cache = self.result_cache.clone();
+        let cloned_state = self.state.clone();
 
         thread_pool.add_new_task(
             user,
             Box::pin(async move {
-                match order_validation {
-                    OrderValidation::Limit(tx, order, _) => {
-                        let mut results = cloned_state.handle_regular_order(order, block_number);
-                        results.add_gas_cost_or_invalidate(&cloned_sim, &token_conversion, true);
+                let freed = cloned_state.cancel_order(user, &order_hash);
+                // validations that finished since the cancel came in cached
+                // outcomes against the reserved balance
+                result_cache.invalidate_user(user);
+                tracing::trace!(?user, ?order_hash, freed, "cancelled order");
+            })
+        )
+    }
+
+    /// queued on the users key so the nonce accounts for all of their orders
+    /// that were sent for validation before it.
+    pub fn next_valid_nonce(
+        &self,
+        sender: tokio::sync::oneshot::Sender<u64>,
+        user: Address,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        let cloned_state = self.state.clone();
 
-                        let _ = tx.send(results);
+        thread_pool.add_new_task(
+            user,
+            Box::pin(async move {
+                let _ = sender.send(cloned_state.next_valid_nonce(user));
+            })
+        )
+    }
+
+    /// loads the account state of every address for the tokens ahead of their
+    /// orders. Queued per address, so it never overtakes an order of theirs
+    /// that is already waiting.
+    pub fn prewarm(
+        &self,
+        addresses: Vec<Address>,
+        tokens: Vec<Address>,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        let tokens: Arc<[Address]> = tokens.into();
+        for user in addresses {
+            let cloned_state = self.state.clone();
+            let tokens = tokens.clone();
+
+            thread_pool.add_new_task(
+                user,
+                Box::pin(async move {
+                    let loaded = cloned_state.prewarm(user, &tokens);
+                    tracing::trace!(?user, loaded, "prewarmed account state");
+                })
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// runs the same checks as [`Self::validate_order`] plus the gas
+    /// simulation, without inserting the order into the account state. With
+    /// `at_block` set the order is checked against that block instead of the
+    /// tip.
+    pub fn simulate_order(
+        &mut self,
+        sender: tokio::sync::oneshot::Sender<SimulationResult>,
+        order: AllOrders,
+        at_block: Option<u64>,
+        token_conversion: TokenPriceGenerator,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        if let Some(at_block) = at_block {
+            return self.simulate_order_at_block(sender, order, at_block, thread_pool)
+        }
+
+        let block_number = self.block_number.load(std::sync::atomic::Ordering::SeqCst);
+        let user = order.from();
+        let cloned_state = self.state.clone();
+        let cloned_sim = self.sim.clone();
+        let config = self.config.clone();
+
+        thread_pool.add_new_task(
+            user,
+            Box::pin(async move {
+                let required_balance = U256::from(order.amount_in());
+                let (is_limit, is_composable) = match &order {
+                    AllOrders::Standing(o) => (true, !o.hook_data().is_empty()),
+                    AllOrders::Flash(o) => (true, !o.hook_data().is_empty()),
+                    AllOrders::TOB(_) => (false, false)
+                };
+
+                let result = match cloned_state.simulate_regular_order(order, block_number) {
+                    Ok(order) => {
+                        let mut results = OrderValidationResults::Valid(order);
+                        results.check_min_notional(&config, &token_conversion);
+                        results.add_gas_cost_or_invalidate(
+                            &cloned_sim,
+                            &token_conversion,
+                            is_limit
+                        );
+
+                        match results {
+                            OrderValidationResults::Valid(order) => SimulationResult {
+                                would_validate: true,
+                                estimated_fill: if order.is_currently_valid {
+                                    order.amount_in()
+                                } else {
+                                    0
+                                },
+                                required_balance,
+                                hook_gas: is_composable.then_some(order.priority_data.gas_units),
+                                rejection_reason: None
+                            },
+                            OrderValidationResults::Invalid(_, rejection) => {
+                                SimulationResult::rejected(required_balance, rejection.message)
+                            }
+                            _ => SimulationResult::rejected(
+                                required_balance,
+                                "failed to simulate the gas of the order"
+                            )
+                        }
+                    }
+                    Err(reason) => SimulationResult::rejected(required_balance, reason)
+                };
+
+                let _ = sender.send(result);
+            })
+        )
+    }
+
+    /// dry run of the order, like [`Self::simulate_order`], that reports which
+    /// checks the order passed and the one it was rejected at
+    pub fn trace_order(
+        &mut self,
+        sender: tokio::sync::oneshot::Sender<ValidationTrace>,
+        order: AllOrders,
+        token_conversion: TokenPriceGenerator,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        let block_number = self.block_number.load(std::sync::atomic::Ordering::SeqCst);
+        let user = order.from();
+        let cloned_state = self.state.clone();
+        let cloned_sim = self.sim.clone();
+        let config = self.config.clone();
+
+        thread_pool.add_new_task(
+            user,
+            Box::pin(async move {
+                let is_limit = !matches!(order, AllOrders::TOB(_));
+                let (mut trace, order) = cloned_state.trace_regular_order(order, block_number);
+
+                if let Some(order) = order {
+                    let mut results = OrderValidationResults::Valid(order);
+                    results.check_min_notional(&config, &token_conversion);
+                    match &results {
+                        OrderValidationResults::Valid(order)
+                            if config.min_notional.contains_key(&order.pool_id) =>
+                        {
+                            trace.record(ValidationStep::Notional, true, "passed")
+                        }
+                        OrderValidationResults::Invalid(_, rejection) => {
+                            trace.reject(ValidationStep::Notional, rejection.clone());
+                            let _ = sender.send(trace);
+                            return
+                        }
+                        _ => {}
                     }
-                    OrderValidation::Searcher(tx, order, _) => {
-                        let mut results = cloned_state.handle_regular_order(order, block_number);
-                        results.add_gas_cost_or_invalidate(&cloned_sim, &token_conversion, false);
+                    results.add_gas_cost_or_invalidate(&cloned_sim, &token_conversion, is_limit);
 
-                        let _ = tx.send(results);
+                    match results {
+                        OrderValidationResults::Valid(order) => trace.record(
+                            ValidationStep::Gas,
+                            true,
+                            format!(
+                                "{} gas units, {} of token0",
+                                order.priority_data.gas_units, order.priority_data.gas
+                            )
+                        ),
+                        OrderValidationResults::Invalid(_, rejection) => {
+                            trace.reject(ValidationStep::Gas, rejection)
+                        }
+                        _ => {}
                     }
-                    _ => unreachable!()
                 }
+
+                let _ = sender.send(trace);
             })
-        );
+        )
+    }
+
+    /// checks the order against the account state as of `block`, deadlines
+    /// against its timestamp. Gas and the minimum notional aren't checked as
+    /// both only run on the latest state and prices, and neither the tracked
+    /// accounts nor the pool see the order.
+    fn simulate_order_at_block(
+        &self,
+        sender: tokio::sync::oneshot::Sender<SimulationResult>,
+        order: AllOrders,
+        block: u64,
+        thread_pool: &mut KeySplitThreadpool<
+            UserAddress,
+            Pin<Box<dyn Future<Output = ()> + Send>>,
+            Handle
+        >
+    ) -> Result<(), PoolClosed> {
+        let required_balance = U256::from(order.amount_in());
+        let Some(historical) = self.historical.clone() else {
+            let _ = sender.send(SimulationResult::rejected(
+                required_balance,
+                "validating at a past block isn't supported by this node"
+            ));
+            return Ok(())
+        };
+        let user = order.from();
+        let config = self.config.clone();
+        let cloned_state = self.state.clone();
+
+        thread_pool.add_new_task(
+            user,
+            Box::pin(async move {
+                let result = match historical.state.state_at(block) {
+                    Ok((db, timestamp)) => {
+                        let fetch = FetchUtils::new(historical.angstrom_address, Arc::new(db));
+                        let accounts = UserAccountProcessor::new(fetch)
+                            .with_config((*config).clone())
+                            .with_clock(Arc::new(FixedClock(timestamp)));
+
+                        match cloned_state.simulate_order_against(&accounts, order, block) {
+                            Ok(order) => SimulationResult {
+                                would_validate: true,
+                                estimated_fill: if order.is_currently_valid {
+                                    order.amount_in()
+                                } else {
+                                    0
+                                },
+                                required_balance,
+                                hook_gas: None,
+                                rejection_reason: None
+                            },
+                            Err(reason) => SimulationResult::rejected(required_balance, reason)
+                        }
+                    }
+                    Err(e) => SimulationResult::rejected(
+                        required_balance,
+                        format!("no state for block {block}: {e}")
+                    )
+                };
+
+                let _ = sender.send(result);
+            })
+        )
     }
 }
@@ -0,0 +1,263 @@
+use angstrom_types::sol_bindings::ext::RawPoolOrder;
+use serde::{Deserialize, Serialize};
+
+use super::state::account::UserAccountVerificationError;
+
+/// Stable, machine readable code of why an order was turned away. Clients
+/// branch on the code, the variant names are part of the rpc api and must
+/// not be renamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RejectionReason {
+    /// the signature doesn't recover to the order's signer
+    InvalidSignature,
+    /// the signature has a high s value, the same signature with a low s is
+    /// accepted
+    NonCanonicalSignature,
+    /// the order's pair has no angstrom pool
+    NoPool,
+    /// the order is malformed on its own, e.g. it asks for nothing
+    Malformed,
+    /// the order was validated against a block that is no longer current,
+    /// it can be submitted again
+    StaleBlock,
+    /// the order or its nonce was cancelled
+    Cancelled,
+    /// another pending order of the sender uses the nonce
+    DuplicateNonce,
+    /// the order is already in the pool or was rejected before
+    DuplicateOrder,
+    /// a flash order for another block than the current one
+    BadBlock,
+    Expired,
+    /// the order has too little time left before its deadline to be matched
+    DeadlineTooSoon,
+    DeadlineTooFar,
+    BelowMinNotional,
+    /// the order uses a token that is denied or outside the allowlist
+    TokenNotPermitted,
+    /// a token of the order takes a fee on transfer, or its fee couldn't be
+    /// measured
+    UnsupportedTransferTax,
+    HookTargetNotPermitted,
+    HookDataTooLarge,
+    /// the pool hasn't synced yet, the order can be submitted again later
+    PoolNotSynced,
+    /// the order's uniswap pool isn't initialized and the order couldn't be
+    /// held until it is, it can be submitted again once the pool is
+    PoolNotInitialized,
+    PriceTooFarFromMarket,
+    /// no price is known for the order's token, so its gas or notional
+    /// can't be valued
+    NoPriceForToken,
+    /// simulating the order's gas failed
+    SimulationFailed,
+    /// an operator paused the pool
+    PoolPaused,
+    /// the pool holds as many orders as it may
+    PoolFull,
+    /// the operator's acceptance policy doesn't allow the sender
+    SenderNotAllowed,
+    /// the operator's acceptance policy turned the order away
+    PolicyRejected,
+    /// the order hasn't rested for its pool's minimum lifetime, the
+    /// cancellation can be sent again later
+    TooSoonToCancel,
+    /// the node couldn't process the order, e.g. because it is shutting down
+    Unavailable
+}
+
+/// Why an order was turned away, as returned over rpc.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rejection {
+    pub code:    RejectionReason,
+    /// human readable description, not meant to be parsed
+    pub message: String
+}
+
+impl Rejection {
+    pub fn new(code: RejectionReason, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+impl<O: RawPoolOrder> From<&UserAccountVerificationError<O>> for Rejection {
+    fn from(error: &UserAccountVerificationError<O>) -> Self {
+        let code = match error {
+            UserAccountVerificationError::Malformed { .. } => RejectionReason::Malformed,
+            UserAccountVerificationError::BlockMissMatch { .. } => RejectionReason::StaleBlock,
+            UserAccountVerificationError::OrderIsCancelled(_) => RejectionReason::Cancelled,
+            UserAccountVerificationError::DuplicateNonce(_) => RejectionReason::DuplicateNonce,
+            UserAccountVerificationError::BadBlock => RejectionReason::BadBlock,
+            UserAccountVerificationError::Expired { .. } => RejectionReason::Expired,
+            UserAccountVerificationError::DeadlineTooSoon { .. } => {
+                RejectionReason::DeadlineTooSoon
+            }
+            UserAccountVerificationError::DeadlineTooFar { .. } => RejectionReason::DeadlineTooFar,
+            UserAccountVerificationError::BelowMinNotional { .. } => {
+                RejectionReason::BelowMinNotional
+            }
+            UserAccountVerificationError::TokenNotPermitted { .. } => {
+                RejectionReason::TokenNotPermitted
+            }
+            UserAccountVerificationError::UnsupportedTransferTax { .. }
+            | UserAccountVerificationError::UnknownTransferTax { .. } => {
+                RejectionReason::UnsupportedTransferTax
+            }
+            UserAccountVerificationError::HookTargetNotPermitted { .. } => {
+                RejectionReason::HookTargetNotPermitted
+            }
+            UserAccountVerificationError::HookDataTooLarge { .. } => {
+                RejectionReason::HookDataTooLarge
+            }
+            UserAccountVerificationError::PoolNotSynced(_) => RejectionReason::PoolNotSynced,
+            UserAccountVerificationError::PriceTooFarFromMarket { .. } => {
+                RejectionReason::PriceTooFarFromMarket
+            }
+            UserAccountVerificationError::NoPriceForToken { .. }
+            | UserAccountVerificationError::NoPoolPrice { .. } => RejectionReason::NoPriceForToken,
+            UserAccountVerificationError::NonCanonicalSignature(_) => {
+                RejectionReason::NonCanonicalSignature
+            }
+        };
+
+        Self::new(code, error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::{Address, B256, U256};
+    use angstrom_types::{
+        primitive::PoolId,
+        sol_bindings::{grouped_orders::GroupedVanillaOrder, StaticOrderError}
+    };
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+    use crate::order::state::pools::UserOrderPoolInfo;
+
+    #[test]
+    fn every_verification_error_maps_to_its_code() {
+        let (order_hash, token, value) = (B256::random(), Address::random(), U256::from(1));
+        let order: GroupedVanillaOrder = UserOrderBuilder::new().standing().build();
+        let pool_info = UserOrderPoolInfo { token, is_bid: true, pool_id: PoolId::random() };
+
+        let cases: Vec<(UserAccountVerificationError<GroupedVanillaOrder>, RejectionReason)> = vec![
+            (
+                UserAccountVerificationError::Malformed {
+                    order_hash,
+                    error: StaticOrderError::ZeroAmountOut
+                },
+                RejectionReason::Malformed
+            ),
+            (
+                UserAccountVerificationError::BlockMissMatch {
+                    requested: 1,
+                    current: 2,
+                    order,
+                    pool_info
+                },
+                RejectionReason::StaleBlock
+            ),
+            (
+                UserAccountVerificationError::OrderIsCancelled(order_hash),
+                RejectionReason::Cancelled
+            ),
+            (
+                UserAccountVerificationError::DuplicateNonce(order_hash),
+                RejectionReason::DuplicateNonce
+            ),
+            (UserAccountVerificationError::BadBlock, RejectionReason::BadBlock),
+            (
+                UserAccountVerificationError::Expired { order_hash, deadline: value },
+                RejectionReason::Expired
+            ),
+            (
+                UserAccountVerificationError::DeadlineTooSoon {
+                    order_hash,
+                    deadline: value,
+                    min_deadline: value
+                },
+                RejectionReason::DeadlineTooSoon
+            ),
+            (
+                UserAccountVerificationError::DeadlineTooFar {
+                    order_hash,
+                    deadline: value,
+                    max_deadline: value
+                },
+                RejectionReason::DeadlineTooFar
+            ),
+            (
+                UserAccountVerificationError::BelowMinNotional {
+                    order_hash,
+                    notional: value,
+                    min_notional: value
+                },
+                RejectionReason::BelowMinNotional
+            ),
+            (
+                UserAccountVerificationError::TokenNotPermitted { order_hash, token },
+                RejectionReason::TokenNotPermitted
+            ),
+            (
+                UserAccountVerificationError::UnsupportedTransferTax {
+                    order_hash,
+                    token,
+                    tax_bps: 1
+                },
+                RejectionReason::UnsupportedTransferTax
+            ),
+            (
+                UserAccountVerificationError::UnknownTransferTax { order_hash, token },
+                RejectionReason::UnsupportedTransferTax
+            ),
+            (
+                UserAccountVerificationError::HookTargetNotPermitted { order_hash, target: None },
+                RejectionReason::HookTargetNotPermitted
+            ),
+            (
+                UserAccountVerificationError::HookDataTooLarge { order_hash, size: 2, max_size: 1 },
+                RejectionReason::HookDataTooLarge
+            ),
+            (
+                UserAccountVerificationError::PoolNotSynced(PoolId::random()),
+                RejectionReason::PoolNotSynced
+            ),
+            (
+                UserAccountVerificationError::PriceTooFarFromMarket {
+                    order_hash,
+                    price: value,
+                    market_price: value
+                },
+                RejectionReason::PriceTooFarFromMarket
+            ),
+            (
+                UserAccountVerificationError::NoPriceForToken { order_hash, token },
+                RejectionReason::NoPriceForToken
+            ),
+            (
+                UserAccountVerificationError::NoPoolPrice { order_hash, pool_id: PoolId::random() },
+                RejectionReason::NoPriceForToken
+            ),
+            (
+                UserAccountVerificationError::NonCanonicalSignature(order_hash),
+                RejectionReason::NonCanonicalSignature
+            ),
+        ];
+
+        for (error, code) in cases {
+            let rejection = Rejection::from(&error);
+            assert_eq!(rejection.code, code);
+            assert_eq!(rejection.message, error.to_string());
+        }
+    }
+
+    #[test]
+    fn codes_serialize_as_their_name() {
+        let rejection = Rejection::new(RejectionReason::PoolNotSynced, "pool isn't synced");
+        let json = serde_json::to_value(&rejection).unwrap();
+        assert_eq!(json["code"], "PoolNotSynced");
+        assert_eq!(serde_json::from_value::<Rejection>(json).unwrap(), rejection);
+    }
+}
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use alloy::primitives::{Address, B256};
+use angstrom_metrics::ValidationMetricsWrapper;
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+
+use super::OrderValidationResults;
+
+/// Caches the orders found valid in the current block.
+///
+/// Unlike the [`SignatureCache`](super::state::signature_cache::SignatureCache)
+/// the outcome depends on chain and account state, so entries are only valid
+/// for the block they were computed in. They are keyed by block and the cache
+/// is cleared whenever a new block is processed. A user's entries are dropped
+/// as soon as their account changes within the block, e.g. through a cancel.
+/// Rejections aren't cached, they may not hold once the account changes.
+#[derive(Clone)]
+pub struct ValidationResultCache {
+    results: Option<Arc<Mutex<LruMap<(B256, u64), OrderValidationResults, ByLength>>>>,
+    metrics: ValidationMetricsWrapper
+}
+
+impl ValidationResultCache {
+    /// a capacity of 0 disables the cache
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            results: (capacity != 0)
+                .then(|| Arc::new(Mutex::new(LruMap::new(ByLength::new(capacity))))),
+            metrics: ValidationMetricsWrapper::new()
+        }
+    }
+
+    /// the outcome of validating the order earlier in `block`
+    pub fn get(&self, order_hash: B256, block: u64) -> Option<OrderValidationResults> {
+        let results = self.results.as_ref()?;
+        let cached = results.lock().get(&(order_hash, block)).cloned();
+        match cached {
+            Some(_) => self.metrics.incr_result_cache_hits(),
+            None => self.metrics.incr_result_cache_misses()
+        }
+
+        cached
+    }
+
+    /// caches `results` if the order is valid
+    pub fn insert(&self, order_hash: B256, block: u64, results: &OrderValidationResults) {
+        let Some(cache) = self.results.as_ref() else { return };
+        if !matches!(results, OrderValidationResults::Valid(_)) {
+            return
+        }
+
+        cache.lock().insert((order_hash, block), results.clone());
+    }
+
+    /// drops the outcomes of `user`'s orders, their account changed
+    pub fn invalidate_user(&self, user: Address) {
+        let Some(results) = self.results.as_ref() else { return };
+        let mut results = results.lock();
+        let stale = results
+            .iter()
+            .filter(|(_, result)| {
+                matches!(result, OrderValidationResults::Valid(order) if order.from() == user)
+            })
+            .map(|(key, _)| *key)
+            .collect::<Vec<_>>();
+        for key in stale {
+            results.remove(&key);
+        }
+    }
+
+    /// drops every outcome, they're stale once a new block comes in
+    pub fn clear(&self) {
+        if let Some(results) = self.results.as_ref() {
+            results.lock().clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+    use angstrom_types::{primitive::ANGSTROM_DOMAIN, sol_bindings::grouped_orders::AllOrders};
+    use testing_tools::type_generator::orders::{SigningInfo, UserOrderBuilder};
+
+    use super::*;
+    use crate::order::rejection::{Rejection, RejectionReason};
+
+    /// a valid order of `wallet`, returned with its hash
+    fn valid(wallet: &PrivateKeySigner, nonce: u64) -> (B256, OrderValidationResults) {
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let order = UserOrderBuilder::new()
+            .standing()
+            .nonce(nonce)
+            .signing_key(Some(signing_info))
+            .with_storage()
+            .build()
+            .try_map_inner(|order| Ok(AllOrders::from(order)))
+            .unwrap();
+
+        (order.order_hash(), OrderValidationResults::Valid(order))
+    }
+
+    #[test]
+    fn outcomes_only_hit_for_their_block() {
+        let cache = ValidationResultCache::new(10);
+        let (order_hash, results) = valid(&PrivateKeySigner::random(), 0);
+
+        cache.insert(order_hash, 10, &results);
+        assert!(matches!(
+            cache.get(order_hash, 10),
+            Some(OrderValidationResults::Valid(order)) if order.order_hash() == order_hash
+        ));
+        assert!(cache.get(order_hash, 11).is_none());
+
+        cache.clear();
+        assert!(cache.get(order_hash, 10).is_none());
+    }
+
+    #[test]
+    fn rejections_are_not_cached() {
+        let cache = ValidationResultCache::new(10);
+        let order_hash = B256::random();
+        let rejection = Rejection::new(RejectionReason::InvalidSignature, "invalid signature");
+
+        cache.insert(order_hash, 10, &OrderValidationResults::Invalid(order_hash, rejection));
+        assert!(cache.get(order_hash, 10).is_none());
+    }
+
+    #[test]
+    fn account_changes_drop_the_users_outcomes() {
+        let cache = ValidationResultCache::new(10);
+        let (user, other) = (PrivateKeySigner::random(), PrivateKeySigner::random());
+        let orders = [valid(&user, 0), valid(&user, 1), valid(&other, 2)];
+        for (order_hash, results) in &orders {
+            cache.insert(*order_hash, 10, results);
+        }
+
+        cache.invalidate_user(user.address());
+        assert!(cache.get(orders[0].0, 10).is_none());
+        assert!(cache.get(orders[1].0, 10).is_none());
+        assert!(cache.get(orders[2].0, 10).is_some());
+    }
+
+    #[test]
+    fn zero_capacity_disables_the_cache() {
+        let cache = ValidationResultCache::new(0);
+        let (order_hash, results) = valid(&PrivateKeySigner::random(), 0);
+
+        cache.insert(order_hash, 10, &results);
+        assert!(cache.get(order_hash, 10).is_none());
+    }
+}
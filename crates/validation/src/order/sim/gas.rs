@@ -1,4 +1,8 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant}
+};
 
 use alloy::{
     primitives::{address, keccak256, Address, TxKind, B256, U160, U256},
@@ -16,12 +20,11 @@ use angstrom_types::{
 };
 use eyre::eyre;
 use pade::PadeEncode;
-use reth_provider::BlockNumReader;
 use revm::{
     db::CacheDB,
     inspector_handle_register,
     primitives::{EnvWithHandlerCfg, ResultAndState, TxEnv},
-    DatabaseRef
+    DatabaseCommit, DatabaseRef
 };
 
 use super::gas_inspector::{GasSimulationInspector, GasUsed};
@@ -42,23 +45,55 @@ const DEFAULT_CREATE2_FACTORY: Address = address!("4e59b44847b379578588920cA78Fb
 pub struct OrderGasCalculations<DB> {
     db:               CacheDB<Arc<DB>>,
     // the deployed addresses in cache_db
-    angstrom_address: Address
+    angstrom_address: Address,
+    /// longest a single simulation may run
+    timeout:          Option<Duration>
 }
 
 impl<DB> OrderGasCalculations<DB>
 where
-    DB: Unpin + Clone + 'static + revm::DatabaseRef + BlockNumReader,
+    DB: Unpin + Clone + 'static + revm::DatabaseRef,
     <DB as revm::DatabaseRef>::Error: Send + Sync
 {
     pub fn new(db: Arc<DB>, angstrom_address: Option<Address>) -> eyre::Result<Self> {
         if let Some(angstrom_address) = angstrom_address {
-            Ok(Self { db: CacheDB::new(db), angstrom_address })
+            Ok(Self { db: CacheDB::new(db), angstrom_address, timeout: None })
         } else {
             let ConfiguredRevm { db, angstrom } =
                 Self::setup_revm_cache_database_for_simulation(db)?;
 
-            Ok(Self { db, angstrom_address: angstrom })
+            Ok(Self { db, angstrom_address: angstrom, timeout: None })
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Deploys Angstrom and the uniswap v4 pool manager into the simulation
+    /// state and moves Angstrom's code and storage to `angstrom_address`, for
+    /// simulating on a chain where the contracts aren't deployed yet. The
+    /// address should carry Angstrom's hook flags like a real deployment
+    /// would. Whatever is at the address on chain is replaced.
+    pub fn with_angstrom_overrides(mut self, angstrom_address: Address) -> eyre::Result<Self> {
+        let ConfiguredRevm { mut db, angstrom } =
+            Self::setup_revm_cache_database_for_simulation(self.db.db.clone())?;
+        let deployed = db
+            .accounts
+            .get(&angstrom)
+            .cloned()
+            .ok_or_else(|| eyre!("angstrom missing from the simulation state"))?;
+
+        db.insert_account_info(angstrom_address, deployed.info);
+        for (slot, value) in deployed.storage {
+            db.insert_account_storage(angstrom_address, slot, value)
+                .map_err(|_| eyre!("failed to insert account into storage"))?;
         }
+
+        self.db = db;
+        self.angstrom_address = angstrom_address;
+        Ok(self)
     }
 
     pub fn gas_of_tob_order(
@@ -125,7 +160,9 @@ where
         )
     }
 
-    fn execute_with_db<D: DatabaseRef, F>(db: D, f: F) -> eyre::Result<(ResultAndState, D)>
+    /// executes the transaction against `db` without changing it. The caller
+    /// commits the returned state if it should persist
+    fn execute_with_db<D: DatabaseRef, F>(db: &D, f: F) -> eyre::Result<ResultAndState>
     where
         F: FnOnce(&mut TxEnv),
         <D as revm::DatabaseRef>::Error: Send + Sync
@@ -146,16 +183,16 @@ where
         let Ok(out) = revm_sim.transact() else {
             return Err(eyre!("failed to transact transaction"))
         };
-        let (cache_db, _) = revm_sim.into_db_and_env_with_handler_cfg();
-        Ok((out, cache_db.0))
+
+        Ok(out)
     }
 
     /// deploys angstrom + univ4 and then sets DEFAULT_FROM address as a node in
     /// the network.
     fn setup_revm_cache_database_for_simulation(db: Arc<DB>) -> eyre::Result<ConfiguredRevm<DB>> {
-        let cache_db = CacheDB::new(db.clone());
+        let mut cache_db = CacheDB::new(db.clone());
 
-        let (out, cache_db) = Self::execute_with_db(cache_db, |tx| {
+        let out = Self::execute_with_db(&cache_db, |tx| {
             tx.transact_to = TxKind::Create;
             tx.caller = DEFAULT_FROM;
             tx.data =
@@ -168,6 +205,7 @@ where
             println!("{:?}", out.result);
             eyre::bail!("failed to deploy uniswap v4 pool manager");
         }
+        cache_db.commit(out.state);
 
         let v4_address = Address::from_slice(&keccak256((DEFAULT_FROM, 0).abi_encode())[12..]);
 
@@ -191,7 +229,7 @@ where
 
         let final_mock_initcode = [salt.abi_encode(), data.to_vec()].concat();
 
-        let (out, cache_db) = Self::execute_with_db(cache_db, |tx| {
+        let out = Self::execute_with_db(&cache_db, |tx| {
             tx.transact_to = TxKind::Call(DEFAULT_CREATE2_FACTORY);
             tx.caller = DEFAULT_FROM;
             tx.data = final_mock_initcode.into();
@@ -201,9 +239,10 @@ where
         if !out.result.is_success() {
             eyre::bail!("failed to deploy angstrom");
         }
+        cache_db.commit(out.state);
 
         // enable default from to call the angstrom contract.
-        let (out, cache_db) = Self::execute_with_db(cache_db, |tx| {
+        let out = Self::execute_with_db(&cache_db, |tx| {
             tx.transact_to = TxKind::Call(angstrom_address);
             tx.caller = DEFAULT_FROM;
             tx.data = angstrom_types::contract_bindings::angstrom::Angstrom::toggleNodesCall::new(
@@ -218,6 +257,7 @@ where
         if !out.result.is_success() {
             eyre::bail!("failed to set default from address as node on angstrom");
         }
+        cache_db.commit(out.state);
 
         Ok(ConfiguredRevm { db: cache_db, angstrom: angstrom_address })
     }
@@ -265,7 +305,9 @@ where
     where
         F: FnOnce(&mut EnvWithHandlerCfg)
     {
-        let mut inspector = GasSimulationInspector::new(self.angstrom_address, offsets);
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut inspector =
+            GasSimulationInspector::new(self.angstrom_address, offsets).with_deadline(deadline);
         let mut evm_handler = EnvWithHandlerCfg::default();
 
         f(&mut evm_handler);
@@ -281,9 +323,11 @@ where
                 })
                 .build();
 
-            let result = evm
-                .transact()
-                .map_err(|_| eyre!("failed to transact with revm"))?;
+            let result = evm.transact();
+            if evm.context.external.timed_out() {
+                return Err(eyre!("gas simulation timed out after {:?}", self.timeout.unwrap()))
+            }
+            let result = result.map_err(|_| eyre!("failed to transact with revm"))?;
 
             if !result.result.is_success() {
                 return Err(eyre::eyre!(
@@ -357,7 +401,10 @@ pub mod test {
     use eyre::eyre;
     use reth_provider::BlockNumReader;
     use reth_revm::primitives::Bytecode;
-    use revm::primitives::AccountInfo;
+    use revm::{
+        db::EmptyDB,
+        primitives::{AccountInfo, KECCAK_EMPTY}
+    };
     use testing_tools::load_reth_db;
 
     use super::*;
@@ -365,6 +412,12 @@ pub mod test {
     const WETH_ADDRESS: Address = address!("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
     const USER_WITH_FUNDS: Address = address!("d02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
 
+    /// runtime code of the deterministic deployment proxy Angstrom is deployed
+    /// through
+    const CREATE2_FACTORY_CODE: &[u8] = &hex!(
+        "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3"
+    );
+
     const ANGSTROM_DOMAIN: alloy::sol_types::Eip712Domain = alloy::sol_types::eip712_domain! {
         name: "angstrom",
         version: "1",
@@ -384,6 +437,68 @@ pub mod test {
         assert!(res.is_ok(), "failed to deploy angstrom structure and v4 to chain");
     }
 
+    /// a chain holding nothing but the create2 factory
+    fn empty_chain() -> Arc<CacheDB<EmptyDB>> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let code = alloy::primitives::Bytes::from_static(CREATE2_FACTORY_CODE);
+        db.insert_account_info(
+            DEFAULT_CREATE2_FACTORY,
+            AccountInfo {
+                code_hash: keccak256(&code),
+                code: Some(Bytecode::new_raw(code)),
+                ..Default::default()
+            }
+        );
+
+        Arc::new(db)
+    }
+
+    fn code_hash<D: DatabaseRef>(db: &D, address: Address) -> B256 {
+        db.basic_ref(address)
+            .ok()
+            .flatten()
+            .map_or(KECCAK_EMPTY, |info| info.code_hash)
+    }
+
+    #[test]
+    fn test_execution_leaves_the_db_untouched() {
+        let db = CacheDB::new(EmptyDB::default());
+
+        let out = OrderGasCalculations::<EmptyDB>::execute_with_db(&db, |tx| {
+            tx.transact_to = TxKind::Create;
+            tx.caller = DEFAULT_FROM;
+            tx.data =
+                angstrom_types::contract_bindings::pool_manager::PoolManager::BYTECODE.clone();
+            tx.nonce = Some(0);
+        })
+        .unwrap();
+
+        assert!(out.result.is_success());
+        let deployed = DEFAULT_FROM.create(0);
+        assert!(out.state.contains_key(&deployed));
+        assert!(db.accounts.is_empty());
+        assert_eq!(code_hash(&db, deployed), KECCAK_EMPTY);
+    }
+
+    #[test]
+    fn test_angstrom_overrides_seed_the_address() {
+        let chain = empty_chain();
+        let gas_calculations = OrderGasCalculations::new(chain.clone(), None).unwrap();
+        let deployed = code_hash(&gas_calculations.db, gas_calculations.angstrom_address);
+        assert_ne!(deployed, KECCAK_EMPTY);
+
+        let angstrom_address = Address::random();
+        let seeded = gas_calculations
+            .with_angstrom_overrides(angstrom_address)
+            .unwrap();
+
+        assert_eq!(seeded.angstrom_address, angstrom_address);
+        assert_eq!(code_hash(&seeded.db, angstrom_address), deployed);
+        // the deployment only lives in the simulation state
+        assert_eq!(code_hash(chain.as_ref(), angstrom_address), KECCAK_EMPTY);
+        assert_eq!(chain.accounts.len(), 1);
+    }
+
     fn signed_tob_order(block: u64) -> (Address, TopOfBlockOrder) {
         let user = LocalSigner::random();
         let address = user.address();
@@ -674,6 +789,48 @@ pub mod test {
         assert_eq!(gas_used, 14);
     }
 
+    #[test]
+    fn test_simulation_halts_past_deadline() {
+        let looping = address!("d02aaa39b223fe8d0a0e5c4f27ead9083c756cc2");
+
+        let mut cache_db = CacheDB::new(revm::db::EmptyDB::default());
+        // JUMPDEST, PUSH1 0x00, JUMP. loops until it runs out of gas
+        let code = alloy::primitives::Bytes::from_static(&hex!("5b600056"));
+        let a = AccountInfo {
+            balance:   U256::ZERO,
+            code_hash: keccak256(&code),
+            code:      Some(Bytecode::new_raw(code)),
+            nonce:     0
+        };
+        cache_db.insert_account_info(looping, a);
+
+        let offsets = std::collections::HashMap::default();
+        let mut inspector =
+            GasSimulationInspector::new(looping, &offsets).with_deadline(Some(Instant::now()));
+
+        let mut evm_handler = EnvWithHandlerCfg::default();
+        let tx = &mut evm_handler.tx;
+        tx.transact_to = TxKind::Call(looping);
+        tx.caller = DEFAULT_FROM;
+        tx.gas_limit = u64::MAX;
+
+        let mut evm = revm::Evm::builder()
+            .with_ref_db(cache_db)
+            .with_external_context(&mut inspector)
+            .with_env_with_handler_cfg(evm_handler)
+            .append_handler_register(inspector_handle_register)
+            .modify_env(|env| {
+                env.cfg.disable_balance_check = true;
+            })
+            .build();
+
+        let result = evm.transact().unwrap();
+        drop(evm);
+
+        assert!(!result.result.is_success());
+        assert!(inspector.timed_out());
+    }
+
     fn set_balances_and_approvals<DB: DatabaseRef + Unpin>(
         cache_db: &mut CacheDB<Arc<DB>>,
         calle_address: Address,
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Instant};
 
 use alloy::primitives::Address;
-use revm::{Database, Inspector};
+use revm::{interpreter::InstructionResult, Database, Inspector};
 
 pub type GasUsed = u64;
 
+/// steps executed between two checks of the deadline, reading the clock on
+/// every opcode would slow down the simulation noticeably
+const DEADLINE_CHECK_INTERVAL: u64 = 1024;
+
 /// the Gas Simulation Inspector allows us to define mutually exclusive ranges
 /// based on the EVM program counter and will store the gas used for execution
 /// in these ranges.
@@ -15,7 +19,11 @@ pub struct GasSimulationInspector<'a> {
     // the current start of the pc we are measuring
     in_flight:           Option<usize>,
     in_flight_start_gas: Option<u64>,
-    angstrom_address:    Address
+    angstrom_address:    Address,
+    /// execution is halted once this has passed
+    deadline:            Option<Instant>,
+    steps:               u64,
+    timed_out:           bool
 }
 
 impl<'a> GasSimulationInspector<'a> {
@@ -27,8 +35,35 @@ impl<'a> GasSimulationInspector<'a> {
             measurement_ranges,
             angstrom_address,
             in_flight: None,
-            in_flight_start_gas: None
+            in_flight_start_gas: None,
+            deadline: None,
+            steps: 0,
+            timed_out: false
+        }
+    }
+
+    pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// whether execution was halted because the deadline passed
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    fn deadline_passed(&mut self) -> bool {
+        if self.timed_out {
+            return true
+        }
+        let Some(deadline) = self.deadline else { return false };
+
+        self.steps += 1;
+        if self.steps % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() >= deadline {
+            self.timed_out = true;
         }
+
+        self.timed_out
     }
 
     pub fn into_gas_used(self) -> GasUsed {
@@ -38,6 +73,12 @@ impl<'a> GasSimulationInspector<'a> {
 
 impl<DB: Database> Inspector<DB> for GasSimulationInspector<'_> {
     fn step(&mut self, interp: &mut revm::interpreter::Interpreter, _: &mut revm::EvmContext<DB>) {
+        // halts every frame, the callers each hit this on their next step
+        if self.deadline_passed() {
+            interp.instruction_result = InstructionResult::OutOfGas;
+            return
+        }
+
         let addr = interp.contract().bytecode_address.unwrap();
         // we only want to check against angstrom PC
         if addr != self.angstrom_address {
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use alloy::primitives::Address;
 use angstrom_types::sol_bindings::{
@@ -9,7 +9,13 @@ use angstrom_types::sol_bindings::{
 use gas::OrderGasCalculations;
 use revm::primitives::ruint::aliases::U256;
 
-use crate::{common::TokenPriceGenerator, order::sim::gas_inspector::GasUsed};
+use crate::{
+    common::TokenPriceGenerator,
+    order::{
+        sim::gas_inspector::GasUsed,
+        state::{account::UserAccountVerificationError, config::MissingPricePolicy}
+    }
+};
 
 mod gas;
 mod gas_inspector;
@@ -18,7 +24,8 @@ pub type GasInToken0 = U256;
 /// validation relating to simulations.
 #[derive(Clone)]
 pub struct SimValidation<DB> {
-    gas_calculator: OrderGasCalculations<DB>
+    gas_calculator: OrderGasCalculations<DB>,
+    missing_price:  MissingPricePolicy
 }
 
 impl<DB> SimValidation<DB>
@@ -29,7 +36,30 @@ where
     pub fn new(db: Arc<DB>, angstrom_address: Option<Address>) -> Self {
         let gas_calculator = OrderGasCalculations::new(db.clone(), angstrom_address)
             .expect("failed to deploy baseline angstrom for gas calculations");
-        Self { gas_calculator }
+        Self { gas_calculator, missing_price: MissingPricePolicy::default() }
+    }
+
+    /// aborts the simulation of an order that runs longer than `timeout`,
+    /// e.g. one with a hook that loops until it runs out of gas
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.gas_calculator = self.gas_calculator.with_timeout(timeout);
+        self
+    }
+
+    /// whether orders whose tokens have no usable eth price are rejected or
+    /// admitted without charging their gas
+    pub fn with_missing_price(mut self, missing_price: MissingPricePolicy) -> Self {
+        self.missing_price = missing_price;
+        self
+    }
+
+    /// simulates against an Angstrom seeded into the simulation state at
+    /// `angstrom_address`, for chains it isn't deployed on yet
+    pub fn with_angstrom_overrides(mut self, angstrom_address: Address) -> eyre::Result<Self> {
+        self.gas_calculator = self
+            .gas_calculator
+            .with_angstrom_overrides(angstrom_address)?;
+        Ok(self)
     }
 
     pub fn calculate_tob_gas(
@@ -46,7 +76,12 @@ where
         };
 
         // grab price conversion
-        let conversion_factor = conversion.get_eth_conversion_price(token0, token1).unwrap();
+        let conversion_factor = self
+            .conversion_factor(token0, token1, conversion)
+            .ok_or_else(|| UserAccountVerificationError::<TopOfBlockOrder>::NoPriceForToken {
+                order_hash: order.order_hash(),
+                token:      token0
+            })?;
         Ok((gas_in_wei, conversion_factor * U256::from(gas_in_wei)))
     }
 
@@ -64,7 +99,81 @@ where
         };
 
         // grab price conversion
-        let conversion_factor = conversion.get_eth_conversion_price(token0, token1).unwrap();
+        let conversion_factor = self
+            .conversion_factor(token0, token1, conversion)
+            .ok_or_else(|| {
+                UserAccountVerificationError::<GroupedVanillaOrder>::NoPriceForToken {
+                    order_hash: order.order_hash(),
+                    token:      token0
+                }
+            })?;
         Ok((gas_in_wei, conversion_factor * U256::from(gas_in_wei)))
     }
 }
+
+impl<DB> SimValidation<DB> {
+    /// the eth price of token0, zero when there is none and orders without a
+    /// price are admitted
+    fn conversion_factor(
+        &self,
+        token0: Address,
+        token1: Address,
+        conversion: &TokenPriceGenerator
+    ) -> Option<U256> {
+        conversion
+            .get_eth_conversion_price(token0, token1)
+            .or_else(|| (self.missing_price == MissingPricePolicy::Admit).then_some(U256::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{node_bindings::WEI_IN_ETHER, primitives::FixedBytes};
+    use angstrom_types::pair_with_price::PairsWithPrice;
+    use revm::db::{CacheDB, EmptyDB};
+
+    use super::*;
+    use crate::common::WETH_ADDRESS;
+
+    fn sim(missing_price: MissingPricePolicy) -> SimValidation<CacheDB<EmptyDB>> {
+        let db = Arc::new(CacheDB::new(EmptyDB::default()));
+        let gas_calculator = OrderGasCalculations::new(db, Some(Address::random())).unwrap();
+        SimValidation { gas_calculator, missing_price }
+    }
+
+    /// prices `token` at 5 per eth, returns the generator and the expected
+    /// conversion price
+    fn priced(token: Address) -> (TokenPriceGenerator, U256) {
+        let price = PairsWithPrice {
+            token0:         token,
+            token1:         WETH_ADDRESS,
+            block_num:      0,
+            price_1_over_0: U256::from(5) * WEI_IN_ETHER
+        };
+        let conversion =
+            TokenPriceGenerator::with_prices(vec![(FixedBytes::with_last_byte(1), price)]);
+
+        (conversion, U256::from(1e36) / U256::from(5e18))
+    }
+
+    #[test]
+    fn unpriced_tokens_have_no_conversion_when_rejected() {
+        let (token, unpriced) = (Address::random(), Address::random());
+        let (conversion, expected) = priced(token);
+        let sim = sim(MissingPricePolicy::Reject);
+
+        assert_eq!(sim.conversion_factor(token, WETH_ADDRESS, &conversion), Some(expected));
+        assert_eq!(sim.conversion_factor(unpriced, WETH_ADDRESS, &conversion), None);
+    }
+
+    #[test]
+    fn unpriced_tokens_convert_at_zero_when_admitted() {
+        let (token, unpriced) = (Address::random(), Address::random());
+        let (conversion, expected) = priced(token);
+        let sim = sim(MissingPricePolicy::Admit);
+
+        // priced tokens are still charged
+        assert_eq!(sim.conversion_factor(token, WETH_ADDRESS, &conversion), Some(expected));
+        assert_eq!(sim.conversion_factor(unpriced, WETH_ADDRESS, &conversion), Some(U256::ZERO));
+    }
+}
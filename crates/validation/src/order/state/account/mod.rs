@@ -1,14 +1,27 @@
 //! keeps track of account state for orders
 
+use std::sync::Arc;
+
 use alloy::primitives::{Address, B256, U256};
 use angstrom_types::{
     orders::OrderId,
-    sol_bindings::{ext::RawPoolOrder, grouped_orders::OrderWithStorageData}
+    primitive::PoolId,
+    sol_bindings::{
+        ext::{RawPoolOrder, StaticOrderError},
+        grouped_orders::OrderWithStorageData
+    }
 };
 use thiserror::Error;
-use user::UserAccounts;
+use user::{PendingUserAction, UserAccounts};
 
-use super::{db_state_utils::StateFetchUtils, pools::UserOrderPoolInfo};
+use super::{config::ValidationConfig, db_state_utils::StateFetchUtils, pools::UserOrderPoolInfo};
+use crate::{
+    common::{Clock, SystemClock},
+    order::{
+        rejection::Rejection,
+        trace::{StepRecorder, ValidationStep, ValidationTrace}
+    }
+};
 
 pub mod user;
 
@@ -19,28 +32,269 @@ pub struct UserAccountProcessor<S> {
     user_accounts: UserAccounts,
     /// utils for fetching the required data to verify
     /// a order.
-    fetch_utils:   S
+    fetch_utils:   S,
+    /// order level limits
+    config:        ValidationConfig,
+    /// time source order deadlines are checked against
+    clock:         Arc<dyn Clock>
 }
 
 impl<S: StateFetchUtils> UserAccountProcessor<S> {
     pub fn new(fetch_utils: S) -> Self {
         let user_accounts = UserAccounts::new();
-        Self { fetch_utils, user_accounts }
+        Self {
+            fetch_utils,
+            user_accounts,
+            config: ValidationConfig::default(),
+            clock: Arc::new(SystemClock)
+        }
+    }
+
+    pub fn with_config(mut self, config: ValidationConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn config(&self) -> &ValidationConfig {
+        &self.config
     }
 
     pub fn prepare_for_new_block(&self, users: Vec<Address>, orders: Vec<B256>) {
         self.user_accounts.new_block(users, orders);
     }
 
+    pub fn next_valid_nonce(&self, user: Address) -> u64 {
+        self.user_accounts.next_valid_nonce(user, &self.fetch_utils)
+    }
+
+    /// frees what the cancelled order reserved of the user's balances.
+    /// Returns false if the order wasn't pending
+    pub fn cancel_order(&self, user: Address, order_hash: &B256) -> bool {
+        self.user_accounts.cancel_order(&user, order_hash)
+    }
+
+    /// fetches the users balances and approvals of `tokens` so their first
+    /// order doesn't have to. Returns how many weren't loaded yet
+    pub fn prewarm(&self, user: Address, tokens: &[Address]) -> usize {
+        tokens
+            .iter()
+            .filter(|token| self.user_accounts.prewarm(user, **token, &self.fetch_utils))
+            .count()
+    }
+
     pub fn verify_order<O: RawPoolOrder>(
         &self,
         order: O,
         pool_info: UserOrderPoolInfo,
         block: u64
+    ) -> Result<OrderWithStorageData<O>, UserAccountVerificationError<O>> {
+        self.process_order(order, pool_info, block, true, &mut ())
+    }
+
+    /// runs the same checks as [`Self::verify_order`] without touching any of
+    /// the tracked account state. The order is never added as a pending
+    /// action and conflicting orders are not cancelled.
+    pub fn simulate_order<O: RawPoolOrder>(
+        &self,
+        order: O,
+        pool_info: UserOrderPoolInfo,
+        block: u64
+    ) -> Result<OrderWithStorageData<O>, UserAccountVerificationError<O>> {
+        self.process_order(order, pool_info, block, false, &mut ())
+    }
+
+    /// [`Self::simulate_order`] that records the outcome of each check into
+    /// `trace`. Returns the order if it would be accepted
+    pub fn trace_order<O: RawPoolOrder>(
+        &self,
+        order: O,
+        pool_info: UserOrderPoolInfo,
+        block: u64,
+        trace: &mut ValidationTrace
+    ) -> Option<OrderWithStorageData<O>> {
+        self.process_order(order, pool_info, block, false, trace)
+            .ok()
+    }
+
+    /// the account checks every order goes through, reported to `recorder`
+    /// as they run. Only with `track` is the order added as a pending action
+    /// of the user and are the orders it replaces cancelled
+    pub(super) fn process_order<O: RawPoolOrder, R: StepRecorder>(
+        &self,
+        order: O,
+        pool_info: UserOrderPoolInfo,
+        block: u64,
+        track: bool,
+        recorder: &mut R
     ) -> Result<OrderWithStorageData<O>, UserAccountVerificationError<O>> {
+        let user = order.from();
+        let respend = order.respend_avoidance_strategy();
+        let conflicting_orders = match self.check_order(&order, &pool_info, block) {
+            Ok(conflicting_orders) => conflicting_orders,
+            Err(e) => {
+                let step = match &e {
+                    UserAccountVerificationError::DuplicateNonce(_)
+                    | UserAccountVerificationError::BadBlock => {
+                        recorder.on_step(ValidationStep::StaticChecks, true, || "passed".into());
+                        ValidationStep::Nonce
+                    }
+                    _ => ValidationStep::StaticChecks
+                };
+                recorder.on_rejection(step, &Rejection::from(&e));
+                return Err(e)
+            }
+        };
+        recorder.on_step(ValidationStep::StaticChecks, true, || "passed".into());
+        recorder.on_step(ValidationStep::Nonce, true, || {
+            format!("{respend:?} is free, replaces {} pending orders", conflicting_orders.len())
+        });
+
+        let live_state = if track {
+            // if new order has lower hash cancel all orders with the same nonce
+            conflicting_orders.iter().for_each(|order| {
+                self.user_accounts.cancel_order(&user, &order.order_hash);
+            });
+            self.user_accounts.get_live_state_for_order(
+                user,
+                pool_info.token,
+                respend,
+                &self.fetch_utils
+            )
+        } else {
+            self.user_accounts.peek_live_state_for_order(
+                user,
+                pool_info.token,
+                respend,
+                &self.fetch_utils
+            )
+        };
+
+        let required = U256::from(order.amount_in());
+        if order.use_internal() {
+            let available = live_state.angstrom_balance;
+            recorder.on_step(ValidationStep::Balance, available >= required, || {
+                format!("{available} of {required} held in angstrom")
+            });
+        } else {
+            recorder.on_step(ValidationStep::Balance, live_state.balance >= required, || {
+                format!("{} of {required} held", live_state.balance)
+            });
+            recorder.on_step(ValidationStep::Approval, live_state.approval >= required, || {
+                format!("{} of {required} approved", live_state.approval)
+            });
+        }
+
+        // ensure that the current live state is enough to satisfy the order
+        let pending_user_action = live_state.can_support_order(&order, &pool_info);
+        let is_cur_valid = pending_user_action.is_some();
+        let mut invalid_orders = match pending_user_action {
+            Some(action) if track => self.user_accounts.insert_pending_user_action(user, action),
+            _ => vec![]
+        };
+
+        // invalidate orders with clashing nonces
+        invalid_orders.extend(conflicting_orders.into_iter().map(|o| o.order_hash));
+
+        Ok(order.into_order_storage_with_data(block, is_cur_valid, true, pool_info, invalid_orders))
+    }
+
+    /// checks that don't depend on the users balances, returns the pending
+    /// orders that share a nonce with the order and would be replaced by it.
+    fn check_order<O: RawPoolOrder>(
+        &self,
+        order: &O,
+        pool_info: &UserOrderPoolInfo,
+        block: u64
+    ) -> Result<Vec<PendingUserAction>, UserAccountVerificationError<O>> {
         let user = order.from();
         let order_hash = order.order_hash();
 
+        order
+            .validate_static()
+            .map_err(|error| UserAccountVerificationError::Malformed { order_hash, error })?;
+
+        if let Some(token) = [order.token_in(), order.token_out()]
+            .into_iter()
+            .find(|token| !self.config.is_token_permitted(token))
+        {
+            return Err(UserAccountVerificationError::TokenNotPermitted { order_hash, token })
+        }
+
+        // fee-on-transfer tokens deliver less than the order accounts for
+        if let Some(max_tax_bps) = self.config.max_transfer_tax_bps {
+            for token in [order.token_in(), order.token_out()] {
+                // a token whose transfer couldn't be simulated may take any fee
+                let Some(tax_bps) = self.fetch_utils.fetch_transfer_tax_bps(token) else {
+                    return Err(UserAccountVerificationError::UnknownTransferTax {
+                        order_hash,
+                        token
+                    })
+                };
+                if tax_bps > max_tax_bps {
+                    return Err(UserAccountVerificationError::UnsupportedTransferTax {
+                        order_hash,
+                        token,
+                        tax_bps
+                    })
+                }
+            }
+        }
+
+        // the hook is checked before anything is simulated
+        let hook_target = order.hook_target();
+        if !self
+            .config
+            .is_hook_permitted(order.hook_calldata(), hook_target)
+        {
+            return Err(UserAccountVerificationError::HookTargetNotPermitted {
+                order_hash,
+                target: hook_target
+            })
+        }
+        if let Some(max_size) = self.config.max_hook_data_bytes {
+            let size = order.hook_calldata().len();
+            if size > max_size {
+                return Err(UserAccountVerificationError::HookDataTooLarge {
+                    order_hash,
+                    size,
+                    max_size
+                })
+            }
+        }
+
+        if let Some(deadline) = order.deadline() {
+            let now = self.clock.now();
+            if deadline < U256::from(now) {
+                return Err(UserAccountVerificationError::Expired { order_hash, deadline })
+            }
+            let min_deadline =
+                U256::from(now.saturating_add(self.config.min_time_to_deadline_secs()));
+            if deadline < min_deadline {
+                return Err(UserAccountVerificationError::DeadlineTooSoon {
+                    order_hash,
+                    deadline,
+                    min_deadline
+                })
+            }
+
+            // bounds how long a single order can sit in the pool
+            if let Some(horizon) = self.config.max_deadline_horizon_secs {
+                let max_deadline = U256::from(now.saturating_add(horizon));
+                if deadline > max_deadline {
+                    return Err(UserAccountVerificationError::DeadlineTooFar {
+                        order_hash,
+                        deadline,
+                        max_deadline
+                    })
+                }
+            }
+        }
+
         // very nonce hasn't been used historically
         //
         let respend = order.respend_avoidance_strategy();
@@ -65,34 +319,8 @@ impl<S: StateFetchUtils> UserAccountProcessor<S> {
         {
             return Err(UserAccountVerificationError::DuplicateNonce(order_hash))
         }
-        // if new order has lower hash cancel all orders with the same nonce
-        conflicting_orders.iter().for_each(|order| {
-            self.user_accounts.cancel_order(&user, &order.order_hash);
-        });
 
-        let live_state = self.user_accounts.get_live_state_for_order(
-            user,
-            pool_info.token,
-            respend,
-            &self.fetch_utils
-        );
-
-        // ensure that the current live state is enough to satisfy the order
-        let (is_cur_valid, mut invalid_orders) = live_state
-            .can_support_order(&order, &pool_info)
-            .map(|pending_user_action| {
-                (
-                    true,
-                    self.user_accounts
-                        .insert_pending_user_action(order.from(), pending_user_action)
-                )
-            })
-            .unwrap_or_default();
-
-        // invalidate orders with clashing nonces
-        invalid_orders.extend(conflicting_orders.into_iter().map(|o| o.order_hash));
-
-        Ok(order.into_order_storage_with_data(block, is_cur_valid, true, pool_info, invalid_orders))
+        Ok(conflicting_orders)
     }
 }
 
@@ -122,13 +350,16 @@ pub trait StorageWithData: RawPoolOrder {
             order_id: OrderId::from_all_orders(&self, pool_info.pool_id),
             invalidates,
             order: self,
-            tob_reward: U256::ZERO
+            tob_reward: U256::ZERO,
+            filled_amount: 0
         }
     }
 }
 
 #[derive(Debug, Error)]
 pub enum UserAccountVerificationError<O: RawPoolOrder> {
+    #[error("order {order_hash:?} is malformed: {error}")]
+    Malformed { order_hash: B256, error: StaticOrderError },
     #[error("tried to verify for block {} where current is {}", requested, current)]
     BlockMissMatch { requested: u64, current: u64, order: O, pool_info: UserOrderPoolInfo },
     #[error("order hash has been cancelled {0:?}")]
@@ -136,30 +367,68 @@ pub enum UserAccountVerificationError<O: RawPoolOrder> {
     #[error("Nonce exists for a current order hash: {0:?}")]
     DuplicateNonce(B256),
     #[error("block for flash order is not current block")]
-    BadBlock
+    BadBlock,
+    #[error("order {order_hash:?} expired at {deadline}")]
+    Expired { order_hash: B256, deadline: U256 },
+    #[error("order {order_hash:?} deadline {deadline} is before the minimum {min_deadline}")]
+    DeadlineTooSoon { order_hash: B256, deadline: U256, min_deadline: U256 },
+    #[error("order {order_hash:?} deadline {deadline} is past the latest allowed {max_deadline}")]
+    DeadlineTooFar { order_hash: B256, deadline: U256, max_deadline: U256 },
+    #[error("order {order_hash:?} notional {notional} is below the pool minimum {min_notional}")]
+    BelowMinNotional { order_hash: B256, notional: U256, min_notional: U256 },
+    #[error("order {order_hash:?} references token {token:?} which isn't permitted")]
+    TokenNotPermitted { order_hash: B256, token: Address },
+    #[error("order {order_hash:?} token {token:?} takes a {tax_bps} bps fee on transfer")]
+    UnsupportedTransferTax { order_hash: B256, token: Address, tax_bps: u32 },
+    #[error("order {order_hash:?} token {token:?} fee on transfer couldn't be measured")]
+    UnknownTransferTax { order_hash: B256, token: Address },
+    #[error("order {order_hash:?} hook target {target:?} isn't permitted")]
+    HookTargetNotPermitted { order_hash: B256, target: Option<Address> },
+    #[error("order {order_hash:?} hook data is {size} bytes, the limit is {max_size}")]
+    HookDataTooLarge { order_hash: B256, size: usize, max_size: usize },
+    #[error("pool {0:?} hasn't been synced yet")]
+    PoolNotSynced(PoolId),
+    #[error("order {order_hash:?} price {price} is too far from the market price {market_price}")]
+    PriceTooFarFromMarket { order_hash: B256, price: U256, market_price: U256 },
+    #[error("order {order_hash:?} token {token:?} has no usable eth price")]
+    NoPriceForToken { order_hash: B256, token: Address },
+    #[error("order {order_hash:?} pool {pool_id:?} has no usable price to value the order")]
+    NoPoolPrice { order_hash: B256, pool_id: PoolId },
+    #[error("order {0:?} signature has a high s value, sign it with a low s instead")]
+    NonCanonicalSignature(B256)
 }
 
 #[cfg(test)]
 pub mod tests {
-    use std::collections::HashSet;
+    use std::{collections::HashSet, sync::Arc, time::Duration};
 
     use alloy::primitives::{Address, U256};
     use angstrom_types::{
+        matching::Ray,
         primitive::PoolId,
-        sol_bindings::{grouped_orders::GroupedVanillaOrder, RawPoolOrder}
+        sol_bindings::{
+            grouped_orders::{GroupedComposableOrder, GroupedVanillaOrder, StandingVariants},
+            RawPoolOrder, RespendAvoidanceMethod, StaticOrderError
+        }
     };
     use testing_tools::type_generator::orders::UserOrderBuilder;
 
     use super::{UserAccountProcessor, UserAccountVerificationError, UserAccounts};
-    use crate::order::state::{
-        db_state_utils::test_fetching::MockFetch,
-        pools::{pool_tracker_mock::MockPoolTracker, PoolsTracker}
+    use crate::{
+        common::{MockClock, SystemClock},
+        order::state::{
+            config::ValidationConfig,
+            db_state_utils::test_fetching::MockFetch,
+            pools::{pool_tracker_mock::MockPoolTracker, PoolsTracker}
+        }
     };
 
     fn setup_test_account_processor() -> UserAccountProcessor<MockFetch> {
         UserAccountProcessor {
             user_accounts: UserAccounts::new(),
-            fetch_utils:   MockFetch::default()
+            fetch_utils:   MockFetch::default(),
+            config:        Default::default(),
+            clock:         Arc::new(SystemClock)
         }
     }
 
@@ -205,6 +474,50 @@ pub mod tests {
             .expect("order should be valid");
     }
 
+    #[test]
+    fn test_simulate_order_does_not_track_order() {
+        let processor = setup_test_account_processor();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order: GroupedVanillaOrder = UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1)
+            .nonce(420)
+            .build();
+        let user = order.from();
+
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order)
+            .expect("pool tracker should have valid state");
+
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(order.amount_in()));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(order.amount_in()));
+
+        // simulating the same order twice never conflicts with itself
+        for _ in 0..2 {
+            let simulated = processor
+                .simulate_order(order.clone(), pool_info.clone(), 420)
+                .expect("order should simulate");
+            assert!(simulated.is_currently_valid);
+        }
+
+        // and leaves no pending state behind
+        let verified = processor
+            .verify_order(order, pool_info, 420)
+            .expect("order should be valid");
+        assert!(verified.is_currently_valid);
+    }
+
     #[test]
     fn test_failure_on_duplicate_pending_nonce() {
         let processor = setup_test_account_processor();
@@ -355,4 +668,607 @@ pub mod tests {
 
         assert!(matches!(e, UserAccountVerificationError::DuplicateNonce(..)));
     }
+
+    #[test]
+    fn test_order_expires_once_deadline_passes() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let processor = setup_test_account_processor().with_clock(clock.clone());
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order: GroupedVanillaOrder = UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1)
+            .nonce(420)
+            .deadline(1_010)
+            .build();
+        let user = order.from();
+
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order)
+            .expect("pool tracker should have valid state");
+
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(order.amount_in()));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(order.amount_in()));
+
+        processor
+            .simulate_order(order.clone(), pool_info.clone(), 420)
+            .expect("order should be valid before its deadline");
+
+        clock.advance(Duration::from_secs(11));
+        let Err(e) = processor.verify_order(order, pool_info, 420) else {
+            panic!("verifying order should of failed")
+        };
+
+        assert!(matches!(e, UserAccountVerificationError::Expired { .. }));
+    }
+
+    #[test]
+    fn test_order_deadline_past_horizon_is_rejected() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let config = ValidationConfig { max_deadline_horizon_secs: Some(60), ..Default::default() };
+        let processor = setup_test_account_processor()
+            .with_config(config)
+            .with_clock(clock);
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order = |nonce: u64, deadline: u64| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .asset_in(token0)
+                .asset_out(token1)
+                .nonce(nonce)
+                .deadline(deadline)
+                .build()
+        };
+
+        let too_far = order(1, 1_061);
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&too_far)
+            .expect("pool tracker should have valid state");
+
+        let Err(e) = processor.simulate_order(too_far, pool_info.clone(), 420) else {
+            panic!("order past the horizon was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::DeadlineTooFar { max_deadline, .. }
+                if max_deadline == U256::from(1_060)
+        ));
+
+        processor
+            .simulate_order(order(2, 1_060), pool_info, 420)
+            .expect("order at the horizon should be valid");
+    }
+
+    #[test]
+    fn test_order_close_to_deadline_is_rejected() {
+        let clock = Arc::new(MockClock::new(1_000));
+        let config = ValidationConfig { min_time_to_deadline_secs: Some(30), ..Default::default() };
+        let processor = setup_test_account_processor()
+            .with_config(config)
+            .with_clock(clock);
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order = |nonce: u64, deadline: u64| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .asset_in(token0)
+                .asset_out(token1)
+                .nonce(nonce)
+                .deadline(deadline)
+                .build()
+        };
+
+        let too_soon = order(1, 1_029);
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&too_soon)
+            .expect("pool tracker should have valid state");
+
+        let Err(e) = processor.simulate_order(too_soon, pool_info.clone(), 420) else {
+            panic!("order about to expire was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::DeadlineTooSoon { min_deadline, .. }
+                if min_deadline == U256::from(1_030)
+        ));
+
+        processor
+            .simulate_order(order(2, 1_030), pool_info, 420)
+            .expect("order with enough time left should be valid");
+    }
+
+    #[test]
+    fn test_malformed_order_is_rejected_before_state() {
+        let processor = setup_test_account_processor();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order = |amount: u128| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(amount)
+                .build()
+        };
+
+        let empty = order(0);
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&empty)
+            .expect("pool tracker should have valid state");
+
+        let Err(e) = processor.simulate_order(empty, pool_info.clone(), 420) else {
+            panic!("order without an amount was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::Malformed { error: StaticOrderError::ZeroAmountIn, .. }
+        ));
+
+        let free = UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1)
+            .min_price(Ray::default())
+            .build();
+        let Err(e) = processor.simulate_order(free, pool_info.clone(), 420) else {
+            panic!("order asking for nothing back was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::Malformed { error: StaticOrderError::ZeroAmountOut, .. }
+        ));
+
+        processor
+            .simulate_order(order(100), pool_info, 420)
+            .expect("well formed order should be valid");
+    }
+
+    #[test]
+    fn test_prewarm_loads_each_token_once() {
+        let processor = setup_test_account_processor();
+        let user = Address::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        assert_eq!(processor.prewarm(user, &[token0]), 1);
+        assert_eq!(processor.prewarm(user, &[token0, token1]), 1);
+        assert_eq!(processor.prewarm(user, &[token0, token1]), 0);
+
+        // a new block drops what was loaded for users whose state changed
+        processor.prepare_for_new_block(vec![user], vec![]);
+        assert_eq!(processor.prewarm(user, &[token0, token1]), 2);
+    }
+
+    #[test]
+    fn test_next_valid_nonce_skips_used_and_pending() {
+        let processor = setup_test_account_processor();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order: GroupedVanillaOrder = UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1)
+            .nonce(3)
+            .build();
+        let user = order.from();
+
+        assert_eq!(processor.next_valid_nonce(user), 0);
+
+        processor
+            .fetch_utils
+            .set_used_nonces(user, HashSet::from([0, 1, 2]));
+        assert_eq!(processor.next_valid_nonce(user), 3);
+
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order)
+            .expect("pool tracker should have valid state");
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(order.amount_in()));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(order.amount_in()));
+        processor
+            .verify_order(order, pool_info, 420)
+            .expect("order should be valid");
+        assert_eq!(processor.next_valid_nonce(user), 4);
+
+        // a full word moves on to the next one
+        processor
+            .fetch_utils
+            .set_used_nonces(user, (4..=256).collect());
+        assert_eq!(processor.next_valid_nonce(user), 257);
+    }
+
+    #[test]
+    fn test_token_allow_and_deny_lists() {
+        let user = Address::random();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        let order = |nonce: u64| -> GroupedVanillaOrder {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(100)
+                .nonce(nonce)
+                .recipient(user)
+                .build()
+        };
+        let verify = |config: ValidationConfig, order: GroupedVanillaOrder| {
+            let pool_info = mock_pool
+                .fetch_pool_info_for_order(&order)
+                .expect("pool tracker should have valid state");
+            setup_test_account_processor()
+                .with_config(config)
+                .verify_order(order, pool_info, 420)
+        };
+
+        let mut denied = ValidationConfig::default();
+        denied.denied_tokens.insert(token1);
+        let Err(e) = verify(denied, order(1)) else { panic!("denied token was accepted") };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::TokenNotPermitted { token, .. } if token == token1
+        ));
+
+        let allowed = ValidationConfig {
+            allowed_tokens: Some(HashSet::from([token0])),
+            ..Default::default()
+        };
+        let Err(e) = verify(allowed, order(2)) else { panic!("token outside allowlist accepted") };
+        assert!(matches!(e, UserAccountVerificationError::TokenNotPermitted { .. }));
+
+        let allowed = ValidationConfig {
+            allowed_tokens: Some(HashSet::from([token0, token1])),
+            ..Default::default()
+        };
+        assert!(verify(allowed, order(3)).is_ok());
+    }
+
+    #[test]
+    fn test_transfer_tax_above_limit_is_rejected() {
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        mock_pool.add_pool(token0, token1, PoolId::default());
+
+        let order: GroupedVanillaOrder = UserOrderBuilder::new()
+            .standing()
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(100)
+            .build();
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order)
+            .expect("pool tracker should have valid state");
+
+        let processor = setup_test_account_processor();
+        processor.fetch_utils.set_transfer_tax(token0, 100);
+
+        // taxes aren't checked unless a limit is configured
+        assert!(processor
+            .simulate_order(order.clone(), pool_info.clone(), 420)
+            .is_ok());
+
+        let processor = processor
+            .with_config(ValidationConfig { max_transfer_tax_bps: Some(50), ..Default::default() });
+        let Err(e) = processor.simulate_order(order.clone(), pool_info.clone(), 420) else {
+            panic!("taxed token was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::UnsupportedTransferTax { token, tax_bps: 100, .. }
+                if token == token0
+        ));
+
+        let processor = processor.with_config(ValidationConfig {
+            max_transfer_tax_bps: Some(100),
+            ..Default::default()
+        });
+        assert!(processor
+            .simulate_order(order.clone(), pool_info.clone(), 420)
+            .is_ok());
+
+        // a token whose transfer couldn't be simulated is rejected once a
+        // limit is configured
+        processor.fetch_utils.set_unknown_transfer_tax(token1);
+        let Err(e) = processor.simulate_order(order, pool_info, 420) else {
+            panic!("token with an unknown tax was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::UnknownTransferTax { token, .. } if token == token1
+        ));
+    }
+
+    #[test]
+    fn test_hook_target_allowlist() {
+        let user = Address::random();
+        let approved_hook = Address::random();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        let config = ValidationConfig {
+            allowed_hook_targets: Some(HashSet::from([approved_hook])),
+            ..Default::default()
+        };
+        let processor = setup_test_account_processor().with_config(config);
+
+        let order = |nonce: u64, hook: Vec<u8>| -> GroupedComposableOrder {
+            let order: GroupedVanillaOrder = UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(100)
+                .nonce(nonce)
+                .recipient(user)
+                .build();
+            let GroupedVanillaOrder::Standing(StandingVariants::Exact(mut order)) = order else {
+                unreachable!()
+            };
+            order.hook_data = hook.into();
+            GroupedComposableOrder::Partial(StandingVariants::Exact(order))
+        };
+        let pool_info = |order: &GroupedComposableOrder| {
+            mock_pool
+                .fetch_pool_info_for_order(order)
+                .expect("pool tracker should have valid state")
+        };
+
+        let hook = |target: Address| [target.as_slice(), &[1u8, 2, 3][..]].concat();
+
+        let approved = order(1, hook(approved_hook));
+        assert!(processor
+            .verify_order(approved.clone(), pool_info(&approved), 420)
+            .is_ok());
+
+        let unknown_hook = Address::random();
+        let unknown = order(2, hook(unknown_hook));
+        let Err(e) = processor.verify_order(unknown.clone(), pool_info(&unknown), 420) else {
+            panic!("hook outside the allowlist was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::HookTargetNotPermitted { target: Some(t), .. }
+                if t == unknown_hook
+        ));
+
+        // too short to name a target
+        let malformed = order(3, vec![1, 2, 3]);
+        let Err(e) = processor.verify_order(malformed.clone(), pool_info(&malformed), 420) else {
+            panic!("malformed hook was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::HookTargetNotPermitted { target: None, .. }
+        ));
+    }
+
+    #[test]
+    fn test_hook_data_size_cap() {
+        let user = Address::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+        mock_pool.add_pool(token0, token1, pool);
+
+        let config = ValidationConfig { max_hook_data_bytes: Some(64), ..Default::default() };
+        let processor = setup_test_account_processor().with_config(config);
+
+        let order = |nonce: u64, hook_size: usize| -> GroupedComposableOrder {
+            let order: GroupedVanillaOrder = UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(100)
+                .nonce(nonce)
+                .recipient(user)
+                .build();
+            let GroupedVanillaOrder::Standing(StandingVariants::Exact(mut order)) = order else {
+                unreachable!()
+            };
+            order.hook_data = vec![1u8; hook_size].into();
+            GroupedComposableOrder::Partial(StandingVariants::Exact(order))
+        };
+        let pool_info = |order: &GroupedComposableOrder| {
+            mock_pool
+                .fetch_pool_info_for_order(order)
+                .expect("pool tracker should have valid state")
+        };
+
+        let at_cap = order(1, 64);
+        assert!(processor
+            .verify_order(at_cap.clone(), pool_info(&at_cap), 420)
+            .is_ok());
+
+        let over_cap = order(2, 65);
+        let Err(e) = processor.verify_order(over_cap.clone(), pool_info(&over_cap), 420) else {
+            panic!("hook data over the cap was accepted")
+        };
+        assert!(matches!(
+            e,
+            UserAccountVerificationError::HookDataTooLarge { size: 65, max_size: 64, .. }
+        ));
+    }
+
+    #[test]
+    fn test_live_state_cache_tracks_pending_actions() {
+        let processor = setup_test_account_processor();
+
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let mock_pool = MockPoolTracker::default();
+        let pool = PoolId::default();
+
+        mock_pool.add_pool(token0, token1, pool);
+
+        let order0: GroupedVanillaOrder = UserOrderBuilder::new()
+            .kill_or_fill()
+            .exact()
+            .block(420)
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(100)
+            .build();
+        let order1: GroupedVanillaOrder = UserOrderBuilder::new()
+            .kill_or_fill()
+            .exact()
+            .block(420)
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(101)
+            .build();
+        let user = order0.from();
+        let respend = order1.respend_avoidance_strategy();
+
+        let pool_info = mock_pool
+            .fetch_pool_info_for_order(&order0)
+            .expect("pool tracker should have valid state");
+
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(150));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token0, U256::from(150));
+
+        let res = processor
+            .verify_order(order0, pool_info.clone(), 420)
+            .expect("order should be valid");
+        assert!(res.is_currently_valid);
+
+        // the cached live state has the first order applied
+        let live_state = processor.user_accounts.get_live_state_for_order(
+            user,
+            token0,
+            respend,
+            &processor.fetch_utils
+        );
+        assert_eq!(live_state.balance, U256::from(50));
+        assert_eq!(live_state.approval, U256::from(50));
+
+        let res = processor
+            .verify_order(order1, pool_info, 420)
+            .expect("order should be valid");
+        assert!(!res.is_currently_valid);
+
+        // a new block drops the cache and reloads the baseline
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(1_000));
+        processor.prepare_for_new_block(vec![user], vec![]);
+        let live_state = processor.user_accounts.get_live_state_for_order(
+            user,
+            token0,
+            respend,
+            &processor.fetch_utils
+        );
+        assert_eq!(live_state.balance, U256::from(1_000));
+    }
+
+    #[test]
+    fn test_live_states_for_all_tokens_of_an_order() {
+        let processor = setup_test_account_processor();
+        let user = Address::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let respend = RespendAvoidanceMethod::Nonce(0);
+
+        processor
+            .fetch_utils
+            .set_balance_for_user(user, token0, U256::from(100));
+        processor
+            .fetch_utils
+            .set_approval_for_user(user, token1, U256::from(200));
+        assert_eq!(processor.prewarm(user, &[token0]), 1);
+
+        let states = processor.user_accounts.get_live_states_for_order(
+            user,
+            &[token0, token1],
+            respend,
+            &processor.fetch_utils
+        );
+        assert_eq!(states.len(), 2);
+        assert_eq!(states[&token0].balance, U256::from(100));
+        assert_eq!(states[&token1].approval, U256::from(200));
+
+        // both baselines are known now
+        assert_eq!(processor.prewarm(user, &[token0, token1]), 0);
+        for token in [token0, token1] {
+            let single = processor.user_accounts.get_live_state_for_order(
+                user,
+                token,
+                respend,
+                &processor.fetch_utils
+            );
+            assert_eq!(states[&token], single);
+        }
+    }
+
+    #[test]
+    fn test_price_deviation_from_market() {
+        let market = *Ray::from(2.0);
+        let within = |bps: u64| market + market * U256::from(bps) / U256::from(10_000);
+
+        // any price is accepted unless a deviation is configured
+        assert!(ValidationConfig::default().is_price_near_market(within(100_000), market));
+
+        let config = ValidationConfig { max_price_deviation_bps: Some(500), ..Default::default() };
+        assert!(config.is_price_near_market(market, market));
+        assert!(config.is_price_near_market(within(500), market));
+        assert!(!config.is_price_near_market(within(501), market));
+        assert!(!config.is_price_near_market(market / U256::from(2), market));
+    }
 }
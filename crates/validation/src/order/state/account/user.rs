@@ -17,6 +17,7 @@ pub struct BaselineState {
     angstrom_balance: HashMap<TokenAddress, Amount>
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct LiveState {
     pub token:            TokenAddress,
     pub approval:         Amount,
@@ -25,6 +26,9 @@ pub struct LiveState {
 }
 
 impl LiveState {
+    /// Whether the user holds enough of the order's token in. The pool fee
+    /// doesn't enter this check, it's taken out of what the pool pays out, so
+    /// the amount in the user has to cover is the order's amount in either way.
     pub fn can_support_order<O: RawPoolOrder>(
         &self,
         order: &O,
@@ -81,7 +85,12 @@ pub struct UserAccounts {
     pending_actions: Arc<DashMap<UserAddress, Vec<PendingUserAction>>>,
 
     /// the last updated state of a given user.
-    last_known_state: Arc<DashMap<UserAddress, BaselineState>>
+    last_known_state: Arc<DashMap<UserAddress, BaselineState>>,
+
+    /// live states computed during the current validation pass, keyed by the
+    /// respend ordering they were computed for. kept in sync as pending
+    /// actions are inserted and dropped at the end of the pass.
+    live_state_cache: Arc<DashMap<(UserAddress, TokenAddress), HashMap<u64, LiveState>>>
 }
 
 impl Default for UserAccounts {
@@ -94,11 +103,15 @@ impl UserAccounts {
     pub fn new() -> Self {
         Self {
             pending_actions:  Arc::new(DashMap::default()),
-            last_known_state: Arc::new(DashMap::default())
+            last_known_state: Arc::new(DashMap::default()),
+            live_state_cache: Arc::new(DashMap::default())
         }
     }
 
     pub fn new_block(&self, users: Vec<Address>, orders: Vec<B256>) {
+        // the pass is over, live states get recomputed against the new baseline
+        self.live_state_cache.clear();
+
         // remove all user specific orders
         users.iter().for_each(|user| {
             self.pending_actions.remove(user);
@@ -122,6 +135,11 @@ impl UserAccounts {
             res |= !matches;
             matches
         });
+        drop(inner_orders);
+
+        if res {
+            self.invalidate_live_states(user);
+        }
 
         res
     }
@@ -147,6 +165,43 @@ impl UserAccounts {
         }
     }
 
+    /// the lowest nonce that is neither used on chain nor taken by one of the
+    /// users pending orders. nonces are stored on chain as a bitmap of 256
+    /// nonces per word, so this goes through them a word at a time.
+    pub fn next_valid_nonce<S: StateFetchUtils>(&self, user: UserAddress, utils: &S) -> u64 {
+        let pending_nonces = self
+            .pending_actions
+            .get(&user)
+            .map(|actions| {
+                actions
+                    .iter()
+                    .filter_map(|action| match action.respend {
+                        RespendAvoidanceMethod::Nonce(nonce) => Some(nonce),
+                        RespendAvoidanceMethod::Block(_) => None
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        let mut word_start = 0u64;
+        loop {
+            let used = pending_nonces
+                .iter()
+                .filter(|nonce| *nonce >> 8 == word_start >> 8)
+                .fold(utils.fetch_used_nonces_word(user, word_start), |word, nonce| {
+                    word | (U256::from(1) << (*nonce as u8))
+                });
+
+            if used != U256::MAX {
+                return word_start + (!used).trailing_zeros() as u64
+            }
+
+            word_start = word_start
+                .checked_add(256)
+                .expect("user has used every nonce");
+        }
+    }
+
     pub fn get_live_state_for_order<S: StateFetchUtils>(
         &self,
         user: UserAddress,
@@ -154,7 +209,17 @@ impl UserAccounts {
         respend: RespendAvoidanceMethod,
         utils: &S
     ) -> LiveState {
-        self.try_fetch_live_pending_state(user, token, respend)
+        let ord = respend.get_ord_for_pending_orders();
+        if let Some(cached) = self
+            .live_state_cache
+            .get(&(user, token))
+            .and_then(|states| states.get(&ord).copied())
+        {
+            return cached
+        }
+
+        let live_state = self
+            .try_fetch_live_pending_state(user, token, respend)
             .unwrap_or_else(|| {
                 self.load_state_for(user, token, utils);
                 self.try_fetch_live_pending_state(user, token, respend)
@@ -162,25 +227,163 @@ impl UserAccounts {
                         "after loading state for a address, the state wasn't found. this should \
                          be impossible"
                     )
+            });
+
+        self.live_state_cache
+            .entry((user, token))
+            .or_default()
+            .insert(ord, live_state);
+
+        live_state
+    }
+
+    /// [`Self::get_live_state_for_order`] for every token an order touches.
+    /// The baselines that aren't known yet are all read before any of them is
+    /// stored, so the users entry is only locked once.
+    pub fn get_live_states_for_order<S: StateFetchUtils>(
+        &self,
+        user: UserAddress,
+        tokens: &[TokenAddress],
+        respend: RespendAvoidanceMethod,
+        utils: &S
+    ) -> HashMap<TokenAddress, LiveState> {
+        let missing = self
+            .last_known_state
+            .get(&user)
+            .map(|state| {
+                tokens
+                    .iter()
+                    .filter(|token| !state.token_balance.contains_key(*token))
+                    .copied()
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_else(|| tokens.to_vec());
+        if !missing.is_empty() {
+            self.load_states_for(user, &missing, utils);
+        }
+
+        tokens
+            .iter()
+            .map(|token| (*token, self.get_live_state_for_order(user, *token, respend, utils)))
+            .collect()
+    }
+
+    /// same as [`Self::get_live_state_for_order`] but never writes to the
+    /// baseline or the live state cache. Used for dry runs.
+    pub fn peek_live_state_for_order<S: StateFetchUtils>(
+        &self,
+        user: UserAddress,
+        token: TokenAddress,
+        respend: RespendAvoidanceMethod,
+        utils: &S
+    ) -> LiveState {
+        let ord = respend.get_ord_for_pending_orders();
+        if let Some(cached) = self
+            .live_state_cache
+            .get(&(user, token))
+            .and_then(|states| states.get(&ord).copied())
+        {
+            return cached
+        }
+
+        self.try_fetch_live_pending_state(user, token, respend)
+            .unwrap_or_else(|| {
+                // nothing is tracked for the user, so there is nothing pending either
+                let balance = utils.fetch_balance_for_token(user, token);
+                LiveState {
+                    token,
+                    approval: utils
+                        .fetch_approval_balance_for_token(user, token)
+                        .unwrap_or_default(),
+                    balance,
+                    angstrom_balance: balance
+                }
             })
     }
 
+    /// drops all cached live states for the user, used when a pending action
+    /// is removed as there is no cheap way to add it back.
+    fn invalidate_live_states(&self, user: &UserAddress) {
+        self.live_state_cache
+            .retain(|(cached_user, _), _| cached_user != user);
+    }
+
+    /// applies a newly inserted pending action to the cached live states that
+    /// include it. i.e all states for the same token with a respend ordering
+    /// greater than or equal to the action.
+    fn apply_to_cached_live_states(&self, user: UserAddress, action: &PendingUserAction) {
+        let Some(mut states) = self.live_state_cache.get_mut(&(user, action.token_address)) else {
+            return
+        };
+
+        let action_ord = action.respend.get_ord_for_pending_orders();
+        states
+            .iter_mut()
+            .filter(|(ord, _)| **ord >= action_ord)
+            .for_each(|(_, state)| {
+                state.approval = state.approval.saturating_sub(action.token_approval);
+                state.balance = state.balance.saturating_sub(action.token_delta);
+                state.angstrom_balance =
+                    state.angstrom_balance.saturating_sub(action.angstrom_delta);
+            });
+    }
+
+    /// loads the users baseline for the token unless it's already known.
+    /// Returns whether anything was fetched
+    pub fn prewarm<S: StateFetchUtils>(
+        &self,
+        user: UserAddress,
+        token: TokenAddress,
+        utils: &S
+    ) -> bool {
+        let known = self
+            .last_known_state
+            .get(&user)
+            .is_some_and(|state| state.token_balance.contains_key(&token));
+        if !known {
+            self.load_state_for(user, token, utils);
+        }
+
+        !known
+    }
+
     fn load_state_for<S: StateFetchUtils>(
         &self,
         user: UserAddress,
         token: TokenAddress,
         utils: &S
     ) {
-        let approvals = utils
-            .fetch_approval_balance_for_token(user, token)
-            .unwrap_or_default();
-        let balances = utils.fetch_balance_for_token(user, token);
+        self.load_states_for(user, &[token], utils);
+    }
+
+    fn load_states_for<S: StateFetchUtils>(
+        &self,
+        user: UserAddress,
+        tokens: &[TokenAddress],
+        utils: &S
+    ) {
+        let fetched = tokens
+            .iter()
+            .map(|token| {
+                let approvals = utils
+                    .fetch_approval_balance_for_token(user, *token)
+                    .unwrap_or_default();
+                (*token, approvals, utils.fetch_balance_for_token(user, *token))
+            })
+            .collect::<Vec<_>>();
 
         let mut entry = self.last_known_state.entry(user).or_default();
         // override as fresh query
-        entry.token_balance.insert(token, balances);
-        entry.token_approval.insert(token, approvals);
-        entry.angstrom_balance.insert(token, balances);
+        for (token, approvals, balances) in &fetched {
+            entry.token_balance.insert(*token, *balances);
+            entry.token_approval.insert(*token, *approvals);
+            entry.angstrom_balance.insert(*token, *balances);
+        }
+        drop(entry);
+
+        for (token, ..) in fetched {
+            self.live_state_cache.remove(&(user, token));
+        }
     }
 
     /// inserts the user action and returns all pending user action hashes that
@@ -192,6 +395,8 @@ impl UserAccounts {
         action: PendingUserAction
     ) -> Vec<B256> {
         let token = action.token_address;
+        self.apply_to_cached_live_states(user, &action);
+
         let mut entry = self.pending_actions.entry(user).or_default();
         let value = entry.value_mut();
 
@@ -1,10 +1,299 @@
-use std::fmt::Debug;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    num::NonZeroUsize,
+    time::Duration
+};
 
 use alloy::primitives::{keccak256, Address, U256};
+use angstrom_types::primitive::PoolId;
 use eyre::eyre;
 use reth_revm::DatabaseRef;
 use serde::Deserialize;
 
+use crate::{bundle::InclusionEntry, common::DbRetryConfig};
+
+/// Seconds an order needs left before its deadline to be accepted, unless
+/// configured otherwise. Short enough to only turn away orders that can't
+/// make it into the next block.
+pub const DEFAULT_MIN_TIME_TO_DEADLINE_SECS: u64 = 2;
+
+/// Order level limits that are enforced during validation.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ValidationConfig {
+    /// the minimum notional value of an order per pool, denominated in the
+    /// pool's quote token (token1). Asks are valued at the pool's settled
+    /// price. Orders below it are rejected as dust.
+    #[serde(default)]
+    pub min_notional:              HashMap<PoolId, U256>,
+    /// retry policy for state reads that fail with a transient db error
+    #[serde(default)]
+    pub db_retry:                  DbRetryConfig,
+    /// holds order validation until prices and pools have loaded
+    #[serde(default)]
+    pub warmup:                    WarmupConfig,
+    /// blocks after which a pair's last settled price is no longer used to
+    /// convert gas. Valuing an order or bundle over a stale pair errors
+    #[serde(default)]
+    pub max_price_age_blocks:      Option<u64>,
+    /// blocks of prices kept per pair, gas is converted at their average.
+    /// 5 if not set
+    #[serde(default)]
+    pub price_history_blocks:      Option<usize>,
+    /// when set, only the prices of this many pairs are kept, the least
+    /// recently priced ones are dropped until they're priced again. Has to
+    /// be positive, a cap of 0 would leave no pair to convert gas over
+    #[serde(default)]
+    pub max_priced_pairs:          Option<NonZeroUsize>,
+    /// loads the recent price history of pairs that have no price at
+    /// startup from the chain, instead of waiting for price updates
+    #[serde(default)]
+    pub backfill_prices:           bool,
+    /// when set, only orders whose tokens are all in this set are accepted
+    #[serde(default)]
+    pub allowed_tokens:            Option<HashSet<Address>>,
+    /// orders touching any of these tokens are rejected
+    #[serde(default)]
+    pub denied_tokens:             HashSet<Address>,
+    /// when set, composable orders may only call hooks on these contracts
+    #[serde(default)]
+    pub allowed_hook_targets:      Option<HashSet<Address>>,
+    /// composable orders whose hook data is longer than this many bytes are
+    /// rejected before they are simulated. Any size is accepted if not set
+    #[serde(default)]
+    pub max_hook_data_bytes:       Option<usize>,
+    /// reuses validation outcomes for orders seen again in the same block
+    #[serde(default)]
+    pub result_cache:              ResultCacheConfig,
+    /// how long the gas simulation of a single order may run before it's
+    /// aborted and the order rejected. Unbounded if not set
+    #[serde(default)]
+    pub order_timeout_ms:          Option<u64>,
+    /// orders with a deadline further ahead than this many seconds are
+    /// rejected, any deadline is accepted if not set
+    #[serde(default)]
+    pub max_deadline_horizon_secs: Option<u64>,
+    /// orders with less than this many seconds left before their deadline
+    /// are rejected, they'd likely expire before being matched.
+    /// [`DEFAULT_MIN_TIME_TO_DEADLINE_SECS`] if not set, 0 accepts any order
+    /// that hasn't expired
+    #[serde(default)]
+    pub min_time_to_deadline_secs: Option<u64>,
+    /// accepts signatures with a high s value. Off by default, as the high
+    /// and low s form of a signature both recover the signer, so any order
+    /// seen on the network can be resent with a second valid signature
+    /// (EIP-2)
+    #[serde(default)]
+    pub accept_high_s_signatures:  bool,
+    /// threads the order and bundle validation runs on
+    #[serde(default)]
+    pub runtime:                   RuntimeConfig,
+    /// orders trading a token that takes a larger fee on transfer than this
+    /// many basis points, or whose transfer can't be simulated, are
+    /// rejected. Transfers aren't probed if not set
+    #[serde(default)]
+    pub max_transfer_tax_bps:      Option<u32>,
+    /// limit orders priced further than this many basis points from the
+    /// pool's spot price are rejected. Off if not set, so resting orders far
+    /// from the market are accepted
+    #[serde(default)]
+    pub max_price_deviation_bps:   Option<u32>,
+    /// what happens to orders whose tokens have no usable eth price, so their
+    /// gas can't be valued
+    #[serde(default)]
+    pub missing_price:             MissingPricePolicy,
+    /// what happens to orders for pairs with an angstrom pool whose uniswap
+    /// pool wasn't initialized yet
+    #[serde(default)]
+    pub uninitialized_pools:       UninitializedPoolPolicy,
+    /// orders bundles have to carry, bundles without them fail validation.
+    /// Entries for flash and top of block orders name the block they're
+    /// required at
+    #[serde(default)]
+    pub inclusion_list:            Vec<InclusionEntry>
+}
+
+impl ValidationConfig {
+    pub fn order_timeout(&self) -> Option<Duration> {
+        self.order_timeout_ms.map(Duration::from_millis)
+    }
+
+    pub fn min_time_to_deadline_secs(&self) -> u64 {
+        self.min_time_to_deadline_secs
+            .unwrap_or(DEFAULT_MIN_TIME_TO_DEADLINE_SECS)
+    }
+
+    /// whether orders are allowed to reference the token
+    pub fn is_token_permitted(&self, token: &Address) -> bool {
+        !self.denied_tokens.contains(token)
+            && self
+                .allowed_tokens
+                .as_ref()
+                .map_or(true, |allowed| allowed.contains(token))
+    }
+
+    /// whether an order with the given hook data is allowed to call its hook.
+    /// Orders without a hook always are
+    pub fn is_hook_permitted(&self, hook_calldata: &[u8], target: Option<Address>) -> bool {
+        let Some(allowed) = self.allowed_hook_targets.as_ref() else { return true };
+        hook_calldata.is_empty() || target.is_some_and(|target| allowed.contains(&target))
+    }
+
+    /// whether `price` is within the allowed deviation of `market_price`, both
+    /// as `Ray`. Any price is if no deviation is set
+    pub fn is_price_near_market(&self, price: U256, market_price: U256) -> bool {
+        let Some(max_deviation_bps) = self.max_price_deviation_bps else { return true };
+        price
+            .abs_diff(market_price)
+            .saturating_mul(U256::from(10_000))
+            <= market_price.saturating_mul(U256::from(max_deviation_bps))
+    }
+}
+
+/// How orders are handled when their tokens have no usable eth price.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissingPricePolicy {
+    /// the order is rejected
+    #[default]
+    Reject,
+    /// the order is accepted without the checks that need a price, its gas
+    /// isn't charged in token0
+    Admit
+}
+
+/// How orders are handled when their pair has an angstrom pool but the
+/// uniswap pool behind it wasn't initialized yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UninitializedPoolPolicy {
+    /// no special handling, limit orders are usually rejected for lack of a
+    /// price and searcher orders wait for the pool to sync
+    #[default]
+    Reject,
+    /// the order is held until the pool is initialized and the pool manager
+    /// reports what initializing the pool takes
+    Hold
+}
+
+/// When the validator starts answering order requests after startup.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WarmupConfig {
+    /// hold order and simulation requests until ready, false validates them
+    /// right away
+    #[serde(default = "WarmupConfig::default_enabled")]
+    pub enabled:           bool,
+    /// token price updates that have to be applied before orders are
+    /// validated
+    #[serde(default = "WarmupConfig::default_min_price_updates")]
+    pub min_price_updates: usize,
+    /// requests held while warming up, orders beyond it are rejected as
+    /// unavailable
+    #[serde(default = "WarmupConfig::default_max_held_requests")]
+    pub max_held_requests: usize
+}
+
+impl WarmupConfig {
+    const fn default_enabled() -> bool {
+        true
+    }
+
+    const fn default_min_price_updates() -> usize {
+        1
+    }
+
+    const fn default_max_held_requests() -> usize {
+        4096
+    }
+
+    /// no warm-up, requests are validated as soon as they arrive
+    pub const fn disabled() -> Self {
+        Self { enabled: false, min_price_updates: 0, max_held_requests: 0 }
+    }
+}
+
+impl Default for WarmupConfig {
+    fn default() -> Self {
+        Self {
+            enabled:           Self::default_enabled(),
+            min_price_updates: Self::default_min_price_updates(),
+            max_held_requests: Self::default_max_held_requests()
+        }
+    }
+}
+
+/// The runtimes validation is driven by.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeConfig {
+    /// worker threads of the order validation runtime
+    #[serde(default = "RuntimeConfig::default_worker_threads")]
+    pub worker_threads:            NonZeroUsize,
+    /// when set, bundles are simulated on a dedicated runtime with this many
+    /// worker threads so heavy bundles can't starve order validation. They
+    /// share the order validation runtime otherwise
+    #[serde(default)]
+    pub bundle_worker_threads:     Option<NonZeroUsize>,
+    /// validations running at once over all senders, on each runtime. The
+    /// rest wait and are scheduled round-robin by sender as slots free up
+    #[serde(default = "RuntimeConfig::default_max_validations_in_flight")]
+    pub max_validations_in_flight: NonZeroUsize
+}
+
+impl RuntimeConfig {
+    fn default_worker_threads() -> NonZeroUsize {
+        NonZeroUsize::new(4).unwrap()
+    }
+
+    fn default_max_validations_in_flight() -> NonZeroUsize {
+        NonZeroUsize::new(16).unwrap()
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads:            Self::default_worker_threads(),
+            bundle_worker_threads:     None,
+            max_validations_in_flight: Self::default_max_validations_in_flight()
+        }
+    }
+}
+
+/// Per block cache of order validation outcomes.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResultCacheConfig {
+    #[serde(default = "ResultCacheConfig::default_enabled")]
+    pub enabled:  bool,
+    /// outcomes kept before the least recently used one is evicted
+    #[serde(default = "ResultCacheConfig::default_capacity")]
+    pub capacity: u32
+}
+
+impl ResultCacheConfig {
+    const fn default_enabled() -> bool {
+        true
+    }
+
+    const fn default_capacity() -> u32 {
+        10_000
+    }
+
+    /// the size to build the cache with, 0 when it's turned off
+    pub const fn effective_capacity(&self) -> u32 {
+        if self.enabled {
+            self.capacity
+        } else {
+            0
+        }
+    }
+}
+
+impl Default for ResultCacheConfig {
+    fn default() -> Self {
+        Self { enabled: Self::default_enabled(), capacity: Self::default_capacity() }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub enum HashMethod {
     #[serde(rename = "sol")]
@@ -95,3 +384,43 @@ impl TokenApprovalSlot {
             .map_err(|_| eyre!("failed to load approval slot"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validations_in_flight_must_be_positive() {
+        assert!(toml::from_str::<RuntimeConfig>("max_validations_in_flight = 0").is_err());
+
+        let config = toml::from_str::<RuntimeConfig>("max_validations_in_flight = 4").unwrap();
+        assert_eq!(config.max_validations_in_flight.get(), 4);
+        let config = toml::from_str::<RuntimeConfig>("").unwrap();
+        assert_eq!(config.max_validations_in_flight.get(), 16);
+    }
+
+    #[test]
+    fn test_max_priced_pairs_must_be_positive() {
+        assert!(toml::from_str::<ValidationConfig>("max_priced_pairs = 0").is_err());
+
+        let config = toml::from_str::<ValidationConfig>("max_priced_pairs = 8").unwrap();
+        assert_eq!(config.max_priced_pairs.map(NonZeroUsize::get), Some(8));
+        let config = toml::from_str::<ValidationConfig>("").unwrap();
+        assert_eq!(config.max_priced_pairs, None);
+    }
+
+    #[test]
+    fn test_worker_threads_must_be_positive() {
+        assert!(toml::from_str::<RuntimeConfig>("worker_threads = 0").is_err());
+        assert!(toml::from_str::<RuntimeConfig>("bundle_worker_threads = 0").is_err());
+
+        let config =
+            toml::from_str::<RuntimeConfig>("worker_threads = 2\nbundle_worker_threads = 1")
+                .unwrap();
+        assert_eq!(config.worker_threads.get(), 2);
+        assert_eq!(config.bundle_worker_threads.map(NonZeroUsize::get), Some(1));
+        let config = toml::from_str::<RuntimeConfig>("").unwrap();
+        assert_eq!(config.worker_threads.get(), 4);
+        assert_eq!(config.bundle_worker_threads, None);
+    }
+}
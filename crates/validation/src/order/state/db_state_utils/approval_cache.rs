@@ -0,0 +1,205 @@
+use std::{collections::HashMap, sync::Arc};
+
+use alloy::{
+    primitives::{Address, Log, U256},
+    sol_types::SolEvent
+};
+use parking_lot::Mutex;
+use reth_provider::{CanonStateNotification, Chain};
+use schnellru::{ByLength, LruMap};
+
+alloy::sol!(
+    event Transfer(address indexed _from, address indexed _to, uint256 _value);
+    event Approval(address indexed _owner, address indexed _spender, uint256 _value);
+);
+
+/// Approvals read from chain, so an owner that approves once and trades many
+/// times isn't read again for every order. An entry is kept until an
+/// `Approval` for its owner, token and spender shows up on chain. A
+/// `Transfer` from the owner drops all of its entries for the token, as
+/// `transferFrom` spends allowance without necessarily emitting an
+/// `Approval`. Beyond [`APPROVAL_CACHE_CAPACITY`] owner and token pairs the
+/// least recently used ones are dropped.
+///
+/// Clones share the same entries.
+#[derive(Clone)]
+pub struct ApprovalCache {
+    inner: Arc<Mutex<Entries>>
+}
+
+/// owner and token pairs whose approvals are kept
+const APPROVAL_CACHE_CAPACITY: u32 = 100_000;
+
+struct Entries {
+    /// (owner, token) to the allowance of each spender
+    approvals:  LruMap<(Address, Address), HashMap<Address, U256>, ByLength>,
+    /// bumped by every invalidation, so an approval read from chain before it
+    /// isn't cached after it
+    generation: u64
+}
+
+impl Default for ApprovalCache {
+    fn default() -> Self {
+        Self::new(APPROVAL_CACHE_CAPACITY)
+    }
+}
+
+impl ApprovalCache {
+    /// keeps the approvals of up to `capacity` owner and token pairs
+    pub fn new(capacity: u32) -> Self {
+        let entries = Entries { approvals: LruMap::new(ByLength::new(capacity)), generation: 0 };
+        Self { inner: Arc::new(Mutex::new(entries)) }
+    }
+
+    pub fn get(&self, owner: Address, token: Address, spender: Address) -> Option<U256> {
+        self.inner
+            .lock()
+            .approvals
+            .get(&(owner, token))
+            .and_then(|spenders| spenders.get(&spender).copied())
+    }
+
+    /// to be read before the approval is loaded from chain and passed to
+    /// [`Self::insert`]
+    pub fn generation(&self) -> u64 {
+        self.inner.lock().generation
+    }
+
+    /// caches the approval loaded from chain when the cache was at
+    /// `generation`. Dropped if the cache was invalidated since, the approval
+    /// may have been read before the change that invalidated it
+    pub fn insert(
+        &self,
+        owner: Address,
+        token: Address,
+        spender: Address,
+        amount: U256,
+        generation: u64
+    ) {
+        let mut inner = self.inner.lock();
+        if inner.generation != generation {
+            return
+        }
+        if let Some(spenders) = inner
+            .approvals
+            .get_or_insert((owner, token), HashMap::default)
+        {
+            spenders.insert(spender, amount);
+        }
+    }
+
+    /// Drops the entries the notification's blocks could have changed. On a
+    /// reorg the reverted blocks are gone too, so whatever they changed is
+    /// dropped along with the changes of the new blocks.
+    pub fn on_canon_state(&self, notification: &CanonStateNotification) {
+        match notification {
+            CanonStateNotification::Commit { new } => self.invalidate_chain(new),
+            CanonStateNotification::Reorg { old, new } => {
+                self.invalidate_chain(old);
+                self.invalidate_chain(new);
+            }
+        }
+    }
+
+    fn invalidate_chain(&self, chain: &Chain) {
+        let mut inner = self.inner.lock();
+        inner.generation += 1;
+        chain
+            .blocks_iter()
+            .filter_map(|block| chain.receipts_by_block_hash(block.hash()))
+            .flatten()
+            .flat_map(|receipt| &receipt.logs)
+            .for_each(|log| inner.invalidate_log(log));
+    }
+}
+
+impl Entries {
+    fn invalidate_log(&mut self, log: &Log) {
+        let token = log.address;
+        if let Ok(approval) = Approval::decode_log(log, true) {
+            if let Some(spenders) = self.approvals.get(&(approval._owner, token)) {
+                spenders.remove(&approval._spender);
+            }
+        } else if let Ok(transfer) = Transfer::decode_log(log, true) {
+            self.approvals.remove(&(transfer._from, token));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::LogData;
+
+    use super::*;
+
+    fn log(token: Address, event: &impl SolEvent) -> Log {
+        Log { address: token, data: event.encode_log_data() }
+    }
+
+    impl ApprovalCache {
+        fn insert_current(&self, owner: Address, token: Address, spender: Address, amount: u64) {
+            self.insert(owner, token, spender, U256::from(amount), self.generation());
+        }
+
+        fn invalidate_log(&self, log: &Log) {
+            self.inner.lock().invalidate_log(log);
+        }
+    }
+
+    #[test]
+    fn events_drop_only_the_approvals_they_change() {
+        let cache = ApprovalCache::default();
+        let (owner, token, spender, other) =
+            (Address::random(), Address::random(), Address::random(), Address::random());
+        cache.insert_current(owner, token, spender, 10);
+        cache.insert_current(owner, token, other, 20);
+
+        let approval = Approval { _owner: owner, _spender: spender, _value: U256::from(5) };
+        cache.invalidate_log(&log(token, &approval));
+        assert_eq!(cache.get(owner, token, spender), None);
+        assert_eq!(cache.get(owner, token, other), Some(U256::from(20)));
+
+        // events of another token leave the approval alone
+        cache.invalidate_log(&log(Address::random(), &approval));
+        assert_eq!(cache.get(owner, token, other), Some(U256::from(20)));
+
+        let transfer = Transfer { _from: owner, _to: spender, _value: U256::from(1) };
+        cache.invalidate_log(&log(token, &transfer));
+        assert_eq!(cache.get(owner, token, other), None);
+
+        // logs that aren't erc20 events are ignored
+        cache.insert_current(owner, token, spender, 10);
+        cache.invalidate_log(&Log { address: token, data: LogData::default() });
+        assert_eq!(cache.get(owner, token, spender), Some(U256::from(10)));
+    }
+
+    #[test]
+    fn approvals_read_before_an_invalidation_are_not_cached() {
+        let cache = ApprovalCache::default();
+        let (owner, token, spender) = (Address::random(), Address::random(), Address::random());
+
+        let generation = cache.generation();
+        cache.invalidate_chain(&Chain::default());
+        cache.insert(owner, token, spender, U256::from(10), generation);
+        assert_eq!(cache.get(owner, token, spender), None);
+
+        cache.insert_current(owner, token, spender, 10);
+        assert_eq!(cache.get(owner, token, spender), Some(U256::from(10)));
+    }
+
+    #[test]
+    fn least_recently_used_owners_are_dropped_beyond_the_capacity() {
+        let cache = ApprovalCache::new(2);
+        let (token, spender) = (Address::random(), Address::random());
+        let owners = [Address::random(), Address::random(), Address::random()];
+
+        cache.insert_current(owners[0], token, spender, 1);
+        cache.insert_current(owners[1], token, spender, 2);
+        assert_eq!(cache.get(owners[0], token, spender), Some(U256::from(1)));
+        cache.insert_current(owners[2], token, spender, 3);
+
+        assert_eq!(cache.get(owners[1], token, spender), None);
+        assert_eq!(cache.get(owners[0], token, spender), Some(U256::from(1)));
+        assert_eq!(cache.get(owners[2], token, spender), Some(U256::from(3)));
+    }
+}
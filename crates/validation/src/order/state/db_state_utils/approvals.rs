@@ -4,18 +4,25 @@ use alloy::primitives::{Address, U256};
 use dashmap::DashMap;
 use reth_revm::DatabaseRef;
 
-use super::finders::find_slot_offset_for_approval;
+use super::{approval_cache::ApprovalCache, finders::find_slot_offset_for_approval};
 use crate::order::state::config::TokenApprovalSlot;
 
 #[derive(Clone)]
 pub struct Approvals {
     angstrom_address: Address,
-    slots:            DashMap<Address, TokenApprovalSlot>
+    slots:            DashMap<Address, TokenApprovalSlot>,
+    cache:            ApprovalCache
 }
 
 impl Approvals {
     pub fn new(angstrom_address: Address) -> Self {
-        Self { angstrom_address, slots: DashMap::default() }
+        Self { angstrom_address, slots: DashMap::default(), cache: ApprovalCache::default() }
+    }
+
+    /// the cache of the approvals read from chain, it has to be fed the
+    /// canonical chain notifications to stay correct
+    pub fn cache(&self) -> ApprovalCache {
+        self.cache.clone()
     }
 
     pub fn fetch_approval_balance_for_token_overrides<DB: revm::DatabaseRef>(
@@ -31,7 +38,11 @@ impl Approvals {
         self.slots
             .get(&token)
             .or_else(|| {
-                let slot = find_slot_offset_for_approval(&db, token);
+                let slot = find_slot_offset_for_approval(&db, token)
+                    .inspect_err(
+                        |error| tracing::debug!(%error, "can't load the token's approvals")
+                    )
+                    .ok()?;
                 let slot = TokenApprovalSlot::new(token, slot as u8);
                 self.slots.insert(token, slot);
                 self.slots.get(&token)
@@ -57,10 +68,20 @@ impl Approvals {
     where
         <DB as DatabaseRef>::Error: Debug + Sync + Send + 'static
     {
-        self.slots
+        if let Some(amount) = self.cache.get(user, token, self.angstrom_address) {
+            return Some(amount)
+        }
+        let generation = self.cache.generation();
+
+        let amount = self
+            .slots
             .get(&token)
             .or_else(|| {
-                let slot = find_slot_offset_for_approval(db, token);
+                let slot = find_slot_offset_for_approval(db, token)
+                    .inspect_err(
+                        |error| tracing::debug!(%error, "can't load the token's approvals")
+                    )
+                    .ok()?;
                 let slot = TokenApprovalSlot::new(token, slot as u8);
                 self.slots.insert(token, slot);
                 self.slots.get(&token)
@@ -68,6 +89,10 @@ impl Approvals {
             .and_then(|slot| {
                 slot.load_approval_amount(user, self.angstrom_address, db)
                     .ok()
-            })
+            })?;
+        self.cache
+            .insert(user, token, self.angstrom_address, amount, generation);
+
+        Some(amount)
     }
 }
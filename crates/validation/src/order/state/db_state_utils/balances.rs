@@ -36,7 +36,9 @@ impl Balances {
         self.tokens
             .get(&token)
             .or_else(|| {
-                let slot = find_slot_offset_for_balance(&db, token);
+                let slot = find_slot_offset_for_balance(&db, token)
+                    .inspect_err(|error| tracing::debug!(%error, "can't load the token's balances"))
+                    .ok()?;
                 let slot = TokenBalanceSlot::new(token, slot as u8);
                 self.tokens.insert(token, slot);
                 self.tokens.get(&token)
@@ -64,7 +66,9 @@ impl Balances {
         self.tokens
             .get(&token)
             .or_else(|| {
-                let slot = find_slot_offset_for_balance(db, token);
+                let slot = find_slot_offset_for_balance(db, token)
+                    .inspect_err(|error| tracing::debug!(%error, "can't load the token's balances"))
+                    .ok()?;
                 let slot = TokenBalanceSlot::new(token, slot as u8);
                 self.tokens.insert(token, slot);
                 self.tokens.get(&token)
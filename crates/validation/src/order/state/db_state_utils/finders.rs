@@ -15,8 +15,23 @@ use revm::{
     primitives::{EnvWithHandlerCfg, TxKind}
 };
 
-/// panics if we cannot find the slot for the given token
-pub fn find_slot_offset_for_balance<DB: revm::DatabaseRef>(db: &DB, token_address: Address) -> u64
+use crate::common::{decode_call_result, ContractCallError};
+
+/// The storage slot a token keeps balances or approvals in couldn't be found
+/// among the first offsets, e.g. because it isn't a solidity erc20.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum FindSlotError {
+    #[error("was not able to find the balance offset of {0}")]
+    Balance(Address),
+    #[error("was not able to find the approval offset of {0}")]
+    Approval(Address)
+}
+
+/// errors if we cannot find the slot for the given token
+pub fn find_slot_offset_for_balance<DB: revm::DatabaseRef>(
+    db: &DB,
+    token_address: Address
+) -> Result<u64, FindSlotError>
 where
     <DB as revm::DatabaseRef>::Error: Debug
 {
@@ -50,18 +65,30 @@ where
             })
             .build();
 
-        let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-        let return_data = balanceOfCall::abi_decode_returns(&output, false).unwrap();
+        let return_data = match evm
+            .transact()
+            .map_err(ContractCallError::transact)
+            .and_then(|result| decode_call_result::<balanceOfCall>(&result.result))
+        {
+            Ok(return_data) => return_data,
+            Err(error) => {
+                tracing::debug!(?token_address, offset, %error, "probe call failed");
+                continue
+            }
+        };
         if return_data._0 == U256::from(123456789) {
-            return offset as u64
+            return Ok(offset as u64)
         }
     }
 
-    panic!("was not able to find balance offset");
+    Err(FindSlotError::Balance(token_address))
 }
 
-/// panics if we cannot prove the slot for the given token
-pub fn find_slot_offset_for_approval<DB: revm::DatabaseRef>(db: &DB, token_address: Address) -> u64
+/// errors if we cannot prove the slot for the given token
+pub fn find_slot_offset_for_approval<DB: revm::DatabaseRef>(
+    db: &DB,
+    token_address: Address
+) -> Result<u64, FindSlotError>
 where
     <DB as revm::DatabaseRef>::Error: Debug
 {
@@ -102,12 +129,42 @@ where
             })
             .build();
 
-        let output = evm.transact().unwrap().result.output().unwrap().to_vec();
-        let return_data = allowanceCall::abi_decode_returns(&output, false).unwrap();
+        let return_data = match evm
+            .transact()
+            .map_err(ContractCallError::transact)
+            .and_then(|result| decode_call_result::<allowanceCall>(&result.result))
+        {
+            Ok(return_data) => return_data,
+            Err(error) => {
+                tracing::debug!(?token_address, offset, %error, "probe call failed");
+                continue
+            }
+        };
         if return_data._0 == U256::from(123456789) {
-            return offset as u64
+            return Ok(offset as u64)
         }
     }
 
-    panic!("was not able to find approval offset");
+    Err(FindSlotError::Approval(token_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use revm::db::EmptyDB;
+
+    use super::*;
+
+    #[test]
+    fn tokens_without_slots_are_errors() {
+        let token = Address::random();
+
+        assert_eq!(
+            find_slot_offset_for_balance(&EmptyDB::default(), token),
+            Err(FindSlotError::Balance(token))
+        );
+        assert_eq!(
+            find_slot_offset_for_approval(&EmptyDB::default(), token),
+            Err(FindSlotError::Approval(token))
+        );
+    }
 }
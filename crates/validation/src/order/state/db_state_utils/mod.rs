@@ -1,6 +1,8 @@
+pub mod approval_cache;
 pub mod approvals;
 pub mod balances;
 pub mod nonces;
+pub mod transfer_tax;
 
 mod finders;
 
@@ -8,11 +10,15 @@ use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
 use alloy::primitives::{Address, U256};
 
-use self::{approvals::Approvals, balances::Balances, nonces::Nonces};
+use self::{approvals::Approvals, balances::Balances, nonces::Nonces, transfer_tax::TransferTaxes};
 
 pub trait StateFetchUtils: Clone + Send + Unpin {
     fn is_valid_nonce(&self, user: Address, nonce: u64) -> bool;
 
+    /// bitmap of the nonces used on chain in the 256 nonce word that `nonce`
+    /// is in
+    fn fetch_used_nonces_word(&self, user: Address, nonce: u64) -> U256;
+
     fn fetch_approval_balance_for_token_overrides(
         &self,
         user: Address,
@@ -32,6 +38,10 @@ pub trait StateFetchUtils: Clone + Send + Unpin {
     fn fetch_balance_for_token(&self, user: Address, token: Address) -> U256;
 
     fn fetch_token_balance_in_angstrom(&self, user: Address, token: Address) -> U256;
+
+    /// fee the token takes on transfer in basis points, `None` if it couldn't
+    /// be measured
+    fn fetch_transfer_tax_bps(&self, token: Address) -> Option<u32>;
 }
 
 #[derive(Debug)]
@@ -47,10 +57,11 @@ pub struct UserAccountDetails {
 
 #[derive(Clone)]
 pub struct FetchUtils<DB> {
-    pub approvals: Approvals,
-    pub balances:  Balances,
-    pub nonces:    Nonces,
-    pub db:        Arc<DB>
+    pub approvals:      Approvals,
+    pub balances:       Balances,
+    pub nonces:         Nonces,
+    pub transfer_taxes: TransferTaxes,
+    pub db:             Arc<DB>
 }
 
 impl<DB> StateFetchUtils for FetchUtils<DB>
@@ -63,6 +74,10 @@ where
         self.nonces.is_valid_nonce(user, nonce, db)
     }
 
+    fn fetch_used_nonces_word(&self, user: Address, nonce: u64) -> U256 {
+        self.nonces.fetch_nonce_word(user, nonce, &self.db)
+    }
+
     fn fetch_approval_balance_for_token_overrides(
         &self,
         user: Address,
@@ -98,6 +113,10 @@ where
     fn fetch_balance_for_token(&self, user: Address, token: Address) -> U256 {
         self.balances.fetch_balance_for_token(user, token, &self.db)
     }
+
+    fn fetch_transfer_tax_bps(&self, token: Address) -> Option<u32> {
+        self.transfer_taxes.fetch_transfer_tax_bps(token, &self.db)
+    }
 }
 
 impl<DB: revm::DatabaseRef> FetchUtils<DB> {
@@ -106,6 +125,7 @@ impl<DB: revm::DatabaseRef> FetchUtils<DB> {
             approvals: Approvals::new(angstrom_address),
             balances: Balances::new(angstrom_address),
             nonces: Nonces::new(angstrom_address),
+            transfer_taxes: TransferTaxes::default(),
             db
         }
     }
@@ -125,7 +145,10 @@ pub mod test_fetching {
         balance_values:  DashMap<Address, HashMap<Address, U256>>,
         angstrom_values: DashMap<Address, HashMap<Address, U256>>,
         approval_values: DashMap<Address, HashMap<Address, U256>>,
-        used_nonces:     DashMap<Address, HashSet<u64>>
+        used_nonces:     DashMap<Address, HashSet<u64>>,
+        /// `None` for tokens whose tax couldn't be measured, tokens that
+        /// aren't in here take no tax
+        transfer_taxes:  DashMap<Address, Option<u32>>
     }
 
     impl MockFetch {
@@ -146,6 +169,14 @@ pub mod test_fetching {
         pub fn set_used_nonces(&self, user: Address, nonces: HashSet<u64>) {
             self.used_nonces.entry(user).or_default().extend(nonces);
         }
+
+        pub fn set_transfer_tax(&self, token: Address, tax_bps: u32) {
+            self.transfer_taxes.insert(token, Some(tax_bps));
+        }
+
+        pub fn set_unknown_transfer_tax(&self, token: Address) {
+            self.transfer_taxes.insert(token, None);
+        }
     }
 
     impl StateFetchUtils for MockFetch {
@@ -156,6 +187,18 @@ pub mod test_fetching {
                 .unwrap_or(true)
         }
 
+        fn fetch_used_nonces_word(&self, user: Address, nonce: u64) -> U256 {
+            self.used_nonces
+                .get(&user)
+                .map(|v| {
+                    v.value()
+                        .iter()
+                        .filter(|used| *used >> 8 == nonce >> 8)
+                        .fold(U256::ZERO, |word, used| word | (U256::from(1) << (*used as u8)))
+                })
+                .unwrap_or_default()
+        }
+
         fn fetch_approval_balance_for_token_overrides(
             &self,
             _: Address,
@@ -193,5 +236,9 @@ pub mod test_fetching {
                 .and_then(|inner| inner.value().get(&token).cloned())
                 .unwrap_or_default()
         }
+
+        fn fetch_transfer_tax_bps(&self, token: Address) -> Option<u32> {
+            self.transfer_taxes.get(&token).map_or(Some(0), |tax| *tax)
+        }
     }
 }
@@ -32,9 +32,7 @@ impl Nonces {
     where
         <DB as DatabaseRef>::Error: Sync + Send + 'static + Debug
     {
-        let slot = self.get_nonce_word_slot(user, nonce);
-
-        let word = db.storage_ref(self.0, slot.into()).unwrap();
+        let word = self.fetch_nonce_word(user, nonce, &db);
         tracing::debug!(?word);
         let flag = U256::from(1) << (nonce as u8);
 
@@ -42,4 +40,19 @@ impl Nonces {
         tracing::debug!(?word, %out);
         out
     }
+
+    /// bitmap of the used nonces in the 256 nonce word that `nonce` is in.
+    /// bit `nonce % 256` is set if the nonce has been used.
+    pub fn fetch_nonce_word<DB: revm::DatabaseRef>(
+        &self,
+        user: Address,
+        nonce: u64,
+        db: &DB
+    ) -> U256
+    where
+        <DB as DatabaseRef>::Error: Sync + Send + 'static + Debug
+    {
+        let slot = self.get_nonce_word_slot(user, nonce);
+        db.storage_ref(self.0, slot.into()).unwrap()
+    }
 }
@@ -0,0 +1,145 @@
+use std::fmt::Debug;
+
+use alloy::{
+    primitives::{Address, U256},
+    sol_types::SolCall
+};
+use angstrom_types::contract_bindings::mintable_mock_erc_20::MintableMockERC20::transferCall;
+use dashmap::DashMap;
+use revm::{
+    db::CacheDB,
+    primitives::{EnvWithHandlerCfg, TxKind}
+};
+
+use super::finders::find_slot_offset_for_balance;
+use crate::order::state::config::TokenBalanceSlot;
+
+/// amount moved by the probe transfer, large enough that a tax of a single
+/// basis point doesn't round away
+const PROBE_AMOUNT: u128 = 1_000_000_000_000_000_000;
+const BPS: u32 = 10_000;
+
+/// Per token cache of the fee taken on transfer. Fee-on-transfer tokens break
+/// the assumption that the amount a user sends is the amount that arrives.
+#[derive(Clone, Default)]
+pub struct TransferTaxes {
+    /// `None` for tokens whose transfer couldn't be simulated
+    taxes: DashMap<Address, Option<u32>>
+}
+
+impl TransferTaxes {
+    pub fn fetch_transfer_tax_bps<DB: revm::DatabaseRef>(
+        &self,
+        token: Address,
+        db: &DB
+    ) -> Option<u32>
+    where
+        <DB as revm::DatabaseRef>::Error: Debug
+    {
+        if let Some(tax) = self.taxes.get(&token) {
+            return *tax
+        }
+
+        let tax = find_slot_offset_for_balance(db, token)
+            .inspect_err(|error| tracing::warn!(%error, "can't measure the token's transfer tax"))
+            .ok()
+            .and_then(|offset| {
+                measure_transfer_tax_bps(db, token, &TokenBalanceSlot::new(token, offset as u8))
+            });
+        self.taxes.insert(token, tax);
+
+        tax
+    }
+}
+
+/// Simulates a transfer between two probe accounts and returns the share of
+/// it that didn't arrive, in basis points. `None` if the transfer reverted.
+pub fn measure_transfer_tax_bps<DB: revm::DatabaseRef>(
+    db: &DB,
+    token: Address,
+    balance_slot: &TokenBalanceSlot
+) -> Option<u32>
+where
+    <DB as revm::DatabaseRef>::Error: Debug
+{
+    let sender = Address::random();
+    let recipient = Address::random();
+    let amount = U256::from(PROBE_AMOUNT);
+    let recipient_slot = balance_slot.generate_slot(recipient).ok()?;
+
+    let mut db = CacheDB::new(db);
+    db.insert_account_storage(token, balance_slot.generate_slot(sender).ok()?, amount)
+        .ok()?;
+
+    let mut evm = revm::Evm::builder()
+        .with_ref_db(db)
+        .with_env_with_handler_cfg(EnvWithHandlerCfg::default())
+        .modify_env(|env| {
+            env.cfg.disable_balance_check = true;
+        })
+        .modify_tx_env(|tx| {
+            tx.caller = sender;
+            tx.transact_to = TxKind::Call(token);
+            tx.data = transferCall::new((recipient, amount)).abi_encode().into();
+            tx.value = U256::ZERO;
+            tx.nonce = None;
+        })
+        .build();
+
+    let result = evm.transact().ok()?;
+    if !result.result.is_success() {
+        return None
+    }
+
+    // the recipient starts out empty so its balance is what arrived
+    let received = result
+        .state
+        .get(&token)
+        .and_then(|account| account.storage.get(&recipient_slot))
+        .map(|slot| slot.present_value)
+        .unwrap_or_default();
+    let tax = amount.saturating_sub(received) * U256::from(BPS) / amount;
+
+    Some(tax.saturating_to())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::primitives::Bytes;
+    use angstrom_types::contract_bindings::mintable_mock_erc_20::MintableMockERC20;
+    use revm::{
+        db::EmptyDB,
+        primitives::{AccountInfo, Bytecode}
+    };
+
+    use super::*;
+
+    fn with_code(code: Bytes) -> (CacheDB<EmptyDB>, Address) {
+        let token = Address::random();
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            token,
+            AccountInfo { code: Some(Bytecode::new_raw(code)), ..Default::default() }
+        );
+
+        (db, token)
+    }
+
+    #[test]
+    fn plain_erc20s_take_no_tax() {
+        let (db, token) = with_code(MintableMockERC20::DEPLOYED_BYTECODE.clone());
+
+        assert_eq!(TransferTaxes::default().fetch_transfer_tax_bps(token, &db), Some(0));
+    }
+
+    #[test]
+    fn tokens_that_cannot_be_probed_have_no_known_tax() {
+        // PUSH1 0 PUSH1 0 REVERT
+        let (db, token) = with_code(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd]));
+        let taxes = TransferTaxes::default();
+
+        assert_eq!(taxes.fetch_transfer_tax_bps(token, &db), None);
+        // cached, the token isn't probed again
+        assert!(taxes.taxes.contains_key(&token));
+    }
+}
@@ -1,19 +1,34 @@
 use std::sync::Arc;
 
-use account::UserAccountProcessor;
-use alloy::primitives::{Address, B256};
-use angstrom_types::sol_bindings::{ext::RawPoolOrder, grouped_orders::AllOrders};
+use account::{UserAccountProcessor, UserAccountVerificationError};
+use alloy::primitives::{Address, B256, U256};
+use angstrom_types::{
+    matching::{Ray, SqrtPriceX96},
+    orders::OrderLocation,
+    primitive::PoolId,
+    sol_bindings::{
+        ext::RawPoolOrder,
+        grouped_orders::{AllOrders, OrderWithStorageData}
+    }
+};
+use config::{UninitializedPoolPolicy, ValidationConfig};
 use db_state_utils::StateFetchUtils;
 use parking_lot::RwLock;
 use pools::PoolsTracker;
+use signature_cache::SignatureCache;
 use uniswap_v4::uniswap::{pool_manager::SyncedUniswapPools, tob::calculate_reward};
 
-use super::{OrderValidation, OrderValidationResults};
+use super::{
+    rejection::{Rejection, RejectionReason},
+    trace::{StepRecorder, ValidationStep, ValidationTrace},
+    OrderValidation, OrderValidationResults
+};
 
 pub mod account;
 pub mod config;
 pub mod db_state_utils;
 pub mod pools;
+pub mod signature_cache;
 
 /// State validation is all validation that requires reading from the Ethereum
 /// database, these operations are:
@@ -27,7 +42,9 @@ pub struct StateValidation<Pools, Fetch> {
     /// tracks all info about the current angstrom pool state.
     pool_tacker:          Arc<RwLock<Pools>>,
     /// keeps up-to-date with the on-chain pool
-    uniswap_pools:        SyncedUniswapPools
+    uniswap_pools:        SyncedUniswapPools,
+    /// signers of orders that have already been verified
+    signature_cache:      SignatureCache
 }
 
 impl<Pools, Fetch> Clone for StateValidation<Pools, Fetch> {
@@ -35,7 +52,8 @@ impl<Pools, Fetch> Clone for StateValidation<Pools, Fetch> {
         Self {
             user_account_tracker: Arc::clone(&self.user_account_tracker),
             pool_tacker:          Arc::clone(&self.pool_tacker),
-            uniswap_pools:        Arc::clone(&self.uniswap_pools)
+            uniswap_pools:        Arc::clone(&self.uniswap_pools),
+            signature_cache:      self.signature_cache.clone()
         }
     }
 }
@@ -49,7 +67,8 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
         Self {
             pool_tacker: Arc::new(RwLock::new(pools)),
             user_account_tracker: Arc::new(user_account_tracker),
-            uniswap_pools
+            uniswap_pools,
+            signature_cache: SignatureCache::default()
         }
     }
 
@@ -58,26 +77,253 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
             .prepare_for_new_block(address_changes, completed_orders)
     }
 
+    pub fn next_valid_nonce(&self, user: Address) -> u64 {
+        self.user_account_tracker.next_valid_nonce(user)
+    }
+
+    pub fn prewarm(&self, user: Address, tokens: &[Address]) -> usize {
+        self.user_account_tracker.prewarm(user, tokens)
+    }
+
+    pub fn cancel_order(&self, user: Address, order_hash: &B256) -> bool {
+        self.user_account_tracker.cancel_order(user, order_hash)
+    }
+
+    /// whether all uniswap pools loaded their on-chain data. A node without
+    /// any pools yet has nothing to wait for
+    pub fn pools_populated(&self) -> bool {
+        self.uniswap_pools
+            .values()
+            .all(|pool| pool.read().unwrap().data_is_populated())
+    }
+
     pub fn handle_regular_order<O: RawPoolOrder + Into<AllOrders>>(
         &self,
         order: O,
         block: u64
     ) -> OrderValidationResults {
+        self.run_checks(&self.user_account_tracker, order, block, true, &mut ())
+    }
+
+    /// dry run of [`Self::handle_regular_order`]. Neither the account state
+    /// nor the signature cache are touched, on failure the reason the order
+    /// would be rejected is returned.
+    pub fn simulate_regular_order<O: RawPoolOrder + Into<AllOrders>>(
+        &self,
+        order: O,
+        block: u64
+    ) -> Result<OrderWithStorageData<AllOrders>, String> {
+        self.simulate_order_against(&self.user_account_tracker, order, block)
+    }
+
+    /// dry run against the given accounts instead of the tracked ones, used
+    /// to check an order against the state of a past block.
+    pub fn simulate_order_against<O, F>(
+        &self,
+        accounts: &UserAccountProcessor<F>,
+        order: O,
+        block: u64
+    ) -> Result<OrderWithStorageData<AllOrders>, String>
+    where
+        O: RawPoolOrder + Into<AllOrders>,
+        F: StateFetchUtils
+    {
+        match self.run_checks(accounts, order, block, false, &mut ()) {
+            OrderValidationResults::Valid(order) => Ok(order),
+            OrderValidationResults::Invalid(_, rejection) => Err(rejection.message),
+            // dry runs reject orders of pools that aren't ready
+            _ => Err("the order's pool isn't ready".to_string())
+        }
+    }
+
+    /// [`Self::simulate_regular_order`] that records which checks the order
+    /// passed on the way, along with the order if it would be accepted.
+    pub fn trace_regular_order<O: RawPoolOrder + Into<AllOrders>>(
+        &self,
+        order: O,
+        block: u64
+    ) -> (ValidationTrace, Option<OrderWithStorageData<AllOrders>>) {
+        let mut trace = ValidationTrace::default();
+        let result = self.run_checks(&self.user_account_tracker, order, block, false, &mut trace);
+        let order = match result {
+            OrderValidationResults::Valid(order) => Some(order),
+            _ => None
+        };
+
+        (trace, order)
+    }
+
+    /// The checks every order goes through, reported to `recorder` as they
+    /// run. With `track` set the order is validated for the pool: verified
+    /// signatures are cached, orders of pools that aren't ready are held and
+    /// the order is added to its user's account. Dry runs leave all of it
+    /// untouched and reject orders of pools that aren't ready.
+    fn run_checks<O, F, R>(
+        &self,
+        accounts: &UserAccountProcessor<F>,
+        order: O,
+        block: u64,
+        track: bool,
+        recorder: &mut R
+    ) -> OrderValidationResults
+    where
+        O: RawPoolOrder + Into<AllOrders>,
+        F: StateFetchUtils,
+        R: StepRecorder
+    {
         let order_hash = order.order_hash();
-        if !order.is_valid_signature() {
-            return OrderValidationResults::Invalid(order_hash)
+        let config = accounts.config();
+
+        let is_valid_signature = if track {
+            self.signature_cache.is_valid_signature(&order)
+        } else {
+            order.is_valid_signature()
+        };
+        if !is_valid_signature {
+            tracing::debug!("invalid order signature");
+            let rejection =
+                Rejection::new(RejectionReason::InvalidSignature, "invalid order signature");
+            recorder.on_rejection(ValidationStep::Signature, &rejection);
+            return OrderValidationResults::Invalid(order_hash, rejection)
+        }
+        if let Err(e) = Self::check_canonical_signature(config, &order) {
+            tracing::debug!(%e, "non-canonical order signature");
+            return Self::reject(order_hash, ValidationStep::Signature, &e, recorder)
         }
+        recorder
+            .on_step(ValidationStep::Signature, true, || format!("signed by {:?}", order.from()));
 
         let Some(pool_info) = self.pool_tacker.read().fetch_pool_info_for_order(&order) else {
-            return OrderValidationResults::Invalid(order_hash);
+            tracing::debug!(
+                token_in = ?order.token_in(),
+                token_out = ?order.token_out(),
+                "no pool for order"
+            );
+            let rejection = Rejection::new(
+                RejectionReason::NoPool,
+                format!("no pool for pair {:?}/{:?}", order.token_in(), order.token_out())
+            );
+            recorder.on_rejection(ValidationStep::Pool, &rejection);
+            return OrderValidationResults::Invalid(order_hash, rejection)
         };
 
-        self.user_account_tracker
-            .verify_order::<O>(order, pool_info, block)
-            .map(|o: _| {
-                OrderValidationResults::Valid(o.try_map_inner(|inner| Ok(inner.into())).unwrap())
+        if track
+            && config.uninitialized_pools == UninitializedPoolPolicy::Hold
+            && !self.uniswap_pools.contains_key(&pool_info.pool_id)
+        {
+            if let Some(init) = self.pool_tacker.read().pool_init_params(&order) {
+                tracing::debug!(
+                    pool_id = ?init.pool_id,
+                    "holding order until its pool is initialized"
+                );
+                return OrderValidationResults::PoolNotInitialized { order: order.into(), init }
+            }
+        }
+
+        if let Err(e) = self.check_pool_synced(&order, pool_info.pool_id) {
+            if !track {
+                return Self::reject(order_hash, ValidationStep::Pool, &e, recorder)
+            }
+            tracing::debug!(%e, "holding order until its pool syncs");
+            return OrderValidationResults::PoolNotSynced {
+                order:   order.into(),
+                pool_id: pool_info.pool_id
+            }
+        }
+        recorder.on_step(ValidationStep::Pool, true, || format!("pool {:?}", pool_info.pool_id));
+
+        if let Err(e) = self.check_market_price(config, &order, pool_info.pool_id) {
+            tracing::debug!(%e, "order priced too far from the market");
+            return Self::reject(order_hash, ValidationStep::MarketPrice, &e, recorder)
+        }
+        if config.max_price_deviation_bps.is_some() {
+            recorder.on_step(ValidationStep::MarketPrice, true, || {
+                "within the allowed deviation".into()
+            });
+        }
+
+        match accounts.process_order(order, pool_info, block, track, recorder) {
+            Ok(order) => OrderValidationResults::Valid(
+                order.try_map_inner(|inner| Ok(inner.into())).unwrap()
+            ),
+            Err(e) => {
+                tracing::debug!(%e, "account verification failed");
+                OrderValidationResults::Invalid(order_hash, Rejection::from(&e))
+            }
+        }
+    }
+
+    /// rejects the order at `step`
+    fn reject<O: RawPoolOrder, R: StepRecorder>(
+        order_hash: B256,
+        step: ValidationStep,
+        error: &UserAccountVerificationError<O>,
+        recorder: &mut R
+    ) -> OrderValidationResults {
+        let rejection = Rejection::from(error);
+        recorder.on_rejection(step, &rejection);
+        OrderValidationResults::Invalid(order_hash, rejection)
+    }
+
+    /// rejects signatures with a high s value unless configured to accept
+    /// them, see [`RawPoolOrder::has_canonical_signature`]
+    fn check_canonical_signature<O: RawPoolOrder>(
+        config: &ValidationConfig,
+        order: &O
+    ) -> Result<(), UserAccountVerificationError<O>> {
+        if !config.accept_high_s_signatures && !order.has_canonical_signature() {
+            return Err(UserAccountVerificationError::NonCanonicalSignature(order.order_hash()))
+        }
+
+        Ok(())
+    }
+
+    /// searcher orders are rewarded off the state of the uniswap pool, so
+    /// they can't be validated before the pool has synced
+    fn check_pool_synced<O: RawPoolOrder>(
+        &self,
+        order: &O,
+        pool_id: PoolId
+    ) -> Result<(), UserAccountVerificationError<O>> {
+        if order.order_location() == OrderLocation::Searcher
+            && !self.uniswap_pools.contains_key(&pool_id)
+        {
+            return Err(UserAccountVerificationError::PoolNotSynced(pool_id))
+        }
+
+        Ok(())
+    }
+
+    /// rejects limit orders priced too far off the spot price of the uniswap
+    /// pool. Pools that haven't loaded a price yet aren't checked
+    fn check_market_price<O: RawPoolOrder>(
+        &self,
+        config: &ValidationConfig,
+        order: &O,
+        pool_id: PoolId
+    ) -> Result<(), UserAccountVerificationError<O>> {
+        if config.max_price_deviation_bps.is_none()
+            || order.order_location() != OrderLocation::Limit
+        {
+            return Ok(())
+        }
+        let Some(pool) = self.uniswap_pools.get(&pool_id) else { return Ok(()) };
+        let sqrt_price = pool.read().unwrap().sqrt_price;
+        if sqrt_price.is_zero() {
+            return Ok(())
+        }
+
+        let market_price = U256::from(Ray::from(SqrtPriceX96::from(sqrt_price)));
+        let price = order.limit_price();
+        if !config.is_price_near_market(price, market_price) {
+            return Err(UserAccountVerificationError::PriceTooFarFromMarket {
+                order_hash: order.order_hash(),
+                price,
+                market_price
             })
-            .unwrap_or_else(|_| OrderValidationResults::Invalid(order_hash))
+        }
+
+        Ok(())
     }
 
     pub fn validate_state_of_regular_order(&self, order: OrderValidation, block: u64) {
@@ -86,6 +332,10 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
                 let results = self.handle_regular_order(order, block);
                 let _ = tx.send(results);
             }
+            OrderValidation::LimitComposable(tx, order, _) => {
+                let results = self.handle_regular_order(order, block);
+                let _ = tx.send(results);
+            }
             OrderValidation::Searcher(tx, order, _) => {
                 let mut results = self.handle_regular_order(order, block);
                 if let OrderValidationResults::Valid(ref mut order_with_storage) = results {
@@ -110,7 +360,409 @@ impl<Pools: PoolsTracker, Fetch: StateFetchUtils> StateValidation<Pools, Fetch>
 
                 let _ = tx.send(results);
             }
-            _ => unreachable!()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use alloy::{
+        primitives::{Parity, Signature as EcdsaSignature, U256},
+        signers::local::PrivateKeySigner
+    };
+    use angstrom_types::{
+        orders::OrderOrigin,
+        primitive::{PoolId, Signature, ANGSTROM_DOMAIN, SECP256K1N_HALF},
+        sol_bindings::grouped_orders::{
+            GroupedComposableOrder, GroupedVanillaOrder, StandingVariants
+        }
+    };
+    use pade::PadeEncode;
+    use testing_tools::type_generator::orders::{SigningInfo, ToBOrderBuilder, UserOrderBuilder};
+    use tokio::sync::oneshot::channel;
+    use uniswap_v4::uniswap::{pool::EnhancedUniswapPool, pool_data_loader::DataLoader};
+
+    use super::*;
+    use crate::order::state::{
+        db_state_utils::test_fetching::MockFetch, pools::pool_tracker_mock::MockPoolTracker
+    };
+
+    #[test]
+    fn no_pools_count_as_populated() {
+        let state = StateValidation::new(
+            UserAccountProcessor::new(MockFetch::default()),
+            MockPoolTracker::default(),
+            Arc::new(HashMap::new())
+        );
+
+        assert!(state.pools_populated());
+    }
+
+    #[test]
+    fn validates_limit_composable_orders() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let pools = MockPoolTracker::default();
+        pools.add_pool(token0, token1, PoolId::default());
+
+        let fetch = MockFetch::default();
+        fetch.set_balance_for_user(wallet.address(), token0, U256::from(1_000));
+        fetch.set_approval_for_user(wallet.address(), token0, U256::from(1_000));
+
+        let state =
+            StateValidation::new(UserAccountProcessor::new(fetch), pools, Arc::new(HashMap::new()));
+
+        let GroupedVanillaOrder::Standing(order) = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(100)
+            .nonce(1)
+            .signing_key(Some(signing_info))
+            .build()
+        else {
+            unreachable!("builder was asked for a standing order")
+        };
+
+        let (tx, rx) = channel();
+        state.validate_state_of_regular_order(
+            OrderValidation::LimitComposable(
+                tx,
+                GroupedComposableOrder::Partial(order),
+                OrderOrigin::External
+            ),
+            1
+        );
+
+        let results = rx
+            .blocking_recv()
+            .expect("validation result should be sent");
+        let OrderValidationResults::Valid(order) = results else {
+            panic!("composable order should be valid")
+        };
+        assert!(order.is_currently_valid);
+    }
+    #[test]
+    fn simulates_against_given_accounts() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let pools = MockPoolTracker::default();
+        pools.add_pool(token0, token1, PoolId::default());
+
+        // the user only had funds at the past block
+        let past = MockFetch::default();
+        past.set_balance_for_user(wallet.address(), token0, U256::from(1_000));
+        past.set_approval_for_user(wallet.address(), token0, U256::from(1_000));
+        let past_accounts = UserAccountProcessor::new(past);
+
+        let state = StateValidation::new(
+            UserAccountProcessor::new(MockFetch::default()),
+            pools,
+            Arc::new(HashMap::new())
+        );
+
+        let order = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(100)
+            .nonce(1)
+            .signing_key(Some(signing_info))
+            .build();
+
+        let live = state.simulate_regular_order(order.clone(), 1).unwrap();
+        assert!(!live.is_currently_valid);
+
+        let at_past_block = state
+            .simulate_order_against(&past_accounts, order, 1)
+            .unwrap();
+        assert!(at_past_block.is_currently_valid);
+    }
+
+    #[test]
+    fn holds_searcher_orders_until_their_pool_syncs() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let pool_id = PoolId::random();
+
+        let pools = MockPoolTracker::default();
+        pools.add_pool(token0, token1, pool_id);
+
+        // no uniswap pool has synced
+        let state = StateValidation::new(
+            UserAccountProcessor::new(MockFetch::default()),
+            pools,
+            Arc::new(HashMap::new())
+        );
+
+        let order = ToBOrderBuilder::new()
+            .asset_in(token0)
+            .asset_out(token1)
+            .quantity_in(100)
+            .valid_block(1)
+            .signing_key(Some(signing_info))
+            .build();
+
+        let results = state.handle_regular_order(order.clone(), 1);
+        let OrderValidationResults::PoolNotSynced { order: held, pool_id: held_pool } = results
+        else {
+            panic!("order should be held until the pool syncs")
+        };
+        assert_eq!(held, AllOrders::TOB(order.clone()));
+        assert_eq!(held_pool, pool_id);
+
+        let reason = state.simulate_regular_order(order, 1).unwrap_err();
+        assert!(reason.contains("hasn't been synced"));
+    }
+
+    #[test]
+    fn holds_orders_for_uninitialized_pools_when_configured() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let pool_id = PoolId::random();
+
+        let pools = MockPoolTracker::default();
+        pools.add_pool(token0, token1, pool_id);
+        let config = ValidationConfig {
+            uninitialized_pools: UninitializedPoolPolicy::Hold,
+            ..Default::default()
+        };
+
+        // no uniswap pool was initialized
+        let state = StateValidation::new(
+            UserAccountProcessor::new(MockFetch::default()).with_config(config),
+            pools,
+            Arc::new(HashMap::new())
+        );
+
+        let order = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .asset_in(token1)
+            .asset_out(token0)
+            .amount(100)
+            .signing_key(Some(signing_info))
+            .build();
+
+        let results = state.handle_regular_order(order.clone(), 1);
+        let OrderValidationResults::PoolNotInitialized { order: held, init } = results else {
+            panic!("order should be held until the pool is initialized")
+        };
+        assert_eq!(held, AllOrders::from(order));
+        assert_eq!(init.pool_id, pool_id);
+        assert_eq!((init.asset0, init.asset1), (token0.min(token1), token0.max(token1)));
+    }
+
+    #[test]
+    fn traces_each_check_of_an_order() {
+        let wallet = PrivateKeySigner::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+
+        let pools = MockPoolTracker::default();
+        pools.add_pool(token0, token1, PoolId::default());
+        let state = StateValidation::new(
+            UserAccountProcessor::new(MockFetch::default()),
+            pools,
+            Arc::new(HashMap::new())
+        );
+        let order = |key: &PrivateKeySigner| {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(100)
+                .nonce(1)
+                .signing_key(Some(SigningInfo {
+                    domain:  ANGSTROM_DOMAIN,
+                    address: wallet.address(),
+                    key:     key.credential().clone()
+                }))
+                .build()
+        };
+
+        // the user holds no funds, the order rests in the pool until they do
+        let (trace, traced) = state.trace_regular_order(order(&wallet), 1);
+        assert!(trace.is_accepted());
+        for step in [
+            ValidationStep::Signature,
+            ValidationStep::Pool,
+            ValidationStep::StaticChecks,
+            ValidationStep::Nonce
+        ] {
+            assert!(trace.step(step).unwrap().passed, "{step:?} should pass");
+        }
+        assert!(!trace.step(ValidationStep::Balance).unwrap().passed);
+        assert!(!traced.unwrap().is_currently_valid);
+
+        // signed by someone other than the user the order is from
+        let (trace, traced) = state.trace_regular_order(order(&PrivateKeySigner::random()), 1);
+        assert_eq!(trace.rejection.unwrap().code, RejectionReason::InvalidSignature);
+        assert_eq!(trace.steps.len(), 1);
+        assert!(traced.is_none());
+    }
+
+    #[test]
+    fn rejects_limit_orders_priced_far_from_the_synced_pool() {
+        let wallet = PrivateKeySigner::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let pool_id = PoolId::random();
+        let config = ValidationConfig { max_price_deviation_bps: Some(500), ..Default::default() };
+        // a uniswap pool synced to a price of 1, or one that hasn't loaded a price
+        let state = |sqrt_price: U256| {
+            let pools = MockPoolTracker::default();
+            pools.add_pool(token0, token1, pool_id);
+            let mut pool = EnhancedUniswapPool::new(DataLoader::default(), 0);
+            pool.sqrt_price = sqrt_price;
+            StateValidation::new(
+                UserAccountProcessor::new(MockFetch::default()).with_config(config.clone()),
+                pools,
+                Arc::new(HashMap::from([(pool_id, std::sync::RwLock::new(pool))]))
+            )
+        };
+        let order = |min_price: f64| {
+            UserOrderBuilder::new()
+                .standing()
+                .exact()
+                .asset_in(token0)
+                .asset_out(token1)
+                .amount(100)
+                .nonce(1)
+                .min_price(Ray::from(min_price))
+                .signing_key(Some(SigningInfo {
+                    domain:  ANGSTROM_DOMAIN,
+                    address: wallet.address(),
+                    key:     wallet.credential().clone()
+                }))
+                .build()
+        };
+
+        let synced = state(U256::from(1) << 96);
+        let OrderValidationResults::Invalid(_, rejection) =
+            synced.handle_regular_order(order(1.1), 1)
+        else {
+            panic!("an order 10% off the market should be rejected")
+        };
+        assert_eq!(rejection.code, RejectionReason::PriceTooFarFromMarket);
+        let (trace, _) = synced.trace_regular_order(order(1.1), 1);
+        assert!(!trace.step(ValidationStep::MarketPrice).unwrap().passed);
+
+        let (trace, _) = synced.trace_regular_order(order(1.04), 1);
+        assert!(trace.step(ValidationStep::MarketPrice).unwrap().passed);
+        assert!(matches!(
+            synced.handle_regular_order(order(1.04), 1),
+            OrderValidationResults::Valid(_)
+        ));
+
+        // without a price there is nothing to compare against
+        let unpriced = state(U256::ZERO);
+        assert!(matches!(
+            unpriced.handle_regular_order(order(1.1), 1),
+            OrderValidationResults::Valid(_)
+        ));
+    }
+
+    /// the order signed with the high s counterpart of its signature, which
+    /// recovers the same signer
+    fn with_high_s(order: GroupedVanillaOrder) -> GroupedVanillaOrder {
+        let GroupedVanillaOrder::Standing(StandingVariants::Exact(mut order)) = order else {
+            panic!("expected an exact standing order")
+        };
+        let sig = Signature::new_from_bytes(&order.meta.signature).unwrap();
+        let curve_order = SECP256K1N_HALF * U256::from(2) + U256::from(1);
+        let high_s =
+            EcdsaSignature::new(sig.r(), curve_order - sig.s(), Parity::from(!sig.v().y_parity()));
+        order.meta.signature = high_s.pade_encode().into();
+
+        GroupedVanillaOrder::Standing(StandingVariants::Exact(order))
+    }
+
+    #[test]
+    fn rejects_high_s_signatures() {
+        let wallet = PrivateKeySigner::random();
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let state = |config: ValidationConfig| {
+            let pools = MockPoolTracker::default();
+            pools.add_pool(token0, token1, PoolId::default());
+            StateValidation::new(
+                UserAccountProcessor::new(MockFetch::default()).with_config(config),
+                pools,
+                Arc::new(HashMap::new())
+            )
+        };
+        let low_s = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .asset_in(token0)
+            .asset_out(token1)
+            .amount(100)
+            .nonce(1)
+            .signing_key(Some(SigningInfo {
+                domain:  ANGSTROM_DOMAIN,
+                address: wallet.address(),
+                key:     wallet.credential().clone()
+            }))
+            .build();
+        let high_s = with_high_s(low_s.clone());
+        assert!(low_s.has_canonical_signature());
+        assert!(high_s.is_valid_signature() && !high_s.has_canonical_signature());
+        // the order hash leaves out the signature, both forms hash the same
+        assert_eq!(low_s.order_hash(), high_s.order_hash());
+
+        let strict = state(ValidationConfig::default());
+        assert!(matches!(
+            strict.handle_regular_order(low_s.clone(), 1),
+            OrderValidationResults::Valid(_)
+        ));
+        let (trace, _) = strict.trace_regular_order(low_s.clone(), 1);
+        assert!(trace.step(ValidationStep::Signature).unwrap().passed);
+
+        let OrderValidationResults::Invalid(order_hash, rejection) =
+            strict.handle_regular_order(high_s.clone(), 1)
+        else {
+            panic!("high s signature should be rejected")
+        };
+        assert_eq!(order_hash, high_s.order_hash());
+        assert_eq!(rejection.code, RejectionReason::NonCanonicalSignature);
+        let (trace, _) = strict.trace_regular_order(high_s.clone(), 1);
+        assert_eq!(trace.rejection.unwrap().code, RejectionReason::NonCanonicalSignature);
+
+        let lenient =
+            state(ValidationConfig { accept_high_s_signatures: true, ..Default::default() });
+        let (trace, _) = lenient.trace_regular_order(high_s, 1);
+        assert!(trace.step(ValidationStep::Signature).unwrap().passed);
+    }
+}
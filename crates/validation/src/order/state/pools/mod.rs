@@ -1,18 +1,20 @@
 use std::sync::Arc;
 
-use alloy::primitives::{
-    aliases::{I24, U24},
-    Address
-};
+use alloy::primitives::Address;
 use angstrom_types::{
-    contract_bindings::angstrom::Angstrom::PoolKey,
-    contract_payloads::angstrom::AngstromPoolConfigStore, primitive::PoolId,
+    contract_payloads::angstrom::AngstromPoolConfigStore,
+    primitive::{derive_pool_id, NewInitializedPool, PoolId},
     sol_bindings::ext::RawPoolOrder
 };
+use dashmap::DashMap;
 
 pub trait PoolsTracker: Send + Unpin {
     /// Returns None if no pool is found
     fn fetch_pool_info_for_order<O: RawPoolOrder>(&self, order: &O) -> Option<UserOrderPoolInfo>;
+
+    /// what initializing the uniswap pool of the order's pair takes, None if
+    /// the pair has no angstrom pool
+    fn pool_init_params<O: RawPoolOrder>(&self, order: &O) -> Option<PoolInitParams>;
 }
 
 #[derive(Debug, Clone)]
@@ -23,31 +25,109 @@ pub struct UserOrderPoolInfo {
     pub pool_id: PoolId
 }
 
+/// The arguments of angstrom's `initializePool` for a pair, apart from the
+/// price the pool starts at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolInitParams {
+    pub pool_id:     PoolId,
+    /// the pair's tokens, sorted
+    pub asset0:      Address,
+    pub asset1:      Address,
+    /// index of the pair in angstrom's pool config store
+    pub store_index: usize
+}
+
+/// An angstrom pool the tracker knows the tokens of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrackedPool {
+    pub pool_id:      PoolId,
+    pub token0:       Address,
+    pub token1:       Address,
+    pub fee_in_e6:    u32,
+    pub tick_spacing: u16
+}
+
 /// keeps track of all valid pools and the mappings of asset id to pool id
 #[derive(Debug, Clone)]
 pub struct AngstromPoolsTracker {
     angstrom_address: Address,
-    pool_store:       Arc<AngstromPoolConfigStore>
+    pool_store:       Arc<AngstromPoolConfigStore>,
+    /// pools whose tokens are known, the config store only holds a hash of
+    /// them. Clones share the same pools.
+    pools:            Arc<DashMap<PoolId, TrackedPool>>
 }
 
 impl AngstromPoolsTracker {
     pub fn new(angstrom_address: Address, pool_store: Arc<AngstromPoolConfigStore>) -> Self {
-        Self { angstrom_address, pool_store }
+        Self { angstrom_address, pool_store, pools: Default::default() }
+    }
+
+    /// registers the pools of the token pairs, pairs without an angstrom pool
+    /// are ignored
+    pub fn with_pools(self, pairs: impl IntoIterator<Item = (Address, Address)>) -> Self {
+        for (token0, token1) in pairs {
+            self.add_pool(token0, token1);
+        }
+        self
+    }
+
+    /// registers the pool of the pair, returning its id. `None` if the config
+    /// store has no angstrom pool for the pair
+    pub fn add_pool(&self, mut token0: Address, mut token1: Address) -> Option<PoolId> {
+        if token1 < token0 {
+            std::mem::swap(&mut token0, &mut token1)
+        };
+        let entry = self.pool_store.get_entry(token0, token1)?;
+        let pool_id = self.get_poolid(token0, token1)?;
+
+        self.pools.insert(
+            pool_id,
+            TrackedPool {
+                pool_id,
+                token0,
+                token1,
+                fee_in_e6: entry.fee_in_e6,
+                tick_spacing: entry.tick_spacing
+            }
+        );
+        Some(pool_id)
+    }
+
+    /// registers a pool seen by the initialization watcher. every uniswap
+    /// pool initialization is seen, so only pools whose id matches the
+    /// angstrom pool of their pair are kept
+    pub fn on_new_pool(&self, pool: NewInitializedPool) {
+        let (token0, token1) = (pool.currency_in, pool.currency_out);
+        if self.get_poolid(token0, token1) != Some(pool.id) {
+            return
+        }
+        self.add_pool(token0, token1);
+    }
+
+    /// ids of all pools the tokens are known of
+    pub fn all_pool_ids(&self) -> Vec<PoolId> {
+        self.pools.iter().map(|pool| *pool.key()).collect()
+    }
+
+    /// all pools the tokens are known of
+    pub fn all_pools(&self) -> Vec<TrackedPool> {
+        self.pools.iter().map(|pool| *pool.value()).collect()
     }
 
     pub fn get_poolid(&self, mut addr1: Address, mut addr2: Address) -> Option<PoolId> {
-        let store = self.pool_store.get_entry(addr1, addr2)?;
+        // the config store is keyed by the sorted pair
         if addr2 < addr1 {
             std::mem::swap(&mut addr1, &mut addr2)
         };
+        let store = self.pool_store.get_entry(addr1, addr2)?;
 
-        Some(PoolId::from(PoolKey {
-            currency0:   addr1,
-            currency1:   addr2,
-            tickSpacing: I24::from_limbs([store.tick_spacing as u64]),
-            hooks:       self.angstrom_address,
-            fee:         U24::from_limbs([store.fee_in_e6 as u64])
-        }))
+        Some(derive_pool_id(
+            addr1,
+            addr2,
+            store.fee_in_e6,
+            store.tick_spacing as i32,
+            self.angstrom_address
+        ))
     }
 
     pub fn order_info(
@@ -69,12 +149,29 @@ impl AngstromPoolsTracker {
 impl PoolsTracker for AngstromPoolsTracker {
     /// None if no pool was found
     fn fetch_pool_info_for_order<O: RawPoolOrder>(&self, order: &O) -> Option<UserOrderPoolInfo> {
-        let (is_bid, pool_id) = self.order_info(order.token_in(), order.token_out())?;
+        let Some((is_bid, pool_id)) = self.order_info(order.token_in(), order.token_out()) else {
+            tracing::debug!(
+                order_hash = ?order.order_hash(),
+                token_in = ?order.token_in(),
+                token_out = ?order.token_out(),
+                "no angstrom pool registered for order tokens"
+            );
+            return None
+        };
 
         let user_info = UserOrderPoolInfo { pool_id, is_bid, token: order.token_in() };
 
         Some(user_info)
     }
+
+    fn pool_init_params<O: RawPoolOrder>(&self, order: &O) -> Option<PoolInitParams> {
+        let (token_in, token_out) = (order.token_in(), order.token_out());
+        let (asset0, asset1) = (token_in.min(token_out), token_in.max(token_out));
+        let entry = self.pool_store.get_entry(asset0, asset1)?;
+        let pool_id = self.get_poolid(asset0, asset1)?;
+
+        Some(PoolInitParams { pool_id, asset0, asset1, store_index: entry.store_index })
+    }
 }
 
 #[cfg(test)]
@@ -112,5 +209,135 @@ pub mod pool_tracker_mock {
 
             Some(user_info)
         }
+
+        fn pool_init_params<O: RawPoolOrder>(&self, order: &O) -> Option<PoolInitParams> {
+            let (token_in, token_out) = (order.token_in(), order.token_out());
+            let pool_id = self.pools.get(&(token_in, token_out))?;
+
+            Some(PoolInitParams {
+                pool_id:     *pool_id,
+                asset0:      token_in.min(token_out),
+                asset1:      token_in.max(token_out),
+                store_index: 0
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{keccak256, Address},
+        sol_types::SolValue
+    };
+    use angstrom_types::sol_bindings::grouped_orders::GroupedVanillaOrder;
+    use testing_tools::type_generator::orders::UserOrderBuilder;
+
+    use super::*;
+
+    /// builds a config store containing a single pool for the two tokens
+    fn store_with_pool(token0: Address, token1: Address) -> AngstromPoolConfigStore {
+        let (token0, token1) = if token0 < token1 { (token0, token1) } else { (token1, token0) };
+        let mut encoded = vec![0u8];
+        encoded.extend_from_slice(&keccak256((token0, token1).abi_encode())[5..32]);
+        // tick spacing
+        encoded.extend_from_slice(&60u16.to_be_bytes());
+        // fee
+        encoded.extend_from_slice(&[0, 0, 0]);
+
+        AngstromPoolConfigStore::try_from(encoded.as_slice()).unwrap()
+    }
+
+    fn order(token_in: Address, token_out: Address) -> GroupedVanillaOrder {
+        UserOrderBuilder::new()
+            .standing()
+            .asset_in(token_in)
+            .asset_out(token_out)
+            .build()
+    }
+
+    #[test]
+    fn finds_pool_for_registered_tokens() {
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let tracker =
+            AngstromPoolsTracker::new(Address::random(), Arc::new(store_with_pool(token0, token1)));
+
+        let ask = tracker
+            .fetch_pool_info_for_order(&order(token0.min(token1), token0.max(token1)))
+            .expect("pool is registered");
+        let bid = tracker
+            .fetch_pool_info_for_order(&order(token0.max(token1), token0.min(token1)))
+            .expect("pool is registered for both directions");
+
+        assert!(!ask.is_bid);
+        assert!(bid.is_bid);
+        assert_eq!(ask.pool_id, bid.pool_id);
+        assert_eq!(bid.token, token0.max(token1));
+    }
+
+    #[test]
+    fn rejects_unregistered_tokens() {
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let tracker =
+            AngstromPoolsTracker::new(Address::random(), Arc::new(store_with_pool(token0, token1)));
+
+        assert!(tracker
+            .fetch_pool_info_for_order(&order(token0, Address::random()))
+            .is_none());
+    }
+
+    #[test]
+    fn init_params_name_the_sorted_pair_and_its_store_entry() {
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let tracker =
+            AngstromPoolsTracker::new(Address::random(), Arc::new(store_with_pool(token0, token1)));
+
+        let params = tracker
+            .pool_init_params(&order(token0.max(token1), token0.min(token1)))
+            .expect("pool is registered");
+        assert_eq!((params.asset0, params.asset1), (token0.min(token1), token0.max(token1)));
+        assert_eq!(params.pool_id, tracker.get_poolid(token0, token1).unwrap());
+        assert_eq!(params.store_index, 0);
+
+        assert!(tracker
+            .pool_init_params(&order(token0, Address::random()))
+            .is_none());
+    }
+
+    #[test]
+    fn lists_registered_and_initialized_pools() {
+        let token0 = Address::random();
+        let token1 = Address::random();
+        let tracker =
+            AngstromPoolsTracker::new(Address::random(), Arc::new(store_with_pool(token0, token1)));
+        assert!(tracker.all_pool_ids().is_empty());
+
+        // an initialization of another pool of the pair isn't angstrom's
+        let other = NewInitializedPool {
+            currency_in:  token0,
+            currency_out: token1,
+            id:           PoolId::ZERO
+        };
+        tracker.on_new_pool(other);
+        assert!(tracker.all_pool_ids().is_empty());
+
+        let pool_id = tracker.get_poolid(token0, token1).unwrap();
+        let pool = NewInitializedPool {
+            currency_in:  token1,
+            currency_out: token0,
+            id:           pool_id
+        };
+        tracker.clone().on_new_pool(pool);
+        assert_eq!(tracker.all_pool_ids(), vec![pool_id]);
+
+        let pool = tracker.all_pools()[0];
+        assert_eq!((pool.token0, pool.token1), (token0.min(token1), token0.max(token1)));
+        assert_eq!((pool.fee_in_e6, pool.tick_spacing), (0, 60));
+
+        let unknown = tracker.clone().with_pools([(token0, Address::random())]);
+        assert_eq!(unknown.all_pool_ids(), vec![pool_id]);
     }
 }
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use alloy::primitives::{Address, Bytes, B256};
+use angstrom_metrics::ValidationMetricsWrapper;
+use angstrom_types::sol_bindings::ext::RawPoolOrder;
+use parking_lot::Mutex;
+use schnellru::{ByLength, LruMap};
+
+/// mostly arbitrary, covers a few blocks worth of resting orders
+const SIGNATURE_CACHE_SIZE: u32 = 50_000;
+
+/// Caches the signer of every order whose signature has already been
+/// verified. Orders get re-validated on every new block and on promotion, this
+/// avoids redoing the ecdsa recovery each time.
+///
+/// The order hash doesn't cover the order meta, so entries are keyed by the
+/// hash and the signature bytes together and only hit if the signer matches
+/// as well. An order resent with another signature is verified again.
+#[derive(Clone)]
+pub struct SignatureCache {
+    verified: Arc<Mutex<LruMap<(B256, Bytes), Address, ByLength>>>,
+    metrics:  ValidationMetricsWrapper
+}
+
+impl Default for SignatureCache {
+    fn default() -> Self {
+        Self::new(SIGNATURE_CACHE_SIZE)
+    }
+}
+
+impl SignatureCache {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            verified: Arc::new(Mutex::new(LruMap::new(ByLength::new(capacity)))),
+            metrics:  ValidationMetricsWrapper::new()
+        }
+    }
+
+    /// Returns whether the order signature is valid, only recovering the signer
+    /// if the order hasn't been verified before.
+    pub fn is_valid_signature<O: RawPoolOrder>(&self, order: &O) -> bool {
+        let key = Self::key(order);
+        {
+            let mut verified = self.verified.lock();
+            match verified.get(&key).copied() {
+                Some(signer) if signer == order.from() => {
+                    self.metrics.incr_signature_cache_hits();
+                    return true
+                }
+                // the signature was verified for another signer, don't trust the entry
+                Some(_) => {
+                    verified.remove(&key);
+                }
+                None => {}
+            }
+        }
+
+        self.metrics.incr_signature_verifications();
+        let is_valid = order.is_valid_signature();
+        if is_valid {
+            self.verified.lock().insert(key, order.from());
+        }
+
+        is_valid
+    }
+
+    pub fn contains<O: RawPoolOrder>(&self, order: &O) -> bool {
+        self.verified.lock().peek(&Self::key(order)).is_some()
+    }
+
+    fn key<O: RawPoolOrder>(order: &O) -> (B256, Bytes) {
+        (order.order_hash(), Bytes::copy_from_slice(order.signature()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::signers::local::PrivateKeySigner;
+    use angstrom_types::{
+        primitive::ANGSTROM_DOMAIN,
+        sol_bindings::grouped_orders::{GroupedVanillaOrder, StandingVariants}
+    };
+    use testing_tools::type_generator::orders::{SigningInfo, UserOrderBuilder};
+
+    use super::*;
+
+    #[test]
+    fn caches_valid_signatures() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let order = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .amount(100)
+            .signing_key(Some(signing_info))
+            .build();
+
+        let cache = SignatureCache::new(10);
+        assert!(!cache.contains(&order));
+        assert!(cache.is_valid_signature(&order));
+        assert!(cache.contains(&order));
+        assert!(cache.is_valid_signature(&order));
+    }
+
+    #[test]
+    fn resent_orders_with_another_signature_are_verified_again() {
+        let wallet = PrivateKeySigner::random();
+        let signing_info = SigningInfo {
+            domain:  ANGSTROM_DOMAIN,
+            address: wallet.address(),
+            key:     wallet.credential().clone()
+        };
+        let order = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .amount(100)
+            .signing_key(Some(signing_info))
+            .build();
+        let GroupedVanillaOrder::Standing(StandingVariants::Exact(mut forged)) = order.clone()
+        else {
+            unreachable!()
+        };
+        // same order hash and signer, garbage signature
+        forged.meta.signature = Bytes::from(vec![1u8; 65]);
+        let forged = GroupedVanillaOrder::Standing(StandingVariants::Exact(forged));
+        assert_eq!(forged.hash(), order.hash());
+
+        let cache = SignatureCache::new(10);
+        assert!(cache.is_valid_signature(&order));
+        assert!(!cache.contains(&forged));
+        assert!(!cache.is_valid_signature(&forged));
+    }
+
+    #[test]
+    fn does_not_cache_invalid_signatures() {
+        let order = UserOrderBuilder::new()
+            .standing()
+            .exact()
+            .amount(100)
+            .build();
+
+        let cache = SignatureCache::new(10);
+        assert!(!cache.is_valid_signature(&order));
+        assert!(!cache.contains(&order));
+    }
+}
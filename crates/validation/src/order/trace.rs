@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use super::rejection::Rejection;
+
+/// A check an order goes through during validation, in the order they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidationStep {
+    Signature,
+    /// the pair has a pool and, for searcher orders, the pool has synced
+    Pool,
+    /// the limit price is close enough to the pool's spot price
+    MarketPrice,
+    /// checks of the order on its own and against the operator's limits:
+    /// format, tokens, hook and deadline
+    StaticChecks,
+    Nonce,
+    Balance,
+    Approval,
+    /// the order is worth at least its pool's minimum notional
+    Notional,
+    Gas
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TracedStep {
+    pub step:   ValidationStep,
+    pub passed: bool,
+    /// what was checked against what, not meant to be parsed
+    pub detail: String
+}
+
+/// Step by step outcome of validating an order without adding it to the
+/// pool. A step that doesn't pass only rejects the order if it's the last
+/// one, an order short on balance or approval rests in the pool until the
+/// user tops up.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationTrace {
+    pub steps:     Vec<TracedStep>,
+    /// why the order would be rejected, `None` if it would be accepted
+    pub rejection: Option<Rejection>
+}
+
+/// Receives the outcome of each check as an order is validated. Validation
+/// reports to `()`, which ignores them, dry runs that trace the order report
+/// to a [`ValidationTrace`].
+pub trait StepRecorder {
+    fn on_step(&mut self, step: ValidationStep, passed: bool, detail: impl FnOnce() -> String);
+
+    /// the order is rejected at `step`
+    fn on_rejection(&mut self, step: ValidationStep, rejection: &Rejection);
+}
+
+impl StepRecorder for () {
+    fn on_step(&mut self, _: ValidationStep, _: bool, _: impl FnOnce() -> String) {}
+
+    fn on_rejection(&mut self, _: ValidationStep, _: &Rejection) {}
+}
+
+impl StepRecorder for ValidationTrace {
+    fn on_step(&mut self, step: ValidationStep, passed: bool, detail: impl FnOnce() -> String) {
+        self.record(step, passed, detail());
+    }
+
+    fn on_rejection(&mut self, step: ValidationStep, rejection: &Rejection) {
+        self.reject(step, rejection.clone());
+    }
+}
+
+impl ValidationTrace {
+    pub fn record(&mut self, step: ValidationStep, passed: bool, detail: impl Into<String>) {
+        self.steps
+            .push(TracedStep { step, passed, detail: detail.into() });
+    }
+
+    /// records the step as the one the order is rejected at
+    pub fn reject(&mut self, step: ValidationStep, rejection: Rejection) {
+        self.record(step, false, rejection.message.clone());
+        self.rejection = Some(rejection);
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        self.rejection.is_none()
+    }
+
+    pub fn step(&self, step: ValidationStep) -> Option<&TracedStep> {
+        self.steps.iter().find(|traced| traced.step == step)
+    }
+}
@@ -1,7 +1,18 @@
-use std::{fmt::Debug, task::Poll};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc
+    },
+    task::Poll
+};
 
-use alloy::primitives::{Address, B256};
-use angstrom_types::contract_payloads::angstrom::{AngstromBundle, BundleGasDetails};
+use alloy::primitives::{Address, B256, U256};
+use angstrom_metrics::ValidationMetricsWrapper;
+use angstrom_types::{
+    contract_payloads::angstrom::{AngstromBundle, BundleGasDetails},
+    sol_bindings::{grouped_orders::AllOrders, RawPoolOrder}
+};
 use futures_util::{Future, FutureExt};
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
@@ -10,8 +21,10 @@ use crate::{
     common::SharedTools,
     order::{
         order_validator::OrderValidator,
-        state::{db_state_utils::StateFetchUtils, pools::PoolsTracker},
-        OrderValidationRequest, OrderValidationResults
+        rejection::{Rejection, RejectionReason},
+        state::{config::WarmupConfig, db_state_utils::StateFetchUtils, pools::PoolsTracker},
+        trace::ValidationTrace,
+        OrderValidationRequest, OrderValidationResults, SimulationResult
     }
 };
 
@@ -29,6 +42,34 @@ pub enum ValidationRequest {
         block_number: u64,
         orders:       Vec<B256>,
         addresses:    Vec<Address>
+    },
+    /// dry run of order validation, doesn't modify any state. Checked
+    /// against the state of `at_block` if set, the tip otherwise
+    Simulate {
+        sender:   tokio::sync::oneshot::Sender<SimulationResult>,
+        order:    AllOrders,
+        at_block: Option<u64>
+    },
+    /// dry run that reports the outcome of each check the order goes through
+    Trace {
+        sender: tokio::sync::oneshot::Sender<ValidationTrace>,
+        order:  AllOrders
+    },
+    /// lowest nonce the user can sign a new order with
+    NextNonce {
+        sender: tokio::sync::oneshot::Sender<u64>,
+        user:   Address
+    },
+    /// loads account state of expected senders before their orders arrive
+    Prewarm {
+        addresses: Vec<Address>,
+        tokens:    Vec<Address>
+    },
+    /// the pool dropped a cancelled order, it no longer holds its user's
+    /// balance
+    CancelOrder {
+        user:       Address,
+        order_hash: B256
     }
 }
 
@@ -39,7 +80,102 @@ pub struct Validator<DB, Pools, Fetch> {
     rx:               UnboundedReceiver<ValidationRequest>,
     order_validator:  OrderValidator<DB, Pools, Fetch>,
     bundle_validator: BundleValidator<DB>,
-    utils:            SharedTools
+    utils:            SharedTools,
+    warmup:           WarmupGate,
+    /// [`Self::validations_queued`] as of the last poll, shared with whoever
+    /// reports it
+    queued:           Arc<AtomicUsize>,
+    metrics:          ValidationMetricsWrapper
+}
+
+/// Holds order and simulation requests until the validator has transitioned
+/// to a block, the price generator has applied enough updates and every pool
+/// has loaded.
+struct WarmupGate {
+    config:       WarmupConfig,
+    /// latched once warm-up has finished
+    ready:        bool,
+    /// last block the validator transitioned to since it started
+    synced_block: Option<u64>,
+    /// requests received while warming up
+    held:         Vec<ValidationRequest>
+}
+
+impl WarmupGate {
+    fn new(config: WarmupConfig) -> Self {
+        Self { ready: !config.enabled, config, synced_block: None, held: vec![] }
+    }
+
+    fn on_new_block(&mut self, block_number: u64) {
+        self.synced_block = Some(block_number);
+    }
+
+    fn is_ready(&self, price_updates: usize, pools_populated: impl FnOnce() -> bool) -> bool {
+        self.ready
+            || (self.synced_block.is_some()
+                && price_updates >= self.config.min_price_updates
+                && pools_populated())
+    }
+
+    /// Holds the request while warming up, returns it if it can run right
+    /// away. Requests beyond `max_held_requests` are answered as unavailable.
+    fn hold(&mut self, req: ValidationRequest) -> Option<ValidationRequest> {
+        let held_kind = matches!(
+            req,
+            ValidationRequest::Order(_)
+                | ValidationRequest::Simulate { .. }
+                | ValidationRequest::Trace { .. }
+        );
+        if self.ready || !held_kind {
+            return Some(req)
+        }
+
+        if self.held.len() >= self.config.max_held_requests {
+            tracing::debug!(held = self.held.len(), "turned away a request while warming up");
+            Self::turn_away(req);
+            return None
+        }
+        self.held.push(req);
+
+        None
+    }
+
+    /// answers a request that can't be held as unavailable
+    fn turn_away(req: ValidationRequest) {
+        let rejection = Rejection::new(RejectionReason::Unavailable, "the validator is warming up");
+        match req {
+            ValidationRequest::Order(OrderValidationRequest::ValidateOrder(tx, order, ..)) => {
+                let _ = tx.send(OrderValidationResults::Invalid(order.order_hash(), rejection));
+            }
+            ValidationRequest::Simulate { sender, .. } => {
+                let _ = sender.send(SimulationResult::rejected(U256::ZERO, rejection.message));
+            }
+            ValidationRequest::Trace { sender, .. } => {
+                let _ = sender
+                    .send(ValidationTrace { rejection: Some(rejection), ..Default::default() });
+            }
+            _ => unreachable!("only order, simulation and trace requests are held")
+        }
+    }
+
+    /// latches readiness and returns the held requests once warm-up has
+    /// finished
+    fn finish(
+        &mut self,
+        price_updates: usize,
+        pools_populated: impl FnOnce() -> bool
+    ) -> Option<Vec<ValidationRequest>> {
+        if self.ready || !self.is_ready(price_updates, pools_populated) {
+            return None
+        }
+        self.ready = true;
+
+        Some(std::mem::take(&mut self.held))
+    }
+
+    fn held(&self) -> usize {
+        self.held.len()
+    }
 }
 
 impl<DB, Pools, Fetch> Validator<DB, Pools, Fetch>
@@ -55,31 +191,123 @@ where
         bundle_validator: BundleValidator<DB>,
         utils: SharedTools
     ) -> Self {
-        Self { order_validator, rx, utils, bundle_validator }
+        Self {
+            order_validator,
+            rx,
+            utils,
+            bundle_validator,
+            warmup: WarmupGate::new(WarmupConfig::disabled()),
+            queued: Arc::default(),
+            metrics: ValidationMetricsWrapper::new()
+        }
+    }
+
+    /// Holds order and simulation requests until the validator has
+    /// transitioned to a block, the price generator has applied
+    /// `min_price_updates` updates and every pool has loaded. A backfilled
+    /// history doesn't count towards the updates. At most
+    /// `max_held_requests` are held.
+    pub fn with_warmup(mut self, warmup: WarmupConfig) -> Self {
+        self.warmup = WarmupGate::new(warmup);
+        self
+    }
+
+    /// publishes [`Self::validations_queued`] to `queued` on every poll
+    pub fn with_queued_counter(mut self, queued: Arc<AtomicUsize>) -> Self {
+        self.queued = queued;
+        self
+    }
+
+    /// whether order requests are being validated rather than held
+    pub fn is_ready(&self) -> bool {
+        self.warmup
+            .is_ready(self.utils.price_updates(), || self.order_validator.pools_populated())
+    }
+
+    /// validations running right now. Requests beyond
+    /// `max_validations_in_flight` wait until one of them finishes
+    pub fn validations_in_flight(&self) -> usize {
+        self.utils.validations_in_flight()
+    }
+
+    /// validations waiting to start, held during warm-up or queued behind
+    /// the running ones
+    pub fn validations_queued(&self) -> usize {
+        self.warmup.held() + self.utils.validations_queued()
+    }
+
+    /// releases the held requests once warm-up has finished. Returns true if
+    /// any were released
+    fn try_finish_warmup(&mut self) -> bool {
+        let price_updates = self.utils.price_updates();
+        let order_validator = &self.order_validator;
+        let Some(held) = self
+            .warmup
+            .finish(price_updates, || order_validator.pools_populated())
+        else {
+            return false
+        };
+
+        tracing::info!(held = held.len(), "validator warmed up");
+        let released = !held.is_empty();
+        held.into_iter()
+            .for_each(|req| self.on_new_validation_request(req));
+
+        released
     }
 
     fn on_new_validation_request(&mut self, req: ValidationRequest) {
-        match req {
+        let Some(req) = self.warmup.hold(req) else { return };
+
+        let res = match req {
             ValidationRequest::Order(order) => self.order_validator.validate_order(
                 order,
                 self.utils.token_pricing_snapshot(),
                 self.utils.thread_pool_mut()
             ),
             ValidationRequest::Bundle { sender, bundle } => {
-                self.bundle_validator.simulate_bundle(
-                    sender,
-                    bundle,
-                    &self.utils.token_pricing,
-                    &mut self.utils.thread_pool
-                );
+                let (token_pricing, thread_pool) = self.utils.bundle_tools();
+                self.bundle_validator
+                    .simulate_bundle(sender, bundle, token_pricing, thread_pool)
             }
             ValidationRequest::NewBlock { sender, block_number, orders, addresses } => {
+                self.warmup.on_new_block(block_number);
                 self.order_validator
                     .on_new_block(block_number, orders, addresses);
-                sender
-                    .send(OrderValidationResults::TransitionedToBlock)
-                    .unwrap();
+                let _ = sender.send(OrderValidationResults::TransitionedToBlock);
+                Ok(())
+            }
+            ValidationRequest::Simulate { sender, order, at_block } => {
+                self.order_validator.simulate_order(
+                    sender,
+                    order,
+                    at_block,
+                    self.utils.token_pricing_snapshot(),
+                    self.utils.thread_pool_mut()
+                )
+            }
+            ValidationRequest::Trace { sender, order } => self.order_validator.trace_order(
+                sender,
+                order,
+                self.utils.token_pricing_snapshot(),
+                self.utils.thread_pool_mut()
+            ),
+            ValidationRequest::NextNonce { sender, user } => {
+                self.order_validator
+                    .next_valid_nonce(sender, user, self.utils.thread_pool_mut())
+            }
+            ValidationRequest::Prewarm { addresses, tokens } => {
+                self.order_validator
+                    .prewarm(addresses, tokens, self.utils.thread_pool_mut())
             }
+            ValidationRequest::CancelOrder { user, order_hash } => self
+                .order_validator
+                .cancel_order(user, order_hash, self.utils.thread_pool_mut())
+        };
+        // the request's sender is dropped along with it, so whoever asked
+        // learns the validator is gone
+        if let Err(e) = res {
+            tracing::debug!(%e, "dropped a validation request");
         }
     }
 }
@@ -97,10 +325,142 @@ where
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>
     ) -> std::task::Poll<Self::Output> {
-        while let Poll::Ready(Some(req)) = self.rx.poll_recv(cx) {
-            self.on_new_validation_request(req);
+        let mut closed = false;
+        loop {
+            match self.rx.poll_recv(cx) {
+                Poll::Ready(Some(req)) => self.on_new_validation_request(req),
+                Poll::Ready(None) => {
+                    closed = true;
+                    break
+                }
+                Poll::Pending => break
+            }
+        }
+
+        let res = self.utils.poll_unpin(cx);
+        if self.try_finish_warmup() {
+            cx.waker().wake_by_ref();
+        }
+        self.metrics
+            .set_validations_in_flight(self.validations_in_flight());
+        let queued = self.validations_queued();
+        self.metrics.set_validations_queued(queued);
+        self.metrics
+            .set_priced_pairs(self.utils.token_pricing_ref().tracked_pairs());
+        self.queued.store(queued, Ordering::Relaxed);
+
+        // nothing can be asked anymore, stop once the running validations have
+        // answered
+        if closed && self.validations_in_flight() == 0 {
+            return Poll::Ready(())
         }
 
-        self.utils.poll_unpin(cx)
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use angstrom_types::{
+        orders::OrderOrigin,
+        sol_bindings::{grouped_orders::StandingVariants, rpc_orders::ExactStandingOrder}
+    };
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    fn warmup(max_held_requests: usize) -> WarmupConfig {
+        WarmupConfig { enabled: true, min_price_updates: 1, max_held_requests }
+    }
+
+    fn order_request(nonce: u64) -> (ValidationRequest, oneshot::Receiver<OrderValidationResults>) {
+        let order = ExactStandingOrder { nonce, ..Default::default() };
+        let order = AllOrders::Standing(StandingVariants::Exact(order));
+        let (tx, rx) = oneshot::channel();
+        let req =
+            OrderValidationRequest::ValidateOrder(tx, order, OrderOrigin::External, Instant::now());
+
+        (ValidationRequest::Order(req), rx)
+    }
+
+    #[test]
+    fn requests_are_held_until_a_block_is_synced() {
+        let mut gate = WarmupGate::new(warmup(16));
+        let (req, _rx) = order_request(0);
+        assert!(gate.hold(req).is_none());
+
+        // prices and pools alone aren't enough
+        assert!(!gate.is_ready(1, || true));
+        assert!(gate.finish(1, || true).is_none());
+
+        gate.on_new_block(1);
+        assert!(!gate.is_ready(0, || true));
+        assert!(!gate.is_ready(1, || false));
+        assert_eq!(gate.finish(1, || true).map(|held| held.len()), Some(1));
+        assert_eq!(gate.held(), 0);
+
+        // once ready requests run right away
+        let (req, _rx) = order_request(1);
+        assert!(gate.hold(req).is_some());
+        assert!(gate.finish(1, || true).is_none());
+    }
+
+    #[test]
+    fn orders_beyond_the_cap_are_rejected() {
+        let mut gate = WarmupGate::new(warmup(1));
+        let (held, mut held_rx) = order_request(0);
+        let (over, mut over_rx) = order_request(1);
+
+        assert!(gate.hold(held).is_none());
+        assert!(gate.hold(over).is_none());
+        assert_eq!(gate.held(), 1);
+
+        assert!(held_rx.try_recv().is_err());
+        let Ok(OrderValidationResults::Invalid(_, rejection)) = over_rx.try_recv() else {
+            panic!("orders over the cap are rejected")
+        };
+        assert_eq!(rejection.code, RejectionReason::Unavailable);
+
+        // requests that aren't held aren't capped either
+        let (tx, _rx) = oneshot::channel();
+        let nonce = ValidationRequest::NextNonce { sender: tx, user: Address::random() };
+        assert!(gate.hold(nonce).is_some());
+    }
+
+    #[test]
+    fn dry_runs_beyond_the_cap_are_answered_as_unavailable() {
+        let mut gate = WarmupGate::new(warmup(1));
+        let (held, _held_rx) = order_request(0);
+        assert!(gate.hold(held).is_none());
+
+        let order = AllOrders::Standing(StandingVariants::Exact(Default::default()));
+        let (tx, mut simulate_rx) = oneshot::channel();
+        let simulate =
+            ValidationRequest::Simulate { sender: tx, order: order.clone(), at_block: None };
+        let (tx, mut trace_rx) = oneshot::channel();
+        let trace = ValidationRequest::Trace { sender: tx, order };
+        assert!(gate.hold(simulate).is_none());
+        assert!(gate.hold(trace).is_none());
+        assert_eq!(gate.held(), 1);
+
+        let simulation = simulate_rx.try_recv().expect("simulations are answered");
+        assert!(!simulation.would_validate);
+        assert_eq!(simulation.rejection_reason.as_deref(), Some("the validator is warming up"));
+        let trace = trace_rx.try_recv().expect("traces are answered");
+        assert_eq!(
+            trace.rejection.map(|rejection| rejection.code),
+            Some(RejectionReason::Unavailable)
+        );
+    }
+
+    #[test]
+    fn disabled_warmup_holds_nothing() {
+        let mut gate = WarmupGate::new(WarmupConfig::disabled());
+        let (req, _rx) = order_request(0);
+
+        assert!(gate.is_ready(0, || false));
+        assert!(gate.hold(req).is_some());
     }
 }
@@ -1,3 +1,5 @@
+/// Tools for running the matching engine on its own
+pub mod matching;
 /// mocks utils for different modules
 pub mod mocks;
 /// Tools for testing network setup
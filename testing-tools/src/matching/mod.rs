@@ -0,0 +1,267 @@
+use angstrom_types::{
+    matching::{uniswap::PoolSnapshot, Ray},
+    orders::{OrderFillState, OrderId, OrderOutcome, OrderSet, PoolSolution},
+    primitive::PoolId,
+    sol_bindings::{
+        grouped_orders::{GroupedVanillaOrder, OrderWithStorageData},
+        rpc_orders::TopOfBlockOrder
+    }
+};
+use matching_engine::build_solution;
+
+use crate::type_generator::orders::{DistributionParameters, OrderDistributionBuilder};
+
+/// Runs [`build_solution`] over a single pool's book, without any of the
+/// network or consensus stack around it.
+///
+/// The matching itself is deterministic, books are drawn from
+/// [`DistributionParameters`] and only vary between runs if those aren't
+/// seeded.
+#[derive(Debug, Clone, Default)]
+pub struct MatchingHarness {
+    pool_id:  PoolId,
+    bids:     Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+    asks:     Vec<OrderWithStorageData<GroupedVanillaOrder>>,
+    searcher: Option<OrderWithStorageData<TopOfBlockOrder>>,
+    amm:      Option<PoolSnapshot>
+}
+
+impl MatchingHarness {
+    pub fn new(pool_id: PoolId) -> Self {
+        Self { pool_id, ..Default::default() }
+    }
+
+    /// `bid_count` bids and `ask_count` asks whose prices and quantities are
+    /// drawn from the first distribution of each pair for bids and from the
+    /// second for asks. Prices are raw `Ray` values. A side without orders
+    /// makes a one-sided book
+    pub fn with_distributions(
+        pool_id: PoolId,
+        (bid_count, ask_count): (usize, usize),
+        (bid_prices, ask_prices): (DistributionParameters, DistributionParameters),
+        (bid_quantities, ask_quantities): (DistributionParameters, DistributionParameters)
+    ) -> Self {
+        Self::new(pool_id)
+            .with_bids(Self::side(pool_id, true, bid_count, bid_prices, bid_quantities))
+            .with_asks(Self::side(pool_id, false, ask_count, ask_prices, ask_quantities))
+    }
+
+    /// `order_count` bids around `bid_price` and as many asks around
+    /// `ask_price`, each for about 100 of the asset, drawn from `seed`
+    pub fn with_book(
+        pool_id: PoolId,
+        order_count: usize,
+        bid_price: f64,
+        ask_price: f64,
+        seed: u64
+    ) -> Self {
+        let (bid_prices, _) = DistributionParameters::fixed_at(bid_price);
+        let (_, ask_prices) = DistributionParameters::fixed_at(ask_price);
+        // every distribution draws from its own seed
+        let prices = DistributionParameters::seeded((bid_prices, ask_prices), seed);
+        let quantities = DistributionParameters::fixed_at(100.0);
+        let quantities = DistributionParameters::seeded(quantities, seed.wrapping_add(2));
+
+        Self::with_distributions(pool_id, (order_count, order_count), prices, quantities)
+    }
+
+    /// a book whose bids are priced at twice `price` and its asks at half of
+    /// it, so every order can be filled
+    pub fn crossed(pool_id: PoolId, order_count: usize, price: f64, seed: u64) -> Self {
+        Self::with_book(pool_id, order_count, price * 2.0, price / 2.0, seed)
+    }
+
+    /// a book whose bids are priced at half `price` and its asks at twice it,
+    /// so no order can be filled against another
+    pub fn uncrossed(pool_id: PoolId, order_count: usize, price: f64, seed: u64) -> Self {
+        Self::with_book(pool_id, order_count, price / 2.0, price * 2.0, seed)
+    }
+
+    pub fn with_bids(mut self, bids: Vec<OrderWithStorageData<GroupedVanillaOrder>>) -> Self {
+        self.bids = bids;
+        self
+    }
+
+    pub fn with_asks(mut self, asks: Vec<OrderWithStorageData<GroupedVanillaOrder>>) -> Self {
+        self.asks = asks;
+        self
+    }
+
+    pub fn with_searcher(mut self, searcher: OrderWithStorageData<TopOfBlockOrder>) -> Self {
+        self.searcher = Some(searcher);
+        self
+    }
+
+    pub fn with_amm(mut self, amm: PoolSnapshot) -> Self {
+        self.amm = Some(amm);
+        self
+    }
+
+    pub fn bids(&self) -> &[OrderWithStorageData<GroupedVanillaOrder>] {
+        &self.bids
+    }
+
+    pub fn asks(&self) -> &[OrderWithStorageData<GroupedVanillaOrder>] {
+        &self.asks
+    }
+
+    pub fn run(&self) -> MatchingOutcome {
+        let limit = self.bids.iter().chain(&self.asks).cloned().collect();
+        let orders = OrderSet { limit, searcher: self.searcher.iter().cloned().collect() };
+
+        MatchingOutcome {
+            solution: build_solution(
+                self.pool_id,
+                &orders,
+                self.searcher.as_ref(),
+                self.amm.clone()
+            )
+        }
+    }
+
+    fn side(
+        pool_id: PoolId,
+        is_bid: bool,
+        order_count: usize,
+        prices: DistributionParameters,
+        quantities: DistributionParameters
+    ) -> Vec<OrderWithStorageData<GroupedVanillaOrder>> {
+        OrderDistributionBuilder::new()
+            .is_bid(is_bid)
+            .order_count(order_count)
+            .price_params(prices)
+            .volume_params(quantities)
+            .pool_id(pool_id)
+            .build()
+            .expect("invalid order distribution")
+    }
+}
+
+/// What [`MatchingHarness::run`] produced, `solution` is `None` if the book
+/// couldn't be solved.
+#[derive(Debug, Clone)]
+pub struct MatchingOutcome {
+    pub solution: Option<PoolSolution>
+}
+
+impl MatchingOutcome {
+    pub fn is_solved(&self) -> bool {
+        self.solution.is_some()
+    }
+
+    pub fn ucp(&self) -> Option<Ray> {
+        self.solution.as_ref().map(|solution| solution.ucp)
+    }
+
+    /// the outcomes of all orders that were at least partially filled
+    pub fn fills(&self) -> Vec<&OrderOutcome> {
+        self.solution
+            .iter()
+            .flat_map(|solution| &solution.limit)
+            .filter(|outcome| outcome.outcome != OrderFillState::Unfilled)
+            .collect()
+    }
+
+    pub fn outcome_of(&self, id: &OrderId) -> Option<&OrderFillState> {
+        self.solution
+            .iter()
+            .flat_map(|solution| &solution.limit)
+            .find(|outcome| outcome.id == *id)
+            .map(|outcome| &outcome.outcome)
+    }
+
+    pub fn searcher(&self) -> Option<&OrderWithStorageData<TopOfBlockOrder>> {
+        self.solution.as_ref()?.searcher.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use angstrom_types::sol_bindings::RawPoolOrder;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+    use crate::type_generator::orders::generate_top_of_block_order;
+
+    #[test]
+    fn crossed_book_fills_orders_of_both_sides() {
+        let pool_id = PoolId::random();
+        let mut rng = StdRng::seed_from_u64(7);
+        let searcher = generate_top_of_block_order(&mut rng, true, Some(pool_id), None, None, None);
+        let harness = MatchingHarness::crossed(pool_id, 4, 1e9, 7).with_searcher(searcher.clone());
+
+        let outcome = harness.run();
+        assert!(outcome.is_solved());
+        assert_eq!(outcome.searcher(), Some(&searcher));
+
+        let filled = |orders: &[OrderWithStorageData<GroupedVanillaOrder>]| {
+            orders
+                .iter()
+                .any(|order| outcome.fills().iter().any(|fill| fill.id == order.order_id))
+        };
+        assert!(filled(harness.bids()));
+        assert!(filled(harness.asks()));
+    }
+
+    #[test]
+    fn uncrossed_book_fills_nothing() {
+        let harness = MatchingHarness::uncrossed(PoolId::random(), 4, 1e9, 7);
+
+        let outcome = harness.run();
+        assert!(outcome.is_solved());
+        assert!(outcome.fills().is_empty());
+        assert!(harness.bids().iter().all(|bid| matches!(
+            outcome.outcome_of(&bid.order_id),
+            None | Some(OrderFillState::Unfilled)
+        )));
+    }
+
+    #[test]
+    fn one_sided_book_fills_nothing() {
+        let prices = DistributionParameters::seeded(DistributionParameters::crossed_at(1e9), 7);
+        let quantities = DistributionParameters::seeded(DistributionParameters::fixed_at(100.0), 9);
+        let harness =
+            MatchingHarness::with_distributions(PoolId::random(), (8, 0), prices, quantities);
+        assert!(harness.asks().is_empty());
+
+        assert!(harness.run().fills().is_empty());
+    }
+
+    #[test]
+    fn thin_book_fills_its_only_orders() {
+        // a single bid well above a single ask
+        let prices = (
+            DistributionParameters::new(2e9, 1.0, 0.0).with_seed(7),
+            DistributionParameters::new(5e8, 1.0, 0.0).with_seed(8)
+        );
+        let quantities = DistributionParameters::seeded(DistributionParameters::fixed_at(100.0), 9);
+        let harness =
+            MatchingHarness::with_distributions(PoolId::random(), (1, 1), prices, quantities);
+
+        let outcome = harness.run();
+        assert!(outcome.is_solved());
+        assert_eq!(outcome.fills().len(), 2);
+    }
+
+    #[test]
+    fn seeded_books_are_drawn_the_same() {
+        let prices = |seed: u64| {
+            let crossed = DistributionParameters::crossed(1e9, 1e6, 4.0);
+            let harness = MatchingHarness::with_distributions(
+                PoolId::random(),
+                (10, 10),
+                DistributionParameters::seeded(crossed, seed),
+                DistributionParameters::seeded(DistributionParameters::fixed_at(100.0), seed + 2)
+            );
+            harness
+                .bids()
+                .iter()
+                .chain(harness.asks())
+                .map(|order| order.limit_price())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(prices(7), prices(7));
+        assert_ne!(prices(7), prices(8));
+    }
+}
@@ -28,6 +28,7 @@ impl MockNetworkHandle {
         let (handle_tx, handle_rx) = unbounded_channel();
 
         let network = StromNetworkHandle::new(
+            Default::default(),
             Default::default(),
             UnboundedMeteredSender::new(handle_tx, "mock strom handle")
         );
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
-use alloy_primitives::{keccak256, Address, FixedBytes};
+use alloy_primitives::{keccak256, Address, FixedBytes, U256};
 use angstrom_types::{
     self,
     contract_payloads::angstrom::{AngstromBundle, BundleGasDetails},
@@ -12,7 +12,10 @@ use pade::PadeEncode;
 use parking_lot::Mutex;
 use validation::{
     bundle::BundleValidatorHandle,
-    order::{GasEstimationFuture, OrderValidationResults, OrderValidatorHandle}
+    order::{
+        GasEstimationFuture, NonceFuture, OrderValidationResults, OrderValidatorHandle,
+        SimulationFuture, SimulationResult
+    }
 };
 
 // all keys are the signer of the order
@@ -71,13 +74,43 @@ impl OrderValidatorHandle for MockValidator {
                 OrderValidationResults::Valid(o) => {
                     Ok((o.priority_data.gas_units, o.priority_data.gas))
                 }
-                OrderValidationResults::Invalid(e) => Err(format!("Invalid order: {}", e)),
+                OrderValidationResults::Invalid(_, rejection) => {
+                    Err(format!("Invalid order: {}", rejection.message))
+                }
                 OrderValidationResults::TransitionedToBlock => {
                     Err("Order transitioned to block".to_string())
                 }
+                OrderValidationResults::PoolNotSynced { pool_id, .. } => {
+                    Err(format!("Pool {pool_id:?} isn't synced yet"))
+                }
+                OrderValidationResults::PoolNotInitialized { init, .. } => {
+                    Err(format!("Pool {:?} isn't initialized yet", init.pool_id))
+                }
             }
         })
     }
+
+    fn simulate_order(&self, order: AllOrders) -> SimulationFuture {
+        let required_balance = U256::from(order.amount_in());
+        let res = self.limit_orders.lock().get(&order.from()).cloned();
+        Box::pin(async move {
+            match res {
+                Some(OrderValidationResults::Valid(o)) => SimulationResult {
+                    would_validate: true,
+                    estimated_fill: if o.is_currently_valid { o.amount_in() } else { 0 },
+                    required_balance,
+                    hook_gas: None,
+                    rejection_reason: None
+                },
+                Some(_) => SimulationResult::rejected(required_balance, "invalid order"),
+                None => SimulationResult::rejected(required_balance, "not in mock")
+            }
+        })
+    }
+
+    fn next_nonce(&self, _user: Address) -> NonceFuture {
+        Box::pin(async { Some(0) })
+    }
 }
 
 impl BundleValidatorHandle for MockValidator {
@@ -2,9 +2,11 @@ mod consensus_future;
 pub(crate) use consensus_future::TestnetConsensusFuture;
 mod eth_peer;
 mod network_future;
+mod reconnect;
 mod strom_peer;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc
@@ -21,6 +23,7 @@ use angstrom_network::{
 pub use eth_peer::*;
 use network_future::TestnetPeerStateFuture;
 use parking_lot::RwLock;
+pub use reconnect::*;
 use reth_chainspec::Hardforks;
 use reth_metrics::common::mpsc::{MeteredPollSender, UnboundedMeteredSender};
 use reth_network::test_utils::PeerConfig;
@@ -59,7 +62,8 @@ where
         pub_key: PublicKey,
         sk: SecretKey,
         to_pool_manager: Option<UnboundedMeteredSender<NetworkOrderEvent>>,
-        to_consensus_manager: Option<UnboundedMeteredSender<StromConsensusEvent>>
+        to_consensus_manager: Option<UnboundedMeteredSender<StromConsensusEvent>>,
+        reconnect: ReconnectConfig
     ) -> Self {
         let peer = PeerConfig::with_secret_key(c.clone(), sk);
 
@@ -68,7 +72,8 @@ where
             version:   0,
             chain:     Chain::mainnet().id(),
             peer:      peer_id,
-            timestamp: 0
+            timestamp: 0,
+            pools:     Default::default()
         };
         let (session_manager_tx, session_manager_rx) = tokio::sync::mpsc::channel(100);
         let sidecar = VerificationSidecar {
@@ -99,9 +104,20 @@ where
         let strom_handle = StromNetworkPeer::new(&strom_network);
         let eth_handle = EthNetworkPeer::new(&eth_peer);
 
+        let reconnector = PeerReconnector::new(
+            reconnect,
+            eth_handle.network_handle().clone(),
+            strom_handle.subscribe_network_events()
+        );
+
         let running = Arc::new(AtomicBool::new(true));
-        let futs =
-            TestnetPeerStateFuture::new(testnet_node_id, eth_peer, strom_network, running.clone());
+        let futs = TestnetPeerStateFuture::new(
+            testnet_node_id,
+            eth_peer,
+            strom_network,
+            reconnector,
+            running.clone()
+        );
 
         Self { strom_handle, secret_key: sk, pubkey: peer_id, networks: futs, eth_handle, running }
     }
@@ -110,6 +126,20 @@ where
         self.pubkey
     }
 
+    /// connects to the peer and redials it whenever the session drops
+    pub fn track_peer(&self, id: PeerId, addr: SocketAddr) {
+        self.networks.reconnector(|r| r.track(id, addr));
+    }
+
+    /// stops redialing the peer
+    pub fn untrack_peer(&self, id: PeerId) {
+        self.networks.reconnector(|r| r.untrack(id));
+    }
+
+    pub fn peer_connection_states(&self) -> Arc<RwLock<HashMap<PeerId, PeerConnectionState>>> {
+        self.networks.reconnector(|r| r.connection_states())
+    }
+
     pub fn stop_network(&self) {
         self.running.store(false, Ordering::Relaxed);
     }
@@ -16,10 +16,13 @@ use reth_provider::BlockReader;
 use tokio::task::JoinHandle;
 use tracing::{span, Level};
 
+use super::PeerReconnector;
+
 pub(crate) struct TestnetPeerStateFuture<C> {
     eth_peer:      Arc<Mutex<Peer<C>>>,
     /// the default ethereum network peer
     strom_network: Arc<Mutex<StromNetworkManager<C>>>,
+    reconnector:   Arc<Mutex<PeerReconnector>>,
     /// JoinHandle for the network futures
     fut:           JoinHandle<()>
 }
@@ -32,17 +35,20 @@ where
         testnet_node_id: u64,
         eth_peer: Peer<C>,
         strom_network: StromNetworkManager<C>,
+        reconnector: PeerReconnector,
         running: Arc<AtomicBool>
     ) -> Self {
         let eth_peer = Arc::new(Mutex::new(eth_peer));
         let strom_network = Arc::new(Mutex::new(strom_network));
+        let reconnector = Arc::new(Mutex::new(reconnector));
         let internal = TestnetPeerStateFutureInternals::new(
             testnet_node_id,
             eth_peer.clone(),
             strom_network.clone(),
+            reconnector.clone(),
             running
         );
-        Self { eth_peer, strom_network, fut: tokio::spawn(internal) }
+        Self { eth_peer, strom_network, reconnector, fut: tokio::spawn(internal) }
     }
 
     pub(crate) fn strom_network<F, R>(&self, f: F) -> R
@@ -73,6 +79,13 @@ where
         f(&mut self.eth_peer.lock())
     }
 
+    pub(crate) fn reconnector<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut PeerReconnector) -> R
+    {
+        f(&mut self.reconnector.lock())
+    }
+
     pub(crate) fn poll_fut_to_initialize(&mut self, cx: &mut Context<'_>) -> Poll<()> {
         self.fut.poll_unpin(cx).map(|_| ())
     }
@@ -83,6 +96,7 @@ struct TestnetPeerStateFutureInternals<C> {
     /// the default ethereum network peer
     eth_peer_fut:      Arc<Mutex<Peer<C>>>,
     strom_network_fut: Arc<Mutex<StromNetworkManager<C>>>,
+    reconnector:       Arc<Mutex<PeerReconnector>>,
     running:           Arc<AtomicBool>
 }
 
@@ -91,9 +105,10 @@ impl<C> TestnetPeerStateFutureInternals<C> {
         testnet_node_id: u64,
         eth_peer_fut: Arc<Mutex<Peer<C>>>,
         strom_network_fut: Arc<Mutex<StromNetworkManager<C>>>,
+        reconnector: Arc<Mutex<PeerReconnector>>,
         running: Arc<AtomicBool>
     ) -> Self {
-        Self { testnet_node_id, eth_peer_fut, strom_network_fut, running }
+        Self { testnet_node_id, eth_peer_fut, strom_network_fut, reconnector, running }
     }
 }
 
@@ -123,6 +138,8 @@ where
                     return Poll::Ready(())
                 }
             }
+
+            this.reconnector.lock().poll(cx);
         }
 
         drop(e);
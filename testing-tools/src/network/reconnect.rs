@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::{Duration, Instant}
+};
+
+use angstrom_network::StromNetworkEvent;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use reth_network::{NetworkHandle, Peers};
+use reth_network_peers::PeerId;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+
+/// How a node reconnects to validators it lost its session with.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectConfig {
+    /// backoff before the first attempt, doubled on every following one
+    pub initial_backoff: Duration,
+    pub max_backoff:     Duration
+}
+
+impl ReconnectConfig {
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX))
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff:     Duration::from_secs(30)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerConnectionState {
+    /// dialed but no session yet
+    Connecting,
+    Connected,
+    /// the session dropped, `attempts` redials were made since
+    Reconnecting {
+        attempts: u32
+    }
+}
+
+struct TrackedPeer {
+    addr:         SocketAddr,
+    attempts:     u32,
+    /// when to dial again, `None` while connected or waiting on a dial
+    next_attempt: Option<Instant>
+}
+
+/// Redials tracked peers whose strom session closed, with exponential backoff.
+/// Peers that are untracked, e.g. because the test disconnected them on
+/// purpose, are left alone.
+pub struct PeerReconnector {
+    config:         ReconnectConfig,
+    network_handle: NetworkHandle,
+    events:         UnboundedReceiverStream<StromNetworkEvent>,
+    peers:          HashMap<PeerId, TrackedPeer>,
+    states:         Arc<RwLock<HashMap<PeerId, PeerConnectionState>>>
+}
+
+impl PeerReconnector {
+    pub fn new(
+        config: ReconnectConfig,
+        network_handle: NetworkHandle,
+        events: UnboundedReceiverStream<StromNetworkEvent>
+    ) -> Self {
+        Self {
+            config,
+            network_handle,
+            events,
+            peers: HashMap::default(),
+            states: Default::default()
+        }
+    }
+
+    /// dials the peer and keeps it connected from here on
+    pub fn track(&mut self, id: PeerId, addr: SocketAddr) {
+        self.peers
+            .insert(id, TrackedPeer { addr, attempts: 0, next_attempt: None });
+        self.states
+            .write()
+            .insert(id, PeerConnectionState::Connecting);
+        self.network_handle.add_peer(id, addr);
+    }
+
+    pub fn untrack(&mut self, id: PeerId) {
+        self.peers.remove(&id);
+        self.states.write().remove(&id);
+    }
+
+    /// connection state of every tracked peer
+    pub fn connection_states(&self) -> Arc<RwLock<HashMap<PeerId, PeerConnectionState>>> {
+        self.states.clone()
+    }
+
+    pub fn poll(&mut self, cx: &mut Context<'_>) {
+        while let Poll::Ready(Some(event)) = self.events.poll_next_unpin(cx) {
+            self.on_network_event(event);
+        }
+
+        let now = Instant::now();
+        for (id, peer) in self.peers.iter_mut() {
+            if !peer.next_attempt.is_some_and(|at| at <= now) {
+                continue
+            }
+
+            peer.attempts += 1;
+            peer.next_attempt = Some(now + self.config.backoff(peer.attempts));
+            tracing::debug!(peer_id = ?id, attempts = peer.attempts, "redialing peer");
+
+            self.network_handle.add_peer(*id, peer.addr);
+            self.states
+                .write()
+                .insert(*id, PeerConnectionState::Reconnecting { attempts: peer.attempts });
+        }
+    }
+
+    fn on_network_event(&mut self, event: StromNetworkEvent) {
+        match event {
+            StromNetworkEvent::SessionEstablished { peer_id } => {
+                let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+                peer.attempts = 0;
+                peer.next_attempt = None;
+                self.states
+                    .write()
+                    .insert(peer_id, PeerConnectionState::Connected);
+            }
+            StromNetworkEvent::SessionClosed { peer_id, reason } => {
+                let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+                tracing::debug!(?peer_id, ?reason, "lost session with peer");
+                peer.next_attempt = Some(Instant::now() + self.config.backoff(0));
+                self.states
+                    .write()
+                    .insert(peer_id, PeerConnectionState::Reconnecting { attempts: 0 });
+            }
+            StromNetworkEvent::PeerAdded(_) | StromNetworkEvent::PeerRemoved(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let config = ReconnectConfig {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff:     Duration::from_millis(1000)
+        };
+
+        assert_eq!(config.backoff(0), Duration::from_millis(100));
+        assert_eq!(config.backoff(3), Duration::from_millis(800));
+        assert_eq!(config.backoff(4), Duration::from_millis(1000));
+        assert_eq!(config.backoff(64), Duration::from_millis(1000));
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashSet, sync::Arc};
 
 use alloy_primitives::Address;
 use angstrom_network::{StromNetworkEvent, StromNetworkHandle, StromNetworkManager};
-use angstrom_types::primitive::PeerId;
+use angstrom_types::primitive::{PeerId, PoolId};
 use parking_lot::RwLock;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
@@ -36,6 +36,10 @@ impl StromNetworkPeer {
         self.network_handle.peer_count()
     }
 
+    pub fn peer_pools(&self, id: PeerId) -> HashSet<PoolId> {
+        self.network_handle.peer_pools(id)
+    }
+
     pub fn remove_validator(&self, id: PeerId) {
         let addr = Address::from_raw_public_key(id.as_slice());
         let set = self.validator_set();
@@ -47,7 +47,9 @@ impl TestnetOrderPool {
             PoolHandle { manager_tx: tx.clone(), pool_manager_tx: pool_manager_tx.clone() };
         let order_storage = Arc::new(OrderStorage::new(&config));
         let inner =
-            OrderIndexer::new(validator, order_storage.clone(), block_number, sub_tx, pool_tracker);
+            OrderIndexer::new(validator, order_storage.clone(), block_number, sub_tx, pool_tracker)
+                .with_order_history(config.history_blocks)
+                .with_cancelled_orders_mode(config.cancelled_orders);
 
         Self {
             pool_manager: PoolManager::new(
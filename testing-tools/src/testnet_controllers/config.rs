@@ -1,9 +1,24 @@
+use std::num::NonZeroUsize;
+
+use crate::network::ReconnectConfig;
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct AngstromTestnetConfig {
     pub intial_node_count:       u64,
+    /// rpc port of the first node, the others use the following ports. If
+    /// zero, every node gets a free port assigned by the OS
     pub initial_rpc_port:        u16,
+    /// how many nodes, starting from the first, serve rpc. all of them if
+    /// `None`
+    pub rpc_node_count:          Option<u64>,
     pub testnet_block_time_secs: u64,
-    pub testnet_kind:            TestnetKind
+    pub testnet_kind:            TestnetKind,
+    /// peers each node forwards orders to, all of them if `None`
+    pub gossip_fanout:           Option<NonZeroUsize>,
+    /// what the rpc server accepts connections over
+    pub rpc_transport:           RpcTransport,
+    /// backoff for redialing peers whose session dropped
+    pub reconnect:               ReconnectConfig
 }
 
 impl AngstromTestnetConfig {
@@ -13,11 +28,49 @@ impl AngstromTestnetConfig {
         testnet_block_time_secs: u64,
         testnet_kind: TestnetKind
     ) -> Self {
-        Self { intial_node_count, initial_rpc_port, testnet_block_time_secs, testnet_kind }
+        Self {
+            intial_node_count,
+            initial_rpc_port,
+            rpc_node_count: None,
+            testnet_block_time_secs,
+            testnet_kind,
+            gossip_fanout: None,
+            rpc_transport: RpcTransport::default(),
+            reconnect: ReconnectConfig::default()
+        }
+    }
+
+    pub fn with_reconnect(mut self, reconnect: ReconnectConfig) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+
+    pub fn with_rpc_transport(mut self, rpc_transport: RpcTransport) -> Self {
+        self.rpc_transport = rpc_transport;
+        self
     }
 
-    pub fn rpc_port_with_node_id(&self, node_id: u64) -> u64 {
-        self.initial_rpc_port as u64 + node_id
+    pub fn with_gossip_fanout(mut self, gossip_fanout: NonZeroUsize) -> Self {
+        self.gossip_fanout = Some(gossip_fanout);
+        self
+    }
+
+    pub fn with_rpc_node_count(mut self, rpc_node_count: u64) -> Self {
+        self.rpc_node_count = Some(rpc_node_count);
+        self
+    }
+
+    /// The port the node's rpc should bind to, `None` if the node doesn't
+    /// serve rpc.
+    pub fn rpc_port_with_node_id(&self, node_id: u64) -> Option<u64> {
+        if self.rpc_node_count.is_some_and(|count| node_id >= count) {
+            return None
+        }
+        if self.initial_rpc_port == 0 {
+            return Some(0)
+        }
+
+        Some(self.initial_rpc_port as u64 + node_id)
     }
 
     pub fn state_machine_config(&self) -> Option<StateMachineConfig> {
@@ -28,6 +81,17 @@ impl AngstromTestnetConfig {
     }
 }
 
+/// Connections the rpc server accepts. Subscriptions need a websocket, plain
+/// http can't stream their items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RpcTransport {
+    Http,
+    Ws,
+    /// http and websocket on the same port
+    #[default]
+    Both
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub enum TestnetKind {
     StateMachine(StateMachineConfig),
@@ -50,3 +114,38 @@ pub struct StateMachineConfig {
     pub start_block: u64,
     pub end_block:   u64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nodes_serve_rpc_on_consecutive_ports() {
+        let config = AngstromTestnetConfig::new(3, 4200, 12, TestnetKind::new_raw());
+
+        let ports = (0..3)
+            .map(|node_id| config.rpc_port_with_node_id(node_id))
+            .collect::<Vec<_>>();
+        assert_eq!(ports, vec![Some(4200), Some(4201), Some(4202)]);
+    }
+
+    #[test]
+    fn test_only_the_first_rpc_nodes_serve_rpc() {
+        let config =
+            AngstromTestnetConfig::new(3, 4200, 12, TestnetKind::new_raw()).with_rpc_node_count(1);
+
+        assert_eq!(config.rpc_port_with_node_id(0), Some(4200));
+        assert_eq!(config.rpc_port_with_node_id(1), None);
+        assert_eq!(config.rpc_port_with_node_id(2), None);
+    }
+
+    #[test]
+    fn test_port_zero_assigns_every_node_a_free_port() {
+        let config =
+            AngstromTestnetConfig::new(3, 0, 12, TestnetKind::new_raw()).with_rpc_node_count(2);
+
+        assert_eq!(config.rpc_port_with_node_id(0), Some(0));
+        assert_eq!(config.rpc_port_with_node_id(1), Some(0));
+        assert_eq!(config.rpc_port_with_node_id(2), None);
+    }
+}
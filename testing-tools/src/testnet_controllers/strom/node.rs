@@ -10,10 +10,11 @@ use angstrom_network::{
     NetworkOrderEvent, StromNetworkEvent, StromNetworkHandle, StromNetworkManager
 };
 use angstrom_types::{
+    contract_bindings::angstrom::Angstrom::PoolKey,
     primitive::PeerId,
     sol_bindings::{grouped_orders::AllOrders, testnet::random::RandomValues}
 };
-use consensus::AngstromValidator;
+use consensus::{AngstromValidator, ValidatorInfo};
 use parking_lot::RwLock;
 use reth_chainspec::Hardforks;
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
@@ -27,14 +28,16 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 use super::strom_internals::AngstromTestnetNodeInternals;
 use crate::{
     anvil_state_provider::state_provider_factory::RpcStateProviderFactoryWrapper,
-    network::{EthPeerPool, TestnetNodeNetwork},
+    network::{EthPeerPool, PeerConnectionState, TestnetNodeNetwork},
     testnet_controllers::AngstromTestnetConfig
 };
 
 pub struct TestnetNode<C> {
     _testnet_node_id: u64,
     network:          TestnetNodeNetwork<C>,
-    strom:            AngstromTestnetNodeInternals
+    strom:            AngstromTestnetNodeInternals,
+    /// the validators the node's consensus was started with
+    validators:       Vec<AngstromValidator>
 }
 
 impl<C> TestnetNode<C>
@@ -60,11 +63,11 @@ where
             network.strom_handle.network_handle().clone(),
             network.secret_key,
             config,
-            initial_validators
+            initial_validators.clone()
         )
         .await?;
 
-        Ok(Self { _testnet_node_id, network, strom })
+        Ok(Self { _testnet_node_id, network, strom, validators: initial_validators })
     }
 
     /// General
@@ -77,6 +80,17 @@ where
         &self.strom.state_provider
     }
 
+    /// Address of the node's order rpc, `None` if it doesn't serve one
+    pub fn rpc_addr(&self) -> Option<SocketAddr> {
+        self.strom.rpc_addr
+    }
+
+    /// The pools deployed to the node's anvil, orders for them pass
+    /// validation
+    pub fn pool_keys(&self) -> &[PoolKey] {
+        &self.strom.pool_keys
+    }
+
     /// Eth
     /// -------------------------------------
     pub fn eth_peer_handle(&self) -> &PeerHandle<EthPeerPool> {
@@ -101,14 +115,28 @@ where
         self.network.strom_handle.network_handle()
     }
 
+    pub fn validator_set(&self) -> Vec<ValidatorInfo> {
+        self.validators
+            .iter()
+            .map(AngstromValidator::info)
+            .collect()
+    }
+
     pub fn strom_validator_set(&self) -> Arc<RwLock<HashSet<Address>>> {
         self.network.strom_handle.validator_set()
     }
 
+    /// disconnects the peer for good, it won't be redialed
     pub fn disconnect_strom_peer(&self, id: PeerId) {
+        self.network.untrack_peer(id);
         self.network.strom_handle.disconnect_peer(id);
     }
 
+    /// connection state of every peer this node keeps connected
+    pub fn peer_connection_states(&self) -> HashMap<PeerId, PeerConnectionState> {
+        self.network.peer_connection_states().read().clone()
+    }
+
     pub fn strom_peer_count(&self) -> usize {
         self.network.strom_handle.peer_count()
     }
@@ -190,7 +218,8 @@ where
     pub async fn connect_to_all_peers(&mut self, other_peers: &mut HashMap<u64, Self>) {
         self.network.start_network();
         other_peers.iter().for_each(|(_, peer)| {
-            self.connect_to_eth_peer(peer.network.pubkey(), peer.eth_socket_addr());
+            self.network
+                .track_peer(peer.network.pubkey(), peer.eth_socket_addr());
 
             self.add_validator_bidirectional(peer);
         });
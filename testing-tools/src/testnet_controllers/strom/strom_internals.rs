@@ -1,4 +1,7 @@
-use std::sync::{atomic::AtomicBool, Arc};
+use std::{
+    net::SocketAddr,
+    sync::{atomic::AtomicBool, Arc}
+};
 
 use alloy::{
     eips::{BlockId, BlockNumberOrTag},
@@ -11,7 +14,9 @@ use alloy::{
 use alloy_primitives::{Address, BlockNumber};
 use angstrom::components::StromHandles;
 use angstrom_eth::handle::Eth;
-use angstrom_network::{pool_manager::PoolHandle, PoolManagerBuilder, StromNetworkHandle};
+use angstrom_network::{
+    pool_manager::PoolHandle, PoolManagerBuilder, StromNetworkConfig, StromNetworkHandle
+};
 use angstrom_rpc::{api::OrderApiServer, OrderApi};
 use angstrom_types::{
     contract_bindings::angstrom::Angstrom::PoolKey,
@@ -23,7 +28,7 @@ use angstrom_types::{
 use consensus::{AngstromValidator, ConsensusManager, ManagerNetworkDeps, Signer};
 use futures::StreamExt;
 use jsonrpsee::server::ServerBuilder;
-use matching_engine::{manager::MatcherHandle, MatchingManager};
+use matching_engine::{manager::MatcherHandle, MatchingManager, MatchingSchedulerHandle};
 use order_pool::{order_storage::OrderStorage, PoolConfig};
 use reth_provider::{CanonStateNotifications, CanonStateSubscriptions};
 use reth_tasks::TokioTaskExecutor;
@@ -45,22 +50,26 @@ use crate::{
     },
     contracts::deploy_contract_and_create_pool,
     network::TestnetConsensusFuture,
-    testnet_controllers::AngstromTestnetConfig,
+    testnet_controllers::{AngstromTestnetConfig, RpcTransport},
     types::{MockBlockSync, SendingStromHandles},
     validation::TestOrderValidator
 };
 
 pub struct AngstromTestnetNodeInternals {
-    pub rpc_port:         u64,
-    pub state_provider:   RpcStateProviderFactoryWrapper,
-    pub order_storage:    Arc<OrderStorage>,
-    pub pool_handle:      PoolHandle,
-    pub tx_strom_handles: SendingStromHandles,
-    pub testnet_hub:      StromContractInstance,
-    pub validator:        TestOrderValidator<RpcStateProviderFactory>,
-    pub matching_handle:  MatcherHandle,
-    _consensus:           TestnetConsensusFuture<PubSubFrontend, MatcherHandle>,
-    _consensus_running:   Arc<AtomicBool>
+    pub rpc_addr:           Option<SocketAddr>,
+    /// the pools deployed to the node's anvil
+    pub pool_keys:          Vec<PoolKey>,
+    pub state_provider:     RpcStateProviderFactoryWrapper,
+    pub order_storage:      Arc<OrderStorage>,
+    pub pool_handle:        PoolHandle,
+    pub tx_strom_handles:   SendingStromHandles,
+    pub testnet_hub:        StromContractInstance,
+    pub validator:          TestOrderValidator<RpcStateProviderFactory>,
+    pub matching_handle:    MatcherHandle,
+    /// triggers matching runs outside of consensus and streams their results
+    pub matching_scheduler: MatchingSchedulerHandle,
+    _consensus:             TestnetConsensusFuture<PubSubFrontend, MatcherHandle>,
+    _consensus_running:     Arc<AtomicBool>
 }
 
 impl AngstromTestnetNodeInternals {
@@ -145,7 +154,7 @@ impl AngstromTestnetNodeInternals {
             .await
             .unwrap();
 
-        let uniswap_registry: UniswapPoolRegistry = pools.into();
+        let uniswap_registry: UniswapPoolRegistry = pools.clone().into();
 
         let uniswap_pool_manager = configure_uniswap_manager(
             state_provider.provider().provider().into(),
@@ -169,7 +178,7 @@ impl AngstromTestnetNodeInternals {
 
         let token_price_update_stream = state_provider.provider().canonical_state_stream();
         let token_price_update_stream =
-            PairsWithPrice::into_price_update_stream(Address::default(), token_price_update_stream)
+            PairsWithPrice::into_price_update_stream(angstrom_addr, token_price_update_stream)
                 .boxed();
 
         let pool_config_store = Arc::new(
@@ -193,7 +202,7 @@ impl AngstromTestnetNodeInternals {
             token_price_update_stream,
             pool_config_store.clone()
         )
-        .await;
+        .await?;
 
         let pool_config = PoolConfig::default();
         let order_storage = Arc::new(OrderStorage::new(&pool_config));
@@ -207,6 +216,10 @@ impl AngstromTestnetNodeInternals {
             block_sync
         )
         .with_config(pool_config)
+        .with_network_config(StromNetworkConfig {
+            gossip_fanout: config.gossip_fanout,
+            ..Default::default()
+        })
         .build_with_channels(
             executor.clone(),
             strom_handles.orderpool_tx,
@@ -215,18 +228,29 @@ impl AngstromTestnetNodeInternals {
             strom_handles.pool_manager_tx
         );
 
-        let rpc_port = config.rpc_port_with_node_id(testnet_node_id);
-        let server = ServerBuilder::default()
-            .build(format!("127.0.0.1:{}", rpc_port))
-            .await?;
-
-        let addr = server.local_addr().unwrap();
-
-        tokio::spawn(async move {
-            let server_handle = server.start(order_api.into_rpc());
-            tracing::info!("rpc server started on: {}", addr);
-            let _ = server_handle.stopped().await;
-        });
+        let rpc_addr = if let Some(rpc_port) = config.rpc_port_with_node_id(testnet_node_id) {
+            let builder = ServerBuilder::default();
+            let builder = match config.rpc_transport {
+                RpcTransport::Http => builder.http_only(),
+                RpcTransport::Ws => builder.ws_only(),
+                RpcTransport::Both => builder
+            };
+            let server = builder.build(format!("127.0.0.1:{}", rpc_port)).await?;
+
+            let addr = server.local_addr().unwrap();
+            let transport = config.rpc_transport;
+
+            tokio::spawn(async move {
+                let server_handle = server.start(order_api.into_rpc());
+                tracing::info!(?transport, "rpc server started on: {}", addr);
+                let _ = server_handle.stopped().await;
+            });
+
+            Some(addr)
+        } else {
+            tracing::debug!(testnet_node_id, "not serving rpc on this node");
+            None
+        };
 
         let testnet_hub = TestnetHub::new(angstrom_addr, state_provider.provider().provider());
 
@@ -254,7 +278,8 @@ impl AngstromTestnetNodeInternals {
             state_provider.provider().provider(),
             matching_handle.clone(),
             block_sync
-        );
+        )
+        .with_matching_scheduler(strom_handles.matching_scheduler);
 
         let _consensus_running = Arc::new(AtomicBool::new(true));
 
@@ -265,13 +290,15 @@ impl AngstromTestnetNodeInternals {
         );
 
         Ok(Self {
-            rpc_port,
+            rpc_addr,
+            pool_keys: pools,
             state_provider,
             order_storage,
             pool_handle,
             tx_strom_handles,
             testnet_hub,
             matching_handle,
+            matching_scheduler: strom_handles.matching_scheduler_handle,
             validator,
             _consensus,
             _consensus_running
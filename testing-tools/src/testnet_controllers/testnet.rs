@@ -3,12 +3,13 @@ use std::{
     future::Future
 };
 
+use alloy_primitives::B256;
 use angstrom::components::initialize_strom_handles;
 use angstrom_network::{
     manager::StromConsensusEvent, NetworkOrderEvent, StromMessage, StromNetworkManager
 };
 use angstrom_types::{primitive::PeerId, sol_bindings::grouped_orders::AllOrders};
-use consensus::AngstromValidator;
+use consensus::{AngstromValidator, ValidatorInfo};
 use futures::StreamExt;
 use rand::{thread_rng, Rng};
 use reth_chainspec::Hardforks;
@@ -63,6 +64,14 @@ where
         StateMachineTestnet::new(self)
     }
 
+    /// spawns a node after the testnet is up and connects it to every peer,
+    /// returns the id of the new node
+    pub async fn spawn_late_node(&mut self, c: C) -> eyre::Result<u64> {
+        self.spawn_new_nodes(c, 1).await?;
+
+        Ok(self.current_max_peer_id - 1)
+    }
+
     async fn spawn_new_nodes(&mut self, c: C, number_nodes: u64) -> eyre::Result<()> {
         let keys = generate_node_keys(number_nodes);
         let initial_validators = keys
@@ -96,7 +105,8 @@ where
             pk,
             sk,
             Some(strom_handles.pool_tx.clone()),
-            Some(strom_handles.consensus_tx_op.clone())
+            Some(strom_handles.consensus_tx_op.clone()),
+            self.config.reconnect
         )
         .await;
 
@@ -129,6 +139,20 @@ where
         ids[id_idx]
     }
 
+    /// every validator any node runs consensus with, ordered by pubkey. Nodes
+    /// spawned late only know of the validators spawned along with them
+    pub fn validator_set(&self) -> Vec<ValidatorInfo> {
+        let mut validators = self
+            .peers
+            .values()
+            .flat_map(TestnetNode::validator_set)
+            .collect::<Vec<_>>();
+        validators.sort_unstable_by_key(|validator| validator.pubkey);
+        validators.dedup_by_key(|validator| validator.pubkey);
+
+        validators
+    }
+
     pub fn get_peer(&self, id: u64) -> &TestnetNode<C> {
         self.peers
             .get(&id)
@@ -239,6 +263,41 @@ where
         out == self.peers.len() - 1
     }
 
+    /// has the requester ask the responder for the given orders and ensures
+    /// the requester gets back the expected ones
+    pub async fn reconcile_orders(
+        &mut self,
+        requester_id: u64,
+        responder_id: u64,
+        order_hashes: Vec<B256>,
+        expected_orders: Vec<AllOrders>
+    ) -> bool {
+        let responder_peer_id = self.get_peer(responder_id).peer_id();
+
+        let (new_tx, mut new_rx) = metered_unbounded_channel("new orderpool");
+        let old_tx = self
+            .get_peer_mut(requester_id)
+            .pre_post_network_event_channel_swap(true, |net| net.swap_pool_manager(new_tx));
+
+        let requester = self.get_peer(requester_id);
+        requester.start_network(true);
+        requester
+            .strom_network_handle()
+            .send_message(responder_peer_id, StromMessage::RequestOrders(order_hashes));
+
+        let received = new_rx.next().await;
+
+        let _ = self
+            .get_peer_mut(requester_id)
+            .pre_post_network_event_channel_swap(false, |net| net.swap_pool_manager(old_tx));
+
+        received
+            == Some(NetworkOrderEvent::IncomingOrders {
+                peer_id: responder_peer_id,
+                orders:  expected_orders
+            })
+    }
+
     /// takes a random peer and gets them to broadcast the message. we then
     /// take all other peers and ensure that they received the message.
     pub async fn broadcast_consensus_message(
@@ -26,6 +26,8 @@ pub fn generate_limit_order_set(
 
 #[cfg(test)]
 mod tests {
+    use angstrom_types::{matching::Ray, orders::PoolSolution, primitive::PoolId};
+
     use crate::type_generator::consensus::{
         preproposal::PreproposalBuilder, proposal::ProposalBuilder
     };
@@ -49,4 +51,24 @@ mod tests {
             .build();
         assert!(proposal.is_valid(), "Proposal cannot validate itself");
     }
+
+    #[test]
+    fn proposal_carries_given_solutions() {
+        let solution = |id| PoolSolution { id, ucp: Ray::from(100_usize), ..Default::default() };
+        let (first, second) = (solution(PoolId::random()), solution(PoolId::random()));
+
+        let proposal = ProposalBuilder::new()
+            .order_count(10)
+            .preproposal_count(1)
+            .for_random_pools(1)
+            .for_block(10)
+            .with_pool_solution(first.clone())
+            .with_pool_solution(second.clone())
+            .build();
+        assert!(proposal.is_valid(), "Proposal cannot validate itself");
+
+        let mut expected = vec![first, second];
+        expected.sort_by_key(|sol| sol.id);
+        assert_eq!(proposal.solutions, expected);
+    }
 }
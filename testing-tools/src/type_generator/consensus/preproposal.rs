@@ -17,11 +17,15 @@ use crate::type_generator::orders::{
 
 #[derive(Debug, Default)]
 pub struct PreproposalBuilder {
-    order_count: Option<usize>,
-    block:       Option<u64>,
-    pools:       Option<Vec<Pool>>,
-    sk:          Option<Secp256SecretKey>,
-    order_key:   Option<SigningInfo>
+    order_count:        Option<usize>,
+    block:              Option<u64>,
+    pools:              Option<Vec<Pool>>,
+    sk:                 Option<Secp256SecretKey>,
+    order_key:          Option<SigningInfo>,
+    /// scale and skew of the limit order prices around each pool's price
+    price_distribution: Option<(f64, f64)>,
+    /// seeds the limit order prices and quantities
+    seed:               Option<u64>
 }
 
 impl PreproposalBuilder {
@@ -56,6 +60,18 @@ impl PreproposalBuilder {
         Self { order_key, ..self }
     }
 
+    /// spreads the limit order prices of each pool as
+    /// [`DistributionParameters::crossed`] does, instead of the default
+    /// [`DistributionParameters::crossed_at`]
+    pub fn price_distribution(self, scale: f64, skew: f64) -> Self {
+        Self { price_distribution: Some((scale, skew)), ..self }
+    }
+
+    /// draws the same limit order prices and quantities on every build
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed: Some(seed), ..self }
+    }
+
     pub fn build(self) -> PreProposal {
         // Extract values from our struct
         let pools = self.pools.unwrap_or_default();
@@ -73,10 +89,21 @@ impl PreproposalBuilder {
 
         let limit = pools
             .iter()
-            .flat_map(|pool| {
-                let (bid_dist, ask_dist) =
-                    DistributionParameters::crossed_at(pool.price().as_float());
-                let (bid_quant, ask_quant) = DistributionParameters::fixed_at(100.0);
+            .enumerate()
+            .flat_map(|(index, pool)| {
+                let price = pool.price().as_float();
+                let mut prices = match self.price_distribution {
+                    Some((scale, skew)) => DistributionParameters::crossed(price, scale, skew),
+                    None => DistributionParameters::crossed_at(price)
+                };
+                let mut quantities = DistributionParameters::fixed_at(100.0);
+                // every pool and side draws from its own seed
+                if let Some(seed) = self.seed {
+                    let seed = seed.wrapping_add(4 * index as u64);
+                    prices = DistributionParameters::seeded(prices, seed);
+                    quantities = DistributionParameters::seeded(quantities, seed.wrapping_add(2));
+                }
+                let ((bid_dist, ask_dist), (bid_quant, ask_quant)) = (prices, quantities);
                 let bids = OrderDistributionBuilder::new()
                     .bid()
                     .order_count(count)
@@ -135,7 +162,8 @@ impl PreproposalBuilder {
                     order_id,
                     pool_id: pool_id.id(),
                     valid_block: block,
-                    tob_reward: U256::ZERO
+                    tob_reward: U256::ZERO,
+                    filled_amount: 0
                 }
             })
             .collect();
@@ -146,7 +174,7 @@ impl PreproposalBuilder {
 
 #[cfg(test)]
 mod tests {
-    use super::PreproposalBuilder;
+    use super::{PoolBuilder, PreproposalBuilder};
 
     #[test]
     fn generates_order_spread_that_crosses() {
@@ -172,4 +200,24 @@ mod tests {
                 });
         assert!(high_price > low_price, "Prices do not cross");
     }
+
+    #[test]
+    fn seeded_builds_draw_the_same_prices() {
+        let pool = PoolBuilder::new().build();
+        let prices = |seed: u64| {
+            PreproposalBuilder::new()
+                .order_count(10)
+                .for_pools(vec![pool.clone()])
+                .price_distribution(1000.0, 4.0)
+                .with_seed(seed)
+                .build()
+                .limit
+                .iter()
+                .map(|order| order.float_price())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(prices(7), prices(7));
+        assert_ne!(prices(7), prices(8));
+    }
 }
@@ -8,6 +8,7 @@ use angstrom_types::{
     consensus::{PreProposal, Proposal},
     contract_bindings::angstrom::Angstrom::PoolKey,
     matching::{uniswap::LiqRange, SqrtPriceX96},
+    orders::PoolSolution,
     primitive::PoolId,
     sol_bindings::{grouped_orders::OrderWithStorageData, rpc_orders::TopOfBlockOrder}
 };
@@ -35,7 +36,9 @@ pub struct ProposalBuilder {
     block:             Option<u64>,
     pools:             Option<Vec<Pool>>,
     sk:                Option<Secp256SecretKey>,
-    order_key:         Option<SigningInfo>
+    order_key:         Option<SigningInfo>,
+    /// used as is instead of matching the preproposals
+    solutions:         Option<Vec<PoolSolution>>
 }
 
 impl ProposalBuilder {
@@ -94,6 +97,14 @@ impl ProposalBuilder {
         Self { order_key, ..self }
     }
 
+    /// Adds a known solution to the proposal. Once any solution is given the
+    /// preproposals aren't matched and the proposal carries exactly the given
+    /// solutions
+    pub fn with_pool_solution(mut self, solution: PoolSolution) -> Self {
+        self.solutions.get_or_insert_with(Vec::new).push(solution);
+        self
+    }
+
     pub fn build(self) -> Proposal {
         // Extract values from our struct
         let ethereum_height = self.ethereum_height.unwrap_or_default();
@@ -119,8 +130,15 @@ impl ProposalBuilder {
                 })
                 .collect::<Vec<_>>()
         });
+        let solutions = self
+            .solutions
+            .unwrap_or_else(|| Self::match_preproposals(&preproposals));
+        Proposal::generate_proposal(ethereum_height, source, preproposals, solutions, vec![], &sk)
+    }
+
+    fn match_preproposals(preproposals: &[PreProposal]) -> Vec<PoolSolution> {
         let books = MatchingManager::<TokioTaskExecutor, MockValidator>::build_books(
-            &preproposals,
+            preproposals,
             &HashMap::default()
         );
         let searcher_orders: HashMap<PoolId, OrderWithStorageData<TopOfBlockOrder>> = preproposals
@@ -130,7 +148,7 @@ impl ProposalBuilder {
                 acc.entry(order.pool_id).or_insert(order.clone());
                 acc
             });
-        let solutions = books
+        books
             .into_iter()
             .map(|b| {
                 let searcher = searcher_orders.get(&b.id()).cloned();
@@ -138,7 +156,6 @@ impl ProposalBuilder {
                     .map(|s| s.solution(searcher))
                     .unwrap()
             })
-            .collect::<Vec<_>>();
-        Proposal::generate_proposal(ethereum_height, source, preproposals, solutions, &sk)
+            .collect()
     }
 }
@@ -65,22 +65,19 @@ impl OrderDistributionBuilder {
         let order_count = self.order_count.unwrap_or_default();
         let pool_id = self.pool_id.unwrap_or_default();
         let valid_block = self.valid_block.unwrap_or_default();
-        let DistributionParameters {
-            location: price_location,
-            scale: price_scale,
-            shape: price_shape
-        } = self.priceparams.unwrap_or_default();
-        let DistributionParameters { location: v_location, scale: v_scale, shape: v_shape } =
-            self.volumeparams.unwrap_or_default();
+        let price_params = self.priceparams.unwrap_or_default();
+        let volume_params = self.volumeparams.unwrap_or_default();
 
-        // We need two RNG handles because we hand them out as a mutable
-        let mut rng = rand::thread_rng();
-        let mut rng2 = rand::thread_rng();
+        // prices and volumes each have their own rng, seeded by their params
+        let mut rng = price_params.rng();
+        let mut rng2 = volume_params.rng();
 
-        let price_gen = SkewNormal::new(price_location, price_scale, price_shape)
-            .map_err(|e| eyre!("Error creating price distribution: {}", e))?;
-        let volume_gen = SkewNormal::new(v_location, v_scale, v_shape)
-            .map_err(|e| eyre!("Error creating price distribution: {}", e))?;
+        let price_gen =
+            SkewNormal::new(price_params.location, price_params.scale, price_params.shape)
+                .map_err(|e| eyre!("Error creating price distribution: {}", e))?;
+        let volume_gen =
+            SkewNormal::new(volume_params.location, volume_params.scale, volume_params.shape)
+                .map_err(|e| eyre!("Error creating price distribution: {}", e))?;
         Ok(price_gen
             .sample_iter(&mut rng)
             .zip(volume_gen.sample_iter(&mut rng2))
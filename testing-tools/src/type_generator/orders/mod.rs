@@ -12,7 +12,7 @@ use angstrom_types::{
     }
 };
 use enr::k256::ecdsa::SigningKey;
-use rand::{rngs::ThreadRng, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 // mod stored;
 mod distribution;
@@ -105,7 +105,8 @@ impl StoredOrderBuilder {
             order_id,
             pool_id,
             valid_block,
-            tob_reward
+            tob_reward,
+            filled_amount: 0
         }
     }
 }
@@ -151,7 +152,7 @@ impl OrderIdBuilder {
 }
 
 pub fn generate_top_of_block_order(
-    rng: &mut ThreadRng,
+    rng: &mut impl Rng,
     is_bid: bool,
     pool_id: Option<PoolId>,
     valid_block: Option<u64>,
@@ -188,29 +189,73 @@ pub fn generate_top_of_block_order(
         order_id,
         pool_id,
         valid_block,
-        tob_reward: U256::ZERO
+        tob_reward: U256::ZERO,
+        filled_amount: 0
     }
 }
 
-#[derive(Debug, Default)]
+/// Skew normal distribution orders' prices or quantities are drawn from.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct DistributionParameters {
+    /// the mean, if not skewed
     pub location: f64,
+    /// the spread around the location, a stddev if not skewed
     pub scale:    f64,
-    pub shape:    f64
+    /// the skew, the tail is longer below the location if negative and above
+    /// it if positive
+    pub shape:    f64,
+    /// samples are drawn from an rng seeded with it, so the same parameters
+    /// always draw the same values. Random if not set
+    pub seed:     Option<u64>
 }
 
 impl DistributionParameters {
+    pub fn new(location: f64, scale: f64, shape: f64) -> Self {
+        Self { location, scale, shape, seed: None }
+    }
+
+    pub fn with_scale(mut self, scale: f64) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_shape(mut self, shape: f64) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
     pub fn crossed_at(location: f64) -> (Self, Self) {
-        let bids = Self { location, scale: 100000.0, shape: -2.0 };
-        let asks = Self { location, scale: 100000.0, shape: 2.0 };
+        Self::crossed(location, 100000.0, 2.0)
+    }
+
+    /// bids and asks around `location`, skewed by `skew` in opposite
+    /// directions so the sides cross. A wider `scale` spreads the book out
+    pub fn crossed(location: f64, scale: f64, skew: f64) -> (Self, Self) {
+        let bids = Self::new(location, scale, -skew);
+        let asks = Self::new(location, scale, skew);
 
         (bids, asks)
     }
 
     pub fn fixed_at(location: f64) -> (Self, Self) {
-        let bids = Self { location, scale: 1.0, shape: 0.0 };
-        let asks = Self { location, scale: 1.0, shape: 0.0 };
+        let bids = Self::new(location, 1.0, 0.0);
+        let asks = Self::new(location, 1.0, 0.0);
 
         (bids, asks)
     }
+
+    /// seeds both sides, each with its own seed derived from `seed`
+    pub fn seeded((bids, asks): (Self, Self), seed: u64) -> (Self, Self) {
+        (bids.with_seed(seed), asks.with_seed(seed.wrapping_add(1)))
+    }
+
+    pub(crate) fn rng(&self) -> StdRng {
+        self.seed
+            .map_or_else(StdRng::from_entropy, StdRng::seed_from_u64)
+    }
 }
@@ -73,7 +73,8 @@ impl<Order: RawPoolOrder> StoredOrderBuilder<Order> {
             order_id,
             pool_id,
             valid_block,
-            tob_reward
+            tob_reward,
+            filled_amount: 0
         }
     }
 }
@@ -1,5 +1,5 @@
 use alloy::{
-    primitives::Address,
+    primitives::{aliases::U40, Address, U256},
     signers::{local::LocalSigner, SignerSync}
 };
 use angstrom_types::{
@@ -24,6 +24,8 @@ pub struct UserOrderBuilder {
     is_exact:    bool,
     block:       u64,
     nonce:       u64,
+    /// unix timestamp standing orders expire at, never if unset
+    deadline:    Option<u64>,
     recipient:   Address,
     asset_in:    Address,
     asset_out:   Address,
@@ -33,8 +35,10 @@ pub struct UserOrderBuilder {
 }
 
 impl UserOrderBuilder {
+    /// starts from an order that passes the static order checks, with a
+    /// non-zero amount and price
     pub fn new() -> Self {
-        Self { ..Default::default() }
+        Self { amount: 1, min_price: Ray::from(U256::from(1)), ..Default::default() }
     }
 
     pub fn standing(self) -> Self {
@@ -71,6 +75,10 @@ impl UserOrderBuilder {
         Self { nonce, ..self }
     }
 
+    pub fn deadline(self, deadline: u64) -> Self {
+        Self { deadline: Some(deadline), ..self }
+    }
+
     pub fn recipient(self, recipient: Address) -> Self {
         Self { recipient, ..self }
     }
@@ -96,6 +104,7 @@ impl UserOrderBuilder {
     }
 
     pub fn build(self) -> GroupedVanillaOrder {
+        let deadline = self.deadline.map_or(U40::MAX, U40::saturating_from);
         match (self.is_standing, self.is_exact) {
             (true, true) => {
                 let mut order = ExactStandingOrder {
@@ -105,6 +114,7 @@ impl UserOrderBuilder {
                     min_price: *self.min_price,
                     recipient: self.recipient,
                     nonce: self.nonce,
+                    deadline,
                     ..Default::default()
                 };
                 if let Some(SigningInfo { domain, address, key }) = self.signing_key {
@@ -126,6 +136,7 @@ impl UserOrderBuilder {
                     max_amount_in: self.amount,
                     min_price: *self.min_price,
                     recipient: self.recipient,
+                    deadline,
                     ..Default::default()
                 };
                 if let Some(SigningInfo { domain, address, key }) = self.signing_key {
@@ -17,16 +17,18 @@ use tokio::sync::mpsc::unbounded_channel;
 use uniswap_v4::uniswap::pool_manager::SyncedUniswapPools;
 use validation::{
     bundle::BundleValidator,
-    common::{db::BlockStateProviderFactory, SharedTools, TokenPriceGenerator},
+    common::{db::BlockStateProviderFactory, SharedTools, SystemClock, TokenPriceGenerator},
     order::{
         order_validator::OrderValidator,
         sim::SimValidation,
         state::{
+            config::ValidationConfig,
             db_state_utils::{nonces::Nonces, FetchUtils},
             pools::AngstromPoolsTracker
         }
     },
-    validator::{ValidationClient, Validator}
+    validator::{ValidationClient, Validator},
+    ValidationInitError
 };
 
 type ValidatorOperation<DB, T> =
@@ -58,7 +60,8 @@ where
         token_conversion: TokenPriceGenerator,
         token_updates: Pin<Box<dyn Stream<Item = Vec<PairsWithPrice>> + 'static>>,
         pool_store: Arc<AngstromPoolConfigStore>
-    ) -> Self {
+    ) -> Result<Self, ValidationInitError> {
+        ValidationInitError::check_angstrom_address(angstrom_address)?;
         let (tx, rx) = unbounded_channel();
 
         let current_block =
@@ -72,8 +75,16 @@ where
         let thread_pool = KeySplitThreadpool::new(handle, 3);
         let sim = SimValidation::new(db.clone(), None);
 
-        let order_validator =
-            OrderValidator::new(sim, current_block, pools, fetch, uniswap_pools).await;
+        let order_validator = OrderValidator::new(
+            sim,
+            current_block,
+            pools,
+            fetch,
+            uniswap_pools,
+            ValidationConfig::default(),
+            Arc::new(SystemClock)
+        )
+        .await;
 
         let bundle_validator = BundleValidator::new(db.clone(), angstrom_address, node_address);
         let shared_utils = SharedTools::new(token_conversion, token_updates, thread_pool);
@@ -81,7 +92,7 @@ where
         let val = Validator::new(rx, order_validator, bundle_validator, shared_utils);
         let client = ValidationClient(tx);
 
-        Self { db, client, underlying: val }
+        Ok(Self { db, client, underlying: val })
     }
 
     pub async fn poll_for(&mut self, duration: Duration) {